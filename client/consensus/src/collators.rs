@@ -351,7 +351,7 @@ where
             // TODO: this doesn't take into account force authoring.
             // So a node with `force_authoring = true` will not propose a block for a parathread until the
             // `min_slot_freq` has elapsed.
-            slot_diff < min_slot_freq
+            slot_diff < parathread_min_slot_diff(min_slot_freq, aux_data.authorities.len())
         } else {
             // In case of error always propose
             false
@@ -362,6 +362,26 @@ where
     }
 }
 
+/// Minimum number of slots between the chain head of a parathread and the next block.
+///
+/// Authors are selected like in Aura, one per slot, so if every block was produced exactly
+/// `min_slot_freq` slots after the previous one and `min_slot_freq` was a multiple of the number of
+/// authorities, the same collator would author all the blocks. To rotate authorship, the first
+/// claimable slot is the first one after `min_slot_freq` whose author is the one following the
+/// author of the chain head. If that collator misses its slot, the next slots can be claimed by the
+/// following authors as usual. This delays blocks by at most `num_authorities - 1` extra slots.
+pub fn parathread_min_slot_diff(min_slot_freq: Slot, num_authorities: usize) -> Slot {
+    if num_authorities <= 1 {
+        return min_slot_freq;
+    }
+
+    let num_authorities = num_authorities as u64;
+    // Smallest `offset` such that `min_slot_freq + offset` is congruent to 1 modulo `num_authorities`
+    let offset = (num_authorities + 1 - *min_slot_freq % num_authorities) % num_authorities;
+
+    min_slot_freq.saturating_add(offset)
+}
+
 /// Attempt to claim a slot using a keystore.
 pub fn claim_slot_inner<P: Pair>(
     slot: Slot,
@@ -20,7 +20,9 @@
 // Most of the items hereby added are intended to make it work with our current consensus mechanism
 use {
     crate::{
-        collators::{tanssi_claim_slot, Collator, Params as CollatorParams},
+        collators::{
+            parathread_min_slot_diff, tanssi_claim_slot, Collator, Params as CollatorParams,
+        },
         OrchestratorAuraWorkerAuxData,
     },
     async_trait::async_trait,
@@ -540,6 +542,84 @@ async fn claim_slot_respects_min_slot_freq() {
     assert_eq!(claimed_slots, vec![0, 4, 8]);
 }
 
+#[tokio::test]
+async fn claim_slot_rotates_parathread_authors() {
+    // There are 2 authorities that can only claim every 2 slots. Without rotation, the authority
+    // of even slots would author all the blocks.
+    let mut authorities: Vec<NimbusId> = vec![];
+    let min_slot_freq = 2;
+
+    let keystore_path = tempfile::tempdir().expect("Creates keystore path");
+    let keystore = LocalKeystore::open(keystore_path.path(), None).expect("Creates keystore.");
+
+    for _ in 0..2 {
+        let public = keystore
+            .sr25519_generate_new(NIMBUS_KEY_ID, None)
+            .expect("Key should be created");
+        authorities.push(public.into());
+    }
+
+    let keystore_ptr: KeystorePtr = keystore.into();
+
+    let mut claimed_slots = vec![];
+    let mut claim_authors = vec![];
+
+    for slot in 0..10 {
+        let parent_slot: u64 = claimed_slots.last().copied().unwrap_or_default();
+        let parent_slot: Slot = parent_slot.into();
+        let pre_digest = Digest {
+            logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, parent_slot.encode())],
+        };
+        let head = TestHeader {
+            parent_hash: Default::default(),
+            // If we use number=0 aura ignores the digest
+            number: claimed_slots.len() as u64,
+            state_root: Default::default(),
+            extrinsics_root: Default::default(),
+            digest: pre_digest,
+        };
+        let aux_data = OrchestratorAuraWorkerAuxData {
+            authorities: authorities.clone(),
+            min_slot_freq: Some(min_slot_freq.into()),
+        };
+        let claim = tanssi_claim_slot::<NimbusPair, TestBlock>(
+            aux_data,
+            &head,
+            slot.into(),
+            false,
+            &keystore_ptr,
+        )
+        .unwrap();
+        if let Some(claim) = claim {
+            claimed_slots.push(slot);
+            claim_authors.push(claim.author_pub().clone());
+        }
+    }
+
+    assert_eq!(claimed_slots, vec![0, 3, 6, 9]);
+    assert_eq!(
+        claim_authors,
+        vec![
+            authorities[0].clone(),
+            authorities[1].clone(),
+            authorities[0].clone(),
+            authorities[1].clone(),
+        ]
+    );
+}
+
+#[test]
+fn parathread_min_slot_diff_rotates_authors() {
+    // A single author keeps the min slot frequency
+    assert_eq!(parathread_min_slot_diff(4.into(), 1), 4.into());
+    // The first claimable slot is always authored by the next authority
+    assert_eq!(parathread_min_slot_diff(1.into(), 3), 1.into());
+    assert_eq!(parathread_min_slot_diff(2.into(), 3), 4.into());
+    assert_eq!(parathread_min_slot_diff(3.into(), 3), 4.into());
+    assert_eq!(parathread_min_slot_diff(4.into(), 3), 4.into());
+    assert_eq!(parathread_min_slot_diff(6.into(), 2), 7.into());
+}
+
 #[tokio::test]
 async fn collate_returns_correct_block() {
     let net = AuraTestNet::new(4);
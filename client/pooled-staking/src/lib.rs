@@ -0,0 +1,149 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! RPC client for Pooled Staking pallet
+
+pub use pallet_pooled_staking_runtime_api::PooledStakingApi as PooledStakingRuntimeApi;
+use {
+    core::marker::PhantomData,
+    jsonrpsee::{
+        core::{async_trait, RpcResult},
+        proc_macros::rpc,
+    },
+    pallet_pooled_staking_runtime_api::PooledStakingApiError,
+    sc_rpc::DenyUnsafe,
+    serde::Serialize,
+    sp_api::ProvideRuntimeApi,
+    sp_runtime::{traits::Block as BlockT, Perbill},
+    std::sync::Arc,
+};
+
+/// Top-level error type for the RPC handler.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Failed to fetch API
+    #[error("Failed to fetch API: {0}")]
+    ApiError(sp_api::ApiError),
+
+    /// Failed to compute the delegator positions.
+    #[error("Failed to fetch delegator positions: {0}")]
+    PooledStakingApiError(PooledStakingApiError),
+}
+
+#[rpc(client, server)]
+pub trait PooledStakingApi<Hash, AccountId, Instant, DelegatorPosition> {
+    /// Get the positions of the delegator towards every candidate it delegates to, including
+    /// its pending joining and leaving requests, its stake in each pool and its claimable
+    /// rewards.
+    ///
+    /// Computing the positions iterates over every pool of every candidate, so this method is
+    /// unsafe and only exposed when the node runs with `--rpc-methods unsafe`.
+    #[method(name = "tanssi_pooledStakingDelegatorPositions")]
+    async fn delegator_positions(
+        &self,
+        block: Hash,
+        delegator: AccountId,
+    ) -> RpcResult<Vec<DelegatorPosition>>;
+
+    /// Get the commission currently applied to the rewards of the candidate.
+    #[method(name = "tanssi_pooledStakingCandidateCommission")]
+    async fn candidate_commission(&self, block: Hash, candidate: AccountId) -> RpcResult<Perbill>;
+
+    /// Get the commission increase requested by the candidate if any, along with the instant at
+    /// which it was requested.
+    #[method(name = "tanssi_pooledStakingPendingCommissionIncrease")]
+    async fn pending_commission_increase(
+        &self,
+        block: Hash,
+        candidate: AccountId,
+    ) -> RpcResult<Option<(Perbill, Instant)>>;
+}
+
+pub struct PooledStaking<Client, Block> {
+    client: Arc<Client>,
+    deny_unsafe: DenyUnsafe,
+    _phantom: PhantomData<Block>,
+}
+
+impl<Client, Block> PooledStaking<Client, Block> {
+    pub fn new(client: Arc<Client>, deny_unsafe: DenyUnsafe) -> Self {
+        Self {
+            client,
+            deny_unsafe,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Block, Hash, Client, AccountId, Instant, DelegatorPosition>
+    PooledStakingApiServer<Hash, AccountId, Instant, DelegatorPosition>
+    for PooledStaking<Client, Block>
+where
+    Hash: Send + 'static,
+    Block: BlockT<Hash = Hash>,
+    Client: ProvideRuntimeApi<Block> + Sync + Send + 'static,
+    Client::Api: PooledStakingRuntimeApi<Block, AccountId, Instant, DelegatorPosition>,
+    AccountId: parity_scale_codec::Codec + Send + 'static,
+    Instant: parity_scale_codec::Codec + Serialize + Send + 'static,
+    DelegatorPosition: parity_scale_codec::Codec + Serialize + Send + 'static,
+{
+    async fn delegator_positions(
+        &self,
+        block: Hash,
+        delegator: AccountId,
+    ) -> RpcResult<Vec<DelegatorPosition>> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let positions = self
+            .client
+            .runtime_api()
+            .delegator_positions(block, delegator)
+            .map_err(|e| internal_err(Error::ApiError(e)))?
+            .map_err(|e| internal_err(Error::PooledStakingApiError(e)))?;
+
+        Ok(positions)
+    }
+
+    async fn candidate_commission(&self, block: Hash, candidate: AccountId) -> RpcResult<Perbill> {
+        self.client
+            .runtime_api()
+            .candidate_commission(block, candidate)
+            .map_err(|e| internal_err(Error::ApiError(e)))
+    }
+
+    async fn pending_commission_increase(
+        &self,
+        block: Hash,
+        candidate: AccountId,
+    ) -> RpcResult<Option<(Perbill, Instant)>> {
+        self.client
+            .runtime_api()
+            .pending_commission_increase(block, candidate)
+            .map_err(|e| internal_err(Error::ApiError(e)))
+    }
+}
+
+pub fn internal_err<T: ToString>(error: T) -> jsonrpsee::core::Error {
+    jsonrpsee::core::Error::Call(jsonrpsee::types::error::CallError::Custom(
+        jsonrpsee::types::error::ErrorObject::borrowed(
+            jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+            &error.to_string(),
+            None,
+        )
+        .into_owned(),
+    ))
+}
@@ -16,19 +16,35 @@
 
 //! RPC client for Author Noting pallet
 
-pub use pallet_stream_payment_runtime_api::StreamPaymentApi as StreamPaymentRuntimeApi;
+pub use pallet_stream_payment_runtime_api::{
+    StreamPaymentApi as StreamPaymentRuntimeApi,
+    StreamPaymentStreamsApi as StreamPaymentStreamsRuntimeApi,
+};
 use {
     core::marker::PhantomData,
+    futures::{future, FutureExt, StreamExt},
     jsonrpsee::{
         core::{async_trait, RpcResult},
         proc_macros::rpc,
+        types::{
+            error::{ErrorObject, ErrorObjectOwned, INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+            SubscriptionResult,
+        },
+        SubscriptionSink,
     },
     pallet_stream_payment_runtime_api::{StreamPaymentApiError, StreamPaymentApiStatus},
-    sp_api::ProvideRuntimeApi,
+    sc_client_api::BlockchainEvents,
+    sc_rpc::SubscriptionTaskExecutor,
+    serde::{Deserialize, Serialize},
+    sp_api::{ApiExt, ApiRef, ProvideRuntimeApi},
     sp_runtime::traits::Block as BlockT,
     std::sync::Arc,
 };
 
+/// Maximum amount of streams a single `tanssi_subscribeStreamPaymentStatus` subscription can
+/// follow, as their statuses are fetched for every new block.
+pub const MAX_SUBSCRIBED_STREAMS: usize = 100;
+
 /// Top-level error type for the RPC handler.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -39,10 +55,36 @@ pub enum Error {
     /// Failed to fetch the current best header.
     #[error("Failed to fetch stream payment status: {0}")]
     StreamPaymentApiError(StreamPaymentApiError),
+
+    /// Too many streams provided to a subscription.
+    #[error("Cannot subscribe to more than {MAX_SUBSCRIBED_STREAMS} streams, got {0}")]
+    TooManyStreams(usize),
+
+    /// The runtime doesn't implement the version of `StreamPaymentApi` providing the method.
+    #[error("{0} is unsupported by runtime (StreamPaymentApi version {1:?})")]
+    UnsupportedByRuntime(&'static str, Option<u32>),
+}
+
+/// Version of `StreamPaymentApi` which added `stream_payment_statuses` and `stream_stall_time`.
+pub const STREAM_PAYMENT_API_V2: u32 = 2;
+
+/// Notification pushed to `tanssi_subscribeStreamPaymentStatus` subscribers for each new block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamPaymentStatusNotification<Hash, StreamId, Balance, Stream> {
+    /// Block at which the statuses have been fetched.
+    pub block: Hash,
+    /// Status of each subscribed stream.
+    pub statuses: Vec<(
+        StreamId,
+        Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError>,
+    )>,
+    /// Subscribed streams for which a change was requested in this block, along with their data
+    /// which contains the pending request.
+    pub change_requests: Vec<(StreamId, Stream)>,
 }
 
 #[rpc(client, server)]
-pub trait StreamPaymentApi<Hash, StreamId, Instant, Balance> {
+pub trait StreamPaymentApi<Hash, StreamId, Instant, Balance, AccountId, Stream> {
     #[method(name = "tanssi_streamPaymentStatus")]
     async fn stream_payment_status(
         &self,
@@ -50,33 +92,98 @@ pub trait StreamPaymentApi<Hash, StreamId, Instant, Balance> {
         stream_id: StreamId,
         now: Option<Instant>,
     ) -> RpcResult<StreamPaymentApiStatus<Balance>>;
+
+    /// List the ids of the streams with given source.
+    #[method(name = "tanssi_streamPaymentStreamsWithSource")]
+    async fn streams_with_source(&self, block: Hash, source: AccountId)
+        -> RpcResult<Vec<StreamId>>;
+
+    /// List the ids of the streams with given target.
+    #[method(name = "tanssi_streamPaymentStreamsWithTarget")]
+    async fn streams_with_target(&self, block: Hash, target: AccountId)
+        -> RpcResult<Vec<StreamId>>;
+
+    /// Get the stream data, including its pending change request if any.
+    #[method(name = "tanssi_streamPaymentStream")]
+    async fn stream(&self, block: Hash, stream_id: StreamId) -> RpcResult<Option<Stream>>;
+
+    /// Project the time (in the stream time unit) at which the stream will stall if its deposit
+    /// is not changed. Returns `None` if the stream will never stall.
+    #[method(name = "tanssi_streamPaymentStallTime")]
+    async fn stream_stall_time(
+        &self,
+        block: Hash,
+        stream_id: StreamId,
+    ) -> RpcResult<Option<Instant>>;
+
+    /// Subscribe to the status of the provided streams, which is pushed for every new best
+    /// block, or every new finalized block if `finalized` is `true`. Change requests made on
+    /// those streams are pushed along with the statuses. At most `MAX_SUBSCRIBED_STREAMS`
+    /// streams can be provided, and the subscription is closed with an error if the statuses
+    /// cannot be fetched.
+    #[subscription(
+        name = "tanssi_subscribeStreamPaymentStatus",
+        unsubscribe = "tanssi_unsubscribeStreamPaymentStatus",
+        item = StreamPaymentStatusNotification<Hash, StreamId, Balance, Stream>
+    )]
+    fn subscribe_stream_payment_status(&self, stream_ids: Vec<StreamId>, finalized: Option<bool>);
+}
+
+/// Ensure the runtime at `block` implements version 2 of `StreamPaymentApi`, before calling
+/// `method` which was added in that version.
+fn ensure_stream_payment_api_v2<Block, Api, StreamId, Instant, Balance>(
+    api: &ApiRef<'_, Api>,
+    block: Block::Hash,
+    method: &'static str,
+) -> Result<(), Error>
+where
+    Block: BlockT,
+    Api: ApiExt<Block> + StreamPaymentRuntimeApi<Block, StreamId, Instant, Balance>,
+    StreamId: parity_scale_codec::Codec,
+    Instant: parity_scale_codec::Codec,
+    Balance: parity_scale_codec::Codec,
+{
+    let version = api
+        .api_version::<dyn StreamPaymentRuntimeApi<Block, StreamId, Instant, Balance>>(block)
+        .map_err(Error::ApiError)?;
+
+    match version {
+        Some(version) if version >= STREAM_PAYMENT_API_V2 => Ok(()),
+        version => Err(Error::UnsupportedByRuntime(method, version)),
+    }
 }
 
 pub struct StreamPayment<Client, Block> {
     client: Arc<Client>,
+    executor: SubscriptionTaskExecutor,
     _phantom: PhantomData<Block>,
 }
 
 impl<Client, Block> StreamPayment<Client, Block> {
-    pub fn new(client: Arc<Client>) -> Self {
+    pub fn new(client: Arc<Client>, executor: SubscriptionTaskExecutor) -> Self {
         Self {
             client,
+            executor,
             _phantom: PhantomData,
         }
     }
 }
 
 #[async_trait]
-impl<Block, Hash, Client, StreamId, Instant, Balance>
-    StreamPaymentApiServer<Hash, StreamId, Instant, Balance> for StreamPayment<Client, Block>
+impl<Block, Hash, Client, StreamId, Instant, Balance, AccountId, Stream>
+    StreamPaymentApiServer<Hash, StreamId, Instant, Balance, AccountId, Stream>
+    for StreamPayment<Client, Block>
 where
-    Hash: Send + 'static,
+    Hash: Serialize + Send + 'static,
     Block: BlockT<Hash = Hash>,
-    Client: ProvideRuntimeApi<Block> + Sync + Send + 'static,
-    Client::Api: StreamPaymentRuntimeApi<Block, StreamId, Instant, Balance>,
-    StreamId: parity_scale_codec::Codec + Send + 'static,
+    Client: ProvideRuntimeApi<Block> + BlockchainEvents<Block> + Sync + Send + 'static,
+    Client::Api: StreamPaymentRuntimeApi<Block, StreamId, Instant, Balance>
+        + StreamPaymentStreamsRuntimeApi<Block, StreamId, AccountId, Stream>,
+    StreamId: parity_scale_codec::Codec + Serialize + Clone + Send + Sync + 'static,
     Instant: parity_scale_codec::Codec + Send + 'static,
-    Balance: parity_scale_codec::Codec + Send + 'static,
+    Balance: parity_scale_codec::Codec + Serialize + Send + 'static,
+    AccountId: parity_scale_codec::Codec + Send + 'static,
+    Stream: parity_scale_codec::Codec + Serialize + Send + 'static,
 {
     async fn stream_payment_status(
         &self,
@@ -93,15 +200,157 @@ where
 
         Ok(status)
     }
+
+    async fn streams_with_source(
+        &self,
+        block: Hash,
+        source: AccountId,
+    ) -> RpcResult<Vec<StreamId>> {
+        self.client
+            .runtime_api()
+            .streams_with_source(block, source)
+            .map_err(|e| internal_err(Error::ApiError(e)))
+    }
+
+    async fn streams_with_target(
+        &self,
+        block: Hash,
+        target: AccountId,
+    ) -> RpcResult<Vec<StreamId>> {
+        self.client
+            .runtime_api()
+            .streams_with_target(block, target)
+            .map_err(|e| internal_err(Error::ApiError(e)))
+    }
+
+    async fn stream(&self, block: Hash, stream_id: StreamId) -> RpcResult<Option<Stream>> {
+        self.client
+            .runtime_api()
+            .stream(block, stream_id)
+            .map_err(|e| internal_err(Error::ApiError(e)))
+    }
+
+    async fn stream_stall_time(
+        &self,
+        block: Hash,
+        stream_id: StreamId,
+    ) -> RpcResult<Option<Instant>> {
+        let api = self.client.runtime_api();
+        ensure_stream_payment_api_v2::<Block, _, StreamId, Instant, Balance>(
+            &api,
+            block,
+            "stream_stall_time",
+        )
+        .map_err(internal_err)?;
+
+        let stall_time = api
+            .stream_stall_time(block, stream_id)
+            .map_err(|e| internal_err(Error::ApiError(e)))?
+            .map_err(|e| internal_err(Error::StreamPaymentApiError(e)))?;
+
+        Ok(stall_time)
+    }
+
+    fn subscribe_stream_payment_status(
+        &self,
+        mut sink: SubscriptionSink,
+        stream_ids: Vec<StreamId>,
+        finalized: Option<bool>,
+    ) -> SubscriptionResult {
+        if stream_ids.len() > MAX_SUBSCRIBED_STREAMS {
+            sink.reject(ErrorObject::owned(
+                INVALID_PARAMS_CODE,
+                Error::TooManyStreams(stream_ids.len()).to_string(),
+                None::<()>,
+            ))?;
+            return Ok(());
+        }
+
+        sink.accept()?;
+
+        let mut blocks = if finalized.unwrap_or(false) {
+            self.client
+                .finality_notification_stream()
+                .map(|notification| notification.hash)
+                .boxed()
+        } else {
+            self.client
+                .import_notification_stream()
+                .filter(|notification| future::ready(notification.is_new_best))
+                .map(|notification| notification.hash)
+                .boxed()
+        };
+
+        let client = self.client.clone();
+        let fut = async move {
+            let mut version_checked = false;
+
+            while let Some(block) = blocks.next().await {
+                let api = client.runtime_api();
+
+                // `stream_payment_statuses` requires version 2 of the API. The runtime is only
+                // expected to move to newer versions, so this is checked on the first block.
+                if !version_checked {
+                    if let Err(e) =
+                        ensure_stream_payment_api_v2::<Block, _, StreamId, Instant, Balance>(
+                            &api,
+                            block,
+                            "stream_payment_statuses",
+                        )
+                    {
+                        sink.close(internal_err_object(e));
+                        break;
+                    }
+                    version_checked = true;
+                }
+
+                let fetched = api
+                    .stream_payment_statuses(block, stream_ids.clone(), None)
+                    .and_then(|statuses| {
+                        let change_requests =
+                            api.streams_with_change_requested(block, stream_ids.clone())?;
+                        Ok((statuses, change_requests))
+                    });
+
+                let (statuses, change_requests) = match fetched {
+                    Ok(fetched) => fetched,
+                    // Let the subscriber know why it will not receive notifications anymore.
+                    Err(e) => {
+                        sink.close(internal_err_object(Error::ApiError(e)));
+                        break;
+                    }
+                };
+
+                let notification = StreamPaymentStatusNotification {
+                    block,
+                    statuses: stream_ids.iter().cloned().zip(statuses).collect(),
+                    change_requests,
+                };
+
+                match sink.send(&notification) {
+                    Ok(true) => (),
+                    // Subscription has been closed, or the notification can't be serialized.
+                    Ok(false) | Err(_) => break,
+                }
+            }
+        };
+
+        self.executor.spawn(
+            "tanssi-stream-payment-subscription",
+            Some("rpc"),
+            fut.boxed(),
+        );
+
+        Ok(())
+    }
 }
 
 pub fn internal_err<T: ToString>(error: T) -> jsonrpsee::core::Error {
     jsonrpsee::core::Error::Call(jsonrpsee::types::error::CallError::Custom(
-        jsonrpsee::types::error::ErrorObject::borrowed(
-            jsonrpsee::types::error::INTERNAL_ERROR_CODE,
-            &error.to_string(),
-            None,
-        )
-        .into_owned(),
+        internal_err_object(error),
     ))
 }
+
+fn internal_err_object<T: ToString>(error: T) -> ErrorObjectOwned {
+    ErrorObject::borrowed(INTERNAL_ERROR_CODE, &error.to_string(), None).into_owned()
+}
@@ -98,6 +98,7 @@ pub fn development_config(
                 collators_per_parathread: 1,
                 parathreads_per_collator: 1,
                 target_container_chain_fullness: Perbill::from_percent(80),
+                stake_weighted_collator_selection: false,
             },
             ..Default::default()
         },
@@ -160,6 +161,7 @@ pub fn local_dancebox_config(
                 collators_per_parathread: 1,
                 parathreads_per_collator: 1,
                 target_container_chain_fullness: Perbill::from_percent(80),
+                stake_weighted_collator_selection: false,
             },
             ..Default::default()
         },
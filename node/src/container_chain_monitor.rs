@@ -22,7 +22,7 @@ use {
     cumulus_primitives_core::ParaId,
     std::{
         cell::Cell,
-        collections::VecDeque,
+        collections::{HashSet, VecDeque},
         sync::{Arc, Mutex},
         time::Instant,
     },
@@ -203,15 +203,22 @@ impl SpawnedContainersMonitor {
 pub async fn monitor_task(state: Arc<Mutex<ContainerChainSpawnerState>>) {
     // Main loop frequency, doesn't need to be fast
     let monitor_period = Duration::from_secs(300 * 0 + 10);
-    // Max number of allowed container chains before printing warnings.
-    // There should be at most 2 container chains running at the same time (1 syncing + 1 collating),
-    // but add a margin of error because a container chain may take a few seconds to stop.
-    let max_running_container_chains = 4;
 
     loop {
         sleep(monitor_period).await;
         log::debug!("Monitor tick");
         let mut state = state.lock().unwrap();
+        // Max number of allowed container chains before printing warnings.
+        // There should be at most 1 container chain running for each assigned para id, in the
+        // current and in the next session (usually 1 syncing + 1 collating, but collators can be
+        // assigned to several parathreads). Add a margin of error because a container chain may
+        // take a few seconds to stop.
+        let max_running_container_chains = state
+            .assigned_para_ids
+            .union(&state.next_assigned_para_ids)
+            .count()
+            .max(2)
+            + 2;
         let monitor_state = &mut state.spawned_containers_monitor;
 
         let running_chains = monitor_state.running_chains();
@@ -252,29 +259,29 @@ pub async fn debug_start_and_stop_same_cc(cc_spawn_tx: UnboundedSender<CcSpawnMs
         sleep(sleep_delay).await;
         cc_spawn_tx
             .send(CcSpawnMsg::UpdateAssignment {
-                current: Some(2000u32.into()),
-                next: None,
+                current: HashSet::from([2000u32.into()]),
+                next: HashSet::new(),
             })
             .unwrap();
         sleep(sleep_delay).await;
         cc_spawn_tx
             .send(CcSpawnMsg::UpdateAssignment {
-                current: None,
-                next: None,
+                current: HashSet::new(),
+                next: HashSet::new(),
             })
             .unwrap();
         sleep(sleep_delay).await;
         cc_spawn_tx
             .send(CcSpawnMsg::UpdateAssignment {
-                current: None,
-                next: Some(2001u32.into()),
+                current: HashSet::new(),
+                next: HashSet::from([2001u32.into()]),
             })
             .unwrap();
         sleep(sleep_delay).await;
         cc_spawn_tx
             .send(CcSpawnMsg::UpdateAssignment {
-                current: None,
-                next: None,
+                current: HashSet::new(),
+                next: HashSet::new(),
             })
             .unwrap();
     }
@@ -90,8 +90,8 @@ pub struct ContainerChainSpawner {
 #[derive(Default)]
 pub struct ContainerChainSpawnerState {
     spawned_container_chains: HashMap<ParaId, ContainerChainState>,
-    assigned_para_id: Option<ParaId>,
-    next_assigned_para_id: Option<ParaId>,
+    assigned_para_ids: HashSet<ParaId>,
+    next_assigned_para_ids: HashSet<ParaId>,
     failed_para_ids: HashSet<ParaId>,
     // For debugging and detecting errors
     pub spawned_containers_monitor: SpawnedContainersMonitor,
@@ -100,6 +100,8 @@ pub struct ContainerChainSpawnerState {
 pub struct ContainerChainState {
     /// Handle that can be used to stop the container chain
     stop_handle: StopContainerChain,
+    /// Whether this container chain uses the ports from the CLI (true) or random ports (false)
+    uses_cli_ports: bool,
 }
 
 /// Stops a container chain when signal is sent. The bool means `keep_db`, whether to keep the
@@ -114,10 +116,11 @@ pub struct StopContainerChain {
 /// `Arc<ContainerChainSpawner>` to other threads.
 #[derive(Debug)]
 pub enum CcSpawnMsg {
-    /// Update container chain assignment
+    /// Update container chain assignment. Collators can be assigned to several parathreads at the
+    /// same time, so this is a set of para ids.
     UpdateAssignment {
-        current: Option<ParaId>,
-        next: Option<ParaId>,
+        current: HashSet<ParaId>,
+        next: HashSet<ParaId>,
     },
 }
 
@@ -224,17 +227,26 @@ impl ContainerChainSpawner {
                 container_chain_para_id
             );
 
-            if !start_collation {
-                log::info!("This is a syncing container chain, using random ports");
-                // Use random ports to avoid conflicts with the other running container chain
-                let random_ports = [23456, 23457, 23458];
+            // Only one container chain can use the ports from the CLI. When collating on several
+            // parathreads, the first one to start keeps them.
+            let use_cli_ports = start_collation
+                && !state
+                    .lock()
+                    .expect("poison error")
+                    .spawned_container_chains
+                    .values()
+                    .any(|container_chain| container_chain.uses_cli_ports);
+            if !use_cli_ports {
+                log::info!("This is a syncing container chain or another collating parathread, using random ports");
+                // Use random ports to avoid conflicts with the other running container chains.
+                // Port 0 lets the OS pick a free port, because there may be several of them.
                 container_chain_cli
                     .base
                     .base
                     .prometheus_params
-                    .prometheus_port = Some(random_ports[0]);
-                container_chain_cli.base.base.network_params.port = Some(random_ports[1]);
-                container_chain_cli.base.base.rpc_port = Some(random_ports[2]);
+                    .prometheus_port = Some(0);
+                container_chain_cli.base.base.network_params.port = Some(0);
+                container_chain_cli.base.base.rpc_port = Some(0);
             }
 
             // Update CLI params
@@ -349,6 +361,7 @@ impl ContainerChainSpawner {
                             signal,
                             id: monitor_id,
                         },
+                        uses_cli_ports: use_cli_ports,
                     },
                 );
             }
@@ -455,8 +468,11 @@ impl ContainerChainSpawner {
         // So all nodes start as orchestrator chain collators, until the first block is imported,
         // then the real assignment is used.
         if validator {
-            self.handle_update_assignment(Some(self.orchestrator_para_id), None)
-                .await;
+            self.handle_update_assignment(
+                HashSet::from([self.orchestrator_para_id]),
+                HashSet::new(),
+            )
+            .await;
         }
 
         while let Some(msg) = rx.recv().await {
@@ -476,7 +492,7 @@ impl ContainerChainSpawner {
     }
 
     /// Handle `CcSpawnMsg::UpdateAssignment`
-    async fn handle_update_assignment(&mut self, current: Option<ParaId>, next: Option<ParaId>) {
+    async fn handle_update_assignment(&mut self, current: HashSet<ParaId>, next: HashSet<ParaId>) {
         let HandleUpdateAssignmentResult {
             chains_to_stop,
             chains_to_start,
@@ -484,11 +500,11 @@ impl ContainerChainSpawner {
         } = handle_update_assignment_state_change(
             &mut self.state.lock().expect("poison error"),
             self.orchestrator_para_id,
-            current,
+            current.clone(),
             next,
         );
 
-        if current != Some(self.orchestrator_para_id) {
+        if !current.contains(&self.orchestrator_para_id) {
             // If not assigned to orchestrator chain anymore, we need to stop the collator process
             let maybe_exit_notification_receiver = self
                 .collation_cancellation_constructs
@@ -509,7 +525,7 @@ impl ContainerChainSpawner {
         // Stop all container chains that are no longer needed
         for para_id in chains_to_stop {
             // Keep db if we are currently assigned to this chain
-            let keep_db = Some(para_id) == current;
+            let keep_db = current.contains(&para_id);
             self.stop(para_id, keep_db);
         }
 
@@ -518,11 +534,11 @@ impl ContainerChainSpawner {
             sleep(Duration::from_secs(10)).await;
         }
 
-        // Start all new container chains (usually 1)
+        // Start all new container chains (usually 1, or more if assigned to several parathreads)
         for para_id in chains_to_start {
             // Edge case: when starting the node it may be assigned to a container chain, so we need to
             // start a container chain already collating.
-            let start_collation = Some(para_id) == current;
+            let start_collation = current.contains(&para_id);
             self.spawn(para_id, start_collation).await;
         }
     }
@@ -538,10 +554,10 @@ struct HandleUpdateAssignmentResult {
 fn handle_update_assignment_state_change(
     state: &mut ContainerChainSpawnerState,
     orchestrator_para_id: ParaId,
-    current: Option<ParaId>,
-    next: Option<ParaId>,
+    current: HashSet<ParaId>,
+    next: HashSet<ParaId>,
 ) -> HandleUpdateAssignmentResult {
-    if (state.assigned_para_id, state.next_assigned_para_id) == (current, next) {
+    if state.assigned_para_ids == current && state.next_assigned_para_ids == next {
         // If nothing changed there is nothing to update
         return HandleUpdateAssignmentResult {
             chains_to_stop: Default::default(),
@@ -553,39 +569,37 @@ fn handle_update_assignment_state_change(
     // Create a set with the container chains that were running before, and the container
     // chains that should be running after the updated assignment. This is used to calculate
     // the difference, and stop and start the required container chains.
-    let mut running_chains_before = HashSet::new();
-    let mut running_chains_after = HashSet::new();
-
-    running_chains_before.extend(state.assigned_para_id);
-    running_chains_before.extend(state.next_assigned_para_id);
+    let mut running_chains_before: HashSet<ParaId> = state
+        .assigned_para_ids
+        .union(&state.next_assigned_para_ids)
+        .copied()
+        .collect();
     // Ignore orchestrator_para_id because it is handled in a special way, as it does not need to
     // start one session before in order to sync.
     running_chains_before.remove(&orchestrator_para_id);
 
-    running_chains_after.extend(current);
-    running_chains_after.extend(next);
+    let mut running_chains_after: HashSet<ParaId> = current.union(&next).copied().collect();
     running_chains_after.remove(&orchestrator_para_id);
-    let mut need_to_restart_current = false;
-    let mut need_to_restart_next = false;
-
-    if state.assigned_para_id != current {
-        if let Some(para_id) = current {
-            // If the assigned container chain has changed, we may need to
-            // restart it in collation mode, unless it is the orchestrator chain.
-            if para_id != orchestrator_para_id {
-                need_to_restart_current = true;
-            }
-        }
 
-        if let Some(para_id) = state.assigned_para_id {
-            if para_id != orchestrator_para_id && Some(para_id) == next {
-                need_to_restart_next = true;
-            }
-        }
-    }
-
-    state.assigned_para_id = current;
-    state.next_assigned_para_id = next;
+    // If the assigned container chains have changed, we may need to restart them in collation
+    // mode, unless it is the orchestrator chain.
+    let mut chains_to_restart: Vec<ParaId> = current
+        .difference(&state.assigned_para_ids)
+        .copied()
+        .filter(|para_id| *para_id != orchestrator_para_id)
+        .collect();
+    // Handle edge case of going from (2000, 2001) to (2001, 2000). In that case we must restart both chains,
+    // because previously 2000 was collating and now 2000 will only be syncing.
+    chains_to_restart.extend(
+        state
+            .assigned_para_ids
+            .difference(&current)
+            .copied()
+            .filter(|para_id| *para_id != orchestrator_para_id && next.contains(para_id)),
+    );
+
+    state.assigned_para_ids = current;
+    state.next_assigned_para_ids = next;
 
     let mut chains_to_stop: Vec<_> = running_chains_before
         .difference(&running_chains_after)
@@ -596,34 +610,22 @@ fn handle_update_assignment_state_change(
         .copied()
         .collect();
 
-    if need_to_restart_current {
-        // Force restart of new assigned container chain: if it was running before it was in "syncing mode",
-        // which doesn't use the correct ports, so start it in "collation mode".
-        let id = current.unwrap();
-        if running_chains_before.contains(&id) && !chains_to_stop.contains(&id) {
-            chains_to_stop.push(id);
-        }
-        if !chains_to_start.contains(&id) {
-            chains_to_start.push(id);
-        }
-    }
-
-    if need_to_restart_next {
-        // Handle edge case of going from (2000, 2001) to (2001, 2000). In that case we must restart both chains,
-        // because previously 2000 was collating and now 2000 will only be syncing.
-        let id = next.unwrap();
-        if running_chains_before.contains(&id) && !chains_to_stop.contains(&id) {
-            chains_to_stop.push(id);
+    for id in &chains_to_restart {
+        // Force restart of container chain: if it was running before it was in "syncing mode",
+        // which doesn't use the correct ports, so start it in "collation mode". Or the other way
+        // around, if it was collating and now it will only be syncing.
+        if running_chains_before.contains(id) && !chains_to_stop.contains(id) {
+            chains_to_stop.push(*id);
         }
-        if !chains_to_start.contains(&id) {
-            chains_to_start.push(id);
+        if !chains_to_start.contains(id) {
+            chains_to_start.push(*id);
         }
     }
 
     HandleUpdateAssignmentResult {
         chains_to_stop,
         chains_to_start,
-        need_to_restart: need_to_restart_current || need_to_restart_next,
+        need_to_restart: !chains_to_restart.is_empty(),
     }
 }
 
@@ -795,25 +797,24 @@ mod tests {
             dyn Fn() -> (CancellationToken, futures::channel::oneshot::Receiver<()>) + Send + Sync,
         >,
         collation_cancellation_constructs: Option<()>,
-        // Keep track of the last CollateOn message, for tests
-        currently_collating_on: Arc<Mutex<Option<ParaId>>>,
+        // Keep track of the chains that received a CollateOn message, for tests
+        currently_collating_on: Arc<Mutex<HashSet<ParaId>>>,
     }
 
     impl MockContainerChainSpawner {
         fn new() -> Self {
             let orchestrator_para_id = 1000.into();
             // The node always starts as an orchestrator chain collator
-            let currently_collating_on = Arc::new(Mutex::new(Some(orchestrator_para_id)));
+            let currently_collating_on =
+                Arc::new(Mutex::new(HashSet::from([orchestrator_para_id])));
             let currently_collating_on2 = currently_collating_on.clone();
             let collate_closure = move || {
                 let mut cco = currently_collating_on2.lock().unwrap();
-                assert_ne!(
-                    *cco,
-                    Some(orchestrator_para_id),
+                assert!(
+                    cco.insert(orchestrator_para_id),
                     "Received CollateOn message when we were already collating on this chain: {}",
                     orchestrator_para_id
                 );
-                *cco = Some(orchestrator_para_id);
                 let (_, receiver) = futures::channel::oneshot::channel();
                 (CancellationToken::new(), receiver)
             };
@@ -826,8 +827,8 @@ mod tests {
             Self {
                 state: Arc::new(Mutex::new(ContainerChainSpawnerState {
                     spawned_container_chains: Default::default(),
-                    assigned_para_id: Some(orchestrator_para_id),
-                    next_assigned_para_id: None,
+                    assigned_para_ids: HashSet::from([orchestrator_para_id]),
+                    next_assigned_para_ids: HashSet::new(),
                     failed_para_ids: Default::default(),
                     spawned_containers_monitor: Default::default(),
                 })),
@@ -844,13 +845,11 @@ mod tests {
             let currently_collating_on2 = self.currently_collating_on.clone();
             let collate_closure = move || {
                 let mut cco = currently_collating_on2.lock().unwrap();
-                assert_ne!(
-                    *cco,
-                    Some(container_chain_para_id),
+                assert!(
+                    cco.insert(container_chain_para_id),
                     "Received CollateOn message when we were already collating on this chain: {}",
                     container_chain_para_id
                 );
-                *cco = Some(container_chain_para_id);
                 let (_, receiver) = futures::channel::oneshot::channel();
                 (CancellationToken::new(), receiver)
             };
@@ -869,6 +868,7 @@ mod tests {
                     container_chain_para_id,
                     ContainerChainState {
                         stop_handle: StopContainerChain { signal, id: 0 },
+                        uses_cli_ports: start_collation,
                     },
                 );
 
@@ -904,13 +904,15 @@ mod tests {
             }
 
             // Update currently_collating_on, if we stopped the chain we are no longer collating there
-            let mut lco = self.currently_collating_on.lock().unwrap();
-            if *lco == Some(container_chain_para_id) {
-                *lco = None;
-            }
+            self.currently_collating_on
+                .lock()
+                .unwrap()
+                .remove(&container_chain_para_id);
         }
 
-        fn handle_update_assignment(&mut self, current: Option<ParaId>, next: Option<ParaId>) {
+        fn handle_update_assignment(&mut self, current: &[ParaId], next: &[ParaId]) {
+            let current = HashSet::from_iter(current.iter().copied());
+            let next = HashSet::from_iter(next.iter().copied());
             let HandleUpdateAssignmentResult {
                 chains_to_stop,
                 chains_to_start,
@@ -918,16 +920,16 @@ mod tests {
             } = handle_update_assignment_state_change(
                 &mut self.state.lock().unwrap(),
                 self.orchestrator_para_id,
-                current,
-                next,
+                current.clone(),
+                next.clone(),
             );
 
-            if current != Some(self.orchestrator_para_id) {
+            if !current.contains(&self.orchestrator_para_id) {
                 // If not assigned to orchestrator chain anymore, we need to stop the collator process
-                let mut cco = self.currently_collating_on.lock().unwrap();
-                if *cco == Some(self.orchestrator_para_id) {
-                    *cco = None;
-                }
+                self.currently_collating_on
+                    .lock()
+                    .unwrap()
+                    .remove(&self.orchestrator_para_id);
                 self.collation_cancellation_constructs = None;
             } else if self.collation_cancellation_constructs.is_none() {
                 let (_cancellation_token, _exit_notification_receiver) = (self.collate_on_tanssi)();
@@ -943,8 +945,8 @@ mod tests {
                         para_id
                     );
                 } else {
-                    // Will try to start and stop container chain with id "current" or "next", so ignore that
-                    if Some(*para_id) != current && Some(*para_id) != next {
+                    // Will try to start and stop container chains in "current" or "next", so ignore those
+                    if !current.contains(para_id) && !next.contains(para_id) {
                         assert!(
                             !chains_to_stop.contains(para_id),
                             "Tried to start and stop same container chain: {}",
@@ -962,28 +964,27 @@ mod tests {
                 self.stop(para_id);
             }
 
-            // Start all new container chains (usually 1)
+            // Start all new container chains
             for para_id in chains_to_start {
                 // Edge case: when starting the node it may be assigned to a container chain, so we need to
                 // start a container chain already collating.
-                let start_collation = Some(para_id) == current;
+                let start_collation = current.contains(&para_id);
                 self.spawn(para_id, start_collation);
             }
 
-            // Assert that if we are currently assigned to a container chain, we are collating there
-            if let Some(para_id) = current {
-                self.assert_collating_on(Some(para_id));
-            } else {
-                self.assert_collating_on(None);
-            }
+            // Assert that we are collating on all the chains we are currently assigned to
+            let current: Vec<ParaId> = current.into_iter().collect();
+            self.assert_collating_on(&current);
         }
 
         #[track_caller]
-        fn assert_collating_on(&self, para_id: Option<ParaId>) {
-            let currently_collating_on = *self.currently_collating_on.lock().unwrap();
-            assert_eq!(currently_collating_on, para_id);
+        fn assert_collating_on(&self, para_ids: &[ParaId]) {
+            let currently_collating_on = self.currently_collating_on.lock().unwrap().clone();
+            assert_eq!(
+                currently_collating_on,
+                HashSet::from_iter(para_ids.iter().copied())
+            );
         }
-
         #[track_caller]
         fn assert_running_chains(&self, para_ids: &[ParaId]) {
             let mut actually_running: Vec<ParaId> = self
@@ -1004,11 +1005,11 @@ mod tests {
     #[test]
     fn starts_collating_on_tanssi() {
         let mut m = MockContainerChainSpawner::new();
-        m.assert_collating_on(Some(1000.into()));
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
     }
 
@@ -1016,24 +1017,24 @@ mod tests {
     fn assigned_to_orchestrator_chain() {
         let mut m = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(1000.into()), Some(1000.into()));
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[1000.into()]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(Some(1000.into()), None);
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, Some(1000.into()));
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[1000.into()]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(Some(1000.into()), Some(1000.into()));
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[1000.into()]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
     }
 
@@ -1041,24 +1042,24 @@ mod tests {
     fn assigned_to_container_chain() {
         let mut m = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(Some(2000.into()), None);
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, Some(2000.into()));
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[2000.into()]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
     }
 
@@ -1066,28 +1067,28 @@ mod tests {
     fn spawn_container_chains() {
         let mut m = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(1000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[2000.into()]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(Some(2000.into()), Some(2001.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2001.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into(), 2001.into()]);
 
-        m.handle_update_assignment(Some(2001.into()), Some(2001.into()));
-        m.assert_collating_on(Some(2001.into()));
+        m.handle_update_assignment(&[2001.into()], &[2001.into()]);
+        m.assert_collating_on(&[2001.into()]);
         m.assert_running_chains(&[2001.into()]);
 
-        m.handle_update_assignment(Some(2001.into()), Some(1000.into()));
-        m.assert_collating_on(Some(2001.into()));
+        m.handle_update_assignment(&[2001.into()], &[1000.into()]);
+        m.assert_collating_on(&[2001.into()]);
         m.assert_running_chains(&[2001.into()]);
 
-        m.handle_update_assignment(Some(1000.into()), Some(1000.into()));
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[1000.into()]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
     }
 
@@ -1096,12 +1097,12 @@ mod tests {
         // Going from (2000, 2001) to (2001, 2000) shouldn't start or stop any container chains
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), Some(2001.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2001.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into(), 2001.into()]);
 
-        m.handle_update_assignment(Some(2001.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2001.into()));
+        m.handle_update_assignment(&[2001.into()], &[2000.into()]);
+        m.assert_collating_on(&[2001.into()]);
         m.assert_running_chains(&[2000.into(), 2001.into()]);
     }
 
@@ -1109,20 +1110,20 @@ mod tests {
     fn stop_collating_orchestrator() {
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(1000.into()), Some(1000.into()));
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[1000.into()]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(Some(1000.into()), None);
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(Some(1000.into()), None);
-        m.assert_collating_on(Some(1000.into()));
+        m.handle_update_assignment(&[1000.into()], &[]);
+        m.assert_collating_on(&[1000.into()]);
         m.assert_running_chains(&[]);
     }
 
@@ -1130,22 +1131,22 @@ mod tests {
     fn stop_collating_container() {
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), None);
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
-        m.handle_update_assignment(None, Some(2000.into()));
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[2000.into()]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[2000.into()]);
 
         // This will send a CollateOn message to the same chain as the last CollateOn,
         // but this is needed because that chain has been stopped
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
     }
 
@@ -1153,17 +1154,17 @@ mod tests {
     fn stop_collating_container_start_immediately() {
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), None);
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
 
         // This will start the chain already collating
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
     }
 
@@ -1171,12 +1172,12 @@ mod tests {
     fn stop_all_chains() {
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), Some(2001.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2001.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into(), 2001.into()]);
 
-        m.handle_update_assignment(None, None);
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[]);
     }
 
@@ -1184,19 +1185,41 @@ mod tests {
     fn keep_collating_on_container() {
         let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
 
-        m.handle_update_assignment(Some(2000.into()), None);
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(None, Some(2000.into()));
-        m.assert_collating_on(None);
+        m.handle_update_assignment(&[], &[2000.into()]);
+        m.assert_collating_on(&[]);
         m.assert_running_chains(&[2000.into()]);
 
-        m.handle_update_assignment(Some(2000.into()), Some(2000.into()));
-        m.assert_collating_on(Some(2000.into()));
+        m.handle_update_assignment(&[2000.into()], &[2000.into()]);
+        m.assert_collating_on(&[2000.into()]);
         m.assert_running_chains(&[2000.into()]);
     }
 
+    #[test]
+    fn collate_on_several_parathreads() {
+        let mut m: MockContainerChainSpawner = MockContainerChainSpawner::new();
+
+        m.handle_update_assignment(&[1000.into()], &[3000.into(), 3001.into()]);
+        m.assert_collating_on(&[1000.into()]);
+        m.assert_running_chains(&[3000.into(), 3001.into()]);
+
+        m.handle_update_assignment(&[3000.into(), 3001.into()], &[3000.into(), 3001.into()]);
+        m.assert_collating_on(&[3000.into(), 3001.into()]);
+        m.assert_running_chains(&[3000.into(), 3001.into()]);
+
+        // 3000 is assigned to other collators in the next session
+        m.handle_update_assignment(&[3000.into(), 3001.into()], &[3001.into()]);
+        m.assert_collating_on(&[3000.into(), 3001.into()]);
+        m.assert_running_chains(&[3000.into(), 3001.into()]);
+
+        m.handle_update_assignment(&[3001.into()], &[3001.into()]);
+        m.assert_collating_on(&[3001.into()]);
+        m.assert_running_chains(&[3001.into()]);
+    }
+
     #[test]
     fn invalid_boot_nodes_are_ignored() {
         let para_id = 100.into();
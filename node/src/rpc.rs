@@ -27,17 +27,22 @@ use {
     dancebox_runtime::{opaque::Block, AccountId, Index as Nonce},
     manual_xcm_rpc::{ManualXcm, ManualXcmApiServer},
     polkadot_primitives::Hash,
-    sc_client_api::AuxStore,
+    pooled_staking_rpc::{PooledStaking, PooledStakingApiServer as _, PooledStakingRuntimeApi},
+    sc_client_api::{AuxStore, BlockchainEvents},
     sc_consensus_manual_seal::{
         rpc::{ManualSeal, ManualSealApiServer},
         EngineCommand,
     },
+    sc_rpc::SubscriptionTaskExecutor,
     sc_transaction_pool_api::TransactionPool,
     sp_api::ProvideRuntimeApi,
     sp_block_builder::BlockBuilder,
     sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata},
     std::sync::Arc,
-    stream_payment_rpc::{StreamPayment, StreamPaymentApiServer as _, StreamPaymentRuntimeApi},
+    stream_payment_rpc::{
+        StreamPayment, StreamPaymentApiServer as _, StreamPaymentRuntimeApi,
+        StreamPaymentStreamsRuntimeApi,
+    },
 };
 
 /// A type representing all RPC extensions.
@@ -58,21 +63,31 @@ pub struct FullDeps<C, P> {
 }
 
 /// Instantiate all RPC extensions.
-pub fn create_full<C, P>(
+pub fn create_full<C, P, Stream>(
     deps: FullDeps<C, P>,
+    subscription_task_executor: SubscriptionTaskExecutor,
 ) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
 where
     C: ProvideRuntimeApi<Block>
         + HeaderBackend<Block>
         + AuxStore
         + HeaderMetadata<Block, Error = BlockChainError>
+        + BlockchainEvents<Block>
         + Send
         + Sync
         + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: BlockBuilder<Block>,
     C::Api: StreamPaymentRuntimeApi<Block, u64, u128, u128>,
+    C::Api: StreamPaymentStreamsRuntimeApi<Block, u64, AccountId, Stream>,
+    C::Api: PooledStakingRuntimeApi<
+        Block,
+        AccountId,
+        u32,
+        pallet_pooled_staking::DelegatorPositionOf<dancebox_runtime::Runtime>,
+    >,
     P: TransactionPool + Sync + Send + 'static,
+    Stream: parity_scale_codec::Codec + serde::Serialize + Send + 'static,
 {
     use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -86,7 +101,8 @@ where
     } = deps;
 
     module.merge(System::new(client.clone(), pool, deny_unsafe).into_rpc())?;
-    module.merge(StreamPayment::<_, Block>::new(client).into_rpc())?;
+    module.merge(PooledStaking::<_, Block>::new(client.clone(), deny_unsafe).into_rpc())?;
+    module.merge(StreamPayment::<_, Block>::new(client, subscription_task_executor).into_rpc())?;
 
     if let Some(command_sink) = command_sink {
         module.merge(
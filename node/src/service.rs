@@ -50,6 +50,7 @@ use {
     nimbus_primitives::NimbusPair,
     node_common::service::NodeBuilderConfig,
     node_common::service::{ManualSealConfiguration, NodeBuilder, Sealing},
+    pallet_collator_assignment_runtime_api::AuthorityParaIdsAssignmentApi,
     pallet_registrar_runtime_api::RegistrarApi,
     parity_scale_codec::Encode,
     polkadot_cli::ProvideRuntimeApi,
@@ -72,7 +73,7 @@ use {
     sp_core::{traits::SpawnEssentialNamed, H256},
     sp_keystore::KeystorePtr,
     sp_state_machine::{Backend as StateBackend, StorageValue},
-    std::{pin::Pin, sync::Arc, time::Duration},
+    std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration},
     substrate_prometheus_endpoint::Registry,
     tc_consensus::{
         collators::lookahead::{
@@ -85,6 +86,9 @@ use {
 
 type FullBackend = TFullBackend<Block>;
 
+/// Stream type of the runtime, returned by the stream payment RPC.
+type StreamPaymentStream = pallet_stream_payment::StreamOf<dancebox_runtime::Runtime>;
+
 /// Native executor type.
 pub struct ParachainNativeExecutor;
 
@@ -207,27 +211,40 @@ fn check_assigned_para_id(
     client_set_aside_for_cidp: Arc<ParachainClient>,
     block_hash: H256,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let runtime_api = client_set_aside_for_cidp.runtime_api();
+
     // Check current assignment
-    let current_container_chain_para_id =
+    let current_container_chain_para_ids =
         tc_consensus::first_eligible_key::<Block, ParachainClient, NimbusPair>(
             client_set_aside_for_cidp.as_ref(),
             &block_hash,
             sync_keystore.clone(),
         )
-        .map(|(_nimbus_key, para_id)| para_id);
+        .map(|(nimbus_key, para_id)| {
+            // Collators can be assigned to several parathreads
+            runtime_api
+                .check_para_ids_assignment(block_hash, nimbus_key)
+                .unwrap_or_else(|_| vec![para_id])
+        })
+        .unwrap_or_default();
 
     // Check assignment in the next session
-    let next_container_chain_para_id =
+    let next_container_chain_para_ids =
         tc_consensus::first_eligible_key_next_session::<Block, ParachainClient, NimbusPair>(
             client_set_aside_for_cidp.as_ref(),
             &block_hash,
             sync_keystore,
         )
-        .map(|(_nimbus_key, para_id)| para_id);
+        .map(|(nimbus_key, para_id)| {
+            runtime_api
+                .check_para_ids_assignment_next_session(block_hash, nimbus_key)
+                .unwrap_or_else(|_| vec![para_id])
+        })
+        .unwrap_or_default();
 
     cc_spawn_tx.send(CcSpawnMsg::UpdateAssignment {
-        current: current_container_chain_para_id,
-        next: next_container_chain_para_id,
+        current: HashSet::from_iter(current_container_chain_para_ids),
+        next: HashSet::from_iter(next_container_chain_para_ids),
     })?;
 
     Ok(())
@@ -350,7 +367,7 @@ async fn start_node_impl(
         let client = node_builder.client.clone();
         let transaction_pool = node_builder.transaction_pool.clone();
 
-        Box::new(move |deny_unsafe, _| {
+        Box::new(move |deny_unsafe, subscription_task_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: transaction_pool.clone(),
@@ -359,7 +376,8 @@ async fn start_node_impl(
                 xcm_senders: None,
             };
 
-            crate::rpc::create_full(deps).map_err(Into::into)
+            crate::rpc::create_full::<_, _, StreamPaymentStream>(deps, subscription_task_executor)
+                .map_err(Into::into)
         })
     };
 
@@ -481,8 +499,8 @@ async fn start_node_impl(
                 // Spawn new container chain node
                 cc_spawn_tx
                     .send(CcSpawnMsg::UpdateAssignment {
-                        current: Some(container_chain_para_id.into()),
-                        next: Some(container_chain_para_id.into()),
+                        current: HashSet::from([container_chain_para_id.into()]),
+                        next: HashSet::from([container_chain_para_id.into()]),
                     })
                     .map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?;
             }
@@ -578,7 +596,7 @@ pub async fn start_node_impl_container(
         let client = node_builder.client.clone();
         let transaction_pool = node_builder.transaction_pool.clone();
 
-        Box::new(move |deny_unsafe, _| {
+        Box::new(move |deny_unsafe, subscription_task_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: transaction_pool.clone(),
@@ -587,7 +605,8 @@ pub async fn start_node_impl_container(
                 xcm_senders: None,
             };
 
-            crate::rpc::create_full(deps).map_err(Into::into)
+            crate::rpc::create_full::<_, _, StreamPaymentStream>(deps, subscription_task_executor)
+                .map_err(Into::into)
         })
     };
 
@@ -1165,7 +1184,7 @@ pub fn start_dev_node(
         let client = node_builder.client.clone();
         let transaction_pool = node_builder.transaction_pool.clone();
 
-        Box::new(move |deny_unsafe, _| {
+        Box::new(move |deny_unsafe, subscription_task_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: transaction_pool.clone(),
@@ -1174,7 +1193,8 @@ pub fn start_dev_node(
                 xcm_senders: xcm_senders.clone(),
             };
 
-            crate::rpc::create_full(deps).map_err(Into::into)
+            crate::rpc::create_full::<_, _, StreamPaymentStream>(deps, subscription_task_executor)
+                .map_err(Into::into)
         })
     };
 
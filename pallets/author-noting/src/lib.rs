@@ -27,6 +27,9 @@
 //!  
 //! Using those two requirements we can select who the author was based on the collators assigned
 //! to that containerChain, by simply assigning the slot position.
+//!
+//! The last session in which each collator authored a container chain block is also stored,
+//! which can be used as a liveness signal to detect assigned collators that never author.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -46,7 +49,10 @@ use {
     sp_inherents::{InherentIdentifier, IsFatalError},
     sp_runtime::{traits::Header, DigestItem, DispatchResult, RuntimeString},
     tp_author_noting_inherent::INHERENT_IDENTIFIER,
-    tp_traits::{AuthorNotingHook, GetContainerChainAuthor, GetCurrentContainerChains},
+    tp_traits::{
+        AuthorNotingHook, GetContainerChainAuthor, GetCurrentContainerChains, GetParathreadParams,
+        GetSessionIndex,
+    },
 };
 
 #[cfg(test)]
@@ -80,6 +86,12 @@ pub mod pallet {
 
         type ContainerChainAuthor: GetContainerChainAuthor<Self::AccountId>;
 
+        /// Used to check that parathread blocks respect their `SlotFrequency`.
+        type GetParathreadParams: GetParathreadParams;
+
+        /// Used to store in which session collators last authored a block.
+        type CurrentSessionIndex: GetSessionIndex<u32>;
+
         type RelayChainStateProvider: cumulus_pallet_parachain_system::RelaychainStateProvider;
 
         /// An entry-point for higher-level logic to react to containers chains authoring.
@@ -179,22 +191,40 @@ pub mod pallet {
                                     if let Some(ref mut old_block_info) = maybe_old_block_info {
                                         if block_info.block_number > old_block_info.block_number {
                                             // We only reward author if the block increases
+                                            let respects_slot_frequency =
+                                                Self::respects_slot_frequency(
+                                                    para_id,
+                                                    old_block_info.latest_slot_number,
+                                                    block_info.latest_slot_number,
+                                                );
+                                            total_weight = total_weight
+                                                .saturating_add(T::DbWeight::get().reads(1));
+                                            total_weight = total_weight.saturating_add(
+                                                Self::note_authored_session(&block_info.author),
+                                            );
                                             total_weight = total_weight.saturating_add(
                                                 T::AuthorNotingHook::on_container_author_noted(
                                                     &block_info.author,
+                                                    Some(old_block_info.block_number),
                                                     block_info.block_number,
                                                     para_id,
+                                                    respects_slot_frequency,
                                                 ),
                                             );
                                             let _ = core::mem::replace(old_block_info, block_info);
                                         }
                                     } else {
                                         // If there is no previous block, we should reward the author of the first block
+                                        total_weight = total_weight.saturating_add(
+                                            Self::note_authored_session(&block_info.author),
+                                        );
                                         total_weight = total_weight.saturating_add(
                                             T::AuthorNotingHook::on_container_author_noted(
                                                 &block_info.author,
+                                                None,
                                                 block_info.block_number,
                                                 para_id,
+                                                true,
                                             ),
                                         );
                                         let _ = core::mem::replace(
@@ -290,6 +320,11 @@ pub mod pallet {
         pub latest_slot_number: Slot,
     }
 
+    /// Last session in which a collator authored a container chain block that was noted.
+    #[pallet::storage]
+    pub type LastAuthoredSession<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, OptionQuery>;
+
     /// Was the containerAuthorData set?
     #[pallet::storage]
     pub(super) type DidSetContainerAuthorData<T: Config> = StorageValue<_, bool, ValueQuery>;
@@ -326,6 +361,30 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+    /// Returns true if `author` authored a container chain block that was noted during `session`
+    /// or later.
+    pub fn authored_since_session(author: &T::AccountId, session: u32) -> bool {
+        LastAuthoredSession::<T>::get(author).is_some_and(|last| last >= session)
+    }
+
+    fn note_authored_session(author: &T::AccountId) -> Weight {
+        LastAuthoredSession::<T>::insert(author, T::CurrentSessionIndex::session_index());
+
+        T::DbWeight::get().reads_writes(1, 1)
+    }
+
+    /// Returns true if a block of `para_id` noted at tanssi slot `slot` respects the
+    /// `SlotFrequency` of the parathread, given the slot at which its previous block was noted.
+    /// Blocks of parachains always respect it.
+    pub fn respects_slot_frequency(para_id: ParaId, previous_slot: Slot, slot: Slot) -> bool {
+        match T::GetParathreadParams::get_parathread_params(para_id) {
+            Some(params) => params
+                .slot_frequency
+                .is_within_bounds(u64::from(slot).saturating_sub(u64::from(previous_slot))),
+            None => true,
+        }
+    }
+
     /// Fetch author and block number from a proof of header
     fn fetch_block_info_from_proof(
         relay_state_proof: &GenericStateProof<cumulus_primitives_core::relay_chain::Block>,
@@ -36,7 +36,9 @@ use {
         BoundedVec, BuildStorage,
     },
     sp_state_machine::StorageProof,
+    std::collections::BTreeMap,
     test_relay_sproof_builder::ParaHeaderSproofBuilder,
+    tp_traits::ParathreadParams,
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -120,12 +122,16 @@ impl mock_data::Config for Test {}
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mocks {
     pub container_chains: BoundedVec<ParaId, ConstU32<100>>,
+    pub parathreads: BTreeMap<ParaId, ParathreadParams>,
+    pub session_index: u32,
 }
 
 impl Default for Mocks {
     fn default() -> Self {
         Self {
             container_chains: bounded_vec![1001.into()],
+            parathreads: BTreeMap::new(),
+            session_index: 0,
         }
     }
 }
@@ -141,6 +147,33 @@ impl tp_traits::GetContainerChainAuthor<AccountId> for MockAuthorFetcher {
     fn set_authors_for_para_id(_para_id: ParaId, _authors: Vec<AccountId>) {}
 }
 
+pub struct MockParathreadParams;
+
+impl tp_traits::GetParathreadParams for MockParathreadParams {
+    fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParams> {
+        MockData::mock().parathreads.get(&para_id).cloned()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_parathread_params(para_id: ParaId, parathread_params: Option<ParathreadParams>) {
+        MockData::mutate(|m| {
+            if let Some(parathread_params) = parathread_params {
+                m.parathreads.insert(para_id, parathread_params);
+            } else {
+                m.parathreads.remove(&para_id);
+            }
+        });
+    }
+}
+
+pub struct MockSessionIndex;
+
+impl tp_traits::GetSessionIndex<u32> for MockSessionIndex {
+    fn session_index() -> u32 {
+        MockData::mock().session_index
+    }
+}
+
 pub struct DummyBeacon {}
 impl nimbus_primitives::SlotBeacon for DummyBeacon {
     fn slot() -> u32 {
@@ -192,6 +225,8 @@ impl Config for Test {
     type WeightInfo = ();
     type RuntimeEvent = RuntimeEvent;
     type ContainerChainAuthor = MockAuthorFetcher;
+    type GetParathreadParams = MockParathreadParams;
+    type CurrentSessionIndex = MockSessionIndex;
     type SelfParaId = ParachainId;
     type SlotBeacon = DummyBeacon;
     type ContainerChains = MockContainerChainGetter;
@@ -15,7 +15,7 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{mock::*, ContainerChainBlockInfo, Event},
+    crate::{mock::*, ContainerChainBlockInfo, Event, LastAuthoredSession},
     bounded_collections::bounded_vec,
     cumulus_primitives_core::ParaId,
     frame_support::{
@@ -34,7 +34,7 @@ use {
         traits::{BlakeTwo256, HashingFor},
     },
     test_relay_sproof_builder::{HeaderAs, ParaHeaderSproofBuilder, ParaHeaderSproofBuilderItem},
-    tp_traits::GetCurrentContainerChains,
+    tp_traits::{GetCurrentContainerChains, ParathreadParams, SlotFrequency},
 };
 
 #[test]
@@ -74,6 +74,44 @@ fn test_author_id_insertion() {
         });
 }
 
+#[test]
+fn test_last_authored_session_is_stored() {
+    BlockTests::new()
+        .with_relay_sproof_builder(|_, relay_block_num, sproof| {
+            let slot: InherentType = match relay_block_num {
+                1 => 13u64.into(),
+                2 => 14u64.into(),
+                _ => unreachable!(),
+            };
+            MockData::mutate(|m| m.session_index = relay_block_num - 1);
+
+            let s = ParaHeaderSproofBuilderItem {
+                para_id: 1001.into(),
+                author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                    parent_hash: Default::default(),
+                    number: relay_block_num,
+                    state_root: Default::default(),
+                    extrinsics_root: Default::default(),
+                    digest: sp_runtime::generic::Digest {
+                        logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                    },
+                }),
+            };
+            sproof.items.push(s);
+        })
+        .add(1, || {
+            assert_eq!(LastAuthoredSession::<Test>::get(13u64), Some(0));
+            assert!(AuthorNoting::authored_since_session(&13u64, 0));
+            assert!(!AuthorNoting::authored_since_session(&14u64, 0));
+        })
+        .add(2, || {
+            assert_eq!(LastAuthoredSession::<Test>::get(13u64), Some(0));
+            assert_eq!(LastAuthoredSession::<Test>::get(14u64), Some(1));
+            assert!(!AuthorNoting::authored_since_session(&13u64, 1));
+            assert!(AuthorNoting::authored_since_session(&14u64, 1));
+        });
+}
+
 #[test]
 fn test_author_id_insertion_real_data() {
     BlockTests::new()
@@ -789,3 +827,47 @@ fn test_author_id_insertion_not_first_log() {
             );
         });
 }
+
+#[test]
+fn test_respects_slot_frequency() {
+    new_test_ext().execute_with(|| {
+        MockData::mutate(|m| {
+            m.parathreads.insert(
+                1002.into(),
+                ParathreadParams {
+                    slot_frequency: SlotFrequency { min: 2, max: 5 },
+                    core_purchase_backend: Default::default(),
+                },
+            );
+        });
+
+        // Parachains always respect the slot frequency
+        assert!(AuthorNoting::respects_slot_frequency(
+            1001.into(),
+            10u64.into(),
+            11u64.into()
+        ));
+
+        // Parathreads must produce blocks between min and max slots
+        assert!(!AuthorNoting::respects_slot_frequency(
+            1002.into(),
+            10u64.into(),
+            11u64.into()
+        ));
+        assert!(AuthorNoting::respects_slot_frequency(
+            1002.into(),
+            10u64.into(),
+            12u64.into()
+        ));
+        assert!(AuthorNoting::respects_slot_frequency(
+            1002.into(),
+            10u64.into(),
+            15u64.into()
+        ));
+        assert!(!AuthorNoting::respects_slot_frequency(
+            1002.into(),
+            10u64.into(),
+            16u64.into()
+        ));
+    });
+}
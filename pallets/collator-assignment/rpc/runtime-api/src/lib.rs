@@ -20,9 +20,11 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use pallet_collator_assignment::{AssignmentPreview, DroppedParaReason};
 use scale_info::prelude::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait CollatorAssignmentApi<AccountId, ParaId> where
         AccountId: parity_scale_codec::Codec,
         ParaId: parity_scale_codec::Codec,
@@ -37,5 +39,28 @@ sp_api::decl_runtime_apis! {
         /// Return the list of collators of the given `ParaId`.
         /// Returns `None` if the `ParaId` is not in the registrar.
         fn parachain_collators(para_id: ParaId) -> Option<Vec<AccountId>>;
+        /// Return the collator assignment that will be computed on the next session change,
+        /// using the current collator candidates, registered paras and credits, along with the
+        /// registered paras that will not be assigned collators and why. Collators are only
+        /// shuffled as in the actual assignment in the last block of a session, when
+        /// `randomness_known` is true.
+        /// Does not modify storage.
+        #[api_version(2)]
+        fn preview_next_assignment() -> AssignmentPreview<AccountId>;
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait AuthorityParaIdsAssignmentApi<AuthorityId, ParaId> where
+        AuthorityId: parity_scale_codec::Codec,
+        ParaId: parity_scale_codec::Codec,
+    {
+        /// Return all the para ids assigned to the given authority. Authorities can be assigned to
+        /// several parathreads at the same time.
+        /// Returns an empty list if the authority is not assigned to any chain.
+        fn check_para_ids_assignment(authority: AuthorityId) -> Vec<ParaId>;
+        /// Return all the para ids assigned to the given authority on the next session.
+        /// On session boundary this returns the same as `check_para_ids_assignment`.
+        fn check_para_ids_assignment_next_session(authority: AuthorityId) -> Vec<ParaId>;
     }
 }
@@ -16,6 +16,7 @@
 
 use {
     dp_collator_assignment::AssignedCollators,
+    rand::{Rng, RngCore},
     sp_std::{
         cmp,
         collections::{btree_map::BTreeMap, btree_set::BTreeSet},
@@ -43,6 +44,7 @@ where
         collators: Vec<T::AccountId>,
         orchestrator_chain: ChainNumCollators,
         chains: Vec<ChainNumCollators>,
+        parathreads_per_collator: u32,
         shuffle: Option<TShuffle>,
     ) -> Result<AssignedCollators<T::AccountId>, AssignmentError>
     where
@@ -56,6 +58,7 @@ where
             orchestrator_chain,
             chains,
             old_assigned,
+            parathreads_per_collator,
             shuffle,
         )
     }
@@ -74,11 +77,15 @@ where
     /// algorithm to truncate the list of collators and only shuffle the first N. This ensures that
     /// shuffling doesn't cause a collator with low priority to be assigned instead of a collator
     /// with higher priority.
+    ///
+    /// Parathreads are packed into groups of up to `parathreads_per_collator`, and all the
+    /// parathreads of a group are assigned the same collators.
     pub fn assign_collators_always_keep_old<TShuffle>(
         collators: Vec<T::AccountId>,
         orchestrator_chain: ChainNumCollators,
         mut chains: Vec<ChainNumCollators>,
         mut old_assigned: AssignedCollators<T::AccountId>,
+        parathreads_per_collator: u32,
         shuffle: Option<TShuffle>,
     ) -> Result<AssignedCollators<T::AccountId>, AssignmentError>
     where
@@ -97,6 +104,9 @@ where
         // Orchestrator chain must be the first one in the list because it always has priority
         chains.insert(0, orchestrator_chain);
         let all_para_ids: Vec<ParaId> = chains.iter().map(|cc| cc.para_id).collect();
+        // From this point on, each group of parathreads is treated as a single chain
+        let parathread_groups =
+            Self::pack_parathreads(&mut chains, &mut old_assigned, parathreads_per_collator);
         let collators_set = BTreeSet::from_iter(collators.iter().cloned());
         let chains_with_collators =
             Self::select_chains_with_collators(collators.len() as u32, &chains);
@@ -113,8 +123,9 @@ where
         // Ensure the first `min_orchestrator_collators` of orchestrator chain are invulnerables
         Self::prioritize_invulnerables(&collators, orchestrator_chain, &mut old_assigned);
 
-        let new_assigned_chains =
+        let mut new_assigned_chains =
             Self::assign_full(collators, chains_with_collators, old_assigned, shuffle)?;
+        Self::unpack_parathreads(&mut new_assigned_chains, &parathread_groups);
 
         let mut new_assigned = AssignedCollators {
             container_chains: new_assigned_chains,
@@ -438,6 +449,177 @@ where
         Ok(old_assigned)
     }
 
+    /// Shuffle `collators` randomly, weighting each collator by `weight`. The probability of a
+    /// collator being placed before the remaining ones is proportional to its weight plus one, so
+    /// collators with a weight of zero can still be selected, just less likely.
+    ///
+    /// Complexity is quadratic in the number of collators, which is fine for the expected
+    /// number of collators.
+    pub fn weighted_shuffle<R: RngCore>(
+        collators: &mut Vec<T::AccountId>,
+        weight: impl Fn(&T::AccountId) -> u64,
+        rng: &mut R,
+    ) {
+        // Weights are `u64` and the sum is `u128`, so this cannot overflow
+        let mut remaining: Vec<(T::AccountId, u128)> = collators
+            .drain(..)
+            .map(|collator| {
+                let w = u128::from(weight(&collator)) + 1;
+                (collator, w)
+            })
+            .collect();
+        let mut total_weight: u128 = remaining.iter().map(|(_collator, w)| w).sum();
+
+        while !remaining.is_empty() {
+            let mut pick = rng.gen_range(0..total_weight);
+            let mut index = remaining.len() - 1;
+            for (i, (_collator, w)) in remaining.iter().enumerate() {
+                if pick < *w {
+                    index = i;
+                    break;
+                }
+                pick -= w;
+            }
+            let (collator, w) = remaining.remove(index);
+            total_weight -= w;
+            collators.push(collator);
+        }
+    }
+
+    /// Pack parathreads into groups of up to `parathreads_per_collator` parathreads that will share
+    /// the same collators. Each group is replaced in `chains` by a single chain with the para id of
+    /// its first parathread, and `old_assigned` is updated so that the old collators of the group
+    /// are assigned to that para id.
+    ///
+    /// Parathreads that were assigned the same collators stay in the same group, if possible, so
+    /// that collators do not need to change chains. The number of groups is always the minimum
+    /// needed to fit all the parathreads. The priority of a group is the priority of its first
+    /// parathread.
+    ///
+    /// # Params
+    ///
+    /// * `old_assigned` must not have duplicate collators, except for parathreads that share
+    /// collators.
+    ///
+    /// # Returns
+    ///
+    /// A map from the para id of each group to the para ids of the other parathreads of that group,
+    /// to be used in `unpack_parathreads`.
+    pub fn pack_parathreads(
+        chains: &mut Vec<ChainNumCollators>,
+        old_assigned: &mut BTreeMap<ParaId, Vec<T::AccountId>>,
+        parathreads_per_collator: u32,
+    ) -> BTreeMap<ParaId, Vec<ParaId>> {
+        if parathreads_per_collator <= 1 {
+            return BTreeMap::new();
+        }
+
+        let parathreads_per_collator = parathreads_per_collator as usize;
+        let parathreads: Vec<(usize, ChainNumCollators)> = chains
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, cc)| cc.parathread)
+            .collect();
+        let num_groups = parathreads.len().div_ceil(parathreads_per_collator);
+
+        // Each group is a list of `(priority, chain)` and the old collators of its first parathread
+        let mut groups: Vec<(Vec<(usize, ChainNumCollators)>, Vec<T::AccountId>)> = vec![];
+        let mut without_old_group = vec![];
+        // Collators can only be in one group
+        let mut grouped_collators = BTreeSet::new();
+        for (priority, cc) in parathreads {
+            let mut old_collators = old_assigned.remove(&cc.para_id).unwrap_or_default();
+
+            let old_group = groups.iter_mut().find(|(members, group_old_collators)| {
+                members.len() < parathreads_per_collator
+                    && !old_collators.is_empty()
+                    && *group_old_collators == old_collators
+            });
+            if let Some((members, _)) = old_group {
+                members.push((priority, cc));
+                continue;
+            }
+
+            old_collators.retain(|c| !grouped_collators.contains(c));
+            if old_collators.is_empty() || groups.len() >= num_groups {
+                // Will join any group with free space, ignoring the old collators
+                without_old_group.push((priority, cc));
+            } else {
+                grouped_collators.extend(old_collators.iter().cloned());
+                groups.push((vec![(priority, cc)], old_collators));
+            }
+        }
+
+        // Fill the existing groups first, then create new groups as needed
+        let mut without_old_group = without_old_group.into_iter();
+        for (members, _) in groups.iter_mut() {
+            while members.len() < parathreads_per_collator {
+                match without_old_group.next() {
+                    Some(member) => members.push(member),
+                    None => break,
+                }
+            }
+        }
+        let without_old_group: Vec<_> = without_old_group.collect();
+        for members in without_old_group.chunks(parathreads_per_collator) {
+            groups.push((members.to_vec(), vec![]));
+        }
+
+        // Replace each group with a single chain, at the position of its first parathread
+        let mut packed: Vec<(usize, ChainNumCollators)> = chains
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, cc)| !cc.parathread)
+            .collect();
+        let mut parathread_groups = BTreeMap::new();
+        for (mut members, old_collators) in groups {
+            members.sort_by_key(|(priority, _)| *priority);
+            let (priority, first) = members[0];
+            let group_chain = ChainNumCollators {
+                para_id: first.para_id,
+                min_collators: members
+                    .iter()
+                    .map(|(_, cc)| cc.min_collators)
+                    .max()
+                    .unwrap_or_default(),
+                max_collators: members
+                    .iter()
+                    .map(|(_, cc)| cc.max_collators)
+                    .max()
+                    .unwrap_or_default(),
+                parathread: true,
+            };
+            packed.push((priority, group_chain));
+            if !old_collators.is_empty() {
+                old_assigned.insert(first.para_id, old_collators);
+            }
+            parathread_groups.insert(
+                first.para_id,
+                members.iter().skip(1).map(|(_, cc)| cc.para_id).collect(),
+            );
+        }
+        packed.sort_by_key(|(priority, _)| *priority);
+        *chains = packed.into_iter().map(|(_, cc)| cc).collect();
+
+        parathread_groups
+    }
+
+    /// Assign the collators of each group of parathreads created by `pack_parathreads` to all the
+    /// parathreads of that group.
+    pub fn unpack_parathreads(
+        assigned: &mut BTreeMap<ParaId, Vec<T::AccountId>>,
+        parathread_groups: &BTreeMap<ParaId, Vec<ParaId>>,
+    ) {
+        for (group_para_id, other_para_ids) in parathread_groups {
+            let collators = assigned.get(group_para_id).cloned().unwrap_or_default();
+            for para_id in other_para_ids {
+                assigned.insert(*para_id, collators.clone());
+            }
+        }
+    }
+
     /// Insert invulnerables ensuring that they are always the first in the list.
     /// The order of both lists is preserved.
     /// `assigned` may already contain the invulnerables, in that case they are only moved to the front.
@@ -491,4 +673,6 @@ pub struct ChainNumCollators {
     pub min_collators: u32,
     // This will only be filled if all the other min have been reached
     pub max_collators: u32,
+    /// Parathreads can share collators with other parathreads
+    pub parathread: bool,
 }
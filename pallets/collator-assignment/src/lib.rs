@@ -23,7 +23,13 @@
 //! The set of container chains is retrieved thanks to the GetContainerChains trait
 //! The number of collators to assign to the orchestrator chain and the number
 //! of collators to assign to each container chain is retrieved through the GetHostConfiguration
-//! trait.
+//! trait. Container chains can request a different number of collators, retrieved through the
+//! GetCollatorRequirements trait.
+//!
+//! Collators are shuffled uniformly by default. If `stake_weighted_collator_selection` is enabled
+//! in the host configuration, collators with more stake, retrieved through the GetCollatorStake
+//! trait, are more likely to be assigned to a container chain and have priority when there are
+//! not enough collators for all the chains.
 //!  
 //! The pallet uses the following approach:
 //!
@@ -43,19 +49,23 @@
 
 use {
     crate::assignment::{Assignment, ChainNumCollators},
-    frame_support::{pallet_prelude::*, traits::Currency},
+    frame_support::{
+        pallet_prelude::*,
+        storage::{with_transaction, TransactionOutcome},
+        traits::Currency,
+    },
     frame_system::pallet_prelude::BlockNumberFor,
     rand::{seq::SliceRandom, SeedableRng},
     rand_chacha::ChaCha20Rng,
     sp_runtime::{
         traits::{AtLeast32BitUnsigned, One, Zero},
-        Saturating,
+        SaturatedConversion, Saturating,
     },
     sp_std::{collections::btree_set::BTreeSet, fmt::Debug, prelude::*, vec},
     tp_traits::{
-        CollatorAssignmentHook, CollatorAssignmentTip, GetContainerChainAuthor,
-        GetHostConfiguration, GetSessionContainerChains, ParaId, RemoveInvulnerables,
-        RemoveParaIdsWithNoCredits, ShouldRotateAllCollators, Slot,
+        CollatorAssignmentHook, CollatorAssignmentTip, GetCollatorRequirements, GetCollatorStake,
+        GetContainerChainAuthor, GetHostConfiguration, GetSessionContainerChains, ParaId,
+        RemoveInvulnerables, RemoveParaIdsWithNoCredits, ShouldRotateAllCollators, Slot,
     },
 };
 pub use {dp_collator_assignment::AssignedCollators, pallet::*};
@@ -103,6 +113,11 @@ pub mod pallet {
         type CollatorAssignmentHook: CollatorAssignmentHook<BalanceOf<Self>>;
         type Currency: Currency<Self::AccountId>;
         type CollatorAssignmentTip: CollatorAssignmentTip<BalanceOf<Self>>;
+        /// Number of collators requested by each container chain, if different from the
+        /// default of the host configuration
+        type RequestedCollators: GetCollatorRequirements;
+        /// Total stake of each collator, used when stake-weighted collator selection is enabled
+        type CollatorStake: GetCollatorStake<Self::AccountId, BalanceOf<Self>>;
         /// The weight information of this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -155,14 +170,34 @@ pub mod pallet {
         pub num_total_registered_paras: u32,
     }
 
+    /// A collator assignment computed by `compute_assignment`, before running the
+    /// `CollatorAssignmentHook` and storing it.
+    struct ComputedAssignment<T: Config> {
+        target_session_index: T::SessionIndex,
+        full_rotation: bool,
+        /// Assignment that was going to be active at the target session before this computation.
+        old_assigned: AssignedCollators<T::AccountId>,
+        /// New assignment for the target session.
+        new_assigned: AssignedCollators<T::AccountId>,
+        /// Para ids registered at the target session, before removing the ones without credits.
+        registered_para_ids: Vec<ParaId>,
+        /// Container chains with enough credits, in priority order.
+        container_chain_ids: Vec<ParaId>,
+        /// Parathreads with enough credits, in priority order.
+        parathreads: Vec<ParaId>,
+        enough_collators_for_all_chain: bool,
+        num_total_registered_paras: u32,
+    }
+
     impl<T: Config> Pallet<T> {
-        /// Assign new collators
-        /// collators should be queued collators
-        pub fn assign_collators(
+        /// Compute the collator assignment for the session after `current_session_index`, without
+        /// applying it. This does not write to storage, so it can also be used to preview the next
+        /// assignment.
+        fn compute_assignment(
             current_session_index: &T::SessionIndex,
             random_seed: [u8; 32],
             mut collators: Vec<T::AccountId>,
-        ) -> SessionChangeOutcome<T> {
+        ) -> ComputedAssignment<T> {
             // We work with one session delay to calculate assignments
             let session_delay = T::SessionIndex::one();
             let target_session_index = current_session_index.saturating_add(session_delay);
@@ -177,6 +212,11 @@ pub mod pallet {
                 .into_iter()
                 .map(|(para_id, _)| para_id)
                 .collect();
+            let registered_para_ids: Vec<ParaId> = container_chain_ids
+                .iter()
+                .chain(parathreads.iter())
+                .cloned()
+                .collect();
 
             // We read current assigned collators
             let old_assigned = Self::read_assigned_collators();
@@ -195,17 +235,41 @@ pub mod pallet {
                 &old_assigned_para_ids,
             );
 
+            let stake_weighted =
+                T::HostConfiguration::stake_weighted_collator_selection(target_session_index);
             let mut shuffle_collators = None;
             // If the random_seed is all zeros, we don't shuffle the list of collators nor the list
             // of container chains.
             // This should only happen in tests, and in the genesis block.
             if random_seed != [0; 32] {
                 let mut rng: ChaCha20Rng = SeedableRng::from_seed(random_seed);
-                collators.shuffle(&mut rng);
+                if stake_weighted {
+                    // Collators with more stake are more likely to be placed first, so they have
+                    // priority when there are not enough collators for all the chains.
+                    Assignment::<T>::weighted_shuffle(
+                        &mut collators,
+                        Self::collator_weight,
+                        &mut rng,
+                    );
+                } else {
+                    collators.shuffle(&mut rng);
+                }
                 container_chain_ids.shuffle(&mut rng);
                 parathreads.shuffle(&mut rng);
                 shuffle_collators = Some(move |collators: &mut Vec<T::AccountId>| {
-                    collators.shuffle(&mut rng);
+                    if stake_weighted {
+                        // New collators are assigned to the orchestrator chain first, so reverse
+                        // the order to make collators with more stake more likely to be assigned
+                        // to a container chain.
+                        Assignment::<T>::weighted_shuffle(
+                            collators,
+                            Self::collator_weight,
+                            &mut rng,
+                        );
+                        collators.reverse();
+                    } else {
+                        collators.shuffle(&mut rng);
+                    }
                 })
             }
 
@@ -217,41 +281,43 @@ pub mod pallet {
                 max_collators: T::HostConfiguration::max_collators_for_orchestrator(
                     target_session_index,
                 ),
+                parathread: false,
             };
             // Initialize list of chains as `[container1, container2, parathread1, parathread2]`.
             // The order means priority: the first chain in the list will be the first one to get assigned collators.
             // Chains will not be assigned less than `min_collators`, except the orchestrator chain.
             // First all chains will be assigned `min_collators`, and then the first one will be assigned up to `max`,
             // then the second one, and so on.
+            // Chains that requested a different number of collators use their own `min` and `max`.
             let mut chains = vec![];
             let collators_per_container =
                 T::HostConfiguration::collators_per_container(target_session_index);
             for para_id in &container_chain_ids {
-                chains.push(ChainNumCollators {
-                    para_id: *para_id,
-                    min_collators: collators_per_container,
-                    max_collators: collators_per_container,
-                });
+                chains.push(Self::chain_num_collators(
+                    *para_id,
+                    collators_per_container,
+                    false,
+                ));
             }
             let collators_per_parathread =
                 T::HostConfiguration::collators_per_parathread(target_session_index);
             for para_id in &parathreads {
-                chains.push(ChainNumCollators {
-                    para_id: *para_id,
-                    min_collators: collators_per_parathread,
-                    max_collators: collators_per_parathread,
-                });
+                chains.push(Self::chain_num_collators(
+                    *para_id,
+                    collators_per_parathread,
+                    true,
+                ));
             }
+            let parathreads_per_collator =
+                T::HostConfiguration::parathreads_per_collator(target_session_index);
 
             // Are there enough collators to satisfy the minimum demand?
             let enough_collators_for_all_chain = collators.len() as u32
                 >= T::HostConfiguration::min_collators_for_orchestrator(target_session_index)
-                    .saturating_add(
-                        collators_per_container.saturating_mul(container_chain_ids.len() as u32),
-                    )
-                    .saturating_add(
-                        collators_per_parathread.saturating_mul(parathreads.len() as u32),
-                    );
+                    .saturating_add(Self::min_collators_for_chains(
+                        &chains,
+                        parathreads_per_collator,
+                    ));
 
             // Prioritize paras by tip on congestion
             // As of now this doesn't distinguish between parachains and parathreads
@@ -265,49 +331,40 @@ pub mod pallet {
 
             // We assign new collators
             // we use the config scheduled at the target_session_index
-            let new_assigned =
-                if T::ShouldRotateAllCollators::should_rotate_all_collators(target_session_index) {
-                    log::debug!(
-                        "Collator assignment: rotating collators. Session {:?}, Seed: {:?}",
-                        current_session_index.encode(),
-                        random_seed
-                    );
-
-                    Self::deposit_event(Event::NewPendingAssignment {
-                        random_seed,
-                        full_rotation: true,
-                        target_session: target_session_index,
-                    });
-
-                    Assignment::<T>::assign_collators_rotate_all(
-                        collators,
-                        orchestrator_chain,
-                        chains,
-                        shuffle_collators,
-                    )
-                } else {
-                    log::debug!(
-                        "Collator assignment: keep old assigned. Session {:?}, Seed: {:?}",
-                        current_session_index.encode(),
-                        random_seed
-                    );
-
-                    Self::deposit_event(Event::NewPendingAssignment {
-                        random_seed,
-                        full_rotation: false,
-                        target_session: target_session_index,
-                    });
-
-                    Assignment::<T>::assign_collators_always_keep_old(
-                        collators,
-                        orchestrator_chain,
-                        chains,
-                        old_assigned.clone(),
-                        shuffle_collators,
-                    )
-                };
+            let full_rotation =
+                T::ShouldRotateAllCollators::should_rotate_all_collators(target_session_index);
+            let new_assigned = if full_rotation {
+                log::debug!(
+                    "Collator assignment: rotating collators. Session {:?}, Seed: {:?}",
+                    current_session_index.encode(),
+                    random_seed
+                );
+
+                Assignment::<T>::assign_collators_rotate_all(
+                    collators,
+                    orchestrator_chain,
+                    chains,
+                    parathreads_per_collator,
+                    shuffle_collators,
+                )
+            } else {
+                log::debug!(
+                    "Collator assignment: keep old assigned. Session {:?}, Seed: {:?}",
+                    current_session_index.encode(),
+                    random_seed
+                );
+
+                Assignment::<T>::assign_collators_always_keep_old(
+                    collators,
+                    orchestrator_chain,
+                    chains,
+                    old_assigned.clone(),
+                    parathreads_per_collator,
+                    shuffle_collators,
+                )
+            };
 
-            let mut new_assigned = match new_assigned {
+            let new_assigned = match new_assigned {
                 Ok(x) => x,
                 Err(e) => {
                     log::error!(
@@ -319,6 +376,72 @@ pub mod pallet {
                 }
             };
 
+            ComputedAssignment {
+                target_session_index,
+                full_rotation,
+                old_assigned,
+                new_assigned,
+                registered_para_ids,
+                container_chain_ids,
+                parathreads,
+                enough_collators_for_all_chain,
+                num_total_registered_paras,
+            }
+        }
+
+        /// Update the state of the paras registered at the session after `current_session_index`
+        /// that cannot pay for it, such as their grace period.
+        fn update_grace_periods(current_session_index: &T::SessionIndex) {
+            let target_session_index = current_session_index.saturating_add(One::one());
+            let container_chains =
+                T::ContainerChains::session_container_chains(target_session_index);
+            let para_ids: Vec<ParaId> = container_chains
+                .parachains
+                .into_iter()
+                .chain(
+                    container_chains
+                        .parathreads
+                        .into_iter()
+                        .map(|(para_id, _)| para_id),
+                )
+                .collect();
+            let old_assigned_para_ids: BTreeSet<ParaId> = Self::read_assigned_collators()
+                .container_chains
+                .into_keys()
+                .collect();
+            T::RemoveParaIdsWithNoCredits::on_session_change(&para_ids, &old_assigned_para_ids);
+        }
+
+        /// Assign new collators
+        /// collators should be queued collators
+        pub fn assign_collators(
+            current_session_index: &T::SessionIndex,
+            random_seed: [u8; 32],
+            collators: Vec<T::AccountId>,
+        ) -> SessionChangeOutcome<T> {
+            // Update the grace period of paras that cannot pay for the target session. This is
+            // done once per session, before computing the assignment, which must not write to
+            // storage.
+            Self::update_grace_periods(current_session_index);
+
+            let ComputedAssignment {
+                target_session_index,
+                full_rotation,
+                old_assigned,
+                mut new_assigned,
+                container_chain_ids,
+                parathreads,
+                enough_collators_for_all_chain,
+                num_total_registered_paras,
+                ..
+            } = Self::compute_assignment(current_session_index, random_seed, collators);
+
+            Self::deposit_event(Event::NewPendingAssignment {
+                random_seed,
+                full_rotation,
+                target_session: target_session_index,
+            });
+
             let mut assigned_containers = new_assigned.container_chains.clone();
             assigned_containers.retain(|_, v| !v.is_empty());
 
@@ -415,6 +538,149 @@ pub mod pallet {
             }
         }
 
+        /// Preview the collator assignment that will be computed when session `session_index`
+        /// starts, using `collators` as the queued collators. That assignment becomes active one
+        /// session later. Registered paras that will not be assigned collators are returned along
+        /// with the reason.
+        ///
+        /// This does not write to storage: grace periods are updated as in `assign_collators`
+        /// inside a storage transaction that is rolled back. The randomness used to shuffle
+        /// collators is only known in the last block of a session, so in any other block the
+        /// preview does not shuffle collators, the actual assignment may differ and
+        /// `randomness_known` is false. The `CollatorAssignmentHook` is not run either, paras
+        /// without enough credits to pay for the assignment are already detected by
+        /// `RemoveParaIdsWithNoCredits`.
+        pub fn preview_assignment(
+            session_index: &T::SessionIndex,
+            collators: Vec<T::AccountId>,
+        ) -> AssignmentPreview<T::AccountId> {
+            let randomness_known = Randomness::<T>::exists();
+            let random_seed = Randomness::<T>::get();
+            let computed = with_transaction(|| {
+                Self::update_grace_periods(session_index);
+                TransactionOutcome::Rollback(Ok::<_, DispatchError>(Self::compute_assignment(
+                    session_index,
+                    random_seed,
+                    collators.clone(),
+                )))
+            });
+            let ComputedAssignment {
+                old_assigned,
+                new_assigned,
+                registered_para_ids,
+                container_chain_ids,
+                parathreads,
+                ..
+            } = match computed {
+                Ok(computed) => computed,
+                Err(e) => {
+                    log::warn!(
+                        "Could not update grace periods in assignment preview: {:?}",
+                        e
+                    );
+
+                    Self::compute_assignment(session_index, random_seed, collators)
+                }
+            };
+
+            let dropped_paras = registered_para_ids
+                .into_iter()
+                .filter_map(|para_id| {
+                    if !container_chain_ids.contains(&para_id) && !parathreads.contains(&para_id) {
+                        // Paras that cannot pay keep their collators until their grace period
+                        // ends, so the ones that lose them now are in its last session
+                        if old_assigned.container_chains.contains_key(&para_id) {
+                            Some((para_id, DroppedParaReason::GracePeriodEnding))
+                        } else {
+                            Some((para_id, DroppedParaReason::NotEnoughCredits))
+                        }
+                    } else if new_assigned
+                        .container_chains
+                        .get(&para_id)
+                        .map_or(true, |collators| collators.is_empty())
+                    {
+                        Some((para_id, DroppedParaReason::NotEnoughCollators))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+
+            AssignmentPreview {
+                assignment: new_assigned,
+                dropped_paras,
+                randomness_known,
+            }
+        }
+
+        /// Weight of a collator in stake-weighted collator selection: its total stake, saturated
+        /// to `u64`.
+        fn collator_weight(collator: &T::AccountId) -> u64 {
+            T::CollatorStake::collator_stake(collator).saturated_into()
+        }
+
+        /// Number of collators of a chain: the requested number of collators, if any, or
+        /// `default_collators` otherwise.
+        fn chain_num_collators(
+            para_id: ParaId,
+            default_collators: u32,
+            parathread: bool,
+        ) -> ChainNumCollators {
+            let (min_collators, max_collators) =
+                match T::RequestedCollators::collator_requirements(para_id) {
+                    Some(requirements) => (requirements.min_collators, requirements.max_collators),
+                    None => (default_collators, default_collators),
+                };
+
+            ChainNumCollators {
+                para_id,
+                min_collators,
+                max_collators,
+                parathread,
+            }
+        }
+
+        /// Minimum number of collators needed to assign collators to all the chains.
+        fn min_collators_for_chains(
+            chains: &[ChainNumCollators],
+            parathreads_per_collator: u32,
+        ) -> u32 {
+            Self::min_collators_needed(
+                chains
+                    .iter()
+                    .filter(|cc| !cc.parathread)
+                    .map(|cc| cc.min_collators),
+                chains
+                    .iter()
+                    .filter(|cc| cc.parathread)
+                    .map(|cc| cc.min_collators),
+                parathreads_per_collator,
+            )
+        }
+
+        /// Minimum number of collators needed to assign collators to all the chains, given the
+        /// min collators of each parachain and parathread. Parathreads share collators, so they
+        /// are grouped in groups of `parathreads_per_collator`, starting with the ones that need
+        /// the most collators.
+        pub fn min_collators_needed(
+            parachains_min_collators: impl IntoIterator<Item = u32>,
+            parathreads_min_collators: impl IntoIterator<Item = u32>,
+            parathreads_per_collator: u32,
+        ) -> u32 {
+            let mut parathreads_min_collators: Vec<u32> =
+                parathreads_min_collators.into_iter().collect();
+            parathreads_min_collators.sort_unstable_by(|a, b| b.cmp(a));
+            let parathreads_min = parathreads_min_collators
+                .chunks(parathreads_per_collator.max(1) as usize)
+                .fold(0u32, |acc, group| acc.saturating_add(group[0]));
+
+            parachains_min_collators
+                .into_iter()
+                .fold(parathreads_min, |acc, min_collators| {
+                    acc.saturating_add(min_collators)
+                })
+        }
+
         // Returns the assigned collators as read from storage.
         // If there is any item in PendingCollatorContainerChain, returns that element.
         // Otherwise, reads and returns the current CollatorContainerChain
@@ -500,6 +766,32 @@ pub mod pallet {
 pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
+/// Reason why a registered para is not assigned collators in an [`AssignmentPreview`].
+#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum DroppedParaReason {
+    /// The para does not have enough credits, or funds in its tank, to pay for block production
+    /// and collator assignment.
+    NotEnoughCredits,
+    /// There are not enough collators for all the paras, and other paras had priority.
+    NotEnoughCollators,
+    /// The para is assigned collators but cannot pay for the target session, and this is the
+    /// last session of its grace period.
+    GracePeriodEnding,
+}
+
+/// Projected collator assignment, as returned by [`Pallet::preview_assignment`].
+#[derive(Clone, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct AssignmentPreview<AccountId> {
+    /// Projected assignment.
+    pub assignment: AssignedCollators<AccountId>,
+    /// Registered paras that will not be assigned collators, and why.
+    pub dropped_paras: Vec<(ParaId, DroppedParaReason)>,
+    /// Whether the randomness used to shuffle collators is already known, which only happens in
+    /// the last block of a session. Otherwise the actual assignment may shuffle collators
+    /// differently.
+    pub randomness_known: bool,
+}
+
 pub struct RotateCollatorsEveryNSessions<Period>(PhantomData<Period>);
 
 impl<Period> ShouldRotateAllCollators<u32> for RotateCollatorsEveryNSessions<Period>
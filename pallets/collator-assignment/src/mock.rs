@@ -33,8 +33,9 @@ use {
     },
     sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet},
     tp_traits::{
-        CollatorAssignmentHook, CollatorAssignmentTip, ParaId, ParathreadParams,
-        RemoveInvulnerables, RemoveParaIdsWithNoCredits, SessionContainerChains,
+        CollatorAssignmentHook, CollatorAssignmentTip, CollatorRequirements,
+        GetCollatorRequirements, GetCollatorStake, ParaId, ParathreadParams, RemoveInvulnerables,
+        RemoveParaIdsWithNoCredits, SessionContainerChains,
     },
     tracing_subscriber::{layer::SubscriberExt, FmtSubscriber},
 };
@@ -119,6 +120,8 @@ pub struct Mocks {
     pub max_orchestrator_chain_collators: u32,
     pub collators_per_container: u32,
     pub collators_per_parathread: u32,
+    // 0 means 1
+    pub parathreads_per_collator: u32,
     pub collators: Vec<u64>,
     pub container_chains: Vec<u32>,
     pub parathreads: Vec<u32>,
@@ -127,6 +130,11 @@ pub struct Mocks {
     pub full_rotation_period: Option<u32>,
     pub apply_tip: bool,
     pub assignment_hook_errors: bool,
+    // Para ids that requested a different number of collators, as `(para_id, min, max)`
+    pub requested_collators: Vec<(u32, u32, u32)>,
+    pub stake_weighted_collator_selection: bool,
+    // Total stake of each collator, as `(collator, stake)`. Collators not in this list have 0 stake
+    pub collators_stake: Vec<(u64, u32)>,
 }
 
 impl mock_data::Config for Test {}
@@ -159,6 +167,14 @@ impl pallet_collator_assignment::GetHostConfiguration<u32> for HostConfiguration
     fn collators_per_parathread(_session_index: u32) -> u32 {
         MockData::mock().collators_per_parathread
     }
+
+    fn parathreads_per_collator(_session_index: u32) -> u32 {
+        MockData::mock().parathreads_per_collator
+    }
+
+    fn stake_weighted_collator_selection(_session_index: u32) -> bool {
+        MockData::mock().stake_weighted_collator_selection
+    }
     #[cfg(feature = "runtime-benchmarks")]
     fn set_host_configuration(_session_index: u32) {
         MockData::mutate(|mocks| {
@@ -198,6 +214,7 @@ impl tp_traits::GetSessionContainerChains<u32> for ContainerChainsGetter {
                     ParaId::from(para_id),
                     ParathreadParams {
                         slot_frequency: Default::default(),
+                        core_purchase_backend: Default::default(),
                     },
                 )
             })
@@ -253,6 +270,35 @@ impl CollatorAssignmentTip<u32> for MockCollatorAssignmentTip {
         }
     }
 }
+
+pub struct MockRequestedCollators;
+
+impl GetCollatorRequirements for MockRequestedCollators {
+    fn collator_requirements(para_id: ParaId) -> Option<CollatorRequirements> {
+        MockData::mock()
+            .requested_collators
+            .iter()
+            .find(|(id, _, _)| ParaId::from(*id) == para_id)
+            .map(|(_, min_collators, max_collators)| CollatorRequirements {
+                min_collators: *min_collators,
+                max_collators: *max_collators,
+            })
+    }
+}
+
+pub struct MockCollatorStake;
+
+impl GetCollatorStake<u64, u32> for MockCollatorStake {
+    fn collator_stake(collator: &u64) -> u32 {
+        MockData::mock()
+            .collators_stake
+            .iter()
+            .find(|(id, _)| id == collator)
+            .map(|(_, stake)| *stake)
+            .unwrap_or_default()
+    }
+}
+
 pub struct MockCollatorAssignmentHook;
 
 impl CollatorAssignmentHook<u32> for MockCollatorAssignmentHook {
@@ -284,6 +330,8 @@ impl pallet_collator_assignment::Config for Test {
     type RemoveParaIdsWithNoCredits = RemoveParaIdsAbove5000;
     type CollatorAssignmentHook = MockCollatorAssignmentHook;
     type CollatorAssignmentTip = MockCollatorAssignmentTip;
+    type RequestedCollators = MockRequestedCollators;
+    type CollatorStake = MockCollatorStake;
     type Currency = ();
     type WeightInfo = ();
 }
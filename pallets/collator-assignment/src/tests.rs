@@ -15,14 +15,18 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 use {
-    crate::{mock::*, CollatorContainerChain, Event, PendingCollatorContainerChain},
+    crate::{
+        mock::*, CollatorContainerChain, DroppedParaReason, Event, PendingCollatorContainerChain,
+    },
     dp_collator_assignment::AssignedCollators,
     std::collections::BTreeMap,
 };
 
 mod assign_full;
+mod pack_parathreads;
 mod prioritize_invulnerables;
 mod select_chains;
+mod weighted_shuffle;
 
 #[test]
 fn assign_initial_collators() {
@@ -1398,3 +1402,266 @@ fn on_collators_assigned_hook_failure_removes_para_from_assignment() {
         );
     });
 }
+
+#[test]
+fn assign_collators_several_parathreads_per_collator() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.collators_per_parathread = 2;
+            m.parathreads_per_collator = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![];
+            m.parathreads = vec![3001, 3002, 3003]
+        });
+        assert_eq!(assigned_collators(), initial_collators(),);
+        run_to_block(11);
+
+        // 3001 and 3002 share the same collators
+        let assigned_collators = CollatorContainerChain::<Test>::get();
+        let expected = AssignedCollators {
+            orchestrator_chain: vec![1, 2, 3, 4, 5],
+            container_chains: BTreeMap::from_iter(vec![
+                (3001.into(), vec![6, 7]),
+                (3002.into(), vec![6, 7]),
+                (3003.into(), vec![8, 9]),
+            ]),
+        };
+        assert_eq!(assigned_collators, expected);
+
+        // A new parathread joins the group with free space, the other groups do not change
+        MockData::mutate(|m| {
+            m.parathreads = vec![3001, 3002, 3003, 3004];
+        });
+        run_to_block(21);
+
+        let assigned_collators = CollatorContainerChain::<Test>::get();
+        let expected = AssignedCollators {
+            orchestrator_chain: vec![1, 2, 3, 4, 5],
+            container_chains: BTreeMap::from_iter(vec![
+                (3001.into(), vec![6, 7]),
+                (3002.into(), vec![6, 7]),
+                (3003.into(), vec![8, 9]),
+                (3004.into(), vec![8, 9]),
+            ]),
+        };
+        assert_eq!(assigned_collators, expected);
+    });
+}
+
+#[test]
+fn assign_collators_with_requested_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.collators_per_parathread = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+            m.container_chains = vec![1001, 1002];
+            // 1001 only wants 1 collator, 1002 wants between 3 and 5 collators
+            m.requested_collators = vec![(1001, 1, 1), (1002, 3, 5)];
+        });
+        run_to_block(11);
+
+        // After all the chains get their min, the remaining collators go to the orchestrator
+        // chain first, and then to 1002
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 1000),
+                (2, 1000),
+                (3, 1000),
+                (4, 1000),
+                (5, 1000),
+                (6, 1001),
+                (7, 1002),
+                (8, 1002),
+                (9, 1002),
+                (10, 1002),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_prioritizing_tip_with_requested_collators() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.collators_per_parathread = 2;
+            m.min_orchestrator_chain_collators = 5;
+            m.max_orchestrator_chain_collators = 5;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+            m.container_chains = vec![1001, 1002, 1003, 1004];
+            m.requested_collators = vec![(1003, 4, 4)];
+            m.apply_tip = true;
+        });
+        run_to_block(11);
+
+        // 1003 has a tip and needs all the collators left after the orchestrator chain, so 1004
+        // does not get any collators even though it also has a tip
+        assert_eq!(
+            assigned_collators(),
+            BTreeMap::from_iter(vec![
+                (1, 1000),
+                (2, 1000),
+                (3, 1000),
+                (4, 1000),
+                (5, 1000),
+                (6, 1003),
+                (7, 1003),
+                (8, 1003),
+                (9, 1003),
+            ]),
+        );
+    });
+}
+
+#[test]
+fn assign_collators_stake_weighted() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.collators_per_parathread = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            // 6 collators but only 4 are needed
+            m.collators = vec![1, 2, 3, 4, 5, 6];
+            m.container_chains = vec![1001];
+            m.random_seed = [1; 32];
+            m.stake_weighted_collator_selection = true;
+            // Collators 5 and 6 have much more stake than the rest
+            m.collators_stake = vec![(5, u32::MAX), (6, u32::MAX)];
+        });
+        run_to_block(11);
+
+        let assigned = assigned_collators();
+        assert_eq!(assigned.len(), 4);
+        // Collators with more stake have priority to be assigned, and are more likely to be
+        // assigned to a container chain than to the orchestrator chain
+        assert_eq!(assigned.get(&5), Some(&1001));
+        assert_eq!(assigned.get(&6), Some(&1001));
+        assert_eq!(
+            assigned
+                .values()
+                .filter(|para_id| **para_id == 1000)
+                .count(),
+            2
+        );
+    });
+}
+
+#[test]
+fn preview_assignment_matches_next_assignment() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.collators_per_parathread = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            // 6 collators are not enough for 3 container chains, and 5001 has no credits
+            m.collators = vec![1, 2, 3, 4, 5, 6];
+            m.container_chains = vec![1001, 1002, 1003, 5001];
+        });
+
+        let storage_root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+        let preview = CollatorAssignment::preview_assignment(&1, MockData::mock().collators);
+        // Preview does not modify storage
+        assert_eq!(
+            sp_io::storage::root(sp_runtime::StateVersion::V1),
+            storage_root
+        );
+
+        assert_eq!(preview.assignment.orchestrator_chain, vec![1, 2]);
+        assert_eq!(
+            preview.assignment.container_chains,
+            BTreeMap::from_iter(vec![
+                (1001.into(), vec![3, 4]),
+                (1002.into(), vec![5, 6]),
+                (1003.into(), vec![]),
+            ])
+        );
+        assert_eq!(
+            preview.dropped_paras,
+            vec![
+                (1003.into(), DroppedParaReason::NotEnoughCollators),
+                (5001.into(), DroppedParaReason::NotEnoughCredits),
+            ]
+        );
+        // There is no randomness outside of the last block of a session
+        assert!(!preview.randomness_known);
+
+        // The assignment computed on session change is the same as the preview
+        run_to_block(6);
+        assert_eq!(
+            PendingCollatorContainerChain::<Test>::get(),
+            Some(preview.assignment)
+        );
+    });
+}
+
+#[test]
+fn preview_assignment_reports_paras_at_end_of_grace_period() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+
+        MockData::mutate(|m| {
+            m.collators_per_container = 2;
+            m.min_orchestrator_chain_collators = 2;
+            m.max_orchestrator_chain_collators = 2;
+
+            m.collators = vec![1, 2, 3, 4, 5, 6];
+            m.container_chains = vec![1001, 5001];
+        });
+
+        // 5001 is running but has no credits, it is dropped once its grace period ends
+        CollatorContainerChain::<Test>::put(AssignedCollators {
+            orchestrator_chain: vec![1, 2],
+            container_chains: BTreeMap::from_iter(vec![(5001.into(), vec![3, 4])]),
+        });
+        PendingCollatorContainerChain::<Test>::kill();
+
+        let preview = CollatorAssignment::preview_assignment(&1, MockData::mock().collators);
+        assert_eq!(preview.assignment.container_chains.get(&5001.into()), None);
+        assert_eq!(
+            preview.dropped_paras,
+            vec![(5001.into(), DroppedParaReason::GracePeriodEnding)]
+        );
+    });
+}
+
+#[test]
+fn preview_assignment_knows_randomness_in_last_block_of_session() {
+    new_test_ext().execute_with(|| {
+        MockData::mutate(|m| {
+            m.random_seed = [1; 32];
+        });
+
+        run_to_block(3);
+        let preview = CollatorAssignment::preview_assignment(&1, MockData::mock().collators);
+        assert!(!preview.randomness_known);
+
+        // Randomness for the session change is read in the block before it
+        run_to_block(4);
+        let preview = CollatorAssignment::preview_assignment(&1, MockData::mock().collators);
+        assert!(preview.randomness_known);
+    });
+}
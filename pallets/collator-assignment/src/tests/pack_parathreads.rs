@@ -0,0 +1,162 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{
+        assignment::{Assignment, ChainNumCollators},
+        tests::Test,
+    },
+    sp_std::collections::btree_map::BTreeMap,
+    tp_traits::ParaId,
+};
+
+fn chain(para_id: u32, parathread: bool) -> ChainNumCollators {
+    ChainNumCollators {
+        para_id: para_id.into(),
+        min_collators: 2,
+        max_collators: 2,
+        parathread,
+    }
+}
+
+fn para_ids(chains: &[ChainNumCollators]) -> Vec<ParaId> {
+    chains.iter().map(|cc| cc.para_id).collect()
+}
+
+#[test]
+fn pack_parathreads_one_per_collator_does_nothing() {
+    let mut chains = vec![chain(1000, false), chain(3000, true), chain(3001, true)];
+    let mut old_assigned = BTreeMap::from_iter(vec![(3000.into(), vec![1, 2])]);
+
+    let groups = Assignment::<Test>::pack_parathreads(&mut chains, &mut old_assigned, 1);
+
+    assert!(groups.is_empty());
+    assert_eq!(
+        para_ids(&chains),
+        vec![1000.into(), 3000.into(), 3001.into()]
+    );
+    assert_eq!(
+        old_assigned,
+        BTreeMap::from_iter(vec![(3000.into(), vec![1, 2])])
+    );
+}
+
+#[test]
+fn pack_parathreads_keeps_priority() {
+    let mut chains = vec![
+        chain(1000, false),
+        chain(3000, true),
+        chain(2000, false),
+        chain(3001, true),
+        chain(3002, true),
+    ];
+    let mut old_assigned = BTreeMap::new();
+
+    let groups = Assignment::<Test>::pack_parathreads(&mut chains, &mut old_assigned, 2);
+
+    assert_eq!(
+        groups,
+        BTreeMap::from_iter(vec![
+            (3000.into(), vec![3001.into()]),
+            (3002.into(), vec![]),
+        ])
+    );
+    assert_eq!(
+        para_ids(&chains),
+        vec![1000.into(), 3000.into(), 2000.into(), 3002.into()]
+    );
+}
+
+#[test]
+fn pack_parathreads_keeps_old_groups() {
+    let mut chains = vec![
+        chain(1000, false),
+        chain(3000, true),
+        chain(3001, true),
+        chain(3002, true),
+        chain(3003, true),
+    ];
+    // 3000 and 3002 were sharing collators
+    let mut old_assigned = BTreeMap::from_iter(vec![
+        (1000.into(), vec![1, 2]),
+        (3000.into(), vec![3, 4]),
+        (3002.into(), vec![3, 4]),
+        (3003.into(), vec![5, 6]),
+    ]);
+
+    let groups = Assignment::<Test>::pack_parathreads(&mut chains, &mut old_assigned, 2);
+
+    assert_eq!(
+        groups,
+        BTreeMap::from_iter(vec![
+            (3000.into(), vec![3002.into()]),
+            (3001.into(), vec![3003.into()]),
+        ])
+    );
+    assert_eq!(
+        para_ids(&chains),
+        vec![1000.into(), 3000.into(), 3001.into()]
+    );
+    assert_eq!(
+        old_assigned,
+        BTreeMap::from_iter(vec![
+            (1000.into(), vec![1, 2]),
+            (3000.into(), vec![3, 4]),
+            (3001.into(), vec![5, 6]),
+        ])
+    );
+}
+
+#[test]
+fn pack_parathreads_uses_minimum_number_of_groups() {
+    let mut chains = vec![chain(3000, true), chain(3001, true), chain(3002, true)];
+    // Each parathread had different collators, but they can all fit in one group
+    let mut old_assigned = BTreeMap::from_iter(vec![
+        (3000.into(), vec![1, 2]),
+        (3001.into(), vec![3, 4]),
+        (3002.into(), vec![5, 6]),
+    ]);
+
+    let groups = Assignment::<Test>::pack_parathreads(&mut chains, &mut old_assigned, 3);
+
+    assert_eq!(
+        groups,
+        BTreeMap::from_iter(vec![(3000.into(), vec![3001.into(), 3002.into()])])
+    );
+    assert_eq!(para_ids(&chains), vec![3000.into()]);
+    assert_eq!(
+        old_assigned,
+        BTreeMap::from_iter(vec![(3000.into(), vec![1, 2])])
+    );
+}
+
+#[test]
+fn unpack_parathreads_copies_collators() {
+    let groups = BTreeMap::from_iter(vec![(3000.into(), vec![3001.into()])]);
+    let mut assigned =
+        BTreeMap::from_iter(vec![(1000.into(), vec![1, 2]), (3000.into(), vec![3, 4])]);
+
+    Assignment::<Test>::unpack_parathreads(&mut assigned, &groups);
+
+    assert_eq!(
+        assigned,
+        BTreeMap::from_iter(vec![
+            (1000.into(), vec![1, 2]),
+            (3000.into(), vec![3, 4]),
+            (3001.into(), vec![3, 4]),
+        ])
+    );
+}
@@ -0,0 +1,79 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{assignment::Assignment, tests::Test},
+    rand::SeedableRng,
+    rand_chacha::ChaCha20Rng,
+    sp_std::collections::btree_set::BTreeSet,
+};
+
+#[test]
+fn weighted_shuffle_empty() {
+    let mut collators = vec![];
+    let mut rng = ChaCha20Rng::from_seed([1; 32]);
+    Assignment::<Test>::weighted_shuffle(&mut collators, |_| 0, &mut rng);
+
+    assert_eq!(collators, vec![]);
+}
+
+#[test]
+fn weighted_shuffle_keeps_all_collators() {
+    let original: Vec<u64> = (1..=20).collect();
+
+    for seed in 0..10u8 {
+        let mut collators = original.clone();
+        let mut rng = ChaCha20Rng::from_seed([seed; 32]);
+        Assignment::<Test>::weighted_shuffle(&mut collators, |c| *c % 3, &mut rng);
+
+        assert_eq!(collators.len(), original.len());
+        assert_eq!(
+            BTreeSet::from_iter(collators.iter().cloned()),
+            BTreeSet::from_iter(original.iter().cloned())
+        );
+    }
+}
+
+#[test]
+fn weighted_shuffle_zero_weights_is_random() {
+    let original: Vec<u64> = (1..=20).collect();
+    let mut rng = ChaCha20Rng::from_seed([1; 32]);
+    let mut collators = original.clone();
+    Assignment::<Test>::weighted_shuffle(&mut collators, |_| 0, &mut rng);
+
+    // All the collators have the same weight, so the list is shuffled like a uniform shuffle
+    assert_ne!(collators, original);
+}
+
+#[test]
+fn weighted_shuffle_high_stake_goes_first() {
+    for seed in 0..10u8 {
+        let mut collators: Vec<u64> = (1..=10).collect();
+        let mut rng = ChaCha20Rng::from_seed([seed; 32]);
+        // Collators 9 and 10 have much more stake than the rest, so they are always the
+        // first two collators
+        Assignment::<Test>::weighted_shuffle(
+            &mut collators,
+            |c| if *c >= 9 { u64::MAX } else { 0 },
+            &mut rng,
+        );
+
+        assert_eq!(
+            BTreeSet::from_iter(collators[..2].iter().cloned()),
+            BTreeSet::from([9, 10])
+        );
+    }
+}
@@ -76,15 +76,19 @@ pub struct HostConfiguration {
     pub collators_per_container: u32,
     /// Rotate all collators once every n sessions. If this value is 0 means that there is no rotation
     pub full_rotation_period: u32,
-    /// How many collators to assign to one parathread
-    // TODO: for now we only support 1 collator per parathread because using Aura for consensus conflicts with
-    // the idea of being able to create blocks every n slots: if there are 2 collators and we create blocks
-    // every 2 slots, 1 collator will create all the blocks.
+    /// How many collators to assign to one parathread. Authorship rotates among them: after waiting for the
+    /// min `SlotFrequency`, the next block is authored by the collator following the author of the previous
+    /// one, which can delay blocks by up to `collators_per_parathread - 1` slots.
     pub collators_per_parathread: u32,
-    /// How many parathreads can be assigned to one collator
+    /// How many parathreads can be assigned to one collator. Parathreads that share collators are assigned
+    /// the same `collators_per_parathread` collators.
     pub parathreads_per_collator: u32,
     /// Ratio of collators that we expect to be assigned to container chains. Affects fees.
     pub target_container_chain_fullness: Perbill,
+    /// Weight collator selection by the stake of each collator instead of shuffling collators uniformly. Collators
+    /// with more stake are more likely to be assigned to a container chain instead of the orchestrator chain, and
+    /// have priority when there are not enough collators for all the chains.
+    pub stake_weighted_collator_selection: bool,
 }
 
 impl Default for HostConfiguration {
@@ -98,6 +102,7 @@ impl Default for HostConfiguration {
             collators_per_parathread: 1,
             parathreads_per_collator: 1,
             target_container_chain_fullness: Perbill::from_percent(80),
+            stake_weighted_collator_selection: false,
         }
     }
 }
@@ -111,8 +116,8 @@ pub enum InconsistentError {
     MinOrchestratorCollatorsTooLow,
     /// `max_collators` must be at least 1
     MaxCollatorsTooLow,
-    /// Tried to modify an unimplemented parameter
-    UnimplementedParameter,
+    /// `parathreads_per_collator` must be at least 1
+    ParathreadsPerCollatorTooLow,
 }
 
 impl HostConfiguration {
@@ -131,8 +136,8 @@ impl HostConfiguration {
         if self.max_orchestrator_collators < self.min_orchestrator_collators {
             return Err(InconsistentError::MaxCollatorsLowerThanMinCollators);
         }
-        if self.parathreads_per_collator != 1 {
-            return Err(InconsistentError::UnimplementedParameter);
+        if self.parathreads_per_collator < 1 {
+            return Err(InconsistentError::ParathreadsPerCollatorTooLow);
         }
         if self.max_collators < self.min_orchestrator_collators {
             return Err(InconsistentError::MaxCollatorsLowerThanMinCollators);
@@ -337,6 +342,21 @@ pub mod pallet {
             })
         }
 
+        #[pallet::call_index(8)]
+        #[pallet::weight((
+        T::WeightInfo::set_config_with_u32(),
+        DispatchClass::Operational,
+        ))]
+        pub fn set_stake_weighted_collator_selection(
+            origin: OriginFor<T>,
+            new: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::schedule_config_update(|config| {
+                config.stake_weighted_collator_selection = new;
+            })
+        }
+
         /// Setting this to true will disable consistency checks for the configuration setters.
         /// Use with caution.
         #[pallet::call_index(44)]
@@ -568,6 +588,32 @@ pub mod pallet {
             config.collators_per_parathread
         }
 
+        fn parathreads_per_collator(session_index: T::SessionIndex) -> u32 {
+            let (past_and_present, _) = Pallet::<T>::pending_configs()
+                .into_iter()
+                .partition::<Vec<_>, _>(|&(apply_at_session, _)| apply_at_session <= session_index);
+
+            let config = if let Some(last) = past_and_present.last() {
+                last.1.clone()
+            } else {
+                Pallet::<T>::config()
+            };
+            config.parathreads_per_collator
+        }
+
+        fn stake_weighted_collator_selection(session_index: T::SessionIndex) -> bool {
+            let (past_and_present, _) = Pallet::<T>::pending_configs()
+                .into_iter()
+                .partition::<Vec<_>, _>(|&(apply_at_session, _)| apply_at_session <= session_index);
+
+            let config = if let Some(last) = past_and_present.last() {
+                last.1.clone()
+            } else {
+                Pallet::<T>::config()
+            };
+            config.stake_weighted_collator_selection
+        }
+
         fn min_collators_for_orchestrator(session_index: T::SessionIndex) -> u32 {
             let (past_and_present, _) = Pallet::<T>::pending_configs()
                 .into_iter()
@@ -365,6 +365,37 @@ fn config_cannot_set_invalid_values() {
             Configuration::set_max_orchestrator_collators(RuntimeOrigin::root(), 0),
             Error::<Test>::InvalidNewValue
         );
+        assert_noop!(
+            Configuration::set_parathreads_per_collator(RuntimeOrigin::root(), 0),
+            Error::<Test>::InvalidNewValue
+        );
+    });
+}
+
+#[test]
+fn config_set_stake_weighted_collator_selection() {
+    new_test_ext_with_genesis(HostConfiguration::default()).execute_with(|| {
+        run_to_block(1);
+        assert!(!Configuration::config().stake_weighted_collator_selection);
+        assert_ok!(Configuration::set_stake_weighted_collator_selection(
+            RuntimeOrigin::root(),
+            true
+        ));
+
+        assert_eq!(
+            PendingConfigs::<Test>::get(),
+            vec![(
+                2,
+                HostConfiguration {
+                    stake_weighted_collator_selection: true,
+                    ..Default::default()
+                }
+            )]
+        );
+
+        // First block of session 2
+        run_to_block(11);
+        assert!(Configuration::config().stake_weighted_collator_selection);
     });
 }
 
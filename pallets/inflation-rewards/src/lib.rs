@@ -40,10 +40,12 @@ use {
     },
     frame_system::pallet_prelude::*,
     sp_runtime::{
-        traits::{Get, Saturating},
+        traits::{Get, Saturating, Zero},
         Perbill,
     },
-    tp_traits::{AuthorNotingHook, DistributeRewards, GetCurrentContainerChains},
+    tp_traits::{
+        blocks_produced_since, AuthorNotingHook, DistributeRewards, GetCurrentContainerChains,
+    },
 };
 
 #[frame_support::pallet]
@@ -65,24 +67,44 @@ pub mod pallet {
             let mut weight = T::DbWeight::get().reads(1);
 
             // Collect indistributed rewards, if any
-            // Any parachain we have not rewarded is handled by onUnbalanced
-            let not_distributed_rewards =
-                if let Some(chains_to_reward) = ChainsToReward::<T>::take() {
-                    // Collect and sum all undistributed rewards
-                    let rewards_not_distributed: BalanceOf<T> = chains_to_reward
-                        .rewards_per_chain
-                        .saturating_mul((chains_to_reward.para_ids.len() as u32).into());
-                    T::Currency::withdraw(
-                        &T::PendingRewardsAccount::get(),
-                        rewards_not_distributed,
-                        Precision::BestEffort,
-                        Preservation::Expendable,
-                        Fortitude::Force,
-                    )
-                    .unwrap_or(CreditOf::<T>::zero())
-                } else {
-                    CreditOf::<T>::zero()
-                };
+            // Rewards of the parachains we have not rewarded are kept for the blocks they will
+            // note later, up to `MaxBlocksRewardedPerNoting - 1` blocks. The rest is handled by
+            // onUnbalanced
+            let not_distributed_rewards = if let Some(chains_to_reward) =
+                ChainsToReward::<T>::take()
+            {
+                let max_unclaimed_blocks = T::MaxBlocksRewardedPerNoting::get().saturating_sub(1);
+                let mut rewards_not_distributed = BalanceOf::<T>::zero();
+
+                for para_id in chains_to_reward.para_ids {
+                    weight += T::DbWeight::get().reads(1);
+                    let mut unclaimed = UnclaimedContainerRewards::<T>::get(para_id);
+
+                    if unclaimed.blocks < max_unclaimed_blocks {
+                        unclaimed.blocks += 1;
+                        unclaimed.amount = unclaimed
+                            .amount
+                            .saturating_add(chains_to_reward.rewards_per_chain);
+
+                        weight += T::DbWeight::get().writes(1);
+                        UnclaimedContainerRewards::<T>::insert(para_id, unclaimed);
+                    } else {
+                        rewards_not_distributed = rewards_not_distributed
+                            .saturating_add(chains_to_reward.rewards_per_chain);
+                    }
+                }
+
+                T::Currency::withdraw(
+                    &T::PendingRewardsAccount::get(),
+                    rewards_not_distributed,
+                    Precision::BestEffort,
+                    Preservation::Expendable,
+                    Fortitude::Force,
+                )
+                .unwrap_or(CreditOf::<T>::zero())
+            } else {
+                CreditOf::<T>::zero()
+            };
 
             // Get the number of chains at this block (tanssi + container chain blocks)
             weight += T::DbWeight::get().reads_writes(1, 1);
@@ -155,6 +177,10 @@ pub mod pallet {
         /// Proportion of the new supply dedicated to staking
         #[pallet::constant]
         type RewardsPortion: Get<Perbill>;
+
+        /// The maximum number of container chain blocks rewarded in a single author noting
+        #[pallet::constant]
+        type MaxBlocksRewardedPerNoting: Get<u32>;
     }
 
     #[pallet::event]
@@ -188,6 +214,21 @@ pub mod pallet {
         pub rewards_per_chain: BalanceOf<T>,
     }
 
+    /// Rewards of the container chains that were not rewarded in previous blocks, kept in the
+    /// pending rewards account to reward the container blocks produced meanwhile once they
+    /// are noted. At most `MaxBlocksRewardedPerNoting - 1` blocks are kept per chain.
+    #[pallet::storage]
+    pub(super) type UnclaimedContainerRewards<T: Config> =
+        StorageMap<_, Twox64Concat, ParaId, UnclaimedRewards<BalanceOf<T>>, ValueQuery>;
+    #[derive(
+        Clone, Default, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo,
+    )]
+    pub struct UnclaimedRewards<Balance> {
+        /// Number of orchestrator blocks in which the chain was not rewarded
+        pub blocks: u32,
+        pub amount: Balance,
+    }
+
     impl<T: Config> Pallet<T> {
         fn reward_orchestrator_author() -> Weight {
             let mut total_weight = T::DbWeight::get().reads(1);
@@ -233,34 +274,71 @@ pub mod pallet {
 // There will be no additional check other than checking if we have already
 // rewarded this author for **in this tanssi block**
 // Any additional check should be done in the calling function
+// The author is rewarded for each block produced since the previous noting, up to
+// `MaxBlocksRewardedPerNoting`. The blocks other than the noted one are paid with the rewards
+// the chain did not claim in previous tanssi blocks.
 // TODO: consider passing a vector here
 impl<T: Config> AuthorNotingHook<T::AccountId> for Pallet<T> {
     fn on_container_author_noted(
         author: &T::AccountId,
-        _block_number: BlockNumber,
+        previous_block_number: Option<BlockNumber>,
+        block_number: BlockNumber,
         para_id: ParaId,
+        respects_slot_frequency: bool,
     ) -> Weight {
+        // Blocks that do not respect the slot frequency of a parathread are not rewarded. The
+        // reward is left undistributed and handled by `OnUnbalanced` in the next block.
+        if !respects_slot_frequency {
+            return Weight::zero();
+        }
+
         let mut total_weight = T::DbWeight::get().reads_writes(1, 0);
         // We take chains to reward, to see what containers are left to reward
         if let Some(mut container_chains_to_reward) = ChainsToReward::<T>::get() {
             // If we find the index is because we still have not rewarded it
             if let Ok(index) = container_chains_to_reward.para_ids.binary_search(&para_id) {
+                let blocks_rewarded = blocks_produced_since(previous_block_number, block_number)
+                    .min(T::MaxBlocksRewardedPerNoting::get());
+
+                // The noted block is paid with the rewards of this tanssi block, the other blocks
+                // with the rewards the chain did not claim previously
+                total_weight += T::DbWeight::get().reads_writes(1, 1);
+                let unclaimed = UnclaimedContainerRewards::<T>::take(para_id);
+                let unclaimed_blocks_rewarded =
+                    blocks_rewarded.saturating_sub(1).min(unclaimed.blocks);
+                let unclaimed_rewards = if unclaimed.blocks.is_zero() {
+                    Zero::zero()
+                } else {
+                    Perbill::from_rational(unclaimed_blocks_rewarded, unclaimed.blocks)
+                        * unclaimed.amount
+                };
+                let rewards = container_chains_to_reward
+                    .rewards_per_chain
+                    .saturating_add(unclaimed_rewards);
+
+                // Unclaimed rewards for blocks that were not produced are handled by onUnbalanced
+                let (rewards_credit, not_rewarded) = T::Currency::withdraw(
+                    &T::PendingRewardsAccount::get(),
+                    container_chains_to_reward
+                        .rewards_per_chain
+                        .saturating_add(unclaimed.amount),
+                    Precision::BestEffort,
+                    Preservation::Expendable,
+                    Fortitude::Force,
+                )
+                .unwrap_or(CreditOf::<T>::zero())
+                .split(rewards);
+                T::OnUnbalanced::on_unbalanced(not_rewarded);
+
                 // we distribute rewards to the author
                 match T::StakingRewardsDistributor::distribute_rewards(
                     author.clone(),
-                    T::Currency::withdraw(
-                        &T::PendingRewardsAccount::get(),
-                        container_chains_to_reward.rewards_per_chain,
-                        Precision::BestEffort,
-                        Preservation::Expendable,
-                        Fortitude::Force,
-                    )
-                    .unwrap_or(CreditOf::<T>::zero()),
+                    rewards_credit,
                 ) {
                     Ok(frame_support::dispatch::PostDispatchInfo { actual_weight, .. }) => {
                         Self::deposit_event(Event::RewardedContainer {
                             account_id: author.clone(),
-                            balance: container_chains_to_reward.rewards_per_chain,
+                            balance: rewards,
                             para_id,
                         });
                         if let Some(weight) = actual_weight {
@@ -199,6 +199,7 @@ parameter_types! {
     pub PendingRewardsAccount: AccountId = 99;
     pub const RewardsPortion: Perbill = Perbill::from_percent(70);
     pub const InflationRate: Perbill = Perbill::from_percent(1);
+    pub static MaxBlocksRewardedPerNoting: u32 = 1;
 }
 
 impl pallet_inflation_rewards::Config for Test {
@@ -211,6 +212,7 @@ impl pallet_inflation_rewards::Config for Test {
     type PendingRewardsAccount = PendingRewardsAccount;
     type StakingRewardsDistributor = MockRewardsDistributor;
     type RewardsPortion = RewardsPortion;
+    type MaxBlocksRewardedPerNoting = MaxBlocksRewardedPerNoting;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -132,8 +132,10 @@ fn test_reward_container_chain_author() {
         let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
         <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
             &container_author,
+            None,
             1,
             registered_para_ids[0],
+            true,
         );
 
         // Author should be rewarded immediately
@@ -150,8 +152,10 @@ fn test_reward_container_chain_author() {
         // Note next container author
         <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
             &container_author_2,
+            Some(1),
             2,
             registered_para_ids[0],
+            true,
         );
 
         // Author should be rewarded immediately
@@ -179,15 +183,19 @@ fn test_cannot_reward_twice_in_same_tanssi_block() {
         let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
         <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
             &container_author,
+            None,
             1,
             registered_para_ids[0],
+            true,
         );
 
         // Regardless if we inject a new block, we cannot reward twice the same paraId
         <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
             &container_author,
+            Some(1),
             2,
             registered_para_ids[0],
+            true,
         );
 
         // Author should be rewarded only once
@@ -199,6 +207,33 @@ fn test_cannot_reward_twice_in_same_tanssi_block() {
     });
 }
 
+#[test]
+fn test_block_not_respecting_slot_frequency_is_not_rewarded() {
+    new_test_ext().execute_with(|| {
+        let container_author = 2;
+        let container_author_balance = get_balance(&container_author);
+
+        <Pallet<Test> as Hooks<u64>>::on_initialize(1);
+
+        // Note container author
+        let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
+        <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
+            &container_author,
+            None,
+            1,
+            registered_para_ids[0],
+            false,
+        );
+
+        // Author should not be rewarded, the para id is still pending to be rewarded
+        assert_eq!(get_balance(&container_author), container_author_balance);
+        assert!(ChainsToReward::<Test>::get()
+            .unwrap()
+            .para_ids
+            .contains(&registered_para_ids[0]));
+    });
+}
+
 #[test]
 fn test_non_claimed_rewards_go_to_on_unbalanced() {
     new_test_ext().execute_with(|| {
@@ -247,3 +282,120 @@ fn test_non_claimed_rewards_go_to_on_unbalanced() {
         );
     });
 }
+
+#[test]
+fn test_reward_container_chain_author_for_each_block_produced() {
+    new_test_ext().execute_with(|| {
+        MaxBlocksRewardedPerNoting::set(3);
+        let container_author = 2;
+        let container_author_balance = get_balance(&container_author);
+        let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
+
+        <Pallet<Test> as Hooks<u64>>::on_initialize(1);
+        let rewards_1 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+
+        // The container chain is not noted in block 1, its rewards are kept
+        <Pallet<Test> as Hooks<u64>>::on_initialize(2);
+        let rewards_2 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+        assert_eq!(
+            UnclaimedContainerRewards::<Test>::get(registered_para_ids[0]),
+            UnclaimedRewards {
+                blocks: 1,
+                amount: rewards_1,
+            }
+        );
+
+        // 2 container blocks were produced since the previous noting
+        <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
+            &container_author,
+            Some(1),
+            3,
+            registered_para_ids[0],
+            true,
+        );
+
+        assert_eq!(
+            get_balance(&container_author),
+            container_author_balance + rewards_1 + rewards_2,
+        );
+        assert_eq!(
+            UnclaimedContainerRewards::<Test>::get(registered_para_ids[0]),
+            UnclaimedRewards::default()
+        );
+    });
+}
+
+#[test]
+fn test_unclaimed_rewards_of_blocks_not_produced_go_to_on_unbalanced() {
+    new_test_ext().execute_with(|| {
+        MaxBlocksRewardedPerNoting::set(3);
+        let container_author = 2;
+        let container_author_balance = get_balance(&container_author);
+        let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
+
+        <Pallet<Test> as Hooks<u64>>::on_initialize(1);
+        let rewards_1 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+        <Pallet<Test> as Hooks<u64>>::on_initialize(2);
+        let rewards_2 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+        let on_unbalanced_account = get_balance(&OnUnbalancedInflationAccount::get());
+
+        // A single container block was produced since the previous noting
+        <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
+            &container_author,
+            Some(1),
+            2,
+            registered_para_ids[0],
+            true,
+        );
+
+        assert_eq!(
+            get_balance(&container_author),
+            container_author_balance + rewards_2,
+        );
+        assert_eq!(
+            get_balance(&OnUnbalancedInflationAccount::get()),
+            on_unbalanced_account + rewards_1,
+        );
+    });
+}
+
+#[test]
+fn test_rewarded_blocks_per_noting_are_capped() {
+    new_test_ext().execute_with(|| {
+        MaxBlocksRewardedPerNoting::set(3);
+        let container_author = 2;
+        let container_author_balance = get_balance(&container_author);
+        let registered_para_ids = <Test as Config>::ContainerChains::current_container_chains();
+
+        <Pallet<Test> as Hooks<u64>>::on_initialize(1);
+        let rewards_1 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+        <Pallet<Test> as Hooks<u64>>::on_initialize(2);
+        let rewards_2 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+        <Pallet<Test> as Hooks<u64>>::on_initialize(3);
+        <Pallet<Test> as Hooks<u64>>::on_initialize(4);
+        let rewards_4 = ChainsToReward::<Test>::get().unwrap().rewards_per_chain;
+
+        // Only the rewards of MaxBlocksRewardedPerNoting - 1 blocks are kept
+        assert_eq!(
+            UnclaimedContainerRewards::<Test>::get(registered_para_ids[0]),
+            UnclaimedRewards {
+                blocks: 2,
+                amount: rewards_1 + rewards_2,
+            }
+        );
+
+        // 10 blocks produced, but only MaxBlocksRewardedPerNoting are rewarded
+        <Pallet<Test> as AuthorNotingHook<AccountId>>::on_container_author_noted(
+            &container_author,
+            Some(1),
+            11,
+            registered_para_ids[0],
+            true,
+        );
+
+        assert_eq!(
+            get_balance(&container_author),
+            container_author_balance + rewards_1 + rewards_2 + rewards_4,
+        );
+    });
+}
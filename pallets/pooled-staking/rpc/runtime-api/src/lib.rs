@@ -0,0 +1,64 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! Runtime API for Pooled Staking pallet. Can be used by delegators to compare the commissions
+//! of the candidates, and by wallets to get the positions of a delegator without iterating the
+//! pallet storage.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use {
+    alloc::{string::String, vec::Vec},
+    parity_scale_codec::{Decode, Encode},
+    sp_runtime::Perbill,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo)]
+#[cfg_attr(
+    feature = "std",
+    derive(thiserror::Error, serde::Serialize, serde::Deserialize)
+)]
+pub enum PooledStakingApiError {
+    #[cfg_attr(feature = "std", error("Other error: {0}"))]
+    Other(String),
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait PooledStakingApi<AccountId, Instant, DelegatorPosition> where
+        AccountId: parity_scale_codec::Codec,
+        Instant: parity_scale_codec::Codec,
+        DelegatorPosition: parity_scale_codec::Codec,
+    {
+        /// Return the positions of the delegator towards every candidate it delegates to,
+        /// including its pending joining and leaving requests along with the instants from which
+        /// they can be executed, its stake in each pool and its claimable manual rewards.
+        /// This iterates over the pools of every candidate, so its cost is not bounded by the
+        /// number of positions of the delegator.
+        fn delegator_positions(
+            delegator: AccountId,
+        ) -> Result<Vec<DelegatorPosition>, PooledStakingApiError>;
+
+        /// Return the commission currently applied to the rewards of the candidate.
+        fn candidate_commission(candidate: AccountId) -> Perbill;
+        /// Return the commission increase requested by the candidate if any, along with the
+        /// instant at which it was requested.
+        fn pending_commission_increase(candidate: AccountId) -> Option<(Perbill, Instant)>;
+        /// Return the minimum and maximum commission candidates can set.
+        fn commission_bounds() -> (Perbill, Perbill);
+    }
+}
@@ -35,6 +35,7 @@ use {
         },
     },
     frame_system::EventRecord,
+    sp_runtime::{traits::Zero, BoundedVec, Perbill},
     sp_std::prelude::*,
 };
 
@@ -610,6 +611,168 @@ mod benchmarks {
         Ok(())
     }
 
+    #[benchmark]
+    fn apply_slash() -> Result<(), BenchmarkError> {
+        const USER_SEED: u32 = 1;
+
+        let source_stake = min_candidate_stk::<T>() * 10u32.into();
+
+        let (caller, _deposit_amount) =
+            create_funded_user::<T>("caller", USER_SEED, source_stake * 3u32.into());
+
+        T::EligibleCandidatesFilter::make_candidate_eligible(&caller, true);
+
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            source_stake,
+        )?;
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::ManualRewards,
+            source_stake,
+        )?;
+
+        let timer = T::JoiningRequestTimer::now();
+
+        T::JoiningRequestTimer::skip_to_elapsed();
+
+        PooledStaking::<T>::execute_pending_operations(
+            RawOrigin::Signed(caller.clone()).into(),
+            vec![
+                PendingOperationQuery {
+                    delegator: caller.clone(),
+                    operation: JoiningAutoCompounding {
+                        candidate: caller.clone(),
+                        at: timer.clone(),
+                    },
+                },
+                PendingOperationQuery {
+                    delegator: caller.clone(),
+                    operation: JoiningManualRewards {
+                        candidate: caller.clone(),
+                        at: timer.clone(),
+                    },
+                },
+            ],
+        )?;
+
+        // Some stake is still joining so that all pools are slashed.
+        PooledStaking::<T>::request_delegate(
+            RawOrigin::Signed(caller.clone()).into(),
+            caller.clone(),
+            TargetPool::AutoCompounding,
+            source_stake,
+        )?;
+
+        #[block]
+        {
+            crate::slashing::Slashing::<T>::apply_slash(&caller, Perbill::from_percent(50))?;
+        }
+
+        assert!(!crate::UnpaidSlashedFunds::<T>::get(&caller).is_zero());
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn cancel_deferred_slashes() -> Result<(), BenchmarkError> {
+        let max = T::MaxDeferredSlashes::get();
+
+        let slashes: Vec<_> = (0..max)
+            .map(|i| DeferredSlash {
+                candidate: account("candidate", i, 0),
+                fraction: Perbill::from_percent(10),
+                at: T::SlashDeferTimer::now(),
+            })
+            .collect();
+        let candidate = slashes
+            .last()
+            .map(|slash| slash.candidate.clone())
+            .ok_or(BenchmarkError::Weightless)?;
+        DeferredSlashes::<T>::put(BoundedVec::truncate_from(slashes));
+
+        let origin = T::SlashCancelOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, candidate.clone());
+
+        assert_eq!(DeferredSlashes::<T>::get().len() as u32, max - 1);
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_commission() -> Result<(), BenchmarkError> {
+        let (min, max) = (Perbill::from_percent(10), Perbill::from_percent(30));
+        CommissionBounds::<T>::put((min, max));
+
+        let (candidate, _) = create_funded_user::<T>("candidate", 1, Zero::zero());
+        // Worst case is an increase replacing a pending increase.
+        PendingCommissionIncreases::<T>::insert(
+            &candidate,
+            PendingCommission {
+                commission: max,
+                at: T::CommissionIncreaseTimer::now(),
+            },
+        );
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(candidate.clone()),
+            Perbill::from_percent(25),
+        );
+
+        assert_eq!(
+            PendingCommissionIncreases::<T>::get(&candidate).map(|pending| pending.commission),
+            Some(Perbill::from_percent(25))
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn execute_commission_increase() -> Result<(), BenchmarkError> {
+        let (min, max) = (Perbill::from_percent(10), Perbill::from_percent(30));
+        CommissionBounds::<T>::put((min, max));
+
+        let (candidate, _) = create_funded_user::<T>("candidate", 1, Zero::zero());
+        let (caller, _) = create_funded_user::<T>("caller", 2, Zero::zero());
+        PendingCommissionIncreases::<T>::insert(
+            &candidate,
+            PendingCommission {
+                commission: max,
+                at: T::CommissionIncreaseTimer::now(),
+            },
+        );
+        T::CommissionIncreaseTimer::skip_to_elapsed();
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(caller), candidate.clone());
+
+        assert_eq!(CandidateCommission::<T>::get(&candidate), Some(max));
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_commission_bounds() -> Result<(), BenchmarkError> {
+        let (min, max) = (Perbill::from_percent(10), Perbill::from_percent(30));
+
+        let origin = T::CommissionBoundsOrigin::try_successful_origin()
+            .map_err(|_| BenchmarkError::Weightless)?;
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, min, max);
+
+        assert_eq!(CommissionBounds::<T>::get(), (min, max));
+
+        Ok(())
+    }
+
     impl_benchmark_test_suite!(
         PooledStaking,
         crate::mock::ExtBuilder::default().build(),
@@ -18,6 +18,7 @@ use {
     crate::{
         candidate::Candidates,
         pools::{self, Pool},
+        slashing::Slashing,
         traits::Timer,
         AllTargetPool, Candidate, Config, Delegator, Error, Event, HoldReason, Pallet,
         PendingOperationKey, PendingOperationQuery, PendingOperationQueryOf, PendingOperations,
@@ -103,6 +104,8 @@ impl<T: Config> Calls<T> {
                 diff,
                 Preservation::Preserve,
             )?;
+            // Excess holds are mostly caused by slashes, which can now be paid.
+            Slashing::<T>::pay_unpaid_slashed_funds(&candidate, diff)?;
             return Ok(().into());
         }
 
@@ -278,11 +281,12 @@ impl<T: Config> Calls<T> {
         pool: TargetPool,
         joining_shares: Shares<T::Balance>,
     ) -> DispatchResultWithPostInfo {
+        // No rewards are distributed to the Joining pools, so there should always
+        // be enough hold. However it can be too much hold if the candidate was slashed.
+        Self::release_excess_hold(&candidate, &delegator, AllTargetPool::Joining)?;
+
         // Convert joining shares into stake.
         let stake = pools::Joining::<T>::sub_shares(&candidate, &delegator, joining_shares)?;
-
-        // No rewards are distributed to the Joining pools, so there should always
-        // be enough hold. Thus no need to rebalance.
         pools::Joining::<T>::decrease_hold(&candidate, &delegator, &stake)?;
 
         // Any change in the amount of Manual Rewards shares requires to claim manual rewards.
@@ -378,11 +382,12 @@ impl<T: Config> Calls<T> {
         delegator: Delegator<T>,
         leavinig_shares: Shares<T::Balance>,
     ) -> DispatchResultWithPostInfo {
+        // No rewards are distributed to the Leaving pools, so there should always
+        // be enough hold. However it can be too much hold if the candidate was slashed.
+        Self::release_excess_hold(&candidate, &delegator, AllTargetPool::Leaving)?;
+
         // Convert leaving shares into stake.
         let stake = pools::Leaving::<T>::sub_shares(&candidate, &delegator, leavinig_shares)?;
-
-        // No rewards are distributed to the Leaving pools, so there should always
-        // be enough hold. Thus no need to rebalance.
         pools::Leaving::<T>::decrease_hold(&candidate, &delegator, &stake)?;
 
         // We release the funds and consider them unstaked.
@@ -533,6 +538,8 @@ impl<T: Config> Calls<T> {
         pool: TargetPool,
         shares: Shares<T::Balance>,
     ) -> Result<Stake<T::Balance>, DispatchErrorWithPostInfo> {
+        Self::release_excess_hold(candidate, delegator, pool.into())?;
+
         match pool {
             TargetPool::AutoCompounding => {
                 let stake = pools::AutoCompounding::<T>::shares_to_stake(candidate, shares)?;
@@ -571,6 +578,52 @@ impl<T: Config> Calls<T> {
         }
     }
 
+    /// Rebalance the hold of the delegator if it is higher than the value of all its shares in
+    /// that pool, which happens if the candidate was slashed. Otherwise the excess would stay
+    /// held after all the shares are destroyed.
+    fn release_excess_hold(
+        candidate: &Candidate<T>,
+        delegator: &Delegator<T>,
+        pool: AllTargetPool,
+    ) -> Result<(), DispatchErrorWithPostInfo> {
+        let (hold, stake) = match pool {
+            AllTargetPool::Joining => {
+                let shares = pools::Joining::<T>::shares(candidate, delegator);
+                (
+                    pools::Joining::<T>::hold(candidate, delegator),
+                    pools::Joining::<T>::shares_to_stake(candidate, shares)?,
+                )
+            }
+            AllTargetPool::AutoCompounding => {
+                let shares = pools::AutoCompounding::<T>::shares(candidate, delegator);
+                (
+                    pools::AutoCompounding::<T>::hold(candidate, delegator),
+                    pools::AutoCompounding::<T>::shares_to_stake(candidate, shares)?,
+                )
+            }
+            AllTargetPool::ManualRewards => {
+                let shares = pools::ManualRewards::<T>::shares(candidate, delegator);
+                (
+                    pools::ManualRewards::<T>::hold(candidate, delegator),
+                    pools::ManualRewards::<T>::shares_to_stake(candidate, shares)?,
+                )
+            }
+            AllTargetPool::Leaving => {
+                let shares = pools::Leaving::<T>::shares(candidate, delegator);
+                (
+                    pools::Leaving::<T>::hold(candidate, delegator),
+                    pools::Leaving::<T>::shares_to_stake(candidate, shares)?,
+                )
+            }
+        };
+
+        if hold.0 > stake.0 {
+            Self::rebalance_hold(candidate.clone(), delegator.clone(), pool)?;
+        }
+
+        Ok(())
+    }
+
     /// Perform the leaving proceduce with provided stake, which will create
     /// Leaving shares and request, and release the rounding dust. It DOES NOT
     /// destroy shares in other pools.
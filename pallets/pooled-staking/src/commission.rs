@@ -0,0 +1,109 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{
+        traits::Timer, Candidate, CandidateCommission, CommissionBounds, Config, Error, Event,
+        Pallet, PendingCommission, PendingCommissionIncreases,
+    },
+    frame_support::pallet_prelude::*,
+    sp_core::Get,
+    sp_runtime::Perbill,
+};
+
+pub struct Commission<T>(PhantomData<T>);
+
+impl<T: Config> Commission<T> {
+    /// Commission applied to the rewards of the candidate. Commissions set before governance
+    /// changed the bounds are clamped into the new bounds.
+    pub fn effective_commission(candidate: &Candidate<T>) -> Perbill {
+        let (min, max) = CommissionBounds::<T>::get();
+        let commission = CandidateCommission::<T>::get(candidate)
+            .unwrap_or_else(T::RewardsCollatorCommission::get);
+
+        commission.clamp(min, max)
+    }
+
+    pub fn set_commission(
+        candidate: Candidate<T>,
+        commission: Perbill,
+    ) -> DispatchResultWithPostInfo {
+        let (min, max) = CommissionBounds::<T>::get();
+        ensure!(
+            min <= commission && commission <= max,
+            Error::<T>::CommissionOutOfBounds
+        );
+
+        // Any new request replaces the pending increase.
+        PendingCommissionIncreases::<T>::remove(&candidate);
+
+        if commission <= Self::effective_commission(&candidate) {
+            Self::apply_commission(candidate, commission);
+        } else {
+            PendingCommissionIncreases::<T>::insert(
+                &candidate,
+                PendingCommission {
+                    commission,
+                    at: T::CommissionIncreaseTimer::now(),
+                },
+            );
+
+            Pallet::<T>::deposit_event(Event::<T>::CommissionIncreaseRequested {
+                candidate,
+                commission,
+            });
+        }
+
+        Ok(().into())
+    }
+
+    pub fn execute_commission_increase(candidate: Candidate<T>) -> DispatchResultWithPostInfo {
+        let pending = PendingCommissionIncreases::<T>::get(&candidate)
+            .ok_or(Error::<T>::NoPendingCommissionIncrease)?;
+
+        ensure!(
+            T::CommissionIncreaseTimer::is_elapsed(&pending.at),
+            Error::<T>::CommissionIncreaseNotReady
+        );
+
+        PendingCommissionIncreases::<T>::remove(&candidate);
+
+        // Bounds could have been changed while the increase was pending.
+        let (min, max) = CommissionBounds::<T>::get();
+        Self::apply_commission(candidate, pending.commission.clamp(min, max));
+
+        Ok(().into())
+    }
+
+    pub fn set_commission_bounds(min: Perbill, max: Perbill) -> DispatchResultWithPostInfo {
+        ensure!(min <= max, Error::<T>::InvalidCommissionBounds);
+
+        CommissionBounds::<T>::put((min, max));
+
+        Pallet::<T>::deposit_event(Event::<T>::CommissionBoundsUpdated { min, max });
+
+        Ok(().into())
+    }
+
+    fn apply_commission(candidate: Candidate<T>, commission: Perbill) {
+        CandidateCommission::<T>::insert(&candidate, commission);
+
+        Pallet::<T>::deposit_event(Event::<T>::CommissionUpdated {
+            candidate,
+            commission,
+        });
+    }
+}
@@ -0,0 +1,115 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{
+        calls::Calls, traits::Timer, weights::WeightInfo, AutoExecuteCursor, Config,
+        PendingOperationKey, PendingOperationKeyOf, PendingOperationQuery, PendingOperations,
+    },
+    frame_support::{pallet_prelude::*, storage::with_storage_layer},
+    sp_core::Get,
+    sp_std::vec,
+};
+
+pub struct Executor<T>(PhantomData<T>);
+
+impl<T: Config> Executor<T> {
+    pub fn is_matured(operation: &PendingOperationKeyOf<T>) -> bool {
+        match operation {
+            PendingOperationKey::JoiningAutoCompounding { at, .. }
+            | PendingOperationKey::JoiningManualRewards { at, .. } => {
+                T::JoiningRequestTimer::is_elapsed(at)
+            }
+            PendingOperationKey::Leaving { at, .. } => T::LeavingRequestTimer::is_elapsed(at),
+        }
+    }
+
+    /// Worst case weight of executing a single pending operation, which is the same as the
+    /// weight of `execute_pending_operations` for a single operation.
+    pub fn execution_weight() -> Weight {
+        T::WeightInfo::execute_pending_operations(1)
+            .saturating_add(T::WeightInfo::claim_manual_rewards(1))
+            .saturating_add(T::WeightInfo::rebalance_hold())
+    }
+
+    /// Check pending operations in storage order starting after `AutoExecuteCursor`, as many as
+    /// allowed by `MaxAutoExecutedOperations` and `remaining_weight`, and execute the ones that
+    /// are matured. Once the end of `PendingOperations` is reached the cursor is reset, such that
+    /// all operations are eventually checked. Returns the consumed weight.
+    pub fn execute_matured_operations(remaining_weight: Weight) -> Weight {
+        let max_checked = T::MaxAutoExecutedOperations::get();
+        // Read and write `AutoExecuteCursor`.
+        let mut used_weight = T::DbWeight::get().reads_writes(1, 1);
+        // Reading the operation, then executing it in the worst case.
+        let read_weight = T::DbWeight::get().reads(1);
+        let operation_weight = read_weight.saturating_add(Self::execution_weight());
+
+        if max_checked == 0 || remaining_weight.any_lt(used_weight.saturating_add(operation_weight))
+        {
+            return Weight::zero();
+        }
+
+        let mut cursor = AutoExecuteCursor::<T>::get();
+        let mut operations = match &cursor {
+            Some(raw_key) => PendingOperations::<T>::iter_from(raw_key.clone()),
+            None => PendingOperations::<T>::iter(),
+        };
+        let mut checked = 0u32;
+
+        while checked < max_checked
+            && used_weight
+                .saturating_add(operation_weight)
+                .all_lte(remaining_weight)
+        {
+            let Some((delegator, operation, _)) = operations.next() else {
+                cursor = None;
+                break;
+            };
+
+            cursor = Some(operations.last_raw_key().to_vec());
+            checked = checked.saturating_add(1);
+            used_weight.saturating_accrue(read_weight);
+
+            if !Self::is_matured(&operation) {
+                continue;
+            }
+
+            used_weight.saturating_accrue(Self::execution_weight());
+
+            // A failing operation must not prevent the other ones from being executed. It stays
+            // in storage and can still be executed manually.
+            let res = with_storage_layer(|| {
+                Calls::<T>::execute_pending_operations(vec![PendingOperationQuery {
+                    delegator: delegator.clone(),
+                    operation: operation.clone(),
+                }])
+            });
+
+            if let Err(e) = res {
+                log::warn!(
+                    "Failed to execute pending operation {:?} of delegator {:?}: {:?}",
+                    operation,
+                    delegator,
+                    e.error,
+                );
+            }
+        }
+
+        AutoExecuteCursor::<T>::set(cursor);
+
+        used_weight
+    }
+}
@@ -30,12 +30,31 @@
 //! id dedicated to the staking pallet, and delegators can call an extrinsic to transfer their rewards
 //! to their own account (but as reserved). Keeping funds reserved in user accounts allow them to
 //! participate in other processes such as gouvernance.
+//!
+//! Candidates can be slashed with [`Pallet::slash`], which reduces the value of the shares of all
+//! their pools proportionally, including the leaving pool. Slashes are deferred and applied once
+//! `SlashDeferTimer` is elapsed, leaving time to cancel them with `cancel_deferred_slashes`. Since the funds backing the shares are
+//! held in the delegators accounts, slashed funds are transfered from `StakingAccount` to
+//! `SlashedFundsAccount` once the excess holds of the delegators of the slashed candidate are
+//! moved back to the staking account by `rebalance_hold`.
+//!
+//! Each candidate can set the part of its rewards it keeps exclusively as commission with
+//! `set_commission`, within bounds set by governance. Decreasing the commission is immediate, while
+//! increasing it must wait for `CommissionIncreaseTimer` to be elapsed to give delegators time to
+//! leave.
+//!
+//! Matured pending operations are executed automatically in `on_idle`, checking at most
+//! `MaxAutoExecutedOperations` of them per block and resuming from where the previous block
+//! stopped. They can still be executed manually with `execute_pending_operations`.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 mod calls;
 mod candidate;
+mod commission;
+mod executor;
 mod pools;
+mod slashing;
 pub mod traits;
 
 #[cfg(test)]
@@ -65,7 +84,7 @@ pub mod pallet {
         core::marker::PhantomData,
         frame_support::{
             pallet_prelude::*,
-            storage::types::{StorageDoubleMap, StorageValue, ValueQuery},
+            storage::types::{StorageDoubleMap, StorageMap, StorageValue, ValueQuery},
             traits::{fungible, tokens::Balance, IsType},
             Blake2_128Concat,
         },
@@ -73,7 +92,7 @@ pub mod pallet {
         parity_scale_codec::{Decode, Encode, FullCodec},
         scale_info::TypeInfo,
         sp_core::Get,
-        sp_runtime::{BoundedVec, Perbill},
+        sp_runtime::{traits::Zero, BoundedVec, Perbill},
         sp_std::vec::Vec,
         tp_maths::MulDiv,
     };
@@ -227,6 +246,80 @@ pub mod pallet {
     #[derive(RuntimeDebug, Default, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo)]
     pub struct Stake<T>(pub T);
 
+    /// A slash waiting for `SlashDeferTimer` to be elapsed before being applied.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct DeferredSlash<A, I> {
+        pub candidate: A,
+        /// Part of the stake of each pool of the candidate that will be slashed.
+        pub fraction: Perbill,
+        /// Instant at which the slash was requested.
+        pub at: I,
+    }
+
+    pub type DeferredSlashOf<T> = DeferredSlash<
+        <T as frame_system::Config>::AccountId,
+        <<T as Config>::SlashDeferTimer as Timer>::Instant,
+    >;
+
+    /// A commission increase waiting for `CommissionIncreaseTimer` to be elapsed before it can
+    /// be executed.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct PendingCommission<I> {
+        pub commission: Perbill,
+        /// Instant at which the increase was requested.
+        pub at: I,
+    }
+
+    /// Pending request of a delegator to join a pool of a candidate.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct PendingJoining<B, I> {
+        pub pool: TargetPool,
+        /// Value of the joining shares of the request.
+        pub stake: B,
+        /// Instant at which the request was made.
+        pub at: I,
+        /// Instant from which the request can be executed.
+        pub unlocks_at: Option<I>,
+    }
+
+    /// Pending request of a delegator to leave the pools of a candidate.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct PendingLeaving<B, I> {
+        /// Value of the leaving shares of the request.
+        pub stake: B,
+        /// Instant at which the request was made.
+        pub at: I,
+        /// Instant from which the request can be executed.
+        pub unlocks_at: Option<I>,
+    }
+
+    /// Everything a delegator has staked towards a candidate.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct DelegatorPosition<A, B, J, L> {
+        pub candidate: A,
+        pub joining: Vec<PendingJoining<B, J>>,
+        pub auto_compounding_stake: B,
+        pub manual_rewards_stake: B,
+        /// Manual rewards that can be claimed with `claim_manual_rewards`.
+        pub claimable_rewards: B,
+        pub leaving: Vec<PendingLeaving<B, L>>,
+    }
+
+    pub type DelegatorPositionOf<T> = DelegatorPosition<
+        <T as frame_system::Config>::AccountId,
+        <T as Config>::Balance,
+        <<T as Config>::JoiningRequestTimer as Timer>::Instant,
+        <<T as Config>::LeavingRequestTimer as Timer>::Instant,
+    >;
+
+    pub type PendingCommissionOf<T> =
+        PendingCommission<<<T as Config>::CommissionIncreaseTimer as Timer>::Instant>;
+
     /// Pooled Staking pallet.
     #[pallet::pallet]
     #[pallet::without_storage_info]
@@ -268,9 +361,14 @@ pub mod pallet {
         /// this minimum prevents from being elected.
         #[pallet::constant]
         type MinimumSelfDelegation: Get<Self::Balance>;
-        /// Part of the rewards that will be sent exclusively to the collator.
+        /// Part of the rewards that will be sent exclusively to the collator, unless it has set
+        /// its own commission. Also used as both commission bounds until governance sets them.
         #[pallet::constant]
         type RewardsCollatorCommission: Get<Perbill>;
+        /// Condition for when a commission increase can be executed.
+        type CommissionIncreaseTimer: Timer;
+        /// Origin allowed to set the bounds of the candidates commissions.
+        type CommissionBoundsOrigin: EnsureOrigin<Self::RuntimeOrigin>;
 
         /// The overarching runtime hold reason.
         type RuntimeHoldReason: From<HoldReason>;
@@ -289,6 +387,31 @@ pub mod pallet {
         /// Additional filter for candidates to be eligible.
         type EligibleCandidatesFilter: IsCandidateEligible<Self::AccountId>;
 
+        /// Account receiving the slashed funds, typically the treasury.
+        #[pallet::constant]
+        type SlashedFundsAccount: Get<Self::AccountId>;
+        /// Condition for when a deferred slash can be applied.
+        type SlashDeferTimer: Timer;
+        /// Maximum amount of slashes waiting to be applied.
+        #[pallet::constant]
+        type MaxDeferredSlashes: Get<u32>;
+        /// Maximum amount of deferred slashes applied in a single block. Slashes that are ready
+        /// but exceed this limit are applied in the following blocks.
+        #[pallet::constant]
+        type MaxSlashesAppliedPerBlock: Get<u32>;
+        /// Maximum amount of pool entries checked by the `on_idle` slash payer in a single block,
+        /// each of them potentially rebalancing the hold of a delegator. Setting it to 0 disables
+        /// the automatic payment of slashed funds.
+        #[pallet::constant]
+        type MaxSlashedHoldsRebalancedPerBlock: Get<u32>;
+        /// Origin allowed to cancel deferred slashes.
+        type SlashCancelOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Maximum amount of pending operations checked by the `on_idle` executor in a single
+        /// block. Setting it to 0 disables the automatic execution of matured operations.
+        #[pallet::constant]
+        type MaxAutoExecutedOperations: Get<u32>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -330,6 +453,51 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Slashes waiting for `SlashDeferTimer` to be elapsed, in the order they were requested.
+    #[pallet::storage]
+    pub type DeferredSlashes<T: Config> =
+        StorageValue<_, BoundedVec<DeferredSlashOf<T>, T::MaxDeferredSlashes>, ValueQuery>;
+
+    /// Slashed funds of each candidate that were not paid yet to `SlashedFundsAccount`, as they are
+    /// still held in the accounts of its delegators. They are paid when `rebalance_hold` moves
+    /// those funds back to `StakingAccount`.
+    #[pallet::storage]
+    pub type UnpaidSlashedFunds<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, T::Balance, ValueQuery>;
+
+    /// Candidate whose slashed funds are being paid by the `on_idle` slash payer, along with the
+    /// raw key of the last pool entry of that candidate it checked. `None` when the payer will
+    /// start from the first candidate in `UnpaidSlashedFunds`.
+    #[pallet::storage]
+    pub type SlashPaymentCursor<T: Config> =
+        StorageValue<_, (Candidate<T>, Option<Vec<u8>>), OptionQuery>;
+
+    /// Raw key of the last pending operation checked by the `on_idle` executor, which resumes
+    /// from the following one. `None` when the executor will start from the beginning.
+    #[pallet::storage]
+    pub type AutoExecuteCursor<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultCommissionBounds<T: Config>() -> (Perbill, Perbill) {
+        let commission = T::RewardsCollatorCommission::get();
+        (commission, commission)
+    }
+
+    /// Minimum and maximum commission candidates can set.
+    #[pallet::storage]
+    pub type CommissionBounds<T: Config> =
+        StorageValue<_, (Perbill, Perbill), ValueQuery, DefaultCommissionBounds<T>>;
+
+    /// Commission set by each candidate. Candidates without one use `RewardsCollatorCommission`.
+    #[pallet::storage]
+    pub type CandidateCommission<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, Perbill, OptionQuery>;
+
+    /// Commission increases waiting for `CommissionIncreaseTimer` to be elapsed.
+    #[pallet::storage]
+    pub type PendingCommissionIncreases<T: Config> =
+        StorageMap<_, Blake2_128Concat, Candidate<T>, PendingCommissionOf<T>, OptionQuery>;
+
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
@@ -446,6 +614,35 @@ pub mod pallet {
             pending_leaving: T::Balance,
             released: T::Balance,
         },
+        /// A slash of that candidate has been requested, and will be applied once
+        /// `SlashDeferTimer` is elapsed.
+        SlashDeferred {
+            candidate: Candidate<T>,
+            fraction: Perbill,
+        },
+        /// Deferred slashes of that candidate have been cancelled.
+        DeferredSlashesCancelled { candidate: Candidate<T>, count: u32 },
+        /// Candidate has been slashed. `amount` has been removed from its pools, and is added to
+        /// `UnpaidSlashedFunds` until it is transfered to `SlashedFundsAccount` once held funds
+        /// are rebalanced.
+        Slashed {
+            candidate: Candidate<T>,
+            fraction: Perbill,
+            amount: T::Balance,
+        },
+        /// Candidate requested to increase its commission, which can be executed once
+        /// `CommissionIncreaseTimer` is elapsed.
+        CommissionIncreaseRequested {
+            candidate: Candidate<T>,
+            commission: Perbill,
+        },
+        /// Commission of the candidate has been updated.
+        CommissionUpdated {
+            candidate: Candidate<T>,
+            commission: Perbill,
+        },
+        /// Bounds of the candidates commissions have been updated.
+        CommissionBoundsUpdated { min: Perbill, max: Perbill },
     }
 
     #[pallet::error]
@@ -464,6 +661,13 @@ pub mod pallet {
         CandidateTransferingOwnSharesForbidden,
         RequestCannotBeExecuted(u16),
         SwapResultsInZeroShares,
+        InvalidSlashFraction,
+        TooManyDeferredSlashes,
+        NoDeferredSlashes,
+        CommissionOutOfBounds,
+        InvalidCommissionBounds,
+        NoPendingCommissionIncrease,
+        CommissionIncreaseNotReady,
     }
 
     impl<T: Config> From<tp_maths::OverflowError> for Error<T> {
@@ -480,6 +684,18 @@ pub mod pallet {
 
     #[pallet::hooks]
     impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            slashing::Slashing::<T>::apply_deferred_slashes()
+        }
+
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            let mut used_weight = slashing::Slashing::<T>::pay_slashed_funds(remaining_weight);
+            used_weight.saturating_accrue(executor::Executor::<T>::execute_matured_operations(
+                remaining_weight.saturating_sub(used_weight),
+            ));
+            used_weight
+        }
+
         #[cfg(feature = "try-runtime")]
         fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
             use sp_std::collections::btree_set::BTreeSet;
@@ -540,7 +756,7 @@ pub mod pallet {
 
         /// Execute pending operations can incur in claim manual rewards per operation, we simply add the worst case
         #[pallet::call_index(2)]
-        #[pallet::weight(T::WeightInfo::execute_pending_operations(operations.len() as u32).saturating_add(T::WeightInfo::claim_manual_rewards(operations.len() as u32)))]
+        #[pallet::weight(T::WeightInfo::execute_pending_operations(operations.len() as u32).saturating_add(T::WeightInfo::claim_manual_rewards(operations.len() as u32)).saturating_add(T::WeightInfo::rebalance_hold().saturating_mul(operations.len() as u64)))]
         pub fn execute_pending_operations(
             origin: OriginFor<T>,
             operations: Vec<PendingOperationQueryOf<T>>,
@@ -601,6 +817,57 @@ pub mod pallet {
 
             Calls::<T>::swap_pool(candidate, delegator, source_pool, amount)
         }
+
+        /// Cancel all the deferred slashes of a candidate that are not applied yet.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::cancel_deferred_slashes())]
+        pub fn cancel_deferred_slashes(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+        ) -> DispatchResultWithPostInfo {
+            T::SlashCancelOrigin::ensure_origin(origin)?;
+
+            slashing::Slashing::<T>::cancel_deferred_slashes(candidate)
+        }
+
+        /// Set the commission of the caller as a candidate. Decreases are applied immediately,
+        /// while increases are pending until `CommissionIncreaseTimer` is elapsed.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_commission())]
+        pub fn set_commission(
+            origin: OriginFor<T>,
+            commission: Perbill,
+        ) -> DispatchResultWithPostInfo {
+            let candidate = ensure_signed(origin)?;
+
+            commission::Commission::<T>::set_commission(candidate, commission)
+        }
+
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::WeightInfo::execute_commission_increase())]
+        pub fn execute_commission_increase(
+            origin: OriginFor<T>,
+            candidate: Candidate<T>,
+        ) -> DispatchResultWithPostInfo {
+            // We don't care about the sender.
+            let _ = ensure_signed(origin)?;
+
+            commission::Commission::<T>::execute_commission_increase(candidate)
+        }
+
+        /// Set the bounds of the candidates commissions. Commissions outside of the new bounds
+        /// are clamped when distributing rewards.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::WeightInfo::set_commission_bounds())]
+        pub fn set_commission_bounds(
+            origin: OriginFor<T>,
+            min: Perbill,
+            max: Perbill,
+        ) -> DispatchResultWithPostInfo {
+            T::CommissionBoundsOrigin::ensure_origin(origin)?;
+
+            commission::Commission::<T>::set_commission_bounds(min, max)
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -627,6 +894,126 @@ pub mod pallet {
             .ok()
             .map(|x| x.0)
         }
+
+        /// Get the positions of a delegator towards all the candidates it has shares of, including
+        /// pending joining and leaving requests. This iterates over the whole `Pools` map, so its
+        /// cost grows with the number of delegations of every candidate. It must not be called
+        /// from the runtime and is only meant to back the runtime API, whose RPC is unsafe.
+        pub fn delegator_positions(
+            delegator: &Delegator<T>,
+        ) -> Result<Vec<DelegatorPositionOf<T>>, Error<T>> {
+            use {pools::Pool, sp_std::collections::btree_map::BTreeMap};
+
+            let mut positions = BTreeMap::new();
+            let new_position = |candidate: &Candidate<T>| DelegatorPosition {
+                candidate: candidate.clone(),
+                joining: Vec::new(),
+                auto_compounding_stake: Zero::zero(),
+                manual_rewards_stake: Zero::zero(),
+                claimable_rewards: Zero::zero(),
+                leaving: Vec::new(),
+            };
+
+            for (candidate, key) in Pools::<T>::iter_keys() {
+                match key {
+                    PoolsKey::AutoCompoundingShares { delegator: d } if &d == delegator => {
+                        let position = positions
+                            .entry(candidate.clone())
+                            .or_insert_with(|| new_position(&candidate));
+                        position.auto_compounding_stake =
+                            pools::AutoCompounding::<T>::computed_stake(&candidate, delegator)?.0;
+                    }
+                    PoolsKey::ManualRewardsShares { delegator: d } if &d == delegator => {
+                        let position = positions
+                            .entry(candidate.clone())
+                            .or_insert_with(|| new_position(&candidate));
+                        position.manual_rewards_stake =
+                            pools::ManualRewards::<T>::computed_stake(&candidate, delegator)?.0;
+                        position.claimable_rewards =
+                            pools::ManualRewards::<T>::pending_rewards(&candidate, delegator)?.0;
+                    }
+                    _ => (),
+                }
+            }
+
+            for (operation, shares) in PendingOperations::<T>::iter_prefix(delegator) {
+                let (candidate, at, pool) = match operation {
+                    PendingOperationKey::JoiningAutoCompounding { candidate, at } => {
+                        (candidate, at, TargetPool::AutoCompounding)
+                    }
+                    PendingOperationKey::JoiningManualRewards { candidate, at } => {
+                        (candidate, at, TargetPool::ManualRewards)
+                    }
+                    PendingOperationKey::Leaving { candidate, at } => {
+                        let stake =
+                            pools::Leaving::<T>::shares_to_stake(&candidate, Shares(shares))?.0;
+                        let unlocks_at = T::LeavingRequestTimer::elapsed_at(&at);
+
+                        positions
+                            .entry(candidate.clone())
+                            .or_insert_with(|| new_position(&candidate))
+                            .leaving
+                            .push(PendingLeaving {
+                                stake,
+                                at,
+                                unlocks_at,
+                            });
+                        continue;
+                    }
+                };
+
+                let stake = pools::Joining::<T>::shares_to_stake(&candidate, Shares(shares))?.0;
+                let unlocks_at = T::JoiningRequestTimer::elapsed_at(&at);
+
+                positions
+                    .entry(candidate.clone())
+                    .or_insert_with(|| new_position(&candidate))
+                    .joining
+                    .push(PendingJoining {
+                        pool,
+                        stake,
+                        at,
+                        unlocks_at,
+                    });
+            }
+
+            Ok(positions.into_values().collect())
+        }
+
+        /// Commission currently applied to the rewards of a candidate.
+        pub fn commission(candidate: &Candidate<T>) -> Perbill {
+            commission::Commission::<T>::effective_commission(candidate)
+        }
+
+        /// Request to slash `fraction` of the stake of all the pools of a candidate, including
+        /// its leaving pool. The slash is applied once `SlashDeferTimer` is elapsed. Candidates
+        /// without any stake are ignored.
+        ///
+        /// `fraction` must be lower than 100%, as a pool with shares but no stake can't be
+        /// used anymore.
+        pub fn slash(candidate: Candidate<T>, fraction: Perbill) -> DispatchResult {
+            ensure!(fraction < Perbill::one(), Error::<T>::InvalidSlashFraction);
+
+            if fraction.is_zero() || !slashing::Slashing::<T>::has_stake(&candidate) {
+                return Ok(());
+            }
+
+            DeferredSlashes::<T>::try_mutate(|slashes| {
+                slashes.try_push(DeferredSlash {
+                    candidate: candidate.clone(),
+                    fraction,
+                    at: T::SlashDeferTimer::now(),
+                })
+            })
+            .map_err(|_| Error::<T>::TooManyDeferredSlashes)?;
+
+            Pallet::<T>::deposit_event(Event::<T>::SlashDeferred {
+                candidate,
+                fraction,
+            });
+
+            Ok(())
+        }
     }
 
     impl<T: Config> tp_traits::DistributeRewards<Candidate<T>, CreditOf<T>> for Pallet<T> {
@@ -637,4 +1024,10 @@ pub mod pallet {
             pools::distribute_rewards::<T>(&candidate, rewards)
         }
     }
+
+    impl<T: Config> tp_traits::GetCollatorStake<Candidate<T>, T::Balance> for Pallet<T> {
+        fn collator_stake(collator: &Candidate<T>) -> T::Balance {
+            candidate::Candidates::<T>::total_stake(collator).0
+        }
+    }
 }
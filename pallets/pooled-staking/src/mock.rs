@@ -29,7 +29,7 @@ use {
             Everything, OnFinalize, OnInitialize,
         },
     },
-    frame_system::pallet_prelude::BlockNumberFor,
+    frame_system::{pallet_prelude::BlockNumberFor, EnsureRoot},
     num_traits::Num,
     parity_scale_codec::{Decode, Encode, MaxEncodedLen},
     scale_info::TypeInfo,
@@ -66,6 +66,7 @@ pub const ACCOUNT_CANDIDATE_1: u64 = 1;
 pub const ACCOUNT_CANDIDATE_2: u64 = 2;
 pub const ACCOUNT_DELEGATOR_1: u64 = 3;
 pub const ACCOUNT_DELEGATOR_2: u64 = 4;
+pub const ACCOUNT_SLASHED_FUNDS: u64 = 5;
 
 pub const KILO: u128 = 1000;
 pub const MEGA: u128 = 1000 * KILO;
@@ -154,6 +155,7 @@ parameter_types! {
     pub const MinimumSelfDelegation: u128 = 10 * MEGA;
     pub const RewardsCollatorCommission: Perbill = Perbill::from_percent(20);
     pub const BlocksToWait: u64 = BLOCKS_TO_WAIT;
+    pub const SlashedFundsAccount: u64 = ACCOUNT_SLASHED_FUNDS;
 }
 
 impl pallet_pooled_staking::Config for Runtime {
@@ -165,11 +167,24 @@ impl pallet_pooled_staking::Config for Runtime {
     type InitialAutoCompoundingShareValue = InitialAutoCompoundingShareValue;
     type MinimumSelfDelegation = MinimumSelfDelegation;
     type RewardsCollatorCommission = RewardsCollatorCommission;
+    type CommissionIncreaseTimer = BlockNumberTimer<Self, BlocksToWait>;
+    type CommissionBoundsOrigin = EnsureRoot<AccountId>;
     type JoiningRequestTimer = BlockNumberTimer<Self, BlocksToWait>;
     type LeavingRequestTimer = BlockNumberTimer<Self, BlocksToWait>;
     // low value so we can test vec bounding, in practice it should be bigger
     type EligibleCandidatesBufferSize = ConstU32<3>;
     type EligibleCandidatesFilter = ();
+    type SlashedFundsAccount = SlashedFundsAccount;
+    type SlashDeferTimer = BlockNumberTimer<Self, BlocksToWait>;
+    // low value so we can test vec bounding, in practice it should be bigger
+    type MaxDeferredSlashes = ConstU32<3>;
+    // low value so we can test slashes carried over to the next block
+    type MaxSlashesAppliedPerBlock = ConstU32<2>;
+    // low value so we can test the cursor, in practice it should be bigger
+    type MaxSlashedHoldsRebalancedPerBlock = ConstU32<2>;
+    type SlashCancelOrigin = EnsureRoot<AccountId>;
+    // low value so we can test the cursor, in practice it should be bigger
+    type MaxAutoExecutedOperations = ConstU32<2>;
     type WeightInfo = ();
     type RuntimeHoldReason = RuntimeHoldReason;
 }
@@ -384,7 +399,7 @@ pub(crate) fn roll_one_block() -> u64 {
     System::set_block_number(System::block_number() + 1);
     System::on_initialize(System::block_number());
     Balances::on_initialize(System::block_number());
-    // Staking::on_initialize(System::block_number());
+    Staking::on_initialize(System::block_number());
     System::block_number()
 }
 
@@ -16,8 +16,8 @@
 
 use {
     crate::{
-        candidate::Candidates, weights::WeightInfo, Candidate, Config, CreditOf, Delegator, Error,
-        Event, Pallet, Pools, PoolsKey, Shares, Stake,
+        candidate::Candidates, commission::Commission, weights::WeightInfo, Candidate, Config,
+        CreditOf, Delegator, Error, Event, Pallet, Pools, PoolsKey, Shares, Stake,
     },
     core::marker::PhantomData,
     frame_support::{
@@ -436,7 +436,7 @@ impl<T: Config> ManualRewards<T> {
 /// and it is the responsibility of the caller to transfer or mint the currency
 /// to the staking pallet account.
 ///
-/// Rewards are split using `RewardsCollatorCommission` between the candidate
+/// Rewards are split using the candidate commission between the candidate
 /// and all the delegators (including the candidate self-delegation). For each,
 /// the rewards are then split according to the value of all the ManualRewards
 /// and AutoCompounding shares.
@@ -479,8 +479,8 @@ fn distribute_rewards_inner<T: Config>(
     candidate: &Candidate<T>,
     rewards: T::Balance,
 ) -> Result<T::Balance, Error<T>> {
-    // `RewardsCollatorCommission` is a `Perbill` so we're not worried about overflow.
-    let candidate_rewards = T::RewardsCollatorCommission::get() * rewards;
+    // Commission is a `Perbill` so we're not worried about overflow.
+    let candidate_rewards = Commission::<T>::effective_commission(candidate) * rewards;
     let delegators_rewards = rewards.err_sub(&candidate_rewards)?;
 
     let Stake(auto_total_stake) = AutoCompounding::<T>::total_staked(candidate);
@@ -0,0 +1,282 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    crate::{
+        calls::Calls,
+        candidate::Candidates,
+        pools::{self, Pool},
+        traits::Timer,
+        weights::WeightInfo,
+        AllTargetPool, Candidate, Config, DeferredSlashes, Error, Event, Pallet, Pools, PoolsKey,
+        SlashPaymentCursor, Stake, UnpaidSlashedFunds,
+    },
+    frame_support::{
+        pallet_prelude::*,
+        storage::with_storage_layer,
+        traits::{fungible::Mutate, tokens::Preservation},
+    },
+    sp_core::Get,
+    sp_runtime::{traits::Zero, BoundedVec, Perbill},
+    tp_maths::{ErrAdd, ErrSub},
+};
+
+pub struct Slashing<T>(PhantomData<T>);
+
+impl<T: Config> Slashing<T> {
+    pub fn has_stake(candidate: &Candidate<T>) -> bool {
+        !Candidates::<T>::total_stake(candidate).0.is_zero()
+    }
+
+    /// Apply the deferred slashes for which `SlashDeferTimer` is elapsed, at most
+    /// `MaxSlashesAppliedPerBlock` of them. Slashes are stored in the order they were requested,
+    /// so we stop at the first one which is not ready yet.
+    pub fn apply_deferred_slashes() -> Weight {
+        let slashes = DeferredSlashes::<T>::get();
+
+        let ready = slashes
+            .iter()
+            .take_while(|slash| T::SlashDeferTimer::is_elapsed(&slash.at))
+            .take(T::MaxSlashesAppliedPerBlock::get() as usize)
+            .count();
+
+        if ready == 0 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut slashes = slashes.into_inner();
+        let remaining = slashes.split_off(ready);
+        DeferredSlashes::<T>::put(BoundedVec::truncate_from(remaining));
+
+        for slash in slashes {
+            // A failing slash must not prevent the other ones from being applied, and must
+            // not leave the pools in a partially slashed state.
+            let res = with_storage_layer(|| Self::apply_slash(&slash.candidate, slash.fraction));
+
+            if let Err(e) = res {
+                log::warn!(
+                    "Failed to apply slash of {:?} to candidate {:?}: {:?}",
+                    slash.fraction,
+                    slash.candidate,
+                    e,
+                );
+            }
+        }
+
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(T::WeightInfo::apply_slash().saturating_mul(ready as u64))
+    }
+
+    /// Slash `fraction` of the stake of all the pools of the candidate. The slashed funds are
+    /// transfered to `SlashedFundsAccount` once they are released from the holds of its
+    /// delegators.
+    pub fn apply_slash(candidate: &Candidate<T>, fraction: Perbill) -> DispatchResult {
+        let joining = fraction.mul_floor(pools::Joining::<T>::total_staked(candidate).0);
+        let auto = fraction.mul_floor(pools::AutoCompounding::<T>::total_staked(candidate).0);
+        let manual = fraction.mul_floor(pools::ManualRewards::<T>::total_staked(candidate).0);
+        let leaving = fraction.mul_floor(pools::Leaving::<T>::total_staked(candidate).0);
+
+        // Reducing the total staked of each pool reduces the value of all their shares.
+        pools::Joining::<T>::slash_stake_among_holders(candidate, Stake(joining))?;
+        pools::AutoCompounding::<T>::slash_stake_among_holders(candidate, Stake(auto))?;
+        pools::ManualRewards::<T>::slash_stake_among_holders(candidate, Stake(manual))?;
+        pools::Leaving::<T>::slash_stake_among_holders(candidate, Stake(leaving))?;
+
+        // This stake no longer contribute to the election of the candidate, which could also
+        // make its self delegation fall below the minimum. Leaving stake already doesn't.
+        let election_stake = joining
+            .err_add(&auto)
+            .and_then(|x| x.err_add(&manual))
+            .map_err(Error::<T>::from)?;
+        Candidates::<T>::sub_total_stake(candidate, Stake(election_stake))?;
+        let amount = election_stake.err_add(&leaving).map_err(Error::<T>::from)?;
+
+        pools::check_candidate_consistency::<T>(candidate)?;
+
+        // Funds backing the slashed stake are held in the accounts of the delegators of this
+        // candidate. The staking account also holds funds of other candidates, so the slash is
+        // only paid when `rebalance_hold` moves the excess holds back to the staking account,
+        // which is done for all the delegators by `pay_slashed_funds`.
+        UnpaidSlashedFunds::<T>::try_mutate(candidate, |total| -> Result<(), Error<T>> {
+            *total = total.err_add(&amount).map_err(Error::<T>::from)?;
+            Ok(())
+        })?;
+
+        Pallet::<T>::deposit_event(Event::<T>::Slashed {
+            candidate: candidate.clone(),
+            fraction,
+            amount,
+        });
+
+        Ok(())
+    }
+
+    /// Transfer up to `released` of the slashed funds of the candidate that were not paid yet
+    /// from the staking account to `SlashedFundsAccount`. `released` must be funds that were just
+    /// released from the holds of the delegators of this candidate.
+    pub fn pay_unpaid_slashed_funds(
+        candidate: &Candidate<T>,
+        released: T::Balance,
+    ) -> DispatchResult {
+        let unpaid = UnpaidSlashedFunds::<T>::get(candidate);
+        let amount = released.min(unpaid);
+
+        if amount.is_zero() {
+            return Ok(());
+        }
+
+        T::Currency::transfer(
+            &T::StakingAccount::get(),
+            &T::SlashedFundsAccount::get(),
+            amount,
+            Preservation::Preserve,
+        )?;
+
+        let unpaid = unpaid.err_sub(&amount).map_err(Error::<T>::from)?;
+        if unpaid.is_zero() {
+            UnpaidSlashedFunds::<T>::remove(candidate);
+        } else {
+            UnpaidSlashedFunds::<T>::insert(candidate, unpaid);
+        }
+
+        Ok(())
+    }
+
+    /// Pay the slashed funds of the candidates in `UnpaidSlashedFunds` by rebalancing the holds
+    /// of their delegators. Pool entries of the candidate being paid are checked in storage order
+    /// starting after `SlashPaymentCursor`, as many as allowed by
+    /// `MaxSlashedHoldsRebalancedPerBlock` and `remaining_weight`. Once all the pool entries of a
+    /// candidate have been checked, or its slashed funds are paid, the next candidate is paid,
+    /// such that all unpaid slashed funds are eventually paid. Returns the consumed weight.
+    pub fn pay_slashed_funds(remaining_weight: Weight) -> Weight {
+        let max_checked = T::MaxSlashedHoldsRebalancedPerBlock::get();
+        // Read and write `SlashPaymentCursor`.
+        let mut used_weight = T::DbWeight::get().reads_writes(1, 1);
+        // Reading the pool entry or the next candidate, then rebalancing the hold in the worst
+        // case.
+        let read_weight = T::DbWeight::get().reads(1);
+        let entry_weight = read_weight.saturating_add(T::WeightInfo::rebalance_hold());
+
+        if max_checked == 0 || remaining_weight.any_lt(used_weight.saturating_add(entry_weight)) {
+            return Weight::zero();
+        }
+
+        let mut cursor = SlashPaymentCursor::<T>::get();
+        let mut checked = 0u32;
+
+        while checked < max_checked
+            && used_weight
+                .saturating_add(entry_weight)
+                .all_lte(remaining_weight)
+        {
+            checked = checked.saturating_add(1);
+            used_weight.saturating_accrue(read_weight);
+
+            let Some((candidate, last_raw_key)) = cursor.take() else {
+                // Start from the first candidate with unpaid slashed funds.
+                match UnpaidSlashedFunds::<T>::iter_keys().next() {
+                    Some(candidate) => {
+                        cursor = Some((candidate, None));
+                        continue;
+                    }
+                    None => break,
+                }
+            };
+
+            let mut entries = match last_raw_key {
+                Some(raw_key) => Pools::<T>::iter_prefix_from(&candidate, raw_key),
+                None => Pools::<T>::iter_prefix(&candidate),
+            };
+
+            let Some((key, _)) = entries.next() else {
+                // All the pool entries of the candidate have been checked. What is left unpaid
+                // can still be paid by later rebalances.
+                cursor = Self::next_unpaid_candidate(&candidate).map(|next| (next, None));
+                if cursor.is_none() {
+                    break;
+                }
+                continue;
+            };
+
+            cursor = Some((candidate.clone(), Some(entries.last_raw_key().to_vec())));
+
+            let (delegator, pool) = match key {
+                PoolsKey::JoiningSharesHeldStake { delegator } => {
+                    (delegator, AllTargetPool::Joining)
+                }
+                PoolsKey::AutoCompoundingSharesHeldStake { delegator } => {
+                    (delegator, AllTargetPool::AutoCompounding)
+                }
+                PoolsKey::ManualRewardsSharesHeldStake { delegator } => {
+                    (delegator, AllTargetPool::ManualRewards)
+                }
+                PoolsKey::LeavingSharesHeldStake { delegator } => {
+                    (delegator, AllTargetPool::Leaving)
+                }
+                _ => continue,
+            };
+
+            used_weight.saturating_accrue(T::WeightInfo::rebalance_hold());
+
+            // A failing rebalance must not prevent the other ones from being performed.
+            let res = with_storage_layer(|| {
+                Calls::<T>::rebalance_hold(candidate.clone(), delegator.clone(), pool)
+            });
+
+            if let Err(e) = res {
+                log::warn!(
+                    "Failed to rebalance hold of delegator {:?} of slashed candidate {:?}: {:?}",
+                    delegator,
+                    candidate,
+                    e.error,
+                );
+            }
+
+            if !UnpaidSlashedFunds::<T>::contains_key(&candidate) {
+                cursor = Self::next_unpaid_candidate(&candidate).map(|next| (next, None));
+                if cursor.is_none() {
+                    break;
+                }
+            }
+        }
+
+        SlashPaymentCursor::<T>::set(cursor);
+
+        used_weight
+    }
+
+    /// Candidate with unpaid slashed funds following `candidate` in storage order, if any.
+    fn next_unpaid_candidate(candidate: &Candidate<T>) -> Option<Candidate<T>> {
+        UnpaidSlashedFunds::<T>::iter_keys_from(UnpaidSlashedFunds::<T>::hashed_key_for(candidate))
+            .next()
+    }
+
+    pub fn cancel_deferred_slashes(candidate: Candidate<T>) -> DispatchResultWithPostInfo {
+        let mut slashes = DeferredSlashes::<T>::get();
+        let count_before = slashes.len();
+        slashes.retain(|slash| slash.candidate != candidate);
+        let count = count_before.saturating_sub(slashes.len()) as u32;
+
+        ensure!(count != 0, Error::<T>::NoDeferredSlashes);
+
+        DeferredSlashes::<T>::put(slashes);
+
+        Pallet::<T>::deposit_event(Event::<T>::DeferredSlashesCancelled { candidate, count });
+
+        Ok(().into())
+    }
+}
@@ -0,0 +1,284 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    super::*,
+    crate::{CandidateCommission, CommissionBounds, PendingCommission, PendingCommissionIncreases},
+    sp_runtime::{DispatchError, Perbill},
+    tp_traits::DistributeRewards,
+};
+
+fn set_bounds(min: u32, max: u32) {
+    assert_ok!(Staking::set_commission_bounds(
+        RuntimeOrigin::root(),
+        Perbill::from_percent(min),
+        Perbill::from_percent(max),
+    ));
+}
+
+#[test]
+fn default_commission_is_rewards_collator_commission() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(
+            CommissionBounds::<Runtime>::get(),
+            (Perbill::from_percent(20), Perbill::from_percent(20))
+        );
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(20)
+        );
+    })
+}
+
+#[test]
+fn set_commission_bounds() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            Staking::set_commission_bounds(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                Perbill::from_percent(10),
+                Perbill::from_percent(30),
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Staking::set_commission_bounds(
+                RuntimeOrigin::root(),
+                Perbill::from_percent(30),
+                Perbill::from_percent(10),
+            ),
+            Error::<Runtime>::InvalidCommissionBounds
+        );
+
+        set_bounds(10, 30);
+        assert_eq!(
+            CommissionBounds::<Runtime>::get(),
+            (Perbill::from_percent(10), Perbill::from_percent(30))
+        );
+        assert_eq!(
+            events().last(),
+            Some(&Event::CommissionBoundsUpdated {
+                min: Perbill::from_percent(10),
+                max: Perbill::from_percent(30),
+            })
+        );
+    })
+}
+
+#[test]
+fn commission_out_of_bounds() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 30);
+
+        assert_noop!(
+            Staking::set_commission(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                Perbill::from_percent(5)
+            ),
+            Error::<Runtime>::CommissionOutOfBounds
+        );
+        assert_noop!(
+            Staking::set_commission(
+                RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+                Perbill::from_percent(35)
+            ),
+            Error::<Runtime>::CommissionOutOfBounds
+        );
+    })
+}
+
+#[test]
+fn commission_decrease_is_immediate() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 30);
+
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(10)
+        ));
+
+        assert_eq!(
+            CandidateCommission::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            Some(Perbill::from_percent(10))
+        );
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(10)
+        );
+        assert_eq!(
+            events().last(),
+            Some(&Event::CommissionUpdated {
+                candidate: ACCOUNT_CANDIDATE_1,
+                commission: Perbill::from_percent(10),
+            })
+        );
+    })
+}
+
+#[test]
+fn commission_increase_is_delayed() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 30);
+
+        let now = block_number();
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(30)
+        ));
+
+        assert_eq!(
+            PendingCommissionIncreases::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            Some(PendingCommission {
+                commission: Perbill::from_percent(30),
+                at: now,
+            })
+        );
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(20)
+        );
+        assert_eq!(
+            events().last(),
+            Some(&Event::CommissionIncreaseRequested {
+                candidate: ACCOUNT_CANDIDATE_1,
+                commission: Perbill::from_percent(30),
+            })
+        );
+
+        assert_noop!(
+            Staking::execute_commission_increase(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1
+            ),
+            Error::<Runtime>::CommissionIncreaseNotReady
+        );
+        assert_noop!(
+            Staking::execute_commission_increase(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_2
+            ),
+            Error::<Runtime>::NoPendingCommissionIncrease
+        );
+
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        assert_ok!(Staking::execute_commission_increase(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1
+        ));
+        assert_eq!(
+            PendingCommissionIncreases::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            None
+        );
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(30)
+        );
+        assert_eq!(
+            events().last(),
+            Some(&Event::CommissionUpdated {
+                candidate: ACCOUNT_CANDIDATE_1,
+                commission: Perbill::from_percent(30),
+            })
+        );
+    })
+}
+
+#[test]
+fn commission_decrease_cancels_pending_increase() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 30);
+
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(30)
+        ));
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(15)
+        ));
+
+        assert_eq!(
+            PendingCommissionIncreases::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+            None
+        );
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(15)
+        );
+    })
+}
+
+#[test]
+fn commission_is_clamped_into_new_bounds() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 30);
+
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(10)
+        ));
+
+        set_bounds(15, 30);
+        assert_eq!(
+            Staking::commission(&ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(15)
+        );
+    })
+}
+
+#[test]
+fn rewards_are_distributed_with_candidate_commission() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_bounds(10, 50);
+
+        let now = block_number();
+        assert_ok!(Staking::set_commission(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            Perbill::from_percent(50)
+        ));
+        roll_to(now + BLOCKS_TO_WAIT);
+        assert_ok!(Staking::execute_commission_increase(
+            RuntimeOrigin::signed(ACCOUNT_CANDIDATE_1),
+            ACCOUNT_CANDIDATE_1
+        ));
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: 2 * SHARE_INIT,
+            expected_increase: 2 * SHARE_INIT,
+            ..default()
+        }
+        .test::<pools::ManualRewards<Runtime>>();
+
+        let candidate_balance_before = total_balance(&ACCOUNT_CANDIDATE_1);
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_1,
+            currency_issue(1_000_000)
+        ));
+
+        assert_eq!(
+            total_balance(&ACCOUNT_CANDIDATE_1) - candidate_balance_before,
+            500_000
+        );
+        assert!(events().contains(&Event::RewardedDelegators {
+            collator: ACCOUNT_CANDIDATE_1,
+            auto_compounding_rewards: 0,
+            manual_claim_rewards: 500_000,
+        }));
+    })
+}
@@ -0,0 +1,153 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    super::*,
+    crate::AutoExecuteCursor,
+    frame_support::{traits::Hooks, weights::Weight},
+};
+
+fn request_delegations(delegators: &[AccountId]) {
+    for delegator in delegators {
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(*delegator),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            2 * SHARE_INIT,
+        ));
+    }
+}
+
+fn pending_operations_count() -> usize {
+    PendingOperations::<Runtime>::iter().count()
+}
+
+#[test]
+fn matured_operations_are_executed_on_idle() {
+    ExtBuilder::default().build().execute_with(|| {
+        let now = block_number();
+        request_delegations(&[ACCOUNT_DELEGATOR_1]);
+
+        roll_to(now + BLOCKS_TO_WAIT);
+        Staking::on_idle(block_number(), Weight::MAX);
+
+        assert_eq!(pending_operations_count(), 0);
+        assert_eq!(
+            pools::AutoCompounding::<Runtime>::computed_stake(
+                &ACCOUNT_CANDIDATE_1,
+                &ACCOUNT_DELEGATOR_1
+            )
+            .unwrap(),
+            Stake(2 * SHARE_INIT)
+        );
+        assert!(events().contains(&Event::ExecutedDelegate {
+            candidate: ACCOUNT_CANDIDATE_1,
+            delegator: ACCOUNT_DELEGATOR_1,
+            pool: TargetPool::AutoCompounding,
+            staked: 2 * SHARE_INIT,
+            released: 0,
+        }));
+        // The whole map has been checked.
+        assert_eq!(AutoExecuteCursor::<Runtime>::get(), None);
+    })
+}
+
+#[test]
+fn operations_not_matured_are_skipped() {
+    ExtBuilder::default().build().execute_with(|| {
+        let now = block_number();
+        request_delegations(&[ACCOUNT_DELEGATOR_1]);
+
+        roll_to(now + BLOCKS_TO_WAIT - 1);
+        Staking::on_idle(block_number(), Weight::MAX);
+
+        assert_eq!(pending_operations_count(), 1);
+        assert_eq!(AutoExecuteCursor::<Runtime>::get(), None);
+    })
+}
+
+#[test]
+fn executor_resumes_from_cursor() {
+    ExtBuilder::default().build().execute_with(|| {
+        let now = block_number();
+        request_delegations(&[
+            ACCOUNT_CANDIDATE_1,
+            ACCOUNT_DELEGATOR_1,
+            ACCOUNT_DELEGATOR_2,
+        ]);
+
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        // Only `MaxAutoExecutedOperations` are checked per block.
+        Staking::on_idle(block_number(), Weight::MAX);
+        assert_eq!(pending_operations_count(), 1);
+        assert!(AutoExecuteCursor::<Runtime>::get().is_some());
+
+        Staking::on_idle(block_number(), Weight::MAX);
+        assert_eq!(pending_operations_count(), 0);
+        assert_eq!(AutoExecuteCursor::<Runtime>::get(), None);
+    })
+}
+
+#[test]
+fn executor_respects_remaining_weight() {
+    ExtBuilder::default().build().execute_with(|| {
+        let now = block_number();
+        request_delegations(&[ACCOUNT_DELEGATOR_1]);
+
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        assert_eq!(
+            Staking::on_idle(block_number(), Weight::zero()),
+            Weight::zero()
+        );
+        assert_eq!(pending_operations_count(), 1);
+    })
+}
+
+#[test]
+fn manually_executed_operations_are_not_executed_twice() {
+    ExtBuilder::default().build().execute_with(|| {
+        let now = block_number();
+        request_delegations(&[ACCOUNT_DELEGATOR_1]);
+
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: now,
+                }
+            }]
+        ));
+        let events_before = events().len();
+
+        Staking::on_idle(block_number(), Weight::MAX);
+
+        assert_eq!(events().len(), events_before);
+        assert_eq!(
+            pools::AutoCompounding::<Runtime>::computed_stake(
+                &ACCOUNT_CANDIDATE_1,
+                &ACCOUNT_DELEGATOR_1
+            )
+            .unwrap(),
+            Stake(2 * SHARE_INIT)
+        );
+    })
+}
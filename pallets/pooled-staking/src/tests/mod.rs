@@ -15,10 +15,14 @@
 // along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
 
 mod candidates;
+mod commission;
 mod delegator_flow;
+mod executor;
 mod manual_rewards;
+mod positions;
 mod rebalance;
 mod rewards;
+mod slashing;
 
 use {
     crate::{
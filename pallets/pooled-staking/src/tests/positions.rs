@@ -0,0 +1,114 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    super::*,
+    crate::{DelegatorPosition, PendingJoining, PendingLeaving},
+    tp_traits::DistributeRewards,
+};
+
+#[test]
+fn no_positions() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert!(Staking::delegator_positions(&ACCOUNT_DELEGATOR_1)
+            .unwrap()
+            .is_empty());
+    })
+}
+
+#[test]
+fn positions_of_delegator() {
+    ExtBuilder::default().build().execute_with(|| {
+        let amount = 2 * SHARE_INIT;
+
+        FullDelegation {
+            candidate: ACCOUNT_CANDIDATE_2,
+            delegator: ACCOUNT_DELEGATOR_1,
+            request_amount: amount,
+            expected_increase: amount,
+            ..default()
+        }
+        .test::<pools::ManualRewards<Runtime>>();
+
+        // 20% commission goes to the candidate, the rest to its only delegator.
+        assert_ok!(Staking::distribute_rewards(
+            ACCOUNT_CANDIDATE_2,
+            currency_issue(1_000_000)
+        ));
+
+        let now = block_number();
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            amount,
+        ));
+
+        assert_eq!(
+            Staking::delegator_positions(&ACCOUNT_DELEGATOR_1).unwrap(),
+            vec![
+                DelegatorPosition {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    joining: vec![PendingJoining {
+                        pool: TargetPool::AutoCompounding,
+                        stake: amount,
+                        at: now,
+                        unlocks_at: Some(now + BLOCKS_TO_WAIT),
+                    }],
+                    auto_compounding_stake: 0,
+                    manual_rewards_stake: 0,
+                    claimable_rewards: 0,
+                    leaving: vec![],
+                },
+                DelegatorPosition {
+                    candidate: ACCOUNT_CANDIDATE_2,
+                    joining: vec![],
+                    auto_compounding_stake: 0,
+                    manual_rewards_stake: amount,
+                    claimable_rewards: 800_000,
+                    leaving: vec![],
+                },
+            ]
+        );
+
+        // Leaving claims the pending rewards.
+        assert_ok!(Staking::request_undelegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_2,
+            TargetPool::ManualRewards,
+            SharesOrStake::Shares(1),
+        ));
+
+        let positions = Staking::delegator_positions(&ACCOUNT_DELEGATOR_1).unwrap();
+        assert_eq!(
+            positions[1],
+            DelegatorPosition {
+                candidate: ACCOUNT_CANDIDATE_2,
+                joining: vec![],
+                auto_compounding_stake: 0,
+                manual_rewards_stake: SHARE_INIT,
+                claimable_rewards: 0,
+                leaving: vec![PendingLeaving {
+                    stake: Leaving::computed_stake(&ACCOUNT_CANDIDATE_2, &ACCOUNT_DELEGATOR_1)
+                        .unwrap()
+                        .0,
+                    at: now,
+                    unlocks_at: Some(now + BLOCKS_TO_WAIT),
+                }],
+            }
+        );
+    })
+}
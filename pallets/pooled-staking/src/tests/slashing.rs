@@ -0,0 +1,408 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    super::*,
+    crate::{DeferredSlash, DeferredSlashes, SlashPaymentCursor, UnpaidSlashedFunds},
+    frame_support::{traits::Hooks, weights::Weight},
+    sp_runtime::{DispatchError, Perbill},
+};
+
+fn delegate<P: PoolExt<Runtime>>(amount: Balance) {
+    FullDelegation {
+        candidate: ACCOUNT_CANDIDATE_1,
+        delegator: ACCOUNT_DELEGATOR_1,
+        request_amount: amount,
+        expected_increase: amount,
+        ..default()
+    }
+    .test::<P>();
+}
+
+pool_test!(
+    fn slash_is_deferred_then_applied<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let initial_amount = 2 * SHARE_INIT;
+            let slashed = initial_amount / 10;
+            delegate::<P>(initial_amount);
+
+            let now = block_number();
+            assert_ok!(Staking::slash(
+                ACCOUNT_CANDIDATE_1,
+                Perbill::from_percent(10)
+            ));
+
+            assert_eq!(
+                DeferredSlashes::<Runtime>::get().into_inner(),
+                vec![DeferredSlash {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    fraction: Perbill::from_percent(10),
+                    at: now,
+                }]
+            );
+            assert_eq!(
+                events().last(),
+                Some(&Event::SlashDeferred {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    fraction: Perbill::from_percent(10),
+                })
+            );
+
+            // Nothing is slashed before the timer is elapsed.
+            roll_to(now + BLOCKS_TO_WAIT - 1);
+            assert_eq!(DeferredSlashes::<Runtime>::get().len(), 1);
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1),
+                Stake(initial_amount)
+            );
+
+            roll_to(now + BLOCKS_TO_WAIT);
+            assert!(DeferredSlashes::<Runtime>::get().is_empty());
+
+            // Value of the shares decreased while hold is unchanged.
+            assert_eq!(
+                P::computed_stake(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1)
+                    .unwrap()
+                    .0,
+                initial_amount - slashed
+            );
+            assert_eq!(
+                P::hold(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+                Stake(initial_amount)
+            );
+            assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_1), initial_amount);
+            assert_eq!(
+                Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1),
+                Stake(initial_amount - slashed)
+            );
+
+            // Funds of the staking account are not used to pay the slash.
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), 0);
+            assert_eq!(total_balance(&ACCOUNT_STAKING), DEFAULT_BALANCE);
+            assert_eq!(
+                UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                slashed
+            );
+            assert!(events().contains(&Event::Slashed {
+                candidate: ACCOUNT_CANDIDATE_1,
+                fraction: Perbill::from_percent(10),
+                amount: slashed,
+            }));
+
+            // Rebalancing moves the excess hold back to the staking account, which pays the slash.
+            do_rebalance_hold::<P>(
+                ACCOUNT_CANDIDATE_1,
+                ACCOUNT_DELEGATOR_1,
+                P::target_pool().into(),
+                SignedBalance::Negative(slashed),
+            );
+            assert_eq!(total_balance(&ACCOUNT_STAKING), DEFAULT_BALANCE);
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), slashed);
+            assert_eq!(UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1), 0);
+        })
+    }
+);
+
+pool_test!(
+    fn unpaid_slash_is_paid_when_undelegating<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let initial_amount = 2 * SHARE_INIT;
+            let slashed = initial_amount / 10;
+            delegate::<P>(initial_amount);
+
+            // Staking account only have the existential deposit.
+            Balances::set_balance(&ACCOUNT_STAKING, 1);
+
+            let now = block_number();
+            assert_ok!(Staking::slash(
+                ACCOUNT_CANDIDATE_1,
+                Perbill::from_percent(10)
+            ));
+            roll_to(now + BLOCKS_TO_WAIT);
+
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), 0);
+            assert_eq!(
+                UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                slashed
+            );
+            assert!(events().contains(&Event::Slashed {
+                candidate: ACCOUNT_CANDIDATE_1,
+                fraction: Perbill::from_percent(10),
+                amount: slashed,
+            }));
+
+            // Leaving releases the excess hold which pays the slash.
+            assert_ok!(Staking::request_undelegate(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1,
+                P::target_pool(),
+                SharesOrStake::Shares(2),
+            ));
+
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), slashed);
+            assert_eq!(total_balance(&ACCOUNT_STAKING), 1);
+            assert_eq!(UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1), 0);
+            assert_eq!(
+                total_balance(&ACCOUNT_DELEGATOR_1),
+                DEFAULT_BALANCE - slashed
+            );
+            assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_1), initial_amount - slashed);
+            assert_eq!(
+                Leaving::hold(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+                Stake(initial_amount - slashed)
+            );
+            assert_eq!(
+                P::hold(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+                Stake(0)
+            );
+        })
+    }
+);
+
+pool_test!(
+    fn unpaid_slash_is_paid_on_idle<P>() {
+        ExtBuilder::default().build().execute_with(|| {
+            let initial_amount = 2 * SHARE_INIT;
+            let slashed = initial_amount / 10;
+            delegate::<P>(initial_amount);
+
+            let now = block_number();
+            assert_ok!(Staking::slash(
+                ACCOUNT_CANDIDATE_1,
+                Perbill::from_percent(10)
+            ));
+            roll_to(now + BLOCKS_TO_WAIT);
+            assert_eq!(
+                UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1),
+                slashed
+            );
+
+            // Nothing is paid without enough weight.
+            assert_eq!(
+                Staking::on_idle(block_number(), Weight::zero()),
+                Weight::zero()
+            );
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), 0);
+
+            // `MaxSlashedHoldsRebalancedPerBlock` is 2 in the mock, so the pool entries of the
+            // candidate are checked over several blocks.
+            for _ in 0..10 {
+                Staking::on_idle(block_number(), Weight::MAX);
+            }
+
+            assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), slashed);
+            assert_eq!(total_balance(&ACCOUNT_STAKING), DEFAULT_BALANCE);
+            assert!(!UnpaidSlashedFunds::<Runtime>::contains_key(
+                ACCOUNT_CANDIDATE_1
+            ));
+            assert_eq!(
+                P::hold(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+                Stake(initial_amount - slashed)
+            );
+            assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_1), initial_amount - slashed);
+            assert_eq!(SlashPaymentCursor::<Runtime>::get(), None);
+        })
+    }
+);
+
+#[test]
+fn slashed_joining_stake_releases_excess_hold_on_execution() {
+    ExtBuilder::default().build().execute_with(|| {
+        let initial_amount = 2 * SHARE_INIT;
+        let slashed = initial_amount / 10;
+
+        let now = block_number();
+        assert_ok!(Staking::request_delegate(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            ACCOUNT_CANDIDATE_1,
+            TargetPool::AutoCompounding,
+            initial_amount,
+        ));
+        assert_ok!(Staking::slash(
+            ACCOUNT_CANDIDATE_1,
+            Perbill::from_percent(10)
+        ));
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        assert_eq!(
+            Joining::computed_stake(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1)
+                .unwrap()
+                .0,
+            initial_amount - slashed
+        );
+        assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), 0);
+
+        assert_ok!(Staking::execute_pending_operations(
+            RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+            vec![PendingOperationQuery {
+                delegator: ACCOUNT_DELEGATOR_1,
+                operation: PendingOperationKey::JoiningAutoCompounding {
+                    candidate: ACCOUNT_CANDIDATE_1,
+                    at: now,
+                }
+            }]
+        ));
+
+        // Remaining stake only allows to get 1 share, the rest is released.
+        assert_eq!(
+            Joining::hold(&ACCOUNT_CANDIDATE_1, &ACCOUNT_DELEGATOR_1),
+            Stake(0)
+        );
+        assert_eq!(balance_hold(&ACCOUNT_DELEGATOR_1), SHARE_INIT);
+        assert_eq!(
+            Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1),
+            Stake(SHARE_INIT)
+        );
+        // Excess hold went back to the staking account, which paid the slash.
+        assert_eq!(total_balance(&ACCOUNT_STAKING), DEFAULT_BALANCE);
+        assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), slashed);
+        assert_eq!(UnpaidSlashedFunds::<Runtime>::get(ACCOUNT_CANDIDATE_1), 0);
+        assert_eq!(
+            total_balance(&ACCOUNT_DELEGATOR_1),
+            DEFAULT_BALANCE - slashed
+        );
+    })
+}
+
+#[test]
+fn slash_whole_stake_is_not_allowed() {
+    ExtBuilder::default().build().execute_with(|| {
+        delegate::<pools::AutoCompounding<Runtime>>(2 * SHARE_INIT);
+
+        assert_noop!(
+            Staking::slash(ACCOUNT_CANDIDATE_1, Perbill::one()),
+            Error::<Runtime>::InvalidSlashFraction
+        );
+    })
+}
+
+#[test]
+fn slash_candidate_without_stake_is_ignored() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(Staking::slash(
+            ACCOUNT_CANDIDATE_1,
+            Perbill::from_percent(10)
+        ));
+        assert!(DeferredSlashes::<Runtime>::get().is_empty());
+        assert!(events().is_empty());
+    })
+}
+
+#[test]
+fn too_many_deferred_slashes() {
+    ExtBuilder::default().build().execute_with(|| {
+        delegate::<pools::AutoCompounding<Runtime>>(2 * SHARE_INIT);
+
+        for _ in 0..3 {
+            assert_ok!(Staking::slash(
+                ACCOUNT_CANDIDATE_1,
+                Perbill::from_percent(10)
+            ));
+        }
+
+        assert_noop!(
+            Staking::slash(ACCOUNT_CANDIDATE_1, Perbill::from_percent(10)),
+            Error::<Runtime>::TooManyDeferredSlashes
+        );
+    })
+}
+
+#[test]
+fn deferred_slashes_are_applied_in_bounded_batches() {
+    ExtBuilder::default().build().execute_with(|| {
+        delegate::<pools::AutoCompounding<Runtime>>(2 * SHARE_INIT);
+
+        let now = block_number();
+        for _ in 0..3 {
+            assert_ok!(Staking::slash(
+                ACCOUNT_CANDIDATE_1,
+                Perbill::from_percent(10)
+            ));
+        }
+
+        // `MaxSlashesAppliedPerBlock` is 2 in the mock.
+        roll_to(now + BLOCKS_TO_WAIT);
+        assert_eq!(DeferredSlashes::<Runtime>::get().len(), 1);
+        assert_eq!(
+            events()
+                .iter()
+                .filter(|e| matches!(e, Event::Slashed { .. }))
+                .count(),
+            2
+        );
+
+        roll_one_block();
+        assert!(DeferredSlashes::<Runtime>::get().is_empty());
+        assert_eq!(
+            events()
+                .iter()
+                .filter(|e| matches!(e, Event::Slashed { .. }))
+                .count(),
+            3
+        );
+    })
+}
+
+#[test]
+fn cancel_deferred_slashes() {
+    ExtBuilder::default().build().execute_with(|| {
+        let initial_amount = 2 * SHARE_INIT;
+        delegate::<pools::AutoCompounding<Runtime>>(initial_amount);
+
+        let now = block_number();
+        assert_ok!(Staking::slash(
+            ACCOUNT_CANDIDATE_1,
+            Perbill::from_percent(10)
+        ));
+        assert_ok!(Staking::slash(
+            ACCOUNT_CANDIDATE_1,
+            Perbill::from_percent(20)
+        ));
+
+        assert_noop!(
+            Staking::cancel_deferred_slashes(
+                RuntimeOrigin::signed(ACCOUNT_DELEGATOR_1),
+                ACCOUNT_CANDIDATE_1
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            Staking::cancel_deferred_slashes(RuntimeOrigin::root(), ACCOUNT_CANDIDATE_2),
+            Error::<Runtime>::NoDeferredSlashes
+        );
+
+        assert_ok!(Staking::cancel_deferred_slashes(
+            RuntimeOrigin::root(),
+            ACCOUNT_CANDIDATE_1
+        ));
+        assert!(DeferredSlashes::<Runtime>::get().is_empty());
+        assert_eq!(
+            events().last(),
+            Some(&Event::DeferredSlashesCancelled {
+                candidate: ACCOUNT_CANDIDATE_1,
+                count: 2,
+            })
+        );
+
+        roll_to(now + BLOCKS_TO_WAIT);
+
+        assert_eq!(
+            Candidates::<Runtime>::total_stake(&ACCOUNT_CANDIDATE_1),
+            Stake(initial_amount)
+        );
+        assert_eq!(total_balance(&ACCOUNT_SLASHED_FUNDS), 0);
+    })
+}
@@ -34,6 +34,10 @@ pub trait Timer {
     /// Check if the timer started at `started` is elapsed.
     fn is_elapsed(start: &Self::Instant) -> bool;
 
+    /// Get the instant at which the timer started at `start` will be elapsed, if it can be
+    /// represented.
+    fn elapsed_at(start: &Self::Instant) -> Option<Self::Instant>;
+
     /// Returns an instant that will make `is_elapsed` true.
     #[cfg(feature = "runtime-benchmarks")]
     fn elapsed_instant() -> Self::Instant;
@@ -59,13 +63,16 @@ where
     }
 
     fn is_elapsed(start: &Self::Instant) -> bool {
-        let delay = G::get();
-        let Some(end) = start.checked_add(&delay) else {
+        let Some(end) = Self::elapsed_at(start) else {
             return false;
         };
         end <= Self::now()
     }
 
+    fn elapsed_at(start: &Self::Instant) -> Option<Self::Instant> {
+        start.checked_add(&G::get())
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn elapsed_instant() -> Self::Instant {
         let delay = G::get();
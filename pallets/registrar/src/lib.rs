@@ -52,8 +52,9 @@ use {
     sp_std::{collections::btree_set::BTreeSet, prelude::*},
     tp_container_chain_genesis_data::ContainerChainGenesisData,
     tp_traits::{
-        GetCurrentContainerChains, GetSessionContainerChains, GetSessionIndex, ParaId,
-        ParathreadParams as ParathreadParamsTy, SlotFrequency,
+        CorePurchaseBackend, GetCurrentContainerChains, GetParathreadParams,
+        GetSessionContainerChains, GetSessionIndex, ParaId, ParathreadParams as ParathreadParamsTy,
+        SlotFrequency,
     },
 };
 
@@ -566,7 +567,10 @@ pub mod pallet {
             let account = ensure_signed(origin)?;
             Self::do_register(account, para_id, genesis_data)?;
             // Insert parathread params
-            let params = ParathreadParamsTy { slot_frequency };
+            let params = ParathreadParamsTy {
+                slot_frequency,
+                core_purchase_backend: Default::default(),
+            };
             ParathreadParams::<T>::insert(para_id, params);
             Self::deposit_event(Event::ParaIdRegistered { para_id });
 
@@ -593,6 +597,27 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Change how the cores of a parathread are purchased in the relay chain
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_parathread_params(T::MaxLengthParaIds::get()))]
+        pub fn set_parathread_core_purchase_backend(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            core_purchase_backend: CorePurchaseBackend,
+        ) -> DispatchResult {
+            T::RegistrarOrigin::ensure_origin(origin)?;
+
+            Self::schedule_parathread_params_change(para_id, |params| {
+                params.core_purchase_backend = core_purchase_backend;
+
+                Self::deposit_event(Event::ParathreadParamsChanged { para_id });
+
+                Ok(())
+            })?;
+
+            Ok(())
+        }
     }
 
     pub struct SessionChangeOutcome<T: Config> {
@@ -1103,6 +1128,21 @@ pub mod pallet {
             RegisteredParaIds::<T>::put(paras);
         }
     }
+
+    impl<T: Config> GetParathreadParams for Pallet<T> {
+        fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParamsTy> {
+            ParathreadParams::<T>::get(para_id)
+        }
+
+        #[cfg(feature = "runtime-benchmarks")]
+        fn set_parathread_params(para_id: ParaId, parathread_params: Option<ParathreadParamsTy>) {
+            if let Some(parathread_params) = parathread_params {
+                ParathreadParams::<T>::insert(para_id, parathread_params);
+            } else {
+                ParathreadParams::<T>::remove(para_id);
+            }
+        }
+    }
 }
 
 pub trait RegistrarHooks {
@@ -21,7 +21,7 @@ use {
     sp_core::Get,
     sp_runtime::DispatchError,
     tp_container_chain_genesis_data::ContainerChainGenesisData,
-    tp_traits::{ParaId, SlotFrequency},
+    tp_traits::{CorePurchaseBackend, ParaId, ParathreadParams, SlotFrequency},
 };
 
 const ALICE: u64 = 1;
@@ -1209,6 +1209,90 @@ fn parathread_change_params_after_two_sessions() {
     });
 }
 
+#[test]
+fn parathread_change_core_purchase_backend_after_registration() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+        assert_ok!(ParaRegistrar::register_parathread(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            SlotFrequency { min: 1, max: 1 },
+            empty_genesis_data()
+        ));
+        assert_ok!(ParaRegistrar::mark_valid_for_collating(
+            RuntimeOrigin::root(),
+            42.into(),
+        ));
+        // On-demand is the default backend
+        assert_eq!(
+            ParaRegistrar::parathread_params(ParaId::from(42)).map(|x| x.core_purchase_backend),
+            Some(CorePurchaseBackend::OnDemand)
+        );
+        assert_ok!(ParaRegistrar::set_parathread_core_purchase_backend(
+            RuntimeOrigin::root(),
+            ParaId::from(42),
+            CorePurchaseBackend::CoretimeCredits
+        ));
+        System::assert_last_event(
+            Event::ParathreadParamsChanged {
+                para_id: 42.into(),
+            }
+            .into(),
+        );
+
+        // Params are updated after 2 sessions, keeping the slot frequency
+        run_to_session(2);
+        assert_eq!(
+            ParaRegistrar::parathread_params(ParaId::from(42)),
+            Some(ParathreadParams {
+                slot_frequency: SlotFrequency { min: 1, max: 1 },
+                core_purchase_backend: CorePurchaseBackend::CoretimeCredits,
+            })
+        );
+    });
+}
+
+#[test]
+fn core_purchase_backend_cannot_be_set_for_parachains() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+        assert_ok!(ParaRegistrar::register(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            empty_genesis_data()
+        ));
+        assert_noop!(
+            ParaRegistrar::set_parathread_core_purchase_backend(
+                RuntimeOrigin::root(),
+                ParaId::from(42),
+                CorePurchaseBackend::CoretimeCredits
+            ),
+            Error::<Test>::NotAParathread
+        );
+    });
+}
+
+#[test]
+fn signed_origin_cannot_set_core_purchase_backend() {
+    new_test_ext().execute_with(|| {
+        run_to_block(1);
+        assert_ok!(ParaRegistrar::register_parathread(
+            RuntimeOrigin::signed(ALICE),
+            42.into(),
+            SlotFrequency { min: 1, max: 1 },
+            empty_genesis_data()
+        ));
+        assert_noop!(
+            ParaRegistrar::set_parathread_core_purchase_backend(
+                RuntimeOrigin::signed(ALICE),
+                ParaId::from(42),
+                CorePurchaseBackend::CoretimeCredits
+            ),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
 #[test]
 fn parathread_params_cannot_be_set_for_parachains() {
     new_test_ext().execute_with(|| {
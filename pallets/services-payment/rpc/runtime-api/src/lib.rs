@@ -0,0 +1,35 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+//! Runtime API for Services Payment pallet. Can be used to check the current price
+//! of block production and collator assignment credits before purchasing them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+sp_api::decl_runtime_apis! {
+    pub trait ServicesPaymentApi<Balance, ParaId> where
+        Balance: parity_scale_codec::Codec,
+        ParaId: parity_scale_codec::Codec,
+    {
+        /// Return the current cost of producing a block of the given `ParaId`.
+        fn block_cost(para_id: ParaId) -> Balance;
+        /// Return the current cost of assigning collators to the given `ParaId` for one session.
+        fn collator_assignment_cost(para_id: ParaId) -> Balance;
+        /// Return the number of sessions the given `ParaId` can pay for with its free credits
+        /// and the funds in its tank.
+        fn sessions_of_runway(para_id: ParaId) -> u32;
+    }
+}
@@ -19,18 +19,21 @@
 //! Benchmarking
 use {
     crate::{
-        BalanceOf, BlockNumberFor, Call, Config, Pallet, ProvideBlockProductionCost,
-        ProvideCollatorAssignmentCost,
+        AssetRateConverter, Assets, BalanceOf, BlockNumberFor, Call, Config, Pallet,
+        ProvideBlockProductionCost, ProvideCollatorAssignmentCost,
     },
-    frame_benchmarking::{account, v2::*},
+    cumulus_primitives_core::ParaId,
+    frame_benchmarking::{account, v2::*, BenchmarkResult},
     frame_support::{
         assert_ok,
-        traits::{Currency, EnsureOriginWithArg, Get},
+        traits::{tokens::Preservation, Currency, EnsureOriginWithArg, Get},
+        weights::Weight,
+        BoundedVec,
     },
     frame_system::RawOrigin,
-    sp_runtime::Saturating,
+    sp_runtime::{traits::Zero, Saturating},
     sp_std::prelude::*,
-    tp_traits::{AuthorNotingHook, CollatorAssignmentHook},
+    tp_traits::{AuthorNotingHook, CollatorAssignmentHook, CollatorRequirements},
 };
 
 // Build genesis storage according to the mock runtime.
@@ -56,6 +59,41 @@ fn create_funded_user<T: Config>(
     user
 }
 
+/// Fund the tank of `para_id` so that a charge of `amount` is paid with an asset, keeping
+/// `native_amount` of native currency in the tank on top of the existential deposit. If no assets
+/// can be used to pay for services in this runtime, the tank is funded with native currency
+/// instead.
+fn fund_tank_with_asset<T: Config>(
+    para_id: ParaId,
+    amount: BalanceOf<T>,
+    native_amount: BalanceOf<T>,
+) {
+    let tank = Pallet::<T>::parachain_tank(para_id);
+    let existential_deposit = <T::Currency>::minimum_balance();
+    let asset_id = T::Assets::bench_worst_case_asset_id();
+    T::AssetRateConverter::bench_set_rate(&asset_id);
+
+    let native_balance = match T::AssetRateConverter::native_to_asset(&asset_id, amount) {
+        Some(asset_amount) => {
+            // Enough to pay and keep the tank alive
+            T::Assets::bench_set_balance(
+                &asset_id,
+                &tank,
+                asset_amount
+                    .saturating_mul(2u32.into())
+                    .saturating_add(1_000_000u32.into()),
+            );
+            crate::TankAssets::<T>::insert(para_id, BoundedVec::truncate_from(vec![asset_id]));
+
+            existential_deposit.saturating_add(native_amount)
+        }
+        None => existential_deposit
+            .saturating_add(native_amount)
+            .saturating_add(amount),
+    };
+    let _ = <T::Currency>::make_free_balance_be(&tank, native_balance);
+}
+
 #[benchmarks(where BalanceOf<T>: From<BlockNumberFor<T>>)]
 mod benchmarks {
     use super::*;
@@ -166,22 +204,26 @@ mod benchmarks {
     #[benchmark]
     fn on_container_author_noted() {
         let para_id = 1001u32;
-        let block_cost = T::ProvideBlockProductionCost::block_cost(&para_id.into()).0;
-        let credits: BalanceOf<T> = 1000u32.into();
-        let balance_to_purchase = block_cost.saturating_mul(credits);
         let caller = create_funded_user::<T>("caller", 1, 1_000_000_000u32);
-        let existential_deposit = <T::Currency>::minimum_balance();
-        assert_ok!(Pallet::<T>::purchase_credits(
-            RawOrigin::Signed(caller.clone()).into(),
-            para_id.into(),
-            balance_to_purchase + existential_deposit
-        ));
+        // Worst case: the max number of blocks is charged, some of them are paid with credits,
+        // and the rest is paid with an asset because the native balance of the tank is not enough
+        let blocks_produced = T::MaxBlocksChargedPerNoting::get();
+        crate::BlockProductionCredits::<T>::insert(
+            ParaId::from(para_id),
+            BlockNumberFor::<T>::from(1u32),
+        );
+        let block_cost = T::ProvideBlockProductionCost::block_cost(&para_id.into()).0;
+        let amount_to_charge = block_cost.saturating_mul(blocks_produced.into());
+        fund_tank_with_asset::<T>(para_id.into(), amount_to_charge, Zero::zero());
+
         #[block]
         {
             <Pallet<T> as AuthorNotingHook<T::AccountId>>::on_container_author_noted(
                 &caller,
-                0,
+                Some(0),
+                blocks_produced,
                 para_id.into(),
+                true,
             );
         }
     }
@@ -189,28 +231,23 @@ mod benchmarks {
     #[benchmark]
     fn on_collators_assigned() {
         let para_id = 1001u32;
-        let collator_assignment_cost =
-            T::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id.into()).0;
-        let max_credit_stored = T::FreeCollatorAssignmentCredits::get();
-        let balance_to_purchase = collator_assignment_cost.saturating_mul(max_credit_stored.into());
-        let caller = create_funded_user::<T>("caller", 1, 1_000_000_000u32);
-        let existential_deposit = <T::Currency>::minimum_balance();
-        let tip = 1_000_000u32;
-        assert_ok!(Pallet::<T>::purchase_credits(
-            RawOrigin::Signed(caller.clone()).into(),
-            para_id.into(),
-            balance_to_purchase + existential_deposit + tip.into()
-        ));
+        let tip: BalanceOf<T> = 1u32.into();
         assert_ok!(Pallet::<T>::set_max_tip(
             RawOrigin::Root.into(),
             para_id.into(),
-            Some(tip.into())
+            Some(tip)
         ));
+        // Worst case: there are no free credits, so the assignment is paid with an asset because
+        // the native balance of the tank is only enough to pay the tip
+        let collator_assignment_cost =
+            T::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id.into()).0;
+        fund_tank_with_asset::<T>(para_id.into(), collator_assignment_cost, tip);
+
         #[block]
         {
             <Pallet<T> as CollatorAssignmentHook<BalanceOf<T>>>::on_collators_assigned(
                 para_id.into(),
-                Some(&tip.into()),
+                Some(&tip),
                 false,
             )
             .expect("failed on_collators_assigned");
@@ -229,5 +266,75 @@ mod benchmarks {
         assert!(crate::MaxTip::<T>::get(para_id).is_some());
     }
 
+    #[benchmark]
+    fn purchase_credits_with_asset() -> Result<(), BenchmarkError> {
+        let para_id = 1001u32.into();
+        let asset_id = T::Assets::bench_worst_case_asset_id();
+        T::AssetRateConverter::bench_set_rate(&asset_id);
+        if T::AssetRateConverter::rate_to_native(&asset_id).is_none() {
+            // No assets can be used to pay for services in this runtime
+            return Err(BenchmarkError::Override(BenchmarkResult::from_weight(
+                Weight::MAX,
+            )));
+        }
+
+        // Must be worth at least the existential deposit
+        let amount: BalanceOf<T> =
+            T::AssetRateConverter::native_to_asset(&asset_id, <T::Currency>::minimum_balance())
+                .unwrap_or_default()
+                .max(1_000_000u32.into());
+        let caller: T::AccountId = account("caller", 1, SEED);
+        T::Assets::bench_set_balance(&asset_id, &caller, amount.saturating_mul(2u32.into()));
+
+        #[extrinsic_call]
+        Pallet::<T>::purchase_credits_with_asset(
+            RawOrigin::Signed(caller),
+            para_id,
+            asset_id,
+            amount,
+        );
+
+        // verification code
+        assert_eq!(
+            T::Assets::reducible_balance(
+                &asset_id,
+                &crate::Pallet::<T>::parachain_tank(para_id),
+                Preservation::Expendable
+            ),
+            amount
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_requested_collators() {
+        let para_id = 1001u32.into();
+
+        let origin = T::ManagerOrigin::try_successful_origin(&para_id)
+            .expect("failed to create ManagerOrigin");
+
+        let requested_collators = CollatorRequirements {
+            min_collators: 1,
+            max_collators: T::MaxRequestedCollators::get(),
+        };
+
+        // Before call: none
+        assert_eq!(crate::RequestedCollators::<T>::get(para_id), None);
+
+        #[extrinsic_call]
+        Pallet::<T>::set_requested_collators(
+            origin as T::RuntimeOrigin,
+            para_id,
+            Some(requested_collators),
+        );
+
+        // After call: some
+        assert_eq!(
+            crate::RequestedCollators::<T>::get(para_id),
+            Some(requested_collators)
+        );
+    }
+
     impl_benchmark_test_suite!(Pallet, crate::benchmarks::new_test_ext(), crate::mock::Test);
 }
@@ -27,16 +27,24 @@ use {
         pallet_prelude::*,
         sp_runtime::{traits::Zero, Saturating},
         traits::{
-            tokens::ExistenceRequirement, Currency, EnsureOriginWithArg, OnUnbalanced,
-            WithdrawReasons,
+            tokens::{ExistenceRequirement, Preservation},
+            Currency, EnsureOriginWithArg, Imbalance, OnUnbalanced, WithdrawReasons,
         },
     },
     frame_system::pallet_prelude::*,
     scale_info::prelude::vec::Vec,
     serde::{Deserialize, Serialize},
     sp_io::hashing::blake2_256,
-    sp_runtime::{traits::TrailingZeroInput, DispatchError},
-    tp_traits::{AuthorNotingHook, BlockNumber, CollatorAssignmentHook, CollatorAssignmentTip},
+    sp_runtime::{
+        helpers_128bit::multiply_by_rational_with_rounding,
+        traits::{AtLeast32BitUnsigned, Convert, One, SaturatedConversion, TrailingZeroInput},
+        DispatchError, DispatchResult, FixedPointNumber, FixedU128, Perbill, Rounding,
+    },
+    tp_traits::{
+        blocks_produced_since, AuthorNotingHook, BlockNumber, CollatorAssignmentHook,
+        CollatorAssignmentTip, CollatorRequirements, DistributeRewards, GetCollatorRequirements,
+        GetSessionIndex,
+    },
 };
 
 #[cfg(any(test, feature = "runtime-benchmarks"))]
@@ -60,9 +68,15 @@ pub mod pallet {
         /// The overarching event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
         /// Handlers for fees
-        type OnChargeForBlock: OnUnbalanced<NegativeImbalanceOf<Self>>;
-        type OnChargeForCollatorAssignment: OnUnbalanced<NegativeImbalanceOf<Self>>;
-        type OnChargeForCollatorAssignmentTip: OnUnbalanced<NegativeImbalanceOf<Self>>;
+        type OnChargeForBlock: OnChargeForService<Self::AccountId, NegativeImbalanceOf<Self>>;
+        type OnChargeForCollatorAssignment: OnChargeForService<
+            Self::AccountId,
+            NegativeImbalanceOf<Self>,
+        >;
+        type OnChargeForCollatorAssignmentTip: OnChargeForService<
+            Self::AccountId,
+            NegativeImbalanceOf<Self>,
+        >;
 
         /// Currency type for fee payment
         type Currency: Currency<Self::AccountId>;
@@ -75,9 +89,48 @@ pub mod pallet {
         #[pallet::constant]
         type FreeBlockProductionCredits: Get<BlockNumberFor<Self>>;
 
+        /// The maximum number of container chain blocks charged in a single author noting
+        #[pallet::constant]
+        type MaxBlocksChargedPerNoting: Get<u32>;
+
         /// The maximum number of collator assigment production credits that can be accumulated
         #[pallet::constant]
         type FreeCollatorAssignmentCredits: Get<u32>;
+
+        /// Number of blocks in a session, used to project the cost of a session
+        #[pallet::constant]
+        type BlocksPerSession: Get<u32>;
+
+        /// A `LowTankBalance` event is emitted when the tank of a container chain can pay for
+        /// less than this number of sessions
+        #[pallet::constant]
+        type LowBalanceWarningSessions: Get<u32>;
+
+        /// Number of sessions a container chain keeps its collators after it runs out of funds
+        #[pallet::constant]
+        type GracePeriodSessions: Get<u32>;
+
+        /// Maximum number of collators a container chain can request with
+        /// `set_requested_collators`
+        #[pallet::constant]
+        type MaxRequestedCollators: Get<u32>;
+
+        /// Identifier of the assets, other than the native currency, that can be used to pay for
+        /// services
+        type AssetId: Parameter + Member + Copy + MaxEncodedLen;
+        /// Assets that can be held in the tank of a container chain
+        type Assets: Assets<Self::AccountId, Self::AssetId, BalanceOf<Self>>;
+        /// Converts amounts of the native currency into amounts of the assets held in the tanks
+        type AssetRateConverter: AssetRateConverter<Self::AssetId, BalanceOf<Self>>;
+        /// Account that receives the fees paid with assets other than the native currency. These
+        /// fees are not handled by `OnChargeForBlock` and `OnChargeForCollatorAssignment`, so
+        /// they are not split between the treasury, the collators and the burned amount.
+        type AssetFeesAccount: Get<Self::AccountId>;
+
+        /// The maximum number of different assets that can be held in the tank of a container chain
+        #[pallet::constant]
+        type MaxTankAssets: Get<u32>;
+
         /// Owner of the container chain, can call some only-owner methods
         type ManagerOrigin: EnsureOriginWithArg<Self::RuntimeOrigin, ParaId>;
 
@@ -89,6 +142,10 @@ pub mod pallet {
         InsufficientFundsToPurchaseCredits,
         InsufficientCredits,
         CreditPriceTooExpensive,
+        AssetNotAccepted,
+        TooManyTankAssets,
+        InvalidCollatorRequirements,
+        AssetAmountTooLow,
     }
 
     #[pallet::pallet]
@@ -102,6 +159,12 @@ pub mod pallet {
             payer: T::AccountId,
             credit: BalanceOf<T>,
         },
+        CreditsPurchasedWithAsset {
+            para_id: ParaId,
+            payer: T::AccountId,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T>,
+        },
         BlockProductionCreditBurned {
             para_id: ParaId,
             credits_remaining: BlockNumberFor<T>,
@@ -131,6 +194,39 @@ pub mod pallet {
             para_id: ParaId,
             credits: u32,
         },
+        RequestedCollatorsUpdated {
+            para_id: ParaId,
+            requested_collators: Option<CollatorRequirements>,
+        },
+        /// The tank of a container chain can only pay for a few more sessions.
+        LowTankBalance {
+            para_id: ParaId,
+            sessions_left: u32,
+        },
+        /// A container chain without enough funds keeps its collators for `sessions_left` more
+        /// sessions.
+        GracePeriodUsed {
+            para_id: ParaId,
+            sessions_left: u32,
+        },
+        /// Fees charged to a container chain have been split between the treasury, the
+        /// collator that provided the service and the burned amount.
+        ServiceFeesDistributed {
+            para_id: ParaId,
+            to_treasury: BalanceOf<T>,
+            burned: BalanceOf<T>,
+            collator: Option<T::AccountId>,
+            to_collator: BalanceOf<T>,
+        },
+        /// Fees charged to a container chain have been paid with an asset held in its tank, and
+        /// sent to the `AssetFeesAccount` without being split. `native_amount` is the charged
+        /// amount of native currency that has been paid with the asset instead.
+        ServiceFeesPaidWithAsset {
+            para_id: ParaId,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T>,
+            native_amount: BalanceOf<T>,
+        },
     }
 
     #[pallet::storage]
@@ -163,6 +259,32 @@ pub mod pallet {
     #[pallet::getter(fn max_tip)]
     pub type MaxTip<T: Config> = StorageMap<_, Blake2_128Concat, ParaId, BalanceOf<T>, OptionQuery>;
 
+    /// Number of collators requested by each container chain, if different from the default
+    #[pallet::storage]
+    pub type RequestedCollators<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, CollatorRequirements, OptionQuery>;
+
+    /// Sessions left in the grace period of container chains that ran out of funds, including the
+    /// session being assigned. A value of 0 means that the grace period is exhausted.
+    #[pallet::storage]
+    pub type GracePeriodSessionsLeft<T: Config> =
+        StorageMap<_, Blake2_128Concat, ParaId, u32, OptionQuery>;
+
+    /// Collator demand of a session, cached by `CollatorDemandCachedPerSession` so that it is
+    /// only computed once per session
+    #[pallet::storage]
+    pub type CachedCollatorDemand<T: Config> = StorageValue<_, (u32, CollatorDemand), OptionQuery>;
+
+    /// Assets, other than the native currency, held in the tank of each container chain
+    #[pallet::storage]
+    pub type TankAssets<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        ParaId,
+        BoundedVec<T::AssetId, T::MaxTankAssets>,
+        ValueQuery,
+    >;
+
     #[pallet::call]
     impl<T: Config> Pallet<T>
     where
@@ -312,6 +434,83 @@ pub mod pallet {
 
             Ok(().into())
         }
+
+        /// Purchase credits with an asset other than the native currency. The asset is held in
+        /// the tank of the container chain, and used to pay for services when the native balance
+        /// of the tank is not enough. The amount must be worth at least the existential deposit
+        /// of the native currency, so that the limited tank asset slots cannot be taken by dust.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::purchase_credits_with_asset())]
+        pub fn purchase_credits_with_asset(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            asset_id: T::AssetId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
+            let account = ensure_signed(origin)?;
+            let native_value = T::AssetRateConverter::asset_to_native(&asset_id, amount)
+                .ok_or(Error::<T>::AssetNotAccepted)?;
+            ensure!(
+                !amount.is_zero() && native_value >= T::Currency::minimum_balance(),
+                Error::<T>::AssetAmountTooLow
+            );
+
+            T::Assets::transfer(
+                &asset_id,
+                &account,
+                &Self::parachain_tank(para_id),
+                amount,
+                Preservation::Preserve,
+            )?;
+            TankAssets::<T>::try_mutate(para_id, |assets| -> DispatchResult {
+                if !assets.contains(&asset_id) {
+                    assets
+                        .try_push(asset_id)
+                        .map_err(|_| Error::<T>::TooManyTankAssets)?;
+                }
+                Ok(())
+            })?;
+
+            Self::deposit_event(Event::<T>::CreditsPurchasedWithAsset {
+                para_id,
+                payer: account,
+                asset_id,
+                amount,
+            });
+
+            Ok(().into())
+        }
+
+        /// Set the number of collators a container chain wants to be assigned, instead of the
+        /// default of the host configuration. The collator assignment cost scales with the max
+        /// number of collators, which is bounded by `MaxRequestedCollators`. Can only be called by
+        /// container chain manager.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::set_requested_collators())]
+        pub fn set_requested_collators(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            requested_collators: Option<CollatorRequirements>,
+        ) -> DispatchResultWithPostInfo {
+            T::ManagerOrigin::ensure_origin(origin, &para_id)?;
+
+            if let Some(requested_collators) = requested_collators {
+                ensure!(
+                    requested_collators.is_valid(T::MaxRequestedCollators::get()),
+                    Error::<T>::InvalidCollatorRequirements
+                );
+                RequestedCollators::<T>::insert(para_id, requested_collators);
+            } else {
+                RequestedCollators::<T>::remove(para_id);
+            }
+
+            Self::deposit_event(Event::<T>::RequestedCollatorsUpdated {
+                para_id,
+                requested_collators,
+            });
+
+            Ok(().into())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -338,6 +537,32 @@ pub mod pallet {
             Ok(().into())
         }
 
+        /// Burn up to `max_credits` block production credits for the given para.
+        /// Returns the number of credits burned.
+        pub fn burn_block_production_free_credits_for_para(
+            para_id: &ParaId,
+            max_credits: u32,
+        ) -> u32 {
+            let existing_credits =
+                BlockProductionCredits::<T>::get(para_id).unwrap_or(BlockNumberFor::<T>::zero());
+            let credits_to_burn = existing_credits.min(max_credits.into());
+
+            if credits_to_burn.is_zero() {
+                return 0;
+            }
+
+            let updated_credits = existing_credits.saturating_sub(credits_to_burn);
+            BlockProductionCredits::<T>::insert(para_id, updated_credits);
+
+            Self::deposit_event(Event::<T>::BlockProductionCreditBurned {
+                para_id: *para_id,
+                credits_remaining: updated_credits,
+            });
+
+            // Cannot be greater than `max_credits`
+            credits_to_burn.saturated_into()
+        }
+
         /// Burn a credit for the given para. Deducts one credit if possible, errors otherwise.
         pub fn burn_collator_assignment_free_credit_for_para(
             para_id: &ParaId,
@@ -488,6 +713,201 @@ pub type NegativeImbalanceOf<T> =
 /// Handler for fee charging. This will be invoked when fees need to be deducted from the fee
 /// account for a given paraId.
 
+/// Interactions the pallet needs with the assets, other than the native currency, that can be
+/// held in the tank of a container chain.
+pub trait Assets<AccountId, AssetId, Balance> {
+    /// Amount of the asset that can be transferred out of the account.
+    fn reducible_balance(
+        asset_id: &AssetId,
+        account: &AccountId,
+        preservation: Preservation,
+    ) -> Balance;
+
+    /// Transfer assets from one account to another.
+    fn transfer(
+        asset_id: &AssetId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Balance,
+        preservation: Preservation,
+    ) -> DispatchResult;
+
+    /// Benchmarks: should return the asset id which has the worst performance when interacting
+    /// with it.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_worst_case_asset_id() -> AssetId;
+
+    /// Benchmarks: should set the balance.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_balance(asset_id: &AssetId, account: &AccountId, amount: Balance);
+}
+
+/// No assets other than the native currency.
+impl<AccountId, AssetId: Default, Balance: Zero> Assets<AccountId, AssetId, Balance> for () {
+    fn reducible_balance(
+        _asset_id: &AssetId,
+        _account: &AccountId,
+        _preservation: Preservation,
+    ) -> Balance {
+        Zero::zero()
+    }
+
+    fn transfer(
+        _asset_id: &AssetId,
+        _from: &AccountId,
+        _to: &AccountId,
+        _amount: Balance,
+        _preservation: Preservation,
+    ) -> DispatchResult {
+        Err(sp_runtime::TokenError::UnknownAsset.into())
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_worst_case_asset_id() -> AssetId {
+        Default::default()
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_balance(_asset_id: &AssetId, _account: &AccountId, _amount: Balance) {}
+}
+
+/// Converts between the native currency and the assets that can be used to pay for services.
+pub trait AssetRateConverter<AssetId, Balance: AtLeast32BitUnsigned + Copy> {
+    /// Units of the native currency worth one unit of the asset, or `None` if the asset cannot be
+    /// used to pay for services.
+    fn rate_to_native(asset_id: &AssetId) -> Option<FixedU128>;
+
+    /// Amount of the asset worth `native_amount`, rounded up so that services are never
+    /// undercharged.
+    fn native_to_asset(asset_id: &AssetId, native_amount: Balance) -> Option<Balance> {
+        let rate = Self::rate_to_native(asset_id)?;
+        multiply_by_rational_with_rounding(
+            native_amount.saturated_into(),
+            FixedU128::DIV,
+            rate.into_inner(),
+            Rounding::Up,
+        )
+        .map(|amount| amount.saturated_into())
+    }
+
+    /// Value in the native currency of `asset_amount`, rounded down.
+    fn asset_to_native(asset_id: &AssetId, asset_amount: Balance) -> Option<Balance> {
+        let rate = Self::rate_to_native(asset_id)?;
+        multiply_by_rational_with_rounding(
+            asset_amount.saturated_into(),
+            rate.into_inner(),
+            FixedU128::DIV,
+            Rounding::Down,
+        )
+        .map(|amount| amount.saturated_into())
+    }
+
+    /// Benchmarks: should make the asset usable to pay for services.
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_rate(asset_id: &AssetId);
+}
+
+/// No assets can be used to pay for services.
+impl<AssetId, Balance: AtLeast32BitUnsigned + Copy> AssetRateConverter<AssetId, Balance> for () {
+    fn rate_to_native(_asset_id: &AssetId) -> Option<FixedU128> {
+        None
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_rate(_asset_id: &AssetId) {}
+}
+
+/// A charge to the tank of a container chain. It has either been withdrawn from the native
+/// balance of the tank, or already paid to the `AssetFeesAccount` with an asset held in the tank.
+enum TankCharge<T: Config> {
+    Native(NegativeImbalanceOf<T>),
+    Asset {
+        asset_id: T::AssetId,
+        amount: BalanceOf<T>,
+        native_amount: BalanceOf<T>,
+    },
+}
+
+/// Handler for the fees charged to a container chain. `collator` is the collator that provided
+/// the charged service, if there is a single one.
+pub trait OnChargeForService<AccountId, Imbalance> {
+    fn on_charge(para_id: ParaId, collator: Option<&AccountId>, imbalance: Imbalance);
+}
+
+impl<AccountId, Imbalance> OnChargeForService<AccountId, Imbalance> for () {
+    fn on_charge(_para_id: ParaId, _collator: Option<&AccountId>, _imbalance: Imbalance) {
+        // Dropping the imbalance burns it
+    }
+}
+
+/// Splits the charged fees: `TreasuryShare` goes to `Treasury`, `BurnShare` is burned and the rest
+/// is given to the collator that provided the service through `RewardsDistributor`. If there is
+/// no such collator, the rest goes to `Treasury`. Fees paid with assets are not split, see
+/// `Config::AssetFeesAccount`.
+pub struct FeeSplitter<T, TreasuryShare, BurnShare, Treasury, RewardsDistributor>(
+    PhantomData<(T, TreasuryShare, BurnShare, Treasury, RewardsDistributor)>,
+);
+
+impl<T, TreasuryShare, BurnShare, Treasury, RewardsDistributor>
+    OnChargeForService<T::AccountId, NegativeImbalanceOf<T>>
+    for FeeSplitter<T, TreasuryShare, BurnShare, Treasury, RewardsDistributor>
+where
+    T: Config,
+    TreasuryShare: Get<Perbill>,
+    BurnShare: Get<Perbill>,
+    Treasury: OnUnbalanced<NegativeImbalanceOf<T>>,
+    RewardsDistributor: DistributeRewards<T::AccountId, NegativeImbalanceOf<T>>,
+{
+    fn on_charge(
+        para_id: ParaId,
+        collator: Option<&T::AccountId>,
+        imbalance: NegativeImbalanceOf<T>,
+    ) {
+        let total = imbalance.peek();
+        let (mut to_treasury, rest) = imbalance.split(TreasuryShare::get() * total);
+        let (to_burn, to_collator) = rest.split(BurnShare::get() * total);
+
+        let mut burned = to_burn.peek();
+        // Dropping the imbalance burns it
+        drop(to_burn);
+
+        let mut rewarded_collator = None;
+        let mut collator_reward = Zero::zero();
+        match collator {
+            Some(collator) if !to_collator.peek().is_zero() => {
+                let reward = to_collator.peek();
+                match RewardsDistributor::distribute_rewards(collator.clone(), to_collator) {
+                    Ok(_) => {
+                        rewarded_collator = Some(collator.clone());
+                        collator_reward = reward;
+                    }
+                    Err(e) => {
+                        // The distributor drops the rewards it could not distribute
+                        burned = burned.saturating_add(reward);
+                        log::warn!(
+                            "Failed to reward collator for container chain {}: {:?}",
+                            u32::from(para_id),
+                            e
+                        );
+                    }
+                }
+            }
+            _ => to_treasury.subsume(to_collator),
+        }
+
+        let treasury_amount = to_treasury.peek();
+        Treasury::on_unbalanced(to_treasury);
+
+        Pallet::<T>::deposit_event(Event::<T>::ServiceFeesDistributed {
+            para_id,
+            to_treasury: treasury_amount,
+            burned,
+            collator: rewarded_collator,
+            to_collator: collator_reward,
+        });
+    }
+}
+
 /// Returns the cost for a given block credit at the current time. This can be a complex operation,
 /// so it also returns the weight it consumes. (TODO: or just rely on benchmarking)
 pub trait ProvideBlockProductionCost<T: Config> {
@@ -500,35 +920,211 @@ pub trait ProvideCollatorAssignmentCost<T: Config> {
     fn collator_assignment_cost(para_id: &ParaId) -> (BalanceOf<T>, Weight);
 }
 
+/// Demand of collators at the current time, used to adjust the price of services.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct CollatorDemand {
+    /// Number of collators needed to serve all the registered container chains
+    pub required_collators: u32,
+    /// Number of collators that can be assigned to container chains
+    pub available_collators: u32,
+    /// Fraction of the available collators that we aim to have assigned to container chains
+    pub target_fullness: Perbill,
+}
+
+impl CollatorDemand {
+    /// Ratio between the required collators and the target number of collators, bounded by
+    /// `min` and `max`. Demand at the target fullness results in a multiplier of 1.
+    pub fn cost_multiplier(&self, min: FixedU128, max: FixedU128) -> FixedU128 {
+        let target_collators = self.target_fullness.mul_floor(self.available_collators);
+        let multiplier = if target_collators == 0 {
+            // No collators to serve the demand, if any
+            if self.required_collators == 0 {
+                FixedU128::one()
+            } else {
+                max
+            }
+        } else {
+            FixedU128::saturating_from_rational(self.required_collators, target_collators)
+        };
+
+        multiplier.max(min).min(max)
+    }
+}
+
+/// Returns the current demand of collators. This can be a complex operation, so it also returns
+/// the weight it consumes.
+pub trait ProvideCollatorDemand {
+    fn collator_demand() -> (CollatorDemand, Weight);
+}
+
+/// Computes the collator demand using `Demand` once per session, and returns the cached value
+/// for the rest of the session. Changes that happen in the middle of a session, such as
+/// container chains requesting a different number of collators, are taken into account in the
+/// next session.
+pub struct CollatorDemandCachedPerSession<T, Demand, SessionIndex>(
+    PhantomData<(T, Demand, SessionIndex)>,
+);
+
+impl<T, Demand, SessionIndex> ProvideCollatorDemand
+    for CollatorDemandCachedPerSession<T, Demand, SessionIndex>
+where
+    T: Config,
+    Demand: ProvideCollatorDemand,
+    SessionIndex: GetSessionIndex<u32>,
+{
+    fn collator_demand() -> (CollatorDemand, Weight) {
+        let session_index = SessionIndex::session_index();
+        // Session index and cached demand
+        let weight = T::DbWeight::get().reads(2);
+
+        match CachedCollatorDemand::<T>::get() {
+            Some((cached_session_index, demand)) if cached_session_index == session_index => {
+                (demand, weight)
+            }
+            _ => {
+                let (demand, demand_weight) = Demand::collator_demand();
+                CachedCollatorDemand::<T>::put((session_index, demand.clone()));
+
+                (
+                    demand,
+                    weight
+                        .saturating_add(demand_weight)
+                        .saturating_add(T::DbWeight::get().writes(1)),
+                )
+            }
+        }
+    }
+}
+
+/// Cost provider that scales `BaseCost` with the current collator demand. The price matches
+/// `BaseCost` when container chains use the target fullness of the available collators, and grows
+/// or shrinks proportionally otherwise, bounded by `MinMultiplier` and `MaxMultiplier`.
+pub struct DemandBasedCost<T, BaseCost, Demand, MinMultiplier, MaxMultiplier>(
+    PhantomData<(T, BaseCost, Demand, MinMultiplier, MaxMultiplier)>,
+);
+
+impl<T, BaseCost, Demand, MinMultiplier, MaxMultiplier>
+    DemandBasedCost<T, BaseCost, Demand, MinMultiplier, MaxMultiplier>
+where
+    T: Config,
+    BaseCost: Get<BalanceOf<T>>,
+    Demand: ProvideCollatorDemand,
+    MinMultiplier: Get<FixedU128>,
+    MaxMultiplier: Get<FixedU128>,
+{
+    pub fn cost() -> (BalanceOf<T>, Weight) {
+        let (demand, weight) = Demand::collator_demand();
+        let multiplier = demand.cost_multiplier(MinMultiplier::get(), MaxMultiplier::get());
+
+        (multiplier.saturating_mul_int(BaseCost::get()), weight)
+    }
+}
+
+impl<T, BaseCost, Demand, MinMultiplier, MaxMultiplier> ProvideBlockProductionCost<T>
+    for DemandBasedCost<T, BaseCost, Demand, MinMultiplier, MaxMultiplier>
+where
+    T: Config,
+    BaseCost: Get<BalanceOf<T>>,
+    Demand: ProvideCollatorDemand,
+    MinMultiplier: Get<FixedU128>,
+    MaxMultiplier: Get<FixedU128>,
+{
+    fn block_cost(_para_id: &ParaId) -> (BalanceOf<T>, Weight) {
+        Self::cost()
+    }
+}
+
+impl<T, BaseCost, Demand, MinMultiplier, MaxMultiplier> ProvideCollatorAssignmentCost<T>
+    for DemandBasedCost<T, BaseCost, Demand, MinMultiplier, MaxMultiplier>
+where
+    T: Config,
+    BaseCost: Get<BalanceOf<T>>,
+    Demand: ProvideCollatorDemand,
+    MinMultiplier: Get<FixedU128>,
+    MaxMultiplier: Get<FixedU128>,
+{
+    fn collator_assignment_cost(_para_id: &ParaId) -> (BalanceOf<T>, Weight) {
+        Self::cost()
+    }
+}
+
+/// Cost provider that scales the collator assignment cost of `BaseCost` with the max number of
+/// collators requested by the container chain in `RequestedCollators`. `DefaultCollators` returns
+/// the default number of collators of each para, which pays exactly `BaseCost`.
+pub struct RequestedCollatorsScaledCost<T, BaseCost, DefaultCollators>(
+    PhantomData<(T, BaseCost, DefaultCollators)>,
+);
+
+impl<T, BaseCost, DefaultCollators> ProvideCollatorAssignmentCost<T>
+    for RequestedCollatorsScaledCost<T, BaseCost, DefaultCollators>
+where
+    T: Config,
+    BaseCost: ProvideCollatorAssignmentCost<T>,
+    DefaultCollators: Convert<ParaId, u32>,
+{
+    fn collator_assignment_cost(para_id: &ParaId) -> (BalanceOf<T>, Weight) {
+        let (cost, weight) = BaseCost::collator_assignment_cost(para_id);
+        let weight = weight.saturating_add(T::DbWeight::get().reads(1));
+        let Some(requested_collators) = RequestedCollators::<T>::get(para_id) else {
+            return (cost, weight);
+        };
+
+        let default_collators = DefaultCollators::convert(*para_id);
+        if default_collators == 0 {
+            // Avoid division by zero, the base cost is the cost of a single collator
+            let cost = cost.saturating_mul(requested_collators.max_collators.into());
+            return (cost, weight);
+        }
+        let multiplier = FixedU128::saturating_from_rational(
+            requested_collators.max_collators,
+            default_collators,
+        );
+
+        (multiplier.saturating_mul_int(cost), weight)
+    }
+}
+
 impl<T: Config> AuthorNotingHook<T::AccountId> for Pallet<T> {
     // This hook is called when pallet_author_noting sees that the block number of a container chain has increased.
-    // Currently we always charge 1 credit, even if a container chain produced more that 1 block in between tanssi
-    // blocks.
+    // We charge 1 credit for each block produced since the previous noted block, up to
+    // `MaxBlocksChargedPerNoting`. Parathread blocks that do not respect the slot frequency are not charged.
     fn on_container_author_noted(
-        _author: &T::AccountId,
-        _block_number: BlockNumber,
+        author: &T::AccountId,
+        previous_block_number: Option<BlockNumber>,
+        block_number: BlockNumber,
         para_id: ParaId,
+        respects_slot_frequency: bool,
     ) -> Weight {
-        if Pallet::<T>::burn_block_production_free_credit_for_para(&para_id).is_err() {
-            let (amount_to_charge, _weight) = T::ProvideBlockProductionCost::block_cost(&para_id);
-            match T::Currency::withdraw(
-                &Self::parachain_tank(para_id),
-                amount_to_charge,
-                WithdrawReasons::FEE,
-                ExistenceRequirement::KeepAlive,
-            ) {
+        if !respects_slot_frequency {
+            return Weight::zero();
+        }
+
+        let blocks_produced = blocks_produced_since(previous_block_number, block_number)
+            .min(T::MaxBlocksChargedPerNoting::get());
+        let blocks_paid_with_credits =
+            Pallet::<T>::burn_block_production_free_credits_for_para(&para_id, blocks_produced);
+        let blocks_to_charge = blocks_produced.saturating_sub(blocks_paid_with_credits);
+
+        let mut weight = T::WeightInfo::on_container_author_noted();
+
+        if !blocks_to_charge.is_zero() {
+            let (block_cost, block_cost_weight) =
+                T::ProvideBlockProductionCost::block_cost(&para_id);
+            weight.saturating_accrue(block_cost_weight);
+            let amount_to_charge = block_cost.saturating_mul(blocks_to_charge.into());
+            match Self::charge_tank(para_id, amount_to_charge) {
                 Err(e) => log::warn!(
                     "Failed to withdraw block production payment for container chain {}: {:?}",
                     u32::from(para_id),
                     e
                 ),
-                Ok(imbalance) => {
-                    T::OnChargeForBlock::on_unbalanced(imbalance);
+                Ok(charge) => {
+                    Self::settle_tank_charge::<T::OnChargeForBlock>(para_id, Some(author), charge);
                 }
             }
         }
 
-        T::WeightInfo::on_container_author_noted()
+        weight
     }
 }
 
@@ -539,21 +1135,25 @@ impl<T: Config> CollatorAssignmentHook<BalanceOf<T>> for Pallet<T> {
         maybe_tip: Option<&BalanceOf<T>>,
         _is_parathread: bool,
     ) -> Result<Weight, DispatchError> {
+        let mut weight = T::WeightInfo::on_collators_assigned();
+
         // Withdraw assignment fee
-        let maybe_assignment_imbalance =
+        let maybe_assignment_charge =
             if Pallet::<T>::burn_collator_assignment_free_credit_for_para(&para_id).is_err() {
-                let (amount_to_charge, _weight) =
+                let (amount_to_charge, cost_weight) =
                     T::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id);
-                Some(T::Currency::withdraw(
-                    &Self::parachain_tank(para_id),
-                    amount_to_charge,
-                    WithdrawReasons::FEE,
-                    ExistenceRequirement::KeepAlive,
-                )?)
+                weight.saturating_accrue(cost_weight);
+                match Self::charge_tank(para_id, amount_to_charge) {
+                    Ok(charge) => Some(charge),
+                    // Paras in their grace period keep their collators even if they cannot pay
+                    Err(_) if Self::using_grace_period(para_id) => None,
+                    Err(e) => return Err(e),
+                }
             } else {
                 None
             };
 
+        // Tips are always paid with the native currency
         if let Some(&tip) = maybe_tip {
             // Only charge the tip to the paras that had a max tip set
             // (aka were willing to tip for being assigned a collator)
@@ -565,12 +1165,9 @@ impl<T: Config> CollatorAssignmentHook<BalanceOf<T>> for Pallet<T> {
                     ExistenceRequirement::KeepAlive,
                 ) {
                     Err(e) => {
-                        // Return assignment imbalance to tank on error
-                        if let Some(assignment_imbalance) = maybe_assignment_imbalance {
-                            T::Currency::resolve_creating(
-                                &Self::parachain_tank(para_id),
-                                assignment_imbalance,
-                            );
+                        // Return assignment charge to tank on error
+                        if let Some(assignment_charge) = maybe_assignment_charge {
+                            Self::refund_tank_charge(para_id, assignment_charge);
                         }
                         return Err(e);
                     }
@@ -580,17 +1177,34 @@ impl<T: Config> CollatorAssignmentHook<BalanceOf<T>> for Pallet<T> {
                             payer: Self::parachain_tank(para_id),
                             tip,
                         });
-                        T::OnChargeForCollatorAssignmentTip::on_unbalanced(tip_imbalance);
+                        T::OnChargeForCollatorAssignmentTip::on_charge(
+                            para_id,
+                            None,
+                            tip_imbalance,
+                        );
                     }
                 }
             }
         }
 
-        if let Some(assignment_imbalance) = maybe_assignment_imbalance {
-            T::OnChargeForCollatorAssignment::on_unbalanced(assignment_imbalance);
+        if let Some(assignment_charge) = maybe_assignment_charge {
+            Self::settle_tank_charge::<T::OnChargeForCollatorAssignment>(
+                para_id,
+                None,
+                assignment_charge,
+            );
+        }
+
+        let (sessions_left, runway_weight) = Self::sessions_of_runway_with_weight(para_id);
+        weight.saturating_accrue(runway_weight);
+        if sessions_left < T::LowBalanceWarningSessions::get() {
+            Self::deposit_event(Event::<T>::LowTankBalance {
+                para_id,
+                sessions_left,
+            });
         }
 
-        Ok(T::WeightInfo::on_collators_assigned())
+        Ok(weight)
     }
 }
 
@@ -600,7 +1214,206 @@ impl<T: Config> CollatorAssignmentTip<BalanceOf<T>> for Pallet<T> {
     }
 }
 
+impl<T: Config> GetCollatorRequirements for Pallet<T> {
+    fn collator_requirements(para_id: ParaId) -> Option<CollatorRequirements> {
+        RequestedCollators::<T>::get(para_id)
+    }
+}
+
 impl<T: Config> Pallet<T> {
+    /// Number of sessions the given para can pay for with its free credits and the funds in its
+    /// tank, assuming it produces a block in every slot. Leftover credits that cannot pay for a
+    /// full session are not taken into account, so this is a lower bound.
+    pub fn sessions_of_runway(para_id: ParaId) -> u32 {
+        Self::sessions_of_runway_with_weight(para_id).0
+    }
+
+    /// Same as `sessions_of_runway`, but also returns the weight consumed by the cost providers.
+    fn sessions_of_runway_with_weight(para_id: ParaId) -> (u32, Weight) {
+        let blocks_per_session = T::BlocksPerSession::get().max(1);
+        let block_credits: u32 = BlockProductionCredits::<T>::get(para_id)
+            .unwrap_or_default()
+            .saturated_into();
+        let session_credits = CollatorAssignmentCredits::<T>::get(para_id).unwrap_or_default();
+        let sessions_paid_with_credits = (block_credits / blocks_per_session).min(session_credits);
+
+        let (block_cost, block_cost_weight) = T::ProvideBlockProductionCost::block_cost(&para_id);
+        let (assignment_cost, assignment_cost_weight) =
+            T::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id);
+        let weight = block_cost_weight.saturating_add(assignment_cost_weight);
+        let session_cost = block_cost
+            .saturating_mul(blocks_per_session.into())
+            .saturating_add(assignment_cost);
+        if session_cost.is_zero() {
+            return (u32::MAX, weight);
+        }
+
+        // The tank must be kept alive
+        let tank_balance = T::Currency::free_balance(&Self::parachain_tank(para_id))
+            .saturating_sub(T::Currency::minimum_balance())
+            .saturating_add(Self::tank_assets_value(para_id));
+        let sessions_paid_with_tank: u32 = (tank_balance / session_cost).saturated_into();
+
+        (
+            sessions_paid_with_credits.saturating_add(sessions_paid_with_tank),
+            weight,
+        )
+    }
+
+    /// Value in the native currency of the assets held in the tank of the given para.
+    pub fn tank_assets_value(para_id: ParaId) -> BalanceOf<T> {
+        let tank = Self::parachain_tank(para_id);
+        TankAssets::<T>::get(para_id)
+            .iter()
+            .fold(Zero::zero(), |value: BalanceOf<T>, asset_id| {
+                let balance = T::Assets::reducible_balance(asset_id, &tank, Preservation::Preserve);
+                let asset_value =
+                    T::AssetRateConverter::asset_to_native(asset_id, balance).unwrap_or_default();
+                value.saturating_add(asset_value)
+            })
+    }
+
+    /// Returns the first asset held in the tank of the given para that can pay for
+    /// `native_amount`, and the amount of that asset to pay.
+    pub fn tank_asset_to_pay(
+        para_id: ParaId,
+        native_amount: BalanceOf<T>,
+    ) -> Option<(T::AssetId, BalanceOf<T>)> {
+        let tank = Self::parachain_tank(para_id);
+        TankAssets::<T>::get(para_id)
+            .into_iter()
+            .find_map(|asset_id| {
+                let amount = T::AssetRateConverter::native_to_asset(&asset_id, native_amount)?;
+                let balance =
+                    T::Assets::reducible_balance(&asset_id, &tank, Preservation::Preserve);
+
+                (balance >= amount).then_some((asset_id, amount))
+            })
+    }
+
+    /// Charge `amount` to the tank of the given para. The native balance of the tank is used if
+    /// possible, otherwise the charge is paid to the `AssetFeesAccount` with one of the assets
+    /// held in the tank. The charge fails if the asset cannot be transferred.
+    fn charge_tank(para_id: ParaId, amount: BalanceOf<T>) -> Result<TankCharge<T>, DispatchError> {
+        let tank = Self::parachain_tank(para_id);
+        let native_error = match T::Currency::withdraw(
+            &tank,
+            amount,
+            WithdrawReasons::FEE,
+            ExistenceRequirement::KeepAlive,
+        ) {
+            Ok(imbalance) => return Ok(TankCharge::Native(imbalance)),
+            Err(e) => e,
+        };
+
+        let (asset_id, asset_amount) =
+            Self::tank_asset_to_pay(para_id, amount).ok_or(native_error)?;
+        T::Assets::transfer(
+            &asset_id,
+            &tank,
+            &T::AssetFeesAccount::get(),
+            asset_amount,
+            Preservation::Preserve,
+        )?;
+
+        Ok(TankCharge::Asset {
+            asset_id,
+            amount: asset_amount,
+            native_amount: amount,
+        })
+    }
+
+    /// Return a charge to the tank of the given para, used when a service that was already
+    /// charged cannot be provided.
+    fn refund_tank_charge(para_id: ParaId, charge: TankCharge<T>) {
+        let tank = Self::parachain_tank(para_id);
+        match charge {
+            TankCharge::Native(imbalance) => T::Currency::resolve_creating(&tank, imbalance),
+            TankCharge::Asset {
+                asset_id, amount, ..
+            } => {
+                if let Err(e) = T::Assets::transfer(
+                    &asset_id,
+                    &T::AssetFeesAccount::get(),
+                    &tank,
+                    amount,
+                    Preservation::Expendable,
+                ) {
+                    log::warn!(
+                        "Failed to refund fees paid with assets to container chain {}: {:?}",
+                        u32::from(para_id),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Hand a charge to `OnCharge`. Charges paid with an asset have already been transferred to
+    /// the `AssetFeesAccount`, so they are not split by `OnCharge`.
+    fn settle_tank_charge<OnCharge: OnChargeForService<T::AccountId, NegativeImbalanceOf<T>>>(
+        para_id: ParaId,
+        collator: Option<&T::AccountId>,
+        charge: TankCharge<T>,
+    ) {
+        match charge {
+            TankCharge::Native(imbalance) => OnCharge::on_charge(para_id, collator, imbalance),
+            TankCharge::Asset {
+                asset_id,
+                amount,
+                native_amount,
+            } => Self::deposit_event(Event::<T>::ServiceFeesPaidWithAsset {
+                para_id,
+                asset_id,
+                amount,
+                native_amount,
+            }),
+        }
+    }
+
+    /// Called once per session change for a running para that cannot pay for its next session.
+    /// Returns whether the para should keep its collators because it is still in its grace period.
+    pub fn use_grace_period(para_id: ParaId) -> bool {
+        let sessions_left = match GracePeriodSessionsLeft::<T>::get(para_id) {
+            // Start of the grace period
+            None => T::GracePeriodSessions::get(),
+            Some(sessions_left) => sessions_left.saturating_sub(1),
+        };
+        GracePeriodSessionsLeft::<T>::insert(para_id, sessions_left);
+        if sessions_left.is_zero() {
+            return false;
+        }
+
+        Self::deposit_event(Event::<T>::GracePeriodUsed {
+            para_id,
+            sessions_left: sessions_left.saturating_sub(1),
+        });
+
+        true
+    }
+
+    /// Whether a running para that cannot pay keeps its collators for the session being
+    /// assigned, because its grace period has not started yet or has sessions left. Does not
+    /// modify storage.
+    pub fn in_grace_period(para_id: ParaId) -> bool {
+        GracePeriodSessionsLeft::<T>::get(para_id)
+            .map_or(!T::GracePeriodSessions::get().is_zero(), |sessions_left| {
+                sessions_left > 0
+            })
+    }
+
+    /// Whether the para is using its grace period in the session being assigned. Unlike
+    /// `in_grace_period`, this is only true after `use_grace_period` was called for the para.
+    fn using_grace_period(para_id: ParaId) -> bool {
+        GracePeriodSessionsLeft::<T>::get(para_id).map_or(false, |sessions_left| sessions_left > 0)
+    }
+
+    /// Called for a para that can pay for its next session, so that it gets a full grace period
+    /// the next time it runs out of funds.
+    pub fn reset_grace_period(para_id: ParaId) {
+        GracePeriodSessionsLeft::<T>::remove(para_id);
+    }
+
     /// Derive a derivative account ID from the paraId.
     pub fn parachain_tank(para_id: ParaId) -> T::AccountId {
         let entropy = (b"modlpy/serpayment", para_id).using_encoded(blake2_256);
@@ -610,6 +1423,24 @@ impl<T: Config> Pallet<T> {
 
     /// Hook to perform things on deregister
     pub fn para_deregistered(para_id: ParaId) {
+        // Drain the para-id account from assets, which cannot be burned
+        let parachain_tank = Self::parachain_tank(para_id);
+        let assets_destination =
+            RefundAddress::<T>::get(para_id).unwrap_or_else(T::AssetFeesAccount::get);
+        for asset_id in TankAssets::<T>::take(para_id) {
+            let balance =
+                T::Assets::reducible_balance(&asset_id, &parachain_tank, Preservation::Expendable);
+            if !balance.is_zero() {
+                let _ = T::Assets::transfer(
+                    &asset_id,
+                    &parachain_tank,
+                    &assets_destination,
+                    balance,
+                    Preservation::Expendable,
+                );
+            }
+        }
+
         // Drain the para-id account from tokens
         let parachain_tank_balance = T::Currency::total_balance(&Self::parachain_tank(para_id));
         if !parachain_tank_balance.is_zero() {
@@ -636,5 +1467,7 @@ impl<T: Config> Pallet<T> {
         CollatorAssignmentCredits::<T>::remove(para_id);
         MaxTip::<T>::remove(para_id);
         MaxCorePrice::<T>::remove(para_id);
+        RequestedCollators::<T>::remove(para_id);
+        GracePeriodSessionsLeft::<T>::remove(para_id);
     }
 }
@@ -16,31 +16,42 @@
 
 use {
     crate::{
-        self as pallet_services_payment, ProvideBlockProductionCost, ProvideCollatorAssignmentCost,
+        self as pallet_services_payment, AssetRateConverter, CollatorDemand,
+        CollatorDemandCachedPerSession, DemandBasedCost, FeeSplitter, NegativeImbalanceOf,
+        ProvideBlockProductionCost, ProvideCollatorAssignmentCost, ProvideCollatorDemand,
+        RequestedCollatorsScaledCost,
     },
     cumulus_primitives_core::ParaId,
     frame_support::{
         pallet_prelude::*,
         parameter_types,
-        traits::{ConstU32, ConstU64, Everything},
+        traits::{
+            fungibles,
+            tokens::{Fortitude, Preservation},
+            AsEnsureOriginWithArg, ConstU128, ConstU32, ConstU64, Currency, Everything,
+            OnUnbalanced,
+        },
     },
-    frame_system::EnsureRoot,
+    frame_system::{EnsureRoot, EnsureSigned},
     sp_core::H256,
     sp_runtime::{
-        traits::{BlakeTwo256, IdentityLookup},
-        BuildStorage,
+        traits::{BlakeTwo256, ConvertToValue, IdentityLookup},
+        BuildStorage, FixedU128, Perbill,
     },
+    tp_traits::{DistributeRewards, GetSessionIndex},
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
 type AccountId = u64;
 type Balance = u128;
+pub(crate) type AssetId = u32;
 
 frame_support::construct_runtime!(
     pub enum Test
     {
         System: frame_system::{Pallet, Call, Config<T>, Storage, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
         PaymentServices: pallet_services_payment::{Pallet, Call, Config<T>, Storage, Event<T>}
     }
 );
@@ -93,27 +104,72 @@ impl pallet_balances::Config for Test {
     type WeightInfo = ();
 }
 
+impl pallet_assets::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Balance = Balance;
+    type AssetId = AssetId;
+    type AssetIdParameter = AssetId;
+    type Currency = Balances;
+    type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<AccountId>>;
+    type ForceOrigin = EnsureRoot<AccountId>;
+    type AssetDeposit = ConstU128<0>;
+    type AssetAccountDeposit = ConstU128<0>;
+    type MetadataDepositBase = ConstU128<0>;
+    type MetadataDepositPerByte = ConstU128<0>;
+    type ApprovalDeposit = ConstU128<0>;
+    type StringLimit = ConstU32<50>;
+    type Freezer = ();
+    type Extra = ();
+    type CallbackHandle = ();
+    type WeightInfo = ();
+    type RemoveItemsLimit = ConstU32<1000>;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = ();
+}
+
 parameter_types! {
     pub const FreeBlockProductionCredits: u64 = 5;
     pub const FreeCollatorAssignmentCredits: u32 = 5;
+    pub const MaxBlocksChargedPerNoting: u32 = 3;
+    pub const BlocksPerSession: u32 = 10;
+    pub const LowBalanceWarningSessions: u32 = 2;
+    pub const GracePeriodSessions: u32 = 2;
+    pub const MaxRequestedCollators: u32 = 10;
+    pub const AssetFeesAccount: AccountId = TREASURY;
+    pub const MaxTankAssets: u32 = 2;
 }
 
 impl pallet_services_payment::Config for Test {
     type RuntimeEvent = RuntimeEvent;
-    type OnChargeForBlock = ();
+    type OnChargeForBlock = MockFeeSplitter;
     type OnChargeForCollatorAssignment = ();
     type OnChargeForCollatorAssignmentTip = ();
     type Currency = Balances;
     type ProvideBlockProductionCost = BlockProductionCost<Test>;
-    type ProvideCollatorAssignmentCost = CollatorAssignmentProductionCost<Test>;
+    type ProvideCollatorAssignmentCost = RequestedCollatorsScaledCost<
+        Test,
+        CollatorAssignmentProductionCost<Test>,
+        ConvertToValue<ConstU32<DEFAULT_COLLATORS>>,
+    >;
     type FreeBlockProductionCredits = FreeBlockProductionCredits;
     type FreeCollatorAssignmentCredits = FreeCollatorAssignmentCredits;
+    type MaxBlocksChargedPerNoting = MaxBlocksChargedPerNoting;
+    type BlocksPerSession = BlocksPerSession;
+    type LowBalanceWarningSessions = LowBalanceWarningSessions;
+    type GracePeriodSessions = GracePeriodSessions;
+    type MaxRequestedCollators = MaxRequestedCollators;
+    type AssetId = AssetId;
+    type Assets = MockAssets;
+    type AssetRateConverter = MockAssetRateConverter;
+    type AssetFeesAccount = AssetFeesAccount;
+    type MaxTankAssets = MaxTankAssets;
     type ManagerOrigin = EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
 pub(crate) const FIXED_BLOCK_PRODUCTION_COST: u128 = 100;
 pub(crate) const FIXED_COLLATOR_ASSIGNMENT_COST: u128 = 200;
+pub(crate) const DEFAULT_COLLATORS: u32 = 2;
 
 pub struct BlockProductionCost<Test>(PhantomData<Test>);
 impl ProvideBlockProductionCost<Test> for BlockProductionCost<Test> {
@@ -129,9 +185,136 @@ impl ProvideCollatorAssignmentCost<Test> for CollatorAssignmentProductionCost<Te
     }
 }
 
+parameter_types! {
+    pub static CurrentCollatorDemand: CollatorDemand = CollatorDemand::default();
+    pub const BaseCost: u128 = 1_000;
+    pub MinimumCostMultiplier: FixedU128 = FixedU128::from_rational(1, 2);
+    pub MaximumCostMultiplier: FixedU128 = FixedU128::from_u32(4);
+}
+
+pub struct CollatorDemandGetter;
+impl ProvideCollatorDemand for CollatorDemandGetter {
+    fn collator_demand() -> (CollatorDemand, Weight) {
+        (CurrentCollatorDemand::get(), Weight::zero())
+    }
+}
+
+pub type DemandBasedTestCost = DemandBasedCost<
+    Test,
+    BaseCost,
+    CollatorDemandGetter,
+    MinimumCostMultiplier,
+    MaximumCostMultiplier,
+>;
+
+parameter_types! {
+    pub static MockSessionIndex: u32 = 0;
+}
+
+pub struct MockSessionIndexGetter;
+impl GetSessionIndex<u32> for MockSessionIndexGetter {
+    fn session_index() -> u32 {
+        MockSessionIndex::get()
+    }
+}
+
+pub type CachedCollatorDemandGetter =
+    CollatorDemandCachedPerSession<Test, CollatorDemandGetter, MockSessionIndexGetter>;
+
+pub(crate) const TREASURY: AccountId = 1000;
+
+parameter_types! {
+    pub const FeesTreasuryShare: Perbill = Perbill::from_percent(20);
+    pub const FeesBurnShare: Perbill = Perbill::from_percent(30);
+    pub static RewardsDistributionFails: bool = false;
+}
+
+pub struct MockTreasury;
+impl OnUnbalanced<NegativeImbalanceOf<Test>> for MockTreasury {
+    fn on_nonzero_unbalanced(amount: NegativeImbalanceOf<Test>) {
+        Balances::resolve_creating(&TREASURY, amount);
+    }
+}
+
+pub struct MockRewardsDistributor;
+impl DistributeRewards<AccountId, NegativeImbalanceOf<Test>> for MockRewardsDistributor {
+    fn distribute_rewards(
+        rewarded: AccountId,
+        amount: NegativeImbalanceOf<Test>,
+    ) -> DispatchResultWithPostInfo {
+        if RewardsDistributionFails::get() {
+            return Err(DispatchError::Other("rewards distribution failed").into());
+        }
+        Balances::resolve_creating(&rewarded, amount);
+        Ok(().into())
+    }
+}
+
+pub type MockFeeSplitter =
+    FeeSplitter<Test, FeesTreasuryShare, FeesBurnShare, MockTreasury, MockRewardsDistributor>;
+
+/// Asset accepted to pay for services
+pub(crate) const USDT: AssetId = 1;
+/// Asset that cannot be used to pay for services
+pub(crate) const UNACCEPTED_ASSET: AssetId = 2;
+pub(crate) const ASSET_OWNER: AccountId = 999;
+
+parameter_types! {
+    // 1 USDT is worth 2 units of the native currency
+    pub static UsdtRate: FixedU128 = FixedU128::from_u32(2);
+}
+
+pub struct MockAssets;
+impl pallet_services_payment::Assets<AccountId, AssetId, Balance> for MockAssets {
+    fn reducible_balance(
+        asset_id: &AssetId,
+        account: &AccountId,
+        preservation: Preservation,
+    ) -> Balance {
+        <Assets as fungibles::Inspect<_>>::reducible_balance(
+            *asset_id,
+            account,
+            preservation,
+            Fortitude::Polite,
+        )
+    }
+
+    fn transfer(
+        asset_id: &AssetId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Balance,
+        preservation: Preservation,
+    ) -> DispatchResult {
+        <Assets as fungibles::Mutate<_>>::transfer(*asset_id, from, to, amount, preservation)
+            .map(|_| ())
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_worst_case_asset_id() -> AssetId {
+        USDT
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_balance(asset_id: &AssetId, account: &AccountId, amount: Balance) {
+        <Assets as fungibles::Mutate<_>>::set_balance(*asset_id, account, amount);
+    }
+}
+
+pub struct MockAssetRateConverter;
+impl AssetRateConverter<AssetId, Balance> for MockAssetRateConverter {
+    fn rate_to_native(asset_id: &AssetId) -> Option<FixedU128> {
+        (*asset_id == USDT).then(UsdtRate::get)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_rate(_asset_id: &AssetId) {}
+}
+
 #[derive(Default)]
 pub struct ExtBuilder {
     balances: Vec<(AccountId, Balance)>,
+    asset_balances: Vec<(AssetId, AccountId, Balance)>,
 }
 
 impl ExtBuilder {
@@ -140,6 +323,14 @@ impl ExtBuilder {
         self
     }
 
+    pub fn with_asset_balances(
+        mut self,
+        asset_balances: Vec<(AssetId, AccountId, Balance)>,
+    ) -> Self {
+        self.asset_balances = asset_balances;
+        self
+    }
+
     pub fn build(self) -> sp_io::TestExternalities {
         let mut t = frame_system::GenesisConfig::<Test>::default()
             .build_storage()
@@ -151,6 +342,17 @@ impl ExtBuilder {
         .assimilate_storage(&mut t)
         .unwrap();
 
+        pallet_assets::GenesisConfig::<Test> {
+            assets: vec![
+                (USDT, ASSET_OWNER, true, 1),
+                (UNACCEPTED_ASSET, ASSET_OWNER, true, 1),
+            ],
+            metadata: vec![],
+            accounts: self.asset_balances,
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+
         t.into()
     }
 }
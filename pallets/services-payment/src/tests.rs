@@ -16,14 +16,18 @@
 
 use {
     crate::{
-        mock::*, pallet as pallet_services_payment, BlockProductionCredits,
-        CollatorAssignmentCredits, ProvideBlockProductionCost, ProvideCollatorAssignmentCost,
-        RefundAddress,
+        mock::*, pallet as pallet_services_payment, BlockProductionCredits, CachedCollatorDemand,
+        CollatorAssignmentCredits, CollatorDemand, GracePeriodSessionsLeft, OnChargeForService,
+        ProvideBlockProductionCost, ProvideCollatorAssignmentCost, ProvideCollatorDemand,
+        RefundAddress, RequestedCollators, TankAssets,
     },
     cumulus_primitives_core::ParaId,
-    frame_support::{assert_err, assert_noop, assert_ok, traits::fungible::Inspect},
-    sp_runtime::DispatchError,
-    tp_traits::{AuthorNotingHook, CollatorAssignmentHook},
+    frame_support::{
+        assert_err, assert_noop, assert_ok,
+        traits::{fungible::Inspect, fungibles, tokens::ExistenceRequirement, WithdrawReasons},
+    },
+    sp_runtime::{DispatchError, FixedU128, Perbill},
+    tp_traits::{AuthorNotingHook, CollatorAssignmentHook, CollatorRequirements},
 };
 
 const ALICE: u64 = 1;
@@ -239,7 +243,7 @@ fn credits_should_be_substracted_from_tank_if_no_free_credits() {
                 1000u128
             );
 
-            PaymentServices::on_container_author_noted(&1, 1, 1.into());
+            PaymentServices::on_container_author_noted(&1, None, 1, 1.into(), true);
 
             assert_eq!(
                 Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
@@ -248,6 +252,107 @@ fn credits_should_be_substracted_from_tank_if_no_free_credits() {
         });
 }
 
+#[test]
+fn every_block_produced_since_previous_noting_is_charged() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            // this should give 10 block credit
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                1.into(),
+                1000u128,
+            ));
+
+            // Blocks 2 and 3 have been produced
+            PaymentServices::on_container_author_noted(&1, Some(1), 3, 1.into(), true);
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
+                800u128
+            );
+        });
+}
+
+#[test]
+fn blocks_charged_per_noting_are_capped() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            // this should give 10 block credit
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                1.into(),
+                1000u128,
+            ));
+
+            // 9 blocks produced, but only MaxBlocksChargedPerNoting are charged
+            PaymentServices::on_container_author_noted(&1, Some(1), 10, 1.into(), true);
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
+                1000u128
+                    - u128::from(MaxBlocksChargedPerNoting::get()) * FIXED_BLOCK_PRODUCTION_COST
+            );
+        });
+}
+
+#[test]
+fn free_credits_are_burned_before_charging_the_tank() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            // this should give 10 block credit
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                1.into(),
+                1000u128,
+            ));
+            assert_ok!(PaymentServices::set_block_production_credits(
+                RuntimeOrigin::root(),
+                1.into(),
+                1u32.into(),
+            ));
+
+            // 3 blocks produced, 1 paid with credits and 2 with the tank
+            PaymentServices::on_container_author_noted(&1, Some(1), 4, 1.into(), true);
+
+            assert_eq!(
+                <BlockProductionCredits<Test>>::get(ParaId::from(1)).unwrap_or_default(),
+                0
+            );
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
+                800u128
+            );
+        });
+}
+
+#[test]
+fn block_not_respecting_slot_frequency_is_not_charged() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            // this should give 10 block credit
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                1.into(),
+                1000u128,
+            ));
+
+            PaymentServices::on_container_author_noted(&1, None, 1, 1.into(), false);
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
+                1000u128
+            );
+        });
+}
+
 #[test]
 fn credits_should_not_be_substracted_from_tank_if_it_involves_death() {
     ExtBuilder::default()
@@ -266,7 +371,7 @@ fn credits_should_not_be_substracted_from_tank_if_it_involves_death() {
                 100u128
             );
 
-            PaymentServices::on_container_author_noted(&1, 1, 1.into());
+            PaymentServices::on_container_author_noted(&1, None, 1, 1.into(), true);
 
             assert_eq!(
                 Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
@@ -303,7 +408,7 @@ fn not_having_enough_tokens_in_tank_should_not_error() {
                 1u128
             );
 
-            PaymentServices::on_container_author_noted(&1, 1, 1.into());
+            PaymentServices::on_container_author_noted(&1, None, 1, 1.into(), true);
 
             assert_eq!(
                 Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
@@ -456,7 +561,7 @@ fn tip_should_be_charged_on_collators_assignment() {
                 false
             ));
 
-            PaymentServices::on_container_author_noted(&1, 1, para_id.into());
+            PaymentServices::on_container_author_noted(&1, None, 1, para_id.into(), true);
 
             let (assignment_cost, _weight) =
                 <Test as crate::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(
@@ -507,3 +612,770 @@ fn insufficient_balance_for_tip_reimburses_fee_imbalance() {
             );
         });
 }
+
+fn set_collator_demand(required_collators: u32, available_collators: u32) {
+    CurrentCollatorDemand::set(CollatorDemand {
+        required_collators,
+        available_collators,
+        target_fullness: Perbill::from_percent(80),
+    });
+}
+
+#[test]
+fn demand_based_cost_is_base_cost_at_target_fullness() {
+    ExtBuilder::default().build().execute_with(|| {
+        // 80% of 10 collators
+        set_collator_demand(8, 10);
+
+        let para_id = ParaId::from(1);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, BaseCost::get());
+        assert_eq!(
+            DemandBasedTestCost::collator_assignment_cost(&para_id).0,
+            BaseCost::get()
+        );
+    });
+}
+
+#[test]
+fn demand_based_cost_increases_with_demand() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        set_collator_demand(12, 10);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 1_500);
+
+        set_collator_demand(16, 10);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 2_000);
+    });
+}
+
+#[test]
+fn demand_based_cost_decreases_with_demand() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        set_collator_demand(6, 10);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 750);
+    });
+}
+
+#[test]
+fn demand_based_cost_is_bounded() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        set_collator_demand(0, 10);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 500);
+
+        set_collator_demand(100, 10);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 4_000);
+    });
+}
+
+#[test]
+fn demand_based_cost_without_available_collators() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        set_collator_demand(0, 0);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, BaseCost::get());
+
+        set_collator_demand(2, 0);
+        assert_eq!(DemandBasedTestCost::block_cost(&para_id).0, 4_000);
+    });
+}
+
+#[test]
+fn collator_demand_is_computed_once_per_session() {
+    ExtBuilder::default().build().execute_with(|| {
+        set_collator_demand(8, 10);
+        let demand = CurrentCollatorDemand::get();
+        assert_eq!(CachedCollatorDemandGetter::collator_demand().0, demand);
+        assert_eq!(
+            CachedCollatorDemand::<Test>::get(),
+            Some((0, demand.clone()))
+        );
+
+        // Changes in the middle of a session are ignored until the next session
+        set_collator_demand(16, 10);
+        assert_eq!(CachedCollatorDemandGetter::collator_demand().0, demand);
+
+        MockSessionIndex::set(1);
+        assert_eq!(
+            CachedCollatorDemandGetter::collator_demand().0,
+            CurrentCollatorDemand::get()
+        );
+    });
+}
+
+#[test]
+fn block_fees_are_split_between_treasury_burn_and_author() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            const AUTHOR: u64 = 5;
+
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                1.into(),
+                1000u128,
+            ));
+            let issuance_before = Balances::total_issuance();
+
+            PaymentServices::on_container_author_noted(&AUTHOR, None, 1, 1.into(), true);
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(1.into())),
+                900u128
+            );
+            assert_eq!(Balances::balance(&TREASURY), 20u128);
+            assert_eq!(Balances::balance(&AUTHOR), 50u128);
+            assert_eq!(Balances::total_issuance(), issuance_before - 30);
+            assert_eq!(
+                events().last(),
+                Some(&pallet_services_payment::Event::ServiceFeesDistributed {
+                    para_id: 1.into(),
+                    to_treasury: 20,
+                    burned: 30,
+                    collator: Some(AUTHOR),
+                    to_collator: 50,
+                })
+            );
+        });
+}
+
+#[test]
+fn fees_without_collator_are_split_between_treasury_and_burn() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let issuance_before = Balances::total_issuance();
+
+            let imbalance = <Balances as frame_support::traits::Currency<_>>::withdraw(
+                &ALICE,
+                100,
+                WithdrawReasons::FEE,
+                ExistenceRequirement::KeepAlive,
+            )
+            .unwrap();
+            MockFeeSplitter::on_charge(1.into(), None, imbalance);
+
+            assert_eq!(Balances::balance(&TREASURY), 70u128);
+            assert_eq!(Balances::total_issuance(), issuance_before - 30);
+            assert_eq!(
+                events().last(),
+                Some(&pallet_services_payment::Event::ServiceFeesDistributed {
+                    para_id: 1.into(),
+                    to_treasury: 70,
+                    burned: 30,
+                    collator: None,
+                    to_collator: 0,
+                })
+            );
+        });
+}
+
+#[test]
+fn collator_share_is_burned_if_rewards_cannot_be_distributed() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            const AUTHOR: u64 = 5;
+            RewardsDistributionFails::set(true);
+            let issuance_before = Balances::total_issuance();
+
+            let imbalance = <Balances as frame_support::traits::Currency<_>>::withdraw(
+                &ALICE,
+                100,
+                WithdrawReasons::FEE,
+                ExistenceRequirement::KeepAlive,
+            )
+            .unwrap();
+            MockFeeSplitter::on_charge(1.into(), Some(&AUTHOR), imbalance);
+
+            assert_eq!(Balances::balance(&TREASURY), 20u128);
+            assert_eq!(Balances::balance(&AUTHOR), 0u128);
+            assert_eq!(Balances::total_issuance(), issuance_before - 80);
+            assert_eq!(
+                events().last(),
+                Some(&pallet_services_payment::Event::ServiceFeesDistributed {
+                    para_id: 1.into(),
+                    to_treasury: 20,
+                    burned: 80,
+                    collator: None,
+                    to_collator: 0,
+                })
+            );
+        });
+}
+
+#[test]
+fn sessions_of_runway_counts_credits_and_tank() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000_000)].into())
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 0);
+
+            // Block credits for 2 sessions, the rest of credits are not enough for a full session
+            assert_ok!(PaymentServices::set_block_production_credits(
+                RuntimeOrigin::root(),
+                para_id,
+                25u64,
+            ));
+            assert_ok!(PaymentServices::set_collator_assignment_credits(
+                RuntimeOrigin::root(),
+                para_id,
+                5u32,
+            ));
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 2);
+
+            // A session costs 10 blocks plus one assignment, and the tank must be kept alive
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                2 * (10 * FIXED_BLOCK_PRODUCTION_COST + FIXED_COLLATOR_ASSIGNMENT_COST),
+            ));
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 3);
+
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1u128,
+            ));
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 4);
+        });
+}
+
+#[test]
+fn low_tank_balance_is_notified_on_collators_assigned() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000_000)].into())
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let para_id = ParaId::from(1);
+
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                3_000u128,
+            ));
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+            assert!(!events().iter().any(|event| matches!(
+                event,
+                pallet_services_payment::Event::LowTankBalance { .. }
+            )));
+
+            // 2_400 left in the tank, which must be kept alive, so only 1 session can be paid
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+            assert_eq!(
+                events().last(),
+                Some(&pallet_services_payment::Event::LowTankBalance {
+                    para_id,
+                    sessions_left: 1,
+                })
+            );
+        });
+}
+
+#[test]
+fn grace_period_is_used_and_exhausted() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let para_id = ParaId::from(1);
+
+        assert!(PaymentServices::use_grace_period(para_id));
+        assert!(PaymentServices::use_grace_period(para_id));
+        assert!(!PaymentServices::use_grace_period(para_id));
+        assert!(!PaymentServices::use_grace_period(para_id));
+        assert_eq!(
+            events(),
+            vec![
+                pallet_services_payment::Event::GracePeriodUsed {
+                    para_id,
+                    sessions_left: 1,
+                },
+                pallet_services_payment::Event::GracePeriodUsed {
+                    para_id,
+                    sessions_left: 0,
+                },
+            ]
+        );
+
+        // Paying for a session gives a full grace period again
+        PaymentServices::reset_grace_period(para_id);
+        assert!(PaymentServices::use_grace_period(para_id));
+        assert!(PaymentServices::in_grace_period(para_id));
+        assert_eq!(
+            GracePeriodSessionsLeft::<Test>::get(para_id),
+            Some(GracePeriodSessions::get())
+        );
+    });
+}
+
+#[test]
+fn collators_assigned_without_funds_during_grace_period() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        assert!(PaymentServices::on_collators_assigned(para_id, None, false).is_err());
+
+        assert!(PaymentServices::use_grace_period(para_id));
+        assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+        assert_eq!(
+            Balances::balance(&crate::Pallet::<Test>::parachain_tank(para_id)),
+            0u128
+        );
+    });
+}
+
+#[test]
+fn collators_assigned_without_funds_after_grace_period_is_exhausted() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        while PaymentServices::use_grace_period(para_id) {}
+        assert_eq!(GracePeriodSessionsLeft::<Test>::get(para_id), Some(0));
+        assert!(!PaymentServices::in_grace_period(para_id));
+        assert!(PaymentServices::on_collators_assigned(para_id, None, false).is_err());
+    });
+}
+
+fn tank_asset_balance(para_id: ParaId) -> u128 {
+    <Assets as fungibles::Inspect<_>>::balance(
+        USDT,
+        &crate::Pallet::<Test>::parachain_tank(para_id),
+    )
+}
+
+#[test]
+fn purchase_credits_with_asset_works() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let para_id = ParaId::from(1);
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                100u128,
+            ));
+
+            assert_eq!(
+                events(),
+                vec![pallet_services_payment::Event::CreditsPurchasedWithAsset {
+                    para_id,
+                    payer: ALICE,
+                    asset_id: USDT,
+                    amount: 100u128,
+                }]
+            );
+            assert_eq!(tank_asset_balance(para_id), 100u128);
+            assert_eq!(TankAssets::<Test>::get(para_id).into_inner(), vec![USDT]);
+        });
+}
+
+#[test]
+fn purchase_credits_with_unaccepted_asset_fails() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(UNACCEPTED_ASSET, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                PaymentServices::purchase_credits_with_asset(
+                    RuntimeOrigin::signed(ALICE),
+                    1.into(),
+                    UNACCEPTED_ASSET,
+                    100u128,
+                ),
+                pallet_services_payment::Error::<Test>::AssetNotAccepted,
+            );
+        });
+}
+
+#[test]
+fn purchase_credits_with_asset_below_minimum_fails() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+
+            assert_noop!(
+                PaymentServices::purchase_credits_with_asset(
+                    RuntimeOrigin::signed(ALICE),
+                    para_id,
+                    USDT,
+                    0u128,
+                ),
+                pallet_services_payment::Error::<Test>::AssetAmountTooLow,
+            );
+
+            // 1 USDT is worth less than the existential deposit of the native currency
+            UsdtRate::set(FixedU128::from_rational(1, 4));
+            assert_noop!(
+                PaymentServices::purchase_credits_with_asset(
+                    RuntimeOrigin::signed(ALICE),
+                    para_id,
+                    USDT,
+                    1u128,
+                ),
+                pallet_services_payment::Error::<Test>::AssetAmountTooLow,
+            );
+            assert!(TankAssets::<Test>::get(para_id).is_empty());
+        });
+}
+
+#[test]
+fn block_production_is_paid_with_assets_if_native_balance_is_not_enough() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            System::set_block_number(1);
+            let para_id = ParaId::from(1);
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1_000u128,
+            ));
+
+            PaymentServices::on_container_author_noted(&5, None, 1, para_id, true);
+
+            // A block costs 100 native units, which are worth 50 USDT
+            assert_eq!(tank_asset_balance(para_id), 950u128);
+            assert_eq!(
+                <Assets as fungibles::Inspect<_>>::balance(USDT, &TREASURY),
+                50u128
+            );
+            assert_eq!(
+                events().last(),
+                Some(&pallet_services_payment::Event::ServiceFeesPaidWithAsset {
+                    para_id,
+                    asset_id: USDT,
+                    amount: 50u128,
+                    native_amount: 100u128,
+                })
+            );
+        });
+}
+
+#[test]
+fn native_balance_is_used_before_assets() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 2_000)].into())
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                1_000u128,
+            ));
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1_000u128,
+            ));
+
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(para_id)),
+                1_000u128 - FIXED_COLLATOR_ASSIGNMENT_COST
+            );
+            assert_eq!(tank_asset_balance(para_id), 1_000u128);
+        });
+}
+
+#[test]
+fn asset_charges_are_rounded_up() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+            UsdtRate::set(FixedU128::from_u32(3));
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1_000u128,
+            ));
+
+            // 200 native units are worth 66.67 USDT
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+            assert_eq!(tank_asset_balance(para_id), 1_000u128 - 67);
+        });
+}
+
+#[test]
+fn failed_tip_does_not_charge_assets() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+            let tip = 10u128;
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1_000u128,
+            ));
+            assert_ok!(PaymentServices::set_max_tip(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(tip),
+            ));
+
+            // Tips can only be paid with the native currency
+            assert!(PaymentServices::on_collators_assigned(para_id, Some(&tip), false).is_err());
+            assert_eq!(tank_asset_balance(para_id), 1_000u128);
+        });
+}
+
+#[test]
+fn sessions_of_runway_counts_tank_assets() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 10_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+
+            // A session costs 1_200 native units, which are worth 600 USDT. The tank must be kept
+            // alive.
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1_200u128,
+            ));
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 1);
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                1u128,
+            ));
+            assert_eq!(PaymentServices::sessions_of_runway(para_id), 2);
+        });
+}
+
+#[test]
+fn on_deregister_refunds_tank_assets() {
+    ExtBuilder::default()
+        .with_asset_balances(vec![(USDT, ALICE, 1_000)])
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+            let refund_address = 10u64;
+
+            assert_ok!(PaymentServices::purchase_credits_with_asset(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                USDT,
+                500u128,
+            ));
+            assert_ok!(PaymentServices::set_refund_address(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(refund_address),
+            ));
+
+            PaymentServices::para_deregistered(para_id);
+
+            assert_eq!(tank_asset_balance(para_id), 0u128);
+            assert_eq!(
+                <Assets as fungibles::Inspect<_>>::balance(USDT, &refund_address),
+                500u128
+            );
+            assert!(TankAssets::<Test>::get(para_id).is_empty());
+        });
+}
+
+#[test]
+fn set_requested_collators_works() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        let para_id = ParaId::from(1);
+        let requested_collators = CollatorRequirements {
+            min_collators: 1,
+            max_collators: 5,
+        };
+
+        assert_ok!(PaymentServices::set_requested_collators(
+            RuntimeOrigin::root(),
+            para_id,
+            Some(requested_collators),
+        ));
+        assert_eq!(
+            RequestedCollators::<Test>::get(para_id),
+            Some(requested_collators)
+        );
+        assert_eq!(
+            events(),
+            vec![pallet_services_payment::Event::RequestedCollatorsUpdated {
+                para_id,
+                requested_collators: Some(requested_collators),
+            }]
+        );
+
+        assert_ok!(PaymentServices::set_requested_collators(
+            RuntimeOrigin::root(),
+            para_id,
+            None,
+        ));
+        assert_eq!(RequestedCollators::<Test>::get(para_id), None);
+    });
+}
+
+#[test]
+fn set_requested_collators_rejects_invalid_values() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        assert_noop!(
+            PaymentServices::set_requested_collators(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(CollatorRequirements {
+                    min_collators: 0,
+                    max_collators: 2,
+                }),
+            ),
+            pallet_services_payment::Error::<Test>::InvalidCollatorRequirements
+        );
+        assert_noop!(
+            PaymentServices::set_requested_collators(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(CollatorRequirements {
+                    min_collators: 3,
+                    max_collators: 2,
+                }),
+            ),
+            pallet_services_payment::Error::<Test>::InvalidCollatorRequirements
+        );
+        assert_noop!(
+            PaymentServices::set_requested_collators(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(CollatorRequirements {
+                    min_collators: 1,
+                    max_collators: MaxRequestedCollators::get() + 1,
+                }),
+            ),
+            pallet_services_payment::Error::<Test>::InvalidCollatorRequirements
+        );
+        assert_noop!(
+            PaymentServices::set_requested_collators(RuntimeOrigin::signed(ALICE), para_id, None,),
+            DispatchError::BadOrigin
+        );
+    });
+}
+
+#[test]
+fn collator_assignment_cost_scales_with_requested_collators() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+        let assignment_cost = || {
+            <Test as crate::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(
+                &para_id,
+            )
+            .0
+        };
+        assert_eq!(assignment_cost(), FIXED_COLLATOR_ASSIGNMENT_COST);
+
+        // The cost depends on the max number of collators, relative to the default
+        RequestedCollators::<Test>::insert(
+            para_id,
+            CollatorRequirements {
+                min_collators: 1,
+                max_collators: 5,
+            },
+        );
+        assert_eq!(assignment_cost(), 5 * FIXED_COLLATOR_ASSIGNMENT_COST / 2);
+
+        RequestedCollators::<Test>::insert(
+            para_id,
+            CollatorRequirements {
+                min_collators: 1,
+                max_collators: 1,
+            },
+        );
+        assert_eq!(assignment_cost(), FIXED_COLLATOR_ASSIGNMENT_COST / 2);
+    });
+}
+
+#[test]
+fn collators_assigned_charges_requested_collators_cost() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000_000)].into())
+        .build()
+        .execute_with(|| {
+            let para_id = ParaId::from(1);
+            let balance = 5_000u128;
+
+            assert_ok!(PaymentServices::purchase_credits(
+                RuntimeOrigin::signed(ALICE),
+                para_id,
+                balance,
+            ));
+            assert_ok!(PaymentServices::set_requested_collators(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(CollatorRequirements {
+                    min_collators: 4,
+                    max_collators: 4,
+                }),
+            ));
+
+            assert_ok!(PaymentServices::on_collators_assigned(para_id, None, false));
+
+            assert_eq!(
+                Balances::balance(&crate::Pallet::<Test>::parachain_tank(para_id)),
+                balance - 2 * FIXED_COLLATOR_ASSIGNMENT_COST,
+            );
+        });
+}
+
+#[test]
+fn on_deregister_removes_requested_collators() {
+    ExtBuilder::default().build().execute_with(|| {
+        let para_id = ParaId::from(1);
+
+        assert_ok!(PaymentServices::set_requested_collators(
+            RuntimeOrigin::root(),
+            para_id,
+            Some(CollatorRequirements {
+                min_collators: 1,
+                max_collators: 1,
+            }),
+        ));
+
+        PaymentServices::para_deregistered(para_id);
+
+        assert_eq!(RequestedCollators::<Test>::get(para_id), None);
+    });
+}
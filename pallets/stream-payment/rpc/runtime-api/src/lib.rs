@@ -21,7 +21,7 @@
 extern crate alloc;
 
 use {
-    alloc::string::String,
+    alloc::{string::String, vec::Vec},
     parity_scale_codec::{Decode, Encode},
 };
 
@@ -36,7 +36,10 @@ pub struct StreamPaymentApiStatus<Balance> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, scale_info::TypeInfo)]
-#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[cfg_attr(
+    feature = "std",
+    derive(thiserror::Error, serde::Serialize, serde::Deserialize)
+)]
 pub enum StreamPaymentApiError {
     #[cfg_attr(feature = "std", error("Unknown stream id"))]
     UnknownStreamId,
@@ -45,6 +48,7 @@ pub enum StreamPaymentApiError {
 }
 
 sp_api::decl_runtime_apis! {
+    #[api_version(2)]
     pub trait StreamPaymentApi<StreamId, Instant, Balance>
     where
         StreamId: parity_scale_codec::Codec,
@@ -61,5 +65,42 @@ sp_api::decl_runtime_apis! {
             stream_id: StreamId,
             now: Option<Instant>,
         ) -> Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError>;
+
+        /// Same as `stream_payment_status` for multiple streams at once, returning a result for
+        /// each provided stream id in the same order.
+        #[api_version(2)]
+        fn stream_payment_statuses(
+            stream_ids: Vec<StreamId>,
+            now: Option<Instant>,
+        ) -> Vec<Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError>>;
+
+        /// Project the time (in the stream time unit) at which the stream will stall if its
+        /// deposit is not changed, which is either when the deposit will be exhausted or the
+        /// deadline of a pending mandatory change request, whichever comes first. Returns `None`
+        /// if the stream will never stall.
+        #[api_version(2)]
+        fn stream_stall_time(stream_id: StreamId) -> Result<Option<Instant>, StreamPaymentApiError>;
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    pub trait StreamPaymentStreamsApi<StreamId, AccountId, Stream>
+    where
+        StreamId: parity_scale_codec::Codec,
+        AccountId: parity_scale_codec::Codec,
+        Stream: parity_scale_codec::Codec,
+    {
+        /// Get the streams among `stream_ids` for which a change was requested in the current
+        /// block, along with their data which contains the pending request.
+        fn streams_with_change_requested(stream_ids: Vec<StreamId>) -> Vec<(StreamId, Stream)>;
+
+        /// Get the ids of all the streams with given source.
+        fn streams_with_source(source: AccountId) -> Vec<StreamId>;
+
+        /// Get the ids of all the streams with given target.
+        fn streams_with_target(target: AccountId) -> Vec<StreamId>;
+
+        /// Get the stream data, including its pending change request if any.
+        fn stream(stream_id: StreamId) -> Option<Stream>;
     }
 }
@@ -16,12 +16,14 @@
 
 use {
     crate::{
-        Assets, Call, ChangeKind, Config, DepositChange, Event, Pallet, Party, StreamConfig,
-        Streams, TimeProvider,
+        Assets, Call, ChangeKind, Config, DepositChange, Event, Operator, OperatorPermissions,
+        Pallet, Party, StalledSince, StreamConfig, Streams, TimeProvider,
     },
     frame_benchmarking::{account, impl_benchmark_test_suite, v2::*, BenchmarkError},
-    frame_support::{assert_ok, dispatch::RawOrigin},
-    frame_system::EventRecord,
+    frame_support::{assert_ok, dispatch::RawOrigin, traits::Get},
+    frame_system::{pallet_prelude::BlockNumberFor, EventRecord},
+    sp_runtime::{traits::Zero, BoundedVec},
+    sp_std::vec::Vec,
 };
 
 /// Create a funded user.
@@ -428,6 +430,178 @@ mod benchmarks {
         Ok(())
     }
 
+    #[benchmark]
+    fn sweep_stream() -> Result<(), BenchmarkError> {
+        // Worst case is closing a stream past a mandatory change deadline and its grace period,
+        // with a pending payment.
+        let time_unit = T::TimeProvider::bench_worst_case_time_unit();
+        let asset_id = T::Assets::bench_worst_case_asset_id();
+
+        let source = create_funded_user::<T>("source", 1, &asset_id);
+        let target = create_funded_user::<T>("target", 2, &asset_id);
+
+        let rate = 100u32.into();
+        let initial_deposit = 1_000_000u32.into();
+        let config = StreamConfig {
+            time_unit: time_unit.clone(),
+            asset_id,
+            rate,
+        };
+
+        assert_ok!(Pallet::<T>::open_stream(
+            RawOrigin::Signed(source.clone()).into(),
+            target,
+            config.clone(),
+            initial_deposit,
+        ));
+
+        let now = T::TimeProvider::now(&time_unit).expect("can fetch time");
+        let deadline_delta: T::Balance = 5u32.into();
+
+        assert_ok!(Pallet::<T>::request_change(
+            RawOrigin::Signed(source).into(),
+            0u32.into(),
+            ChangeKind::Mandatory {
+                deadline: now + deadline_delta,
+            },
+            StreamConfig {
+                rate: 50u32.into(),
+                ..config
+            },
+            None,
+        ));
+
+        // Change time past the deadline.
+        let delta: T::Balance = 10u32.into();
+        T::TimeProvider::bench_set_now(now + delta);
+
+        // Stream was found stalled long enough ago to be closed.
+        StalledSince::<T>::insert::<T::StreamId, BlockNumberFor<T>>(0u32.into(), Zero::zero());
+        frame_system::Pallet::<T>::set_block_number(
+            T::StalledStreamGracePeriod::get().max(frame_system::Pallet::<T>::block_number()),
+        );
+
+        #[block]
+        {
+            let stream = Streams::<T>::get::<T::StreamId>(0u32.into()).expect("stream exists");
+            Pallet::<T>::sweep_stream(0u32.into(), stream);
+        }
+
+        assert_last_event::<T>(
+            Event::StreamClosed {
+                stream_id: 0u32.into(),
+                refunded: initial_deposit - (rate * deadline_delta)
+                    + T::OpenStreamHoldAmount::get(),
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn set_operator() -> Result<(), BenchmarkError> {
+        let time_unit = T::TimeProvider::bench_worst_case_time_unit();
+        let asset_id = T::Assets::bench_worst_case_asset_id();
+
+        let source = create_funded_user::<T>("source", 1, &asset_id);
+        let target = create_funded_user::<T>("target", 2, &asset_id);
+        let operator: T::AccountId = account("operator", 3, 0);
+
+        assert_ok!(Pallet::<T>::open_stream(
+            RawOrigin::Signed(source.clone()).into(),
+            target,
+            StreamConfig {
+                time_unit,
+                asset_id,
+                rate: 100u32.into(),
+            },
+            1_000_000u32.into(),
+        ));
+
+        let operator = Operator {
+            account: operator,
+            permissions: OperatorPermissions {
+                request_change: true,
+                accept_change: true,
+                increase_deposit: true,
+                decrease_deposit: true,
+            },
+        };
+
+        #[extrinsic_call]
+        _(
+            RawOrigin::Signed(source),
+            0u32.into(),
+            Some(operator.clone()),
+        );
+
+        assert_last_event::<T>(
+            Event::StreamOperatorChanged {
+                stream_id: 0u32.into(),
+                party: Party::Source,
+                operator: Some(operator),
+            }
+            .into(),
+        );
+
+        Ok(())
+    }
+
+    #[benchmark]
+    fn batch_perform_payment(
+        n: Linear<0, { T::MaxBatchedPayments::get() }>,
+    ) -> Result<(), BenchmarkError> {
+        let time_unit = T::TimeProvider::bench_worst_case_time_unit();
+        let asset_id = T::Assets::bench_worst_case_asset_id();
+
+        let source = create_funded_user::<T>("source", 1, &asset_id);
+        let target = create_funded_user::<T>("target", 2, &asset_id);
+
+        let rate = 100u32.into();
+        let initial_deposit = 1_000_000u32.into();
+
+        let mut stream_ids = Vec::new();
+        for i in 0..n {
+            assert_ok!(Pallet::<T>::open_stream(
+                RawOrigin::Signed(source.clone()).into(),
+                target.clone(),
+                StreamConfig {
+                    time_unit: time_unit.clone(),
+                    asset_id: asset_id.clone(),
+                    rate,
+                },
+                initial_deposit,
+            ));
+            stream_ids.push(i.into());
+        }
+
+        // Change time to trigger payment.
+        let now = T::TimeProvider::now(&time_unit).expect("can fetch time");
+        let delta: T::Balance = 10u32.into();
+        T::TimeProvider::bench_set_now(now + delta);
+
+        let stream_ids = BoundedVec::truncate_from(stream_ids);
+
+        #[extrinsic_call]
+        _(RawOrigin::Signed(source.clone()), stream_ids);
+
+        if n > 0 {
+            assert_last_event::<T>(
+                Event::StreamPayment {
+                    stream_id: (n - 1).into(),
+                    source,
+                    target,
+                    amount: rate * delta,
+                    stalled: false,
+                }
+                .into(),
+            );
+        }
+
+        Ok(())
+    }
+
     impl_benchmark_test_suite!(
         Pallet,
         crate::mock::ExtBuilder::default().build(),
@@ -38,7 +38,10 @@ use {
         dispatch::DispatchErrorWithPostInfo,
         pallet,
         pallet_prelude::*,
-        storage::types::{StorageDoubleMap, StorageMap},
+        storage::{
+            types::{StorageDoubleMap, StorageMap},
+            with_storage_layer,
+        },
         traits::{
             fungible::{Inspect, MutateHold},
             tokens::{Balance, Precision},
@@ -52,7 +55,7 @@ use {
         traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, One, Saturating, Zero},
         ArithmeticError,
     },
-    sp_std::{fmt::Debug, marker::PhantomData},
+    sp_std::{fmt::Debug, marker::PhantomData, vec::Vec},
 };
 
 pub use pallet::*;
@@ -165,6 +168,20 @@ pub mod pallet {
         /// Provide the current time in given unit.
         type TimeProvider: TimeProvider<Self::TimeUnit, Self::Balance>;
 
+        /// Maximum amount of streams checked by the `on_idle` sweeper in a single block.
+        #[pallet::constant]
+        type MaxStreamsSweptPerBlock: Get<u32>;
+
+        /// Amount of blocks a drained stream can stay stalled, once found stalled by the `on_idle`
+        /// sweeper, before being closed by it. Gives time to the source to refill the stream.
+        /// Streams past the deadline of a mandatory change request are closed without delay.
+        #[pallet::constant]
+        type StalledStreamGracePeriod: Get<BlockNumberFor<Self>>;
+
+        /// Maximum amount of streams that can be provided to `batch_perform_payment`.
+        #[pallet::constant]
+        type MaxBatchedPayments: Get<u32>;
+
         type WeightInfo: weights::WeightInfo;
     }
 
@@ -273,6 +290,44 @@ pub mod pallet {
         pub deposit_change: Option<DepositChange<Balance>>,
     }
 
+    /// Permissions granted to a stream operator. An operator acts on behalf of the party
+    /// that set it, and can only perform the actions allowed by its permissions.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, Default)]
+    pub struct OperatorPermissions {
+        /// Can request changes with `request_change` and cancel them with
+        /// `cancel_change_request`.
+        pub request_change: bool,
+        /// Can accept changes requested by the other party with `accept_requested_change`.
+        pub accept_change: bool,
+        /// Can increase the deposit. Only relevant for operators of the source.
+        pub increase_deposit: bool,
+        /// Can decrease the deposit. Only relevant for operators of the source.
+        pub decrease_deposit: bool,
+    }
+
+    impl OperatorPermissions {
+        /// Whenever those permissions allow to perform given deposit change. An absolute change
+        /// can both increase or decrease the deposit, and thus requires both permissions.
+        pub fn allows_deposit_change<Balance>(&self, change: &DepositChange<Balance>) -> bool {
+            match change {
+                DepositChange::Increase(_) => self.increase_deposit,
+                DepositChange::Decrease(_) => self.decrease_deposit,
+                DepositChange::Absolute(_) => self.increase_deposit && self.decrease_deposit,
+            }
+        }
+    }
+
+    /// An account allowed to act on behalf of a party of a stream.
+    #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+    #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Clone, TypeInfo)]
+    pub struct Operator<AccountId> {
+        pub account: AccountId,
+        pub permissions: OperatorPermissions,
+    }
+
+    pub type OperatorOf<T> = Operator<AccountIdOf<T>>;
+
     pub type StreamOf<T> =
         Stream<AccountIdOf<T>, <T as Config>::TimeUnit, AssetIdOf<T>, <T as Config>::Balance>;
 
@@ -304,6 +359,34 @@ pub mod pallet {
         QueryKind = OptionQuery,
     >;
 
+    /// Raw key of the last stream checked by the `on_idle` sweeper, which resumes from the
+    /// following one. `None` when the sweeper will start from the beginning.
+    #[pallet::storage]
+    pub type SweepCursor<T: Config> = StorageValue<Value = Vec<u8>, QueryKind = OptionQuery>;
+
+    /// Block at which the `on_idle` sweeper found each stream stalled. A stream is closed by the
+    /// sweeper once `StalledStreamGracePeriod` blocks have elapsed since then. The entry is
+    /// removed when the stream is refilled, its change request is handled or it is found no
+    /// longer stalled.
+    #[pallet::storage]
+    pub type StalledSince<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::StreamId,
+        Value = BlockNumberFor<T>,
+        QueryKind = OptionQuery,
+    >;
+
+    /// Operator of each party of a stream, if any. Each party can have at most one operator.
+    #[pallet::storage]
+    pub type StreamOperators<T: Config> = StorageDoubleMap<
+        Key1 = T::StreamId,
+        Hasher1 = Blake2_128Concat,
+        Key2 = Party,
+        Hasher2 = Blake2_128Concat,
+        Value = OperatorOf<T>,
+        QueryKind = OptionQuery,
+    >;
+
     /// Lookup for all streams with given source.
     /// To avoid maintaining a growing list of stream ids, they are stored in
     /// the form of an entry (AccountId, StreamId). If such entry exists then
@@ -352,6 +435,8 @@ pub mod pallet {
         ChangingAssetRequiresAbsoluteDepositChange,
         TargetCantChangeDeposit,
         ImmediateDepositChangeRequiresSameAssetId,
+        OperatorNotAllowed,
+        OperatorCantBeStreamParty,
     }
 
     #[pallet::event]
@@ -384,6 +469,17 @@ pub mod pallet {
             new_config: StreamConfigOf<T>,
             deposit_change: Option<DepositChange<T::Balance>>,
         },
+        StreamOperatorChanged {
+            stream_id: T::StreamId,
+            party: Party,
+            operator: Option<OperatorOf<T>>,
+        },
+        /// Payment of a stream failed in `batch_perform_payment`, other streams of the batch
+        /// were still paid.
+        StreamPaymentFailed {
+            stream_id: T::StreamId,
+            error: DispatchError,
+        },
     }
 
     /// Freeze reason to use if needed.
@@ -399,6 +495,13 @@ pub mod pallet {
         StreamOpened,
     }
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::sweep_streams(remaining_weight)
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Create a payment stream from the origin to the target with provided config
@@ -445,29 +548,7 @@ pub mod pallet {
             // Update stream before closing it to ensure fair payment.
             Self::perform_stream_payment(stream_id, &mut stream)?;
 
-            // Unfreeze funds left in the stream.
-            T::Assets::decrease_deposit(&stream.config.asset_id, &stream.source, stream.deposit)?;
-
-            // Release opening deposit
-            if stream.opening_deposit > 0u32.into() {
-                T::Currency::release(
-                    &HoldReason::StreamOpened.into(),
-                    &stream.source,
-                    stream.opening_deposit,
-                    Precision::Exact,
-                )?;
-            }
-
-            // Remove stream from storage.
-            Streams::<T>::remove(stream_id);
-            LookupStreamsWithSource::<T>::remove(stream.source, stream_id);
-            LookupStreamsWithTarget::<T>::remove(stream.target, stream_id);
-
-            // Emit event.
-            Pallet::<T>::deposit_event(Event::<T>::StreamClosed {
-                stream_id,
-                refunded: stream.deposit.saturating_add(stream.opening_deposit),
-            });
+            Self::remove_stream(stream_id, stream)?;
 
             Ok(().into())
         }
@@ -498,6 +579,9 @@ pub mod pallet {
         /// This call accepts a deposit change, which can only be provided by the source of the
         /// stream. An absolute change is required when changing asset id, as the current deposit
         /// will be released and a new deposit is required in the new asset.
+        ///
+        /// Operators need the `request_change` permission, and the deposit permissions matching
+        /// the provided deposit change.
         #[pallet::call_index(3)]
         #[pallet::weight(
             T::WeightInfo::request_change_immediate()
@@ -513,9 +597,13 @@ pub mod pallet {
             let origin = ensure_signed(origin)?;
             let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
 
-            let requester = stream
-                .account_to_party(origin)
-                .ok_or(Error::<T>::UnauthorizedOrigin)?;
+            let (requester, permissions) = Self::origin_to_party(stream_id, &stream, origin)?;
+            Self::ensure_operator_allowed(permissions, |p| {
+                p.request_change
+                    && deposit_change
+                        .as_ref()
+                        .map_or(true, |change| p.allows_deposit_change(change))
+            })?;
 
             ensure!(
                 requester == Party::Source || deposit_change.is_none(),
@@ -587,6 +675,9 @@ pub mod pallet {
         /// Accepts a change requested before by the other party. Takes a nonce to prevent
         /// frontrunning attacks. If the target made a request, the source is able to change their
         /// deposit.
+        ///
+        /// Operators need the `accept_change` permission, and the deposit permissions matching
+        /// the provided deposit change.
         #[pallet::call_index(4)]
         #[pallet::weight(T::WeightInfo::accept_requested_change())]
         pub fn accept_requested_change(
@@ -598,9 +689,13 @@ pub mod pallet {
             let origin = ensure_signed(origin)?;
             let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
 
-            let accepter = stream
-                .account_to_party(origin)
-                .ok_or(Error::<T>::UnauthorizedOrigin)?;
+            let (accepter, permissions) = Self::origin_to_party(stream_id, &stream, origin)?;
+            Self::ensure_operator_allowed(permissions, |p| {
+                p.accept_change
+                    && deposit_change
+                        .as_ref()
+                        .map_or(true, |change| p.allows_deposit_change(change))
+            })?;
 
             let Some(request) = stream.pending_request.clone() else {
                 return Err(Error::<T>::NoPendingRequest.into());
@@ -676,10 +771,13 @@ pub mod pallet {
             stream.config = request.new_config;
             stream.pending_request = None;
             Streams::<T>::insert(stream_id, stream);
+            StalledSince::<T>::remove(stream_id);
 
             Ok(().into())
         }
 
+        /// Cancel a change request made by the origin party. Operators need the `request_change`
+        /// permission.
         #[pallet::call_index(5)]
         #[pallet::weight(T::WeightInfo::cancel_change_request())]
         pub fn cancel_change_request(
@@ -689,9 +787,8 @@ pub mod pallet {
             let origin = ensure_signed(origin)?;
             let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
 
-            let accepter = stream
-                .account_to_party(origin)
-                .ok_or(Error::<T>::UnauthorizedOrigin)?;
+            let (accepter, permissions) = Self::origin_to_party(stream_id, &stream, origin)?;
+            Self::ensure_operator_allowed(permissions, |p| p.request_change)?;
 
             let Some(request) = stream.pending_request.take() else {
                 return Err(Error::<T>::NoPendingRequest.into());
@@ -705,6 +802,7 @@ pub mod pallet {
             // Update storage.
             // Pending request is removed by calling `.take()`.
             Streams::<T>::insert(stream_id, stream);
+            StalledSince::<T>::remove(stream_id);
 
             Ok(().into())
         }
@@ -714,6 +812,8 @@ pub mod pallet {
         /// The call takes an asset id to ensure it has not changed (by an accepted request) before
         /// the call is included in a block, in which case the unit is no longer the same and quantities
         /// will not have the same scale/value.
+        ///
+        /// Operators of the source need the deposit permissions matching the change.
         #[pallet::call_index(6)]
         #[pallet::weight(T::WeightInfo::immediately_change_deposit())]
         pub fn immediately_change_deposit(
@@ -725,7 +825,10 @@ pub mod pallet {
             let origin = ensure_signed(origin)?;
             let mut stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
 
-            ensure!(stream.source == origin, Error::<T>::UnauthorizedOrigin);
+            let (party, permissions) = Self::origin_to_party(stream_id, &stream, origin)?;
+            ensure!(party == Party::Source, Error::<T>::UnauthorizedOrigin);
+            Self::ensure_operator_allowed(permissions, |p| p.allows_deposit_change(&change))?;
+
             ensure!(
                 stream.config.asset_id == asset_id,
                 Error::<T>::ImmediateDepositChangeRequiresSameAssetId
@@ -747,9 +850,93 @@ pub mod pallet {
 
             // Update stream in storage.
             Streams::<T>::insert(stream_id, stream);
+            StalledSince::<T>::remove(stream_id);
 
             Ok(().into())
         }
+
+        /// Set or remove the operator acting on behalf of the origin party in a stream. Only the
+        /// source or target themselves can change their operator, and an operator can't be a
+        /// party of the stream. Operators can't close the stream.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::WeightInfo::set_operator())]
+        pub fn set_operator(
+            origin: OriginFor<T>,
+            stream_id: T::StreamId,
+            operator: Option<OperatorOf<T>>,
+        ) -> DispatchResultWithPostInfo {
+            let origin = ensure_signed(origin)?;
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
+
+            let party = stream
+                .account_to_party(origin)
+                .ok_or(Error::<T>::UnauthorizedOrigin)?;
+
+            match &operator {
+                Some(operator) => {
+                    ensure!(
+                        stream.account_to_party(operator.account.clone()).is_none(),
+                        Error::<T>::OperatorCantBeStreamParty
+                    );
+                    StreamOperators::<T>::insert(stream_id, party, operator);
+                }
+                None => StreamOperators::<T>::remove(stream_id, party),
+            }
+
+            Pallet::<T>::deposit_event(Event::<T>::StreamOperatorChanged {
+                stream_id,
+                party,
+                operator,
+            });
+
+            Ok(().into())
+        }
+
+        /// Perform the pending payment of multiple streams. Anyone can call this.
+        /// Unknown stream ids are skipped, such that a stream closed before the inclusion of
+        /// the call doesn't prevent payment of the other streams. Payments that fail are
+        /// reverted and reported with a `StreamPaymentFailed` event.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::WeightInfo::batch_perform_payment(stream_ids.len() as u32))]
+        pub fn batch_perform_payment(
+            origin: OriginFor<T>,
+            stream_ids: BoundedVec<T::StreamId, T::MaxBatchedPayments>,
+        ) -> DispatchResultWithPostInfo {
+            // No problem with anyone updating any stream.
+            let _ = ensure_signed(origin)?;
+
+            let mut performed = 0u32;
+            let mut skipped = 0u64;
+
+            for stream_id in stream_ids {
+                let Some(mut stream) = Streams::<T>::get(stream_id) else {
+                    skipped += 1;
+                    continue;
+                };
+
+                // A failing payment must not prevent the other ones from being performed.
+                let res = with_storage_layer(|| -> DispatchResult {
+                    Self::perform_stream_payment(stream_id, &mut stream).map_err(|e| e.error)?;
+                    Streams::<T>::insert(stream_id, stream);
+                    Ok(())
+                });
+
+                if let Err(error) = res {
+                    Pallet::<T>::deposit_event(Event::<T>::StreamPaymentFailed {
+                        stream_id,
+                        error,
+                    });
+                }
+
+                performed += 1;
+            }
+
+            // Skipped streams only cost a storage read.
+            let actual_weight = T::WeightInfo::batch_perform_payment(performed)
+                .saturating_add(T::DbWeight::get().reads(skipped));
+
+            Ok(Some(actual_weight).into())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -826,6 +1013,84 @@ pub mod pallet {
             Self::stream_payment_status_by_ref(&stream, last_time_updated, now)
         }
 
+        /// Project the time (in the stream time unit) at which the stream will stall if its
+        /// deposit is not changed, which is either when the deposit will be exhausted or the
+        /// deadline of a pending mandatory change request, whichever comes first. Returns `None`
+        /// if the stream will never stall.
+        pub fn stream_stall_time(stream_id: T::StreamId) -> Result<Option<T::Balance>, Error<T>> {
+            let stream = Streams::<T>::get(stream_id).ok_or(Error::<T>::UnknownStreamId)?;
+
+            let deadline = match &stream.pending_request {
+                Some(ChangeRequest {
+                    kind: ChangeKind::Mandatory { deadline },
+                    ..
+                }) => Some(*deadline),
+                _ => None,
+            };
+
+            // With a zero rate the deposit is never exhausted.
+            let drained_time = if stream.config.rate.is_zero() {
+                None
+            } else {
+                // Round up, as the stream only stalls once the whole deposit is paid.
+                let mut delta = stream.deposit / stream.config.rate;
+                if !(stream.deposit % stream.config.rate).is_zero() {
+                    delta = delta.saturating_add(One::one());
+                }
+                Some(stream.last_time_updated.saturating_add(delta))
+            };
+
+            Ok(match (drained_time, deadline) {
+                (Some(drained_time), Some(deadline)) => Some(min(drained_time, deadline)),
+                (drained_time, deadline) => drained_time.or(deadline),
+            })
+        }
+
+        /// Resolve for which party the origin acts in given stream, either directly as its source
+        /// or target, or as the operator of one of them. Operator permissions are returned if the
+        /// origin is an operator.
+        fn origin_to_party(
+            stream_id: T::StreamId,
+            stream: &StreamOf<T>,
+            origin: AccountIdOf<T>,
+        ) -> Result<(Party, Option<OperatorPermissions>), Error<T>> {
+            if let Some(party) = stream.account_to_party(origin.clone()) {
+                return Ok((party, None));
+            }
+
+            [Party::Source, Party::Target]
+                .into_iter()
+                .find_map(|party| match StreamOperators::<T>::get(stream_id, party) {
+                    Some(operator) if operator.account == origin => {
+                        Some((party, Some(operator.permissions)))
+                    }
+                    _ => None,
+                })
+                .ok_or(Error::<T>::UnauthorizedOrigin)
+        }
+
+        /// Ensure an operator is allowed to perform an action. Parties themselves are always
+        /// allowed.
+        fn ensure_operator_allowed(
+            permissions: Option<OperatorPermissions>,
+            allowed: impl FnOnce(&OperatorPermissions) -> bool,
+        ) -> Result<(), Error<T>> {
+            match permissions {
+                Some(permissions) if !allowed(&permissions) => Err(Error::<T>::OperatorNotAllowed),
+                _ => Ok(()),
+            }
+        }
+
+        /// Get the ids of all the streams with given source.
+        pub fn streams_with_source(source: &AccountIdOf<T>) -> Vec<T::StreamId> {
+            LookupStreamsWithSource::<T>::iter_key_prefix(source).collect()
+        }
+
+        /// Get the ids of all the streams with given target.
+        pub fn streams_with_target(target: &AccountIdOf<T>) -> Vec<T::StreamId> {
+            LookupStreamsWithTarget::<T>::iter_key_prefix(target).collect()
+        }
+
         fn stream_payment_status_by_ref(
             stream: &StreamOf<T>,
             last_time_updated: T::Balance,
@@ -888,6 +1153,138 @@ pub mod pallet {
             })
         }
 
+        /// Refund the deposits of a stream, remove it from storage and emit `StreamClosed`.
+        /// Pending payment must be performed before calling this function.
+        fn remove_stream(stream_id: T::StreamId, stream: StreamOf<T>) -> DispatchResult {
+            // Unfreeze funds left in the stream.
+            T::Assets::decrease_deposit(&stream.config.asset_id, &stream.source, stream.deposit)?;
+
+            // Release opening deposit
+            if stream.opening_deposit > 0u32.into() {
+                T::Currency::release(
+                    &HoldReason::StreamOpened.into(),
+                    &stream.source,
+                    stream.opening_deposit,
+                    Precision::Exact,
+                )?;
+            }
+
+            // Remove stream from storage.
+            Streams::<T>::remove(stream_id);
+            LookupStreamsWithSource::<T>::remove(stream.source, stream_id);
+            LookupStreamsWithTarget::<T>::remove(stream.target, stream_id);
+            StreamOperators::<T>::remove(stream_id, Party::Source);
+            StreamOperators::<T>::remove(stream_id, Party::Target);
+            StalledSince::<T>::remove(stream_id);
+
+            // Emit event.
+            Pallet::<T>::deposit_event(Event::<T>::StreamClosed {
+                stream_id,
+                refunded: stream.deposit.saturating_add(stream.opening_deposit),
+            });
+
+            Ok(())
+        }
+
+        /// Check streams in storage order starting after `SweepCursor`, as many as allowed by
+        /// `MaxStreamsSweptPerBlock` and `remaining_weight`. Once the end of `Streams` is reached
+        /// the cursor is reset, such that all streams are eventually checked. Returns the consumed
+        /// weight.
+        pub fn sweep_streams(remaining_weight: Weight) -> Weight {
+            let max_swept = T::MaxStreamsSweptPerBlock::get();
+            // Read and write `SweepCursor`.
+            let mut used_weight = T::DbWeight::get().reads_writes(1, 1);
+            let stream_weight = T::WeightInfo::sweep_stream();
+
+            if max_swept == 0 || remaining_weight.any_lt(used_weight.saturating_add(stream_weight))
+            {
+                return Weight::zero();
+            }
+
+            let mut cursor = SweepCursor::<T>::get();
+            let mut streams = match &cursor {
+                Some(raw_key) => Streams::<T>::iter_from(raw_key.clone()),
+                None => Streams::<T>::iter(),
+            };
+            let mut swept = 0u32;
+
+            while swept < max_swept
+                && used_weight
+                    .saturating_add(stream_weight)
+                    .all_lte(remaining_weight)
+            {
+                let Some((stream_id, stream)) = streams.next() else {
+                    cursor = None;
+                    break;
+                };
+
+                cursor = Some(streams.last_raw_key().to_vec());
+                swept = swept.saturating_add(1);
+                used_weight.saturating_accrue(stream_weight);
+
+                Self::sweep_stream(stream_id, stream);
+            }
+
+            SweepCursor::<T>::set(cursor);
+
+            used_weight
+        }
+
+        /// Check a stream that is either drained or past the deadline of a mandatory change
+        /// request. A stream past the deadline is closed right away. The first time a drained
+        /// stream is found its pending payment is performed, and it is closed once
+        /// `StalledStreamGracePeriod` blocks have elapsed. Failures are ignored, as the stream can
+        /// still be updated or closed by calls.
+        pub(crate) fn sweep_stream(stream_id: T::StreamId, mut stream: StreamOf<T>) {
+            let Some(now) = T::TimeProvider::now(&stream.config.time_unit) else {
+                return;
+            };
+
+            let Ok(status) =
+                Self::stream_payment_status_by_ref(&stream, stream.last_time_updated, now)
+            else {
+                return;
+            };
+
+            if !status.stalled {
+                StalledSince::<T>::remove(stream_id);
+                return;
+            }
+
+            let deadline_passed = matches!(
+                &stream.pending_request,
+                Some(ChangeRequest {
+                    kind: ChangeKind::Mandatory { deadline },
+                    ..
+                }) if now >= *deadline
+            );
+
+            if !deadline_passed {
+                let block_number = frame_system::Pallet::<T>::block_number();
+                let Some(stalled_since) = StalledSince::<T>::get(stream_id) else {
+                    let _ = with_storage_layer(|| -> DispatchResult {
+                        let payment = Self::perform_stream_payment(stream_id, &mut stream)
+                            .map_err(|e| e.error)?;
+                        if !payment.is_zero() {
+                            Streams::<T>::insert(stream_id, stream);
+                        }
+                        Ok(())
+                    });
+                    StalledSince::<T>::insert(stream_id, block_number);
+                    return;
+                };
+
+                if block_number < stalled_since.saturating_add(T::StalledStreamGracePeriod::get()) {
+                    return;
+                }
+            }
+
+            let _ = with_storage_layer(|| -> DispatchResult {
+                Self::perform_stream_payment(stream_id, &mut stream).map_err(|e| e.error)?;
+                Self::remove_stream(stream_id, stream)
+            });
+        }
+
         /// Behavior:
         /// A stream payment consist of a locked deposit, a rate per unit of time and the
         /// last time the stream was updated. When updating the stream, **at most**
@@ -897,6 +1294,8 @@ pub mod pallet {
         /// however there will be no retroactive payment for the time spent as drained.
         /// If the stream payment is used to rent a service, the target should pause the service
         /// while the stream is drained, and resume it once it is refilled.
+        /// Streams drained for `StalledStreamGracePeriod` blocks are closed by the `on_idle`
+        /// sweeper.
         fn perform_stream_payment(
             stream_id: T::StreamId,
             stream: &mut StreamOf<T>,
@@ -1029,6 +1428,7 @@ pub mod pallet {
             // Update storage.
             stream.config = new_config.clone();
             Streams::<T>::insert(stream_id, stream);
+            StalledSince::<T>::remove(stream_id);
 
             Ok(true)
         }
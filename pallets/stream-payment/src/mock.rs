@@ -237,6 +237,9 @@ impl pallet_stream_payment::TimeProvider<TimeUnit, Balance> for TimeProvider {
 
 parameter_types! {
     pub const OpenStreamHoldAmount: Balance = 100;
+    pub const MaxStreamsSweptPerBlock: u32 = 3;
+    pub const StalledStreamGracePeriod: u64 = 5;
+    pub const MaxBatchedPayments: u32 = 10;
 }
 
 impl pallet_stream_payment::Config for Runtime {
@@ -250,6 +253,9 @@ impl pallet_stream_payment::Config for Runtime {
     type OpenStreamHoldAmount = OpenStreamHoldAmount;
     type RuntimeHoldReason = RuntimeHoldReason;
     type TimeProvider = TimeProvider;
+    type MaxStreamsSweptPerBlock = MaxStreamsSweptPerBlock;
+    type StalledStreamGracePeriod = StalledStreamGracePeriod;
+    type MaxBatchedPayments = MaxBatchedPayments;
     type WeightInfo = ();
 }
 
@@ -19,14 +19,19 @@ use {
         assert_event_emitted, assert_event_not_emitted,
         mock::{
             roll_to, AccountId, Balance, Balances, ExtBuilder, OpenStreamHoldAmount, Runtime,
-            RuntimeOrigin, StreamPayment, StreamPaymentAssetId, StreamPaymentAssets, TimeUnit,
-            ALICE, BOB, CHARLIE, DEFAULT_BALANCE, MEGA,
+            RuntimeEvent, RuntimeOrigin, StreamPayment, StreamPaymentAssetId, StreamPaymentAssets,
+            System, TimeUnit, ALICE, BOB, CHARLIE, DEFAULT_BALANCE, MEGA,
         },
         ArithmeticError, Assets, ChangeKind, DepositChange, DispatchResultWithPostInfo, Event,
-        LookupStreamsWithSource, LookupStreamsWithTarget, NextStreamId, Party, Stream,
-        StreamConfig, StreamConfigOf, StreamOf, Streams,
+        LookupStreamsWithSource, LookupStreamsWithTarget, NextStreamId, Operator,
+        OperatorPermissions, Party, Stream, StreamConfig, StreamConfigOf, StreamOf,
+        StreamOperators, Streams, SweepCursor, WeightInfo,
+    },
+    frame_support::{
+        assert_err, assert_ok,
+        traits::{fungible::MutateHold, tokens::Precision, Get},
+        weights::Weight,
     },
-    frame_support::{assert_err, assert_ok},
     sp_runtime::TokenError,
     tap::tap::Tap,
 };
@@ -1871,3 +1876,845 @@ mod immediately_change_deposit {
         })
     }
 }
+
+mod operators {
+    use super::*;
+
+    fn set_operator(party: AccountId, permissions: OperatorPermissions) {
+        assert_ok!(StreamPayment::set_operator(
+            RuntimeOrigin::signed(party),
+            0,
+            Some(Operator {
+                account: CHARLIE,
+                permissions,
+            })
+        ));
+    }
+
+    #[test]
+    fn cannot_set_operator_of_unknown_stream() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_err!(
+                StreamPayment::set_operator(RuntimeOrigin::signed(ALICE), 0, None),
+                Error::UnknownStreamId
+            );
+        })
+    }
+
+    #[test]
+    fn third_party_cant_set_operator() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+
+            assert_err!(
+                StreamPayment::set_operator(
+                    RuntimeOrigin::signed(CHARLIE),
+                    0,
+                    Some(Operator {
+                        account: CHARLIE,
+                        permissions: default(),
+                    })
+                ),
+                Error::UnauthorizedOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn operator_cant_set_operator() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+            set_operator(ALICE, default());
+
+            assert_err!(
+                StreamPayment::set_operator(RuntimeOrigin::signed(CHARLIE), 0, None),
+                Error::UnauthorizedOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn operator_cant_be_stream_party() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+
+            assert_err!(
+                StreamPayment::set_operator(
+                    RuntimeOrigin::signed(ALICE),
+                    0,
+                    Some(Operator {
+                        account: BOB,
+                        permissions: default(),
+                    })
+                ),
+                Error::OperatorCantBeStreamParty
+            );
+        })
+    }
+
+    #[test]
+    fn set_and_remove_operator() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+
+            let operator = Operator {
+                account: CHARLIE,
+                permissions: OperatorPermissions {
+                    increase_deposit: true,
+                    ..default()
+                },
+            };
+            assert_ok!(StreamPayment::set_operator(
+                RuntimeOrigin::signed(BOB),
+                0,
+                Some(operator.clone())
+            ));
+
+            assert_event_emitted!(Event::<Runtime>::StreamOperatorChanged {
+                stream_id: 0,
+                party: Party::Target,
+                operator: Some(operator.clone()),
+            });
+            assert_eq!(
+                StreamOperators::<Runtime>::get(0, Party::Target),
+                Some(operator)
+            );
+            assert_eq!(StreamOperators::<Runtime>::get(0, Party::Source), None);
+
+            assert_ok!(StreamPayment::set_operator(
+                RuntimeOrigin::signed(BOB),
+                0,
+                None
+            ));
+
+            assert_event_emitted!(Event::<Runtime>::StreamOperatorChanged {
+                stream_id: 0,
+                party: Party::Target,
+                operator: None,
+            });
+            assert_eq!(StreamOperators::<Runtime>::get(0, Party::Target), None);
+
+            // Removed operator can no longer act on the stream.
+            assert_err!(
+                StreamPayment::request_change(
+                    RuntimeOrigin::signed(CHARLIE),
+                    0,
+                    ChangeKind::Suggestion,
+                    StreamConfig {
+                        rate: 101,
+                        ..default_config()
+                    },
+                    None,
+                ),
+                Error::UnauthorizedOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn operator_can_only_increase_deposit_with_permission() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+            set_operator(
+                ALICE,
+                OperatorPermissions {
+                    increase_deposit: true,
+                    ..default()
+                },
+            );
+
+            // Operator can top up the deposit, which is taken from the source.
+            assert_ok!(StreamPayment::immediately_change_deposit(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                StreamPaymentAssetId::Native,
+                DepositChange::Increase(500),
+            ));
+            assert_eq!(get_deposit(ALICE), open_stream.deposit + 500);
+            assert_eq!(Balances::free_balance(CHARLIE), DEFAULT_BALANCE);
+
+            for change in [DepositChange::Decrease(500), DepositChange::Absolute(500)] {
+                assert_err!(
+                    StreamPayment::immediately_change_deposit(
+                        RuntimeOrigin::signed(CHARLIE),
+                        0,
+                        StreamPaymentAssetId::Native,
+                        change,
+                    ),
+                    Error::OperatorNotAllowed
+                );
+            }
+
+            // Operator can't change the rate.
+            assert_err!(
+                StreamPayment::request_change(
+                    RuntimeOrigin::signed(CHARLIE),
+                    0,
+                    ChangeKind::Suggestion,
+                    StreamConfig {
+                        rate: 101,
+                        ..open_stream.config
+                    },
+                    None,
+                ),
+                Error::OperatorNotAllowed
+            );
+
+            // Operator can't close the stream.
+            assert_err!(
+                StreamPayment::close_stream(RuntimeOrigin::signed(CHARLIE), 0),
+                Error::UnauthorizedOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn target_operator_cant_change_deposit() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+            set_operator(
+                BOB,
+                OperatorPermissions {
+                    increase_deposit: true,
+                    decrease_deposit: true,
+                    ..default()
+                },
+            );
+
+            assert_err!(
+                StreamPayment::immediately_change_deposit(
+                    RuntimeOrigin::signed(CHARLIE),
+                    0,
+                    StreamPaymentAssetId::Native,
+                    DepositChange::Decrease(500),
+                ),
+                Error::UnauthorizedOrigin
+            );
+        })
+    }
+
+    #[test]
+    fn operator_can_request_and_cancel_change() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+            set_operator(
+                BOB,
+                OperatorPermissions {
+                    request_change: true,
+                    ..default()
+                },
+            );
+
+            let new_config = StreamConfig {
+                rate: 101,
+                ..open_stream.config
+            };
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                ChangeKind::Suggestion,
+                new_config,
+                None,
+            ));
+
+            assert_event_emitted!(Event::<Runtime>::StreamConfigChangeRequested {
+                stream_id: 0,
+                request_nonce: 1,
+                requester: Party::Target,
+                old_config: open_stream.config,
+                new_config,
+            });
+
+            // Change requested by the operator is considered made by the target.
+            assert_err!(
+                StreamPayment::accept_requested_change(RuntimeOrigin::signed(BOB), 0, 1, None),
+                Error::CantAcceptOwnRequest
+            );
+
+            assert_ok!(StreamPayment::cancel_change_request(
+                RuntimeOrigin::signed(CHARLIE),
+                0
+            ));
+            assert_eq!(Streams::<Runtime>::get(0).unwrap().pending_request, None);
+        })
+    }
+
+    #[test]
+    fn operator_can_only_accept_change_with_permission() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+
+            let new_config = StreamConfig {
+                rate: 99,
+                ..open_stream.config
+            };
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                ChangeKind::Suggestion,
+                new_config,
+                None,
+            ));
+
+            set_operator(
+                BOB,
+                OperatorPermissions {
+                    request_change: true,
+                    ..default()
+                },
+            );
+            assert_err!(
+                StreamPayment::accept_requested_change(RuntimeOrigin::signed(CHARLIE), 0, 1, None),
+                Error::OperatorNotAllowed
+            );
+
+            set_operator(
+                BOB,
+                OperatorPermissions {
+                    accept_change: true,
+                    ..default()
+                },
+            );
+            assert_ok!(StreamPayment::accept_requested_change(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                1,
+                None
+            ));
+            assert_eq!(Streams::<Runtime>::get(0).unwrap().config, new_config);
+        })
+    }
+
+    #[test]
+    fn source_operator_needs_deposit_permission_to_accept_with_deposit_change() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ChangeKind::Suggestion,
+                StreamConfig {
+                    rate: 101,
+                    ..open_stream.config
+                },
+                None,
+            ));
+
+            set_operator(
+                ALICE,
+                OperatorPermissions {
+                    accept_change: true,
+                    ..default()
+                },
+            );
+            assert_err!(
+                StreamPayment::accept_requested_change(
+                    RuntimeOrigin::signed(CHARLIE),
+                    0,
+                    1,
+                    Some(DepositChange::Increase(500))
+                ),
+                Error::OperatorNotAllowed
+            );
+
+            set_operator(
+                ALICE,
+                OperatorPermissions {
+                    accept_change: true,
+                    increase_deposit: true,
+                    ..default()
+                },
+            );
+            assert_ok!(StreamPayment::accept_requested_change(
+                RuntimeOrigin::signed(CHARLIE),
+                0,
+                1,
+                Some(DepositChange::Increase(500))
+            ));
+            assert_eq!(get_deposit(ALICE), open_stream.deposit + 500);
+        })
+    }
+
+    #[test]
+    fn operators_are_removed_on_close() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+            set_operator(ALICE, default());
+
+            assert_ok!(StreamPayment::close_stream(RuntimeOrigin::signed(ALICE), 0));
+
+            assert_eq!(StreamOperators::<Runtime>::get(0, Party::Source), None);
+        })
+    }
+}
+
+mod batch_perform_payment {
+    use super::*;
+
+    #[test]
+    fn batch_perform_payment_works() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+            assert_ok!(OpenStream {
+                from: CHARLIE,
+                ..default()
+            }
+            .call());
+
+            let delta = u128::from(roll_to(10));
+            let payment = delta * open_stream.config.rate;
+
+            // Unknown stream 5 is skipped.
+            let post_info = StreamPayment::batch_perform_payment(
+                // Anyone can dispatch an update.
+                RuntimeOrigin::signed(CHARLIE),
+                vec![0, 5, 1].try_into().unwrap(),
+            )
+            .expect("batch payment succeeds");
+
+            assert_eq!(
+                post_info.actual_weight,
+                Some(
+                    <() as WeightInfo>::batch_perform_payment(2).saturating_add(
+                        <Runtime as frame_system::Config>::DbWeight::get().reads(1)
+                    )
+                )
+            );
+
+            assert_event_emitted!(PaymentEvent {
+                amount: payment,
+                ..default()
+            });
+            assert_event_emitted!(PaymentEvent {
+                stream_id: 1,
+                source: CHARLIE,
+                amount: payment,
+                ..default()
+            });
+
+            assert_eq!(get_deposit(ALICE), open_stream.deposit - payment);
+            assert_eq!(get_deposit(CHARLIE), open_stream.deposit - payment);
+            assert_eq!(Balances::free_balance(BOB), DEFAULT_BALANCE + 2 * payment);
+        })
+    }
+
+    #[test]
+    fn batch_perform_payment_skips_failed_payments() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+            assert_ok!(OpenStream {
+                from: CHARLIE,
+                ..default()
+            }
+            .call());
+
+            // Deposit of stream 0 is released outside of the pallet, so its payment fails.
+            assert_ok!(Balances::release(
+                &crate::HoldReason::StreamPayment.into(),
+                &ALICE,
+                open_stream.deposit,
+                Precision::Exact,
+            ));
+
+            let delta = u128::from(roll_to(10));
+            let payment = delta * open_stream.config.rate;
+
+            assert_ok!(StreamPayment::batch_perform_payment(
+                RuntimeOrigin::signed(CHARLIE),
+                vec![0, 1].try_into().unwrap(),
+            ));
+
+            assert!(System::events().iter().any(|record| matches!(
+                record.event,
+                RuntimeEvent::StreamPayment(Event::StreamPaymentFailed { stream_id: 0, .. })
+            )));
+            assert_event_emitted!(PaymentEvent {
+                stream_id: 1,
+                source: CHARLIE,
+                amount: payment,
+                ..default()
+            });
+
+            // Failed payment was reverted.
+            assert_eq!(
+                Streams::<Runtime>::get(0).map(|stream| stream.last_time_updated),
+                Some(1)
+            );
+            assert_eq!(get_deposit(CHARLIE), open_stream.deposit - payment);
+            assert_eq!(Balances::free_balance(BOB), DEFAULT_BALANCE + payment);
+        })
+    }
+}
+
+mod sweep_streams {
+    use {super::*, crate::StalledSince};
+
+    #[test]
+    fn sweep_does_nothing_without_enough_weight() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream {
+                deposit: 500,
+                ..default()
+            };
+            assert_ok!(open_stream.call());
+
+            roll_to(10);
+
+            assert_eq!(StreamPayment::sweep_streams(Weight::zero()), Weight::zero());
+            assert_event_not_emitted!(PaymentEvent {
+                amount: open_stream.deposit,
+                stalled: true,
+                ..default()
+            });
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+            assert_eq!(SweepCursor::<Runtime>::get(), None);
+        })
+    }
+
+    #[test]
+    fn sweep_ignores_streams_that_are_not_stalled() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+
+            roll_to(10);
+            StreamPayment::sweep_streams(Weight::MAX);
+
+            assert_event_not_emitted!(PaymentEvent {
+                amount: 9 * open_stream.config.rate,
+                ..default()
+            });
+            assert_eq!(
+                Streams::<Runtime>::get(0),
+                Some(Stream {
+                    deposit: open_stream.deposit,
+                    last_time_updated: 1,
+                    ..default_stream()
+                })
+            );
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+            // All streams were checked, next sweep starts from the beginning.
+            assert_eq!(SweepCursor::<Runtime>::get(), None);
+        })
+    }
+
+    #[test]
+    fn sweep_closes_drained_stream_after_grace_period() {
+        ExtBuilder::default().build().execute_with(|| {
+            let opening_deposit = OpenStreamHoldAmount::get();
+            let open_stream = OpenStream {
+                deposit: 500,
+                ..default()
+            };
+            assert_ok!(open_stream.call());
+
+            roll_to(10);
+            StreamPayment::sweep_streams(Weight::MAX);
+
+            // Stalled stream is settled but not closed yet.
+            assert_event_emitted!(PaymentEvent {
+                amount: open_stream.deposit,
+                stalled: true,
+                ..default()
+            });
+            assert_eq!(StalledSince::<Runtime>::get(0), Some(10));
+            assert_eq!(Streams::<Runtime>::get(0).map(|s| s.deposit), Some(0));
+
+            // `StalledStreamGracePeriod` is 5 in the mock.
+            roll_to(14);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert!(Streams::<Runtime>::get(0).is_some());
+
+            roll_to(15);
+            StreamPayment::sweep_streams(Weight::MAX);
+
+            assert_event_emitted!(Event::<Runtime>::StreamClosed {
+                stream_id: 0,
+                refunded: opening_deposit
+            });
+            assert_eq!(Streams::<Runtime>::get(0), None);
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+            assert_eq!(LookupStreamsWithSource::<Runtime>::get(ALICE, 0), None);
+            assert_eq!(LookupStreamsWithTarget::<Runtime>::get(BOB, 0), None);
+            assert_balance_change!(-, ALICE, open_stream.deposit);
+            assert_balance_change!(+, BOB, open_stream.deposit);
+        })
+    }
+
+    #[test]
+    fn sweep_does_not_close_refilled_stream() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream {
+                deposit: 500,
+                ..default()
+            };
+            assert_ok!(open_stream.call());
+
+            roll_to(10);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(StalledSince::<Runtime>::get(0), Some(10));
+
+            assert_ok!(StreamPayment::immediately_change_deposit(
+                RuntimeOrigin::signed(ALICE),
+                0,
+                StreamPaymentAssetId::Native,
+                DepositChange::Increase(1 * MEGA),
+            ));
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+
+            roll_to(15);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert!(Streams::<Runtime>::get(0).is_some());
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+        })
+    }
+
+    #[test]
+    fn sweep_closes_stream_past_mandatory_deadline_immediately() {
+        ExtBuilder::default().build().execute_with(|| {
+            let opening_deposit = OpenStreamHoldAmount::get();
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ChangeKind::Mandatory { deadline: 5 },
+                StreamConfig {
+                    rate: 101,
+                    ..open_stream.config
+                },
+                None,
+            ));
+
+            // Deadline is not reached yet, the stream is not stalled.
+            roll_to(3);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+            assert!(Streams::<Runtime>::get(0).is_some());
+
+            // Payment stops at deadline, and the stream is closed without grace period.
+            let payment = 4 * open_stream.config.rate;
+            roll_to(10);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_event_emitted!(PaymentEvent {
+                amount: payment,
+                stalled: true,
+                ..default()
+            });
+            assert_event_emitted!(Event::<Runtime>::StreamClosed {
+                stream_id: 0,
+                refunded: open_stream.deposit - payment + opening_deposit
+            });
+            assert_eq!(Streams::<Runtime>::get(0), None);
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+            assert_eq!(get_deposit(ALICE), 0);
+            assert_balance_change!(-, ALICE, payment);
+            assert_balance_change!(+, BOB, payment);
+        })
+    }
+
+    #[test]
+    fn sweep_closes_drained_stream_once_mandatory_deadline_passes() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream {
+                deposit: 500,
+                ..default()
+            };
+            assert_ok!(open_stream.call());
+
+            roll_to(10);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(StalledSince::<Runtime>::get(0), Some(10));
+
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ChangeKind::Mandatory { deadline: 12 },
+                StreamConfig {
+                    rate: 101,
+                    ..open_stream.config
+                },
+                None,
+            ));
+
+            // Deadline is reached before the end of the grace period.
+            roll_to(12);
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(Streams::<Runtime>::get(0), None);
+            assert_eq!(StalledSince::<Runtime>::get(0), None);
+        })
+    }
+
+    #[test]
+    fn sweep_is_bounded_and_resumes_from_cursor() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream {
+                deposit: 500,
+                ..default()
+            };
+            for _ in 0..5 {
+                assert_ok!(open_stream.call());
+            }
+
+            roll_to(10);
+            let stalled_count = || StalledSince::<Runtime>::iter().count();
+
+            // `MaxStreamsSweptPerBlock` is 3 in the mock.
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(stalled_count(), 3);
+            assert!(SweepCursor::<Runtime>::get().is_some());
+
+            // Next sweep checks the remaining streams and resets the cursor.
+            StreamPayment::sweep_streams(Weight::MAX);
+            assert_eq!(stalled_count(), 5);
+            assert_eq!(SweepCursor::<Runtime>::get(), None);
+        })
+    }
+}
+
+mod queries {
+    use super::*;
+
+    #[test]
+    fn streams_are_listed_by_source_and_target() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream::default().call());
+            assert_ok!(OpenStream {
+                from: BOB,
+                to: CHARLIE,
+                ..default()
+            }
+            .call());
+            assert_ok!(OpenStream {
+                from: ALICE,
+                to: CHARLIE,
+                ..default()
+            }
+            .call());
+
+            let sorted = |v: Vec<u64>| v.tap_mut(|v| v.sort());
+
+            assert_eq!(
+                sorted(StreamPayment::streams_with_source(&ALICE)),
+                vec![0, 2]
+            );
+            assert_eq!(sorted(StreamPayment::streams_with_source(&BOB)), vec![1]);
+            assert_eq!(StreamPayment::streams_with_source(&CHARLIE), vec![]);
+
+            assert_eq!(StreamPayment::streams_with_target(&ALICE), vec![]);
+            assert_eq!(sorted(StreamPayment::streams_with_target(&BOB)), vec![0]);
+            assert_eq!(
+                sorted(StreamPayment::streams_with_target(&CHARLIE)),
+                vec![1, 2]
+            );
+        })
+    }
+
+    #[test]
+    fn stall_time_of_unknown_stream() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_eq!(
+                StreamPayment::stream_stall_time(0),
+                Err(Error::UnknownStreamId)
+            );
+        })
+    }
+
+    #[test]
+    fn stall_time_when_deposit_is_exhausted() {
+        ExtBuilder::default().build().execute_with(|| {
+            // 1050 / 100 = 10.5, the stream stalls after 11 blocks.
+            assert_ok!(OpenStream {
+                deposit: 1050,
+                ..default()
+            }
+            .call());
+
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(Some(1 + 11)));
+
+            // Projection is updated with the stream.
+            roll_to(5);
+            assert_ok!(StreamPayment::perform_payment(
+                RuntimeOrigin::signed(CHARLIE),
+                0
+            ));
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(Some(12)));
+
+            // Stream will be stalled at projected time, not before.
+            assert!(
+                !StreamPayment::stream_payment_status(0, Some(11))
+                    .unwrap()
+                    .stalled
+            );
+            assert!(
+                StreamPayment::stream_payment_status(0, Some(12))
+                    .unwrap()
+                    .stalled
+            );
+        })
+    }
+
+    #[test]
+    fn stall_time_with_zero_rate() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(OpenStream {
+                config: StreamConfig {
+                    rate: 0,
+                    ..default_config()
+                },
+                ..default()
+            }
+            .call());
+
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(None));
+        })
+    }
+
+    #[test]
+    fn stall_time_with_mandatory_deadline() {
+        ExtBuilder::default().build().execute_with(|| {
+            let open_stream = OpenStream::default();
+            assert_ok!(open_stream.call());
+
+            // Deposit lasts 10_000 blocks.
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(Some(1 + 10_000)));
+
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ChangeKind::Mandatory { deadline: 100 },
+                StreamConfig {
+                    rate: 101,
+                    ..open_stream.config
+                },
+                None,
+            ));
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(Some(100)));
+
+            // A suggestion doesn't stop payments.
+            assert_ok!(StreamPayment::cancel_change_request(
+                RuntimeOrigin::signed(BOB),
+                0
+            ));
+            assert_ok!(StreamPayment::request_change(
+                RuntimeOrigin::signed(BOB),
+                0,
+                ChangeKind::Suggestion,
+                StreamConfig {
+                    rate: 101,
+                    ..open_stream.config
+                },
+                None,
+            ));
+            assert_eq!(StreamPayment::stream_stall_time(0), Ok(Some(1 + 10_000)));
+        })
+    }
+}
@@ -61,6 +61,9 @@ pub trait WeightInfo {
 	fn accept_requested_change() -> Weight;
 	fn cancel_change_request() -> Weight;
 	fn immediately_change_deposit() -> Weight;
+	fn sweep_stream() -> Weight;
+	fn set_operator() -> Weight;
+	fn batch_perform_payment(n: u32, ) -> Weight;
 }
 
 /// Weights for pallet_stream_payment using the Substrate node and recommended hardware.
@@ -188,6 +191,57 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(4_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	/// Storage: `StreamPayment::Streams` (r:1 w:1)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(229), added: 2704, mode: `MaxEncodedLen`)
+	/// Storage: `StreamPayment::LookupStreamsWithTarget` (r:0 w:1)
+	/// Proof: `StreamPayment::LookupStreamsWithTarget` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `StreamPayment::LookupStreamsWithSource` (r:0 w:1)
+	/// Proof: `StreamPayment::LookupStreamsWithSource` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn sweep_stream() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `580`
+		//  Estimated: `6196`
+		// Minimum execution time: 113_779_000 picoseconds.
+		Weight::from_parts(116_675_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+	}
+	/// Storage: `StreamPayment::Streams` (r:1 w:0)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `StreamPayment::StreamOperators` (r:0 w:1)
+	/// Proof: `StreamPayment::StreamOperators` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_operator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `252`
+		//  Estimated: `3717`
+		// Minimum execution time: 12_455_000 picoseconds.
+		Weight::from_parts(13_102_000, 3717)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `StreamPayment::Streams` (r:100 w:100)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:200 w:200)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:100 w:100)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(229), added: 2704, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	fn batch_perform_payment(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + n * (580 ±0)`
+		//  Estimated: `990 + n * (5206 ±0)`
+		// Minimum execution time: 3_128_000 picoseconds.
+		Weight::from_parts(3_402_000, 990)
+			// Standard Error: 21_367
+			.saturating_add(Weight::from_parts(84_971_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(n.into()))
+	}
 }
 
 // For backwards compatibility and tests
@@ -314,4 +368,55 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(4_u64))
 			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
+	/// Storage: `StreamPayment::Streams` (r:1 w:1)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:1 w:1)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(229), added: 2704, mode: `MaxEncodedLen`)
+	/// Storage: `StreamPayment::LookupStreamsWithTarget` (r:0 w:1)
+	/// Proof: `StreamPayment::LookupStreamsWithTarget` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `StreamPayment::LookupStreamsWithSource` (r:0 w:1)
+	/// Proof: `StreamPayment::LookupStreamsWithSource` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn sweep_stream() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `580`
+		//  Estimated: `6196`
+		// Minimum execution time: 113_779_000 picoseconds.
+		Weight::from_parts(116_675_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(6_u64))
+	}
+	/// Storage: `StreamPayment::Streams` (r:1 w:0)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `StreamPayment::StreamOperators` (r:0 w:1)
+	/// Proof: `StreamPayment::StreamOperators` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_operator() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `252`
+		//  Estimated: `3717`
+		// Minimum execution time: 12_455_000 picoseconds.
+		Weight::from_parts(13_102_000, 3717)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `StreamPayment::Streams` (r:100 w:100)
+	/// Proof: `StreamPayment::Streams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:200 w:200)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `Balances::Holds` (r:100 w:100)
+	/// Proof: `Balances::Holds` (`max_values`: None, `max_size`: Some(229), added: 2704, mode: `MaxEncodedLen`)
+	/// The range of component `n` is `[0, 100]`.
+	fn batch_perform_payment(n: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + n * (580 ±0)`
+		//  Estimated: `990 + n * (5206 ±0)`
+		// Minimum execution time: 3_128_000 picoseconds.
+		Weight::from_parts(3_402_000, 990)
+			// Standard Error: 21_367
+			.saturating_add(Weight::from_parts(84_971_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads((4_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(n.into())))
+			.saturating_add(Weight::from_parts(0, 5206).saturating_mul(n.into()))
+	}
 }
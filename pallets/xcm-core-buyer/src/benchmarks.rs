@@ -19,61 +19,121 @@
 //! Benchmarking
 use {
     crate::{
-        Call, Config, GetParathreadCollators, GetParathreadParams, InFlightOrders, Pallet,
-        RelayXcmWeightConfig, RelayXcmWeightConfigInner,
+        BuyCoreCollatorProof, Call, CollatorSignatureNonce, Config, ConsecutiveFailedOrders,
+        GetParathreadCollators, GetParathreadParams, InFlightCoreBuyingOrder, InFlightOrders,
+        OrderCooldown, Pallet, QueryIdToParaId, RelayXcmWeightConfig, RelayXcmWeightConfigInner,
     },
     core::marker::PhantomData,
-    frame_benchmarking::{account, v2::*},
-    frame_support::{assert_ok, pallet_prelude::Weight, BoundedBTreeSet},
+    frame_benchmarking::v2::*,
+    frame_support::{
+        assert_ok,
+        pallet_prelude::{Decode, Weight},
+        traits::{EnsureOrigin, UnfilteredDispatchable},
+        BoundedBTreeMap,
+    },
     frame_system::RawOrigin,
-    sp_std::{collections::btree_set::BTreeSet, vec},
-    tp_traits::{ParaId, ParathreadParams, SlotFrequency},
+    sp_runtime::{
+        traits::{TrailingZeroInput, ValidateUnsigned},
+        transaction_validity::TransactionSource,
+        RuntimeAppPublic,
+    },
+    sp_std::{collections::btree_map::BTreeMap, vec},
+    staging_xcm::v3::{MaybeErrorCode, Response},
+    tp_traits::{GetSessionIndex, ParaId, ParathreadParams, SlotFrequency},
 };
 
 pub const BUY_EXECUTION_COST: u128 = 50_000_000;
 pub const PLACE_ORDER_WEIGHT_AT_MOST: Weight = Weight::from_parts(1_000_000_000, 100_000);
 
+/// Worst case setup for buying a core: `x` orders already in flight, and `x + 1` is a parathread
+/// without assigned collators. Returns the para id of that parathread.
+fn prepare_buy_core<T: Config>(x: u32) -> ParaId {
+    assert_ok!(Pallet::<T>::set_relay_xcm_weight_config(
+        RawOrigin::Root.into(),
+        Some(RelayXcmWeightConfigInner {
+            buy_execution_cost: BUY_EXECUTION_COST,
+            weight_at_most: PLACE_ORDER_WEIGHT_AT_MOST,
+            _phantom: PhantomData,
+        }),
+    ));
+
+    let para_id = ParaId::from(x + 1);
+    assert!(InFlightOrders::<T>::get().is_empty());
+
+    // Mock `x` xcm messages already sent and not yet expired
+    let bbm: BoundedBTreeMap<ParaId, _, _> = BTreeMap::from_iter((0..x).map(|i| {
+        (
+            ParaId::from(i),
+            InFlightCoreBuyingOrder {
+                relay_block_number: 0,
+                query_id: i.into(),
+            },
+        )
+    }))
+    .try_into()
+    .expect("x is greater than MaxParathreads");
+    InFlightOrders::<T>::put(bbm);
+    assert!(!InFlightOrders::<T>::get().contains_key(&para_id));
+
+    // For the extrinsic to succeed, the para_id must be a parathread. Collators are assigned by
+    // each benchmark.
+    T::GetParathreadParams::set_parathread_params(
+        para_id,
+        Some(ParathreadParams {
+            slot_frequency: SlotFrequency { min: 10, max: 10 },
+            core_purchase_backend: Default::default(),
+        }),
+    );
+
+    para_id
+}
+
 #[benchmarks]
 mod benchmarks {
     use super::*;
 
     #[benchmark]
-    fn force_buy_core(x: Linear<1, 99>) {
-        assert_ok!(Pallet::<T>::set_relay_xcm_weight_config(
-            RawOrigin::Root.into(),
-            Some(RelayXcmWeightConfigInner {
-                buy_execution_cost: BUY_EXECUTION_COST,
-                weight_at_most: PLACE_ORDER_WEIGHT_AT_MOST,
-                _phantom: PhantomData,
-            }),
-        ));
-
-        let para_id = ParaId::from(x + 1);
-        assert_eq!(InFlightOrders::<T>::get(), BTreeSet::new());
-
-        // Mock `x` xcm messages already sent in this block
-        let bbs: BoundedBTreeSet<ParaId, _> = BTreeSet::from_iter((0..x).map(ParaId::from))
-            .try_into()
-            .expect("x is greater than MaxParathreads");
-        InFlightOrders::<T>::put(bbs);
-        assert!(!InFlightOrders::<T>::get().contains(&para_id));
-
-        // For the extrinsic to succeed, we need to ensure that:
-        // * the para_id is a parathread
-        // * it has assigned collators
-        T::GetParathreadParams::set_parathread_params(
+    fn buy_core(x: Linear<1, 99>) {
+        let para_id = prepare_buy_core::<T>(x);
+
+        // The proof must be signed by a collator assigned to this parathread
+        let collator = T::CollatorPublicKey::generate_pair(None);
+        T::GetAssignedCollators::set_parathread_collators(para_id, vec![collator.clone()]);
+        let proof = BuyCoreCollatorProof::new(
+            Pallet::<T>::genesis_hash(),
+            T::CurrentSessionIndex::session_index(),
+            CollatorSignatureNonce::<T>::get(para_id),
             para_id,
-            Some(ParathreadParams {
-                slot_frequency: SlotFrequency { min: 10, max: 10 },
-            }),
-        );
-        let author: T::AccountId = account("account id", 0u32, 0u32);
-        T::GetAssignedCollators::set_parathread_collators(para_id, vec![author]);
+            collator,
+        )
+        .expect("key is in the keystore");
+        let call = Call::<T>::buy_core { para_id, proof };
+
+        // The signature is verified in `validate_unsigned`, so include it in the weight
+        #[block]
+        {
+            assert_ok!(Pallet::<T>::validate_unsigned(
+                TransactionSource::InBlock,
+                &call
+            ));
+            assert_ok!(call.dispatch_bypass_filter(RawOrigin::None.into()));
+        }
+
+        assert!(InFlightOrders::<T>::get().contains_key(&para_id));
+    }
+
+    #[benchmark]
+    fn force_buy_core(x: Linear<1, 99>) {
+        let para_id = prepare_buy_core::<T>(x);
+
+        let collator = T::CollatorPublicKey::decode(&mut TrailingZeroInput::zeroes())
+            .expect("valid public key");
+        T::GetAssignedCollators::set_parathread_collators(para_id, vec![collator]);
 
         #[extrinsic_call]
         Pallet::<T>::force_buy_core(RawOrigin::Root, para_id);
 
-        assert!(InFlightOrders::<T>::get().contains(&para_id));
+        assert!(InFlightOrders::<T>::get().contains_key(&para_id));
     }
 
     #[benchmark]
@@ -96,5 +156,54 @@ mod benchmarks {
         Pallet::<T>::set_relay_chain(RawOrigin::Root, Some(T::RelayChain::default()));
     }
 
+    #[benchmark]
+    fn set_order_cooldown() {
+        let para_id = ParaId::from(1000);
+
+        #[extrinsic_call]
+        Pallet::<T>::set_order_cooldown(RawOrigin::Root, para_id, Some(10));
+
+        assert_eq!(OrderCooldown::<T>::get(para_id), Some(10));
+    }
+
+    #[benchmark]
+    fn query_response() {
+        let para_id = ParaId::from(1000);
+        let query_id = 0;
+        let origin = T::XcmResponseOrigin::try_successful_origin()
+            .expect("failed to create XcmResponseOrigin");
+
+        // Worst case: the order failed, so it must be removed from the in-flight orders
+        let bbm: BoundedBTreeMap<ParaId, _, _> = BTreeMap::from_iter([(
+            para_id,
+            InFlightCoreBuyingOrder {
+                relay_block_number: 0,
+                query_id,
+            },
+        )])
+        .try_into()
+        .expect("MaxParathreads is 0");
+        InFlightOrders::<T>::put(bbm);
+        QueryIdToParaId::<T>::insert(query_id, para_id);
+        let response = Response::DispatchResult(MaybeErrorCode::Error(Default::default()));
+
+        #[extrinsic_call]
+        Pallet::<T>::query_response(origin as T::RuntimeOrigin, query_id, response);
+
+        assert!(InFlightOrders::<T>::get().is_empty());
+        assert_eq!(ConsecutiveFailedOrders::<T>::get(para_id), 1);
+    }
+
+    #[benchmark]
+    fn resume_core_buying() {
+        let para_id = ParaId::from(1000);
+        ConsecutiveFailedOrders::<T>::insert(para_id, 10);
+
+        #[extrinsic_call]
+        Pallet::<T>::resume_core_buying(RawOrigin::Root, para_id);
+
+        assert_eq!(ConsecutiveFailedOrders::<T>::get(para_id), 0);
+    }
+
     impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
 }
@@ -36,18 +36,29 @@ pub use weights::WeightInfo;
 use {
     dp_core::ParaId,
     frame_support::{
+        dispatch::GetDispatchInfo,
         pallet_prelude::*,
-        traits::fungible::{Balanced, Inspect},
+        traits::{
+            fungible::{Balanced, Inspect},
+            EnsureOrigin,
+        },
     },
     frame_system::pallet_prelude::*,
+    nimbus_primitives::SlotBeacon,
     parity_scale_codec::EncodeLike,
-    sp_runtime::traits::{AccountIdConversion, Convert, Get},
+    sp_runtime::{
+        traits::{AccountIdConversion, BlockNumberProvider, Convert, Get, Zero},
+        RuntimeAppPublic,
+    },
     sp_std::{vec, vec::Vec},
     staging_xcm::{
         prelude::*,
         v3::{InteriorMultiLocation, MultiAsset, MultiAssets, Xcm},
     },
-    tp_traits::ParathreadParams,
+    tp_traits::{
+        AuthorNotingHook, BlockNumber, CorePurchaseBackend, GetParathreadParams, GetSessionIndex,
+        Slot,
+    },
 };
 
 #[frame_support::pallet]
@@ -67,8 +78,6 @@ pub mod pallet {
         /// Get encoded call to buy a core in the relay chain. This will be passed to the XCM
         /// `Transact` instruction.
         type GetPurchaseCoreCall: GetPurchaseCoreCall<Self::RelayChain>;
-        /// Get current block number, used in `validate_unsigned`.
-        type GetBlockNumber: Get<u32>;
         /// How to convert a `ParaId` into an `AccountId32`. Used to derive the parathread tank
         /// account in `interior_multilocation`.
         type GetParathreadAccountId: Convert<ParaId, [u8; 32]>;
@@ -90,11 +99,54 @@ pub mod pallet {
         /// Limit how many in-flight XCM requests can be sent to the relay chain in one block.
         #[pallet::constant]
         type MaxParathreads: Get<u32>;
-        /// Get the parathread params. Used to verify that the para id is a parathread.
-        // TODO: and in the future to restrict the ability to buy a core depending on slot frequency
+        /// Get the current relay chain block number. Used to know when in-flight orders expire.
+        type RelayChainBlockNumberProvider: BlockNumberProvider<BlockNumber = BlockNumber>;
+        /// Number of relay chain blocks that an order stays in flight if `pallet_author_noting` does
+        /// not see a new block of that parathread in the meantime.
+        #[pallet::constant]
+        type InFlightOrderTtl: Get<BlockNumber>;
+        /// Max number of expired in-flight orders removed in one block. Any other expired orders
+        /// are removed in the following blocks.
+        #[pallet::constant]
+        type MaxExpiredOrdersPerBlock: Get<u32>;
+        /// The overarching call type, used to build the `query_response` call that will be
+        /// dispatched when the relay chain reports the result of the `Transact`.
+        type RuntimeCall: From<Call<Self>> + Encode + GetDispatchInfo;
+        /// Origin of the XCM responses. Must only allow responses to queries registered using
+        /// `XcmNotifier`, and return the location of the responder.
+        type XcmResponseOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = MultiLocation>;
+        /// Register XCM queries, so that the response to the `ReportTransactStatus` instruction
+        /// dispatches `query_response`.
+        type XcmNotifier: XcmNotifier<Self>;
+        /// The universal location of this chain. Used to tell the relay chain where to send the
+        /// response to.
+        type UniversalLocation: Get<InteriorMultiLocation>;
+        /// Number of blocks after which a pending XCM query will not be notified anymore.
+        #[pallet::constant]
+        type CoreBuyingXcmQueryTtl: Get<BlockNumberFor<Self>>;
+        /// Number of consecutive failed orders after which buying cores for a parathread is
+        /// paused, until root calls `resume_core_buying`.
+        #[pallet::constant]
+        type MaxConsecutiveFailedOrders: Get<u32>;
+        /// Get the parathread params. Used to verify that the para id is a parathread, and to
+        /// restrict the ability to buy a core depending on its slot frequency.
         type GetParathreadParams: GetParathreadParams;
+        /// Get the tanssi slot at which the latest block of a parathread was noted.
+        type GetLatestNotedSlot: GetLatestNotedSlot;
+        /// Current tanssi slot, compared with the latest noted slot to enforce the
+        /// `SlotFrequency` of parathreads.
+        type SlotBeacon: SlotBeacon;
         /// Get a list of collators assigned to this parathread. Used to verify the collator proof.
-        type GetAssignedCollators: GetParathreadCollators<Self::AccountId>;
+        type GetAssignedCollators: GetParathreadCollators<Self::CollatorPublicKey>;
+        /// Public key of the collators, used to sign and verify the `BuyCoreCollatorProof`.
+        type CollatorPublicKey: Member
+            + Parameter
+            + RuntimeAppPublic
+            + MaybeSerializeDeserialize
+            + MaxEncodedLen;
+        /// Get the current session index. Collator proofs are only valid in the session they were
+        /// signed for, because collators are reassigned every session.
+        type CurrentSessionIndex: GetSessionIndex<u32>;
         /// A configuration for base priority of unsigned transactions.
         ///
         /// This is exposed so that it can be tuned for particular runtime, when
@@ -109,7 +161,29 @@ pub mod pallet {
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// An XCM message to buy a core for this parathread has been sent to the relay chain.
-        BuyCoreXcmSent { para_id: ParaId },
+        BuyCoreXcmSent {
+            para_id: ParaId,
+            transaction_status_query_id: QueryId,
+        },
+        /// The relay chain reported that the core was bought successfully.
+        BuyCoreSucceeded { para_id: ParaId, query_id: QueryId },
+        /// The relay chain reported that the `Transact` to buy a core failed. Any assets that were
+        /// not spent are refunded to the parathread tank in the relay chain.
+        BuyCoreFailed {
+            para_id: ParaId,
+            query_id: QueryId,
+            error: MaybeErrorCode,
+        },
+        /// Too many consecutive orders for this parathread have failed, buying cores is paused
+        /// until root calls `resume_core_buying`.
+        CoreBuyingPaused { para_id: ParaId },
+        /// Buying cores for this parathread has been resumed.
+        CoreBuyingResumed { para_id: ParaId },
+        /// An in-flight order expired before any new block of this parathread was noted.
+        BuyCoreOrderExpired {
+            para_id: ParaId,
+            relay_block_number: BlockNumber,
+        },
     }
 
     #[pallet::error]
@@ -133,23 +207,74 @@ pub mod pallet {
         XcmWeightStorageNotSet,
         /// Converting a multilocation into a relay relative multilocation failed
         ReanchorFailed,
+        /// The nonce in the collator proof does not match the expected nonce for this para id
+        IncorrectCollatorSignatureNonce,
+        /// Not enough relay blocks have passed since the last order for this para id, see
+        /// `OrderCooldown`
+        OrderCooldownNotElapsed,
+        /// Inverting the location of the relay chain, to know where to send the response, failed
+        LocationInversionFailed,
+        /// The relay chain sent a response that is not a `DispatchResult`
+        UnexpectedXcmResponse,
+        /// Too many consecutive orders for this para id have failed
+        CoreBuyingPaused,
+        /// Not enough slots have passed since the latest block of this parathread, according to
+        /// the `min` of its `SlotFrequency`
+        SlotFrequencyMinNotReached,
+        /// The session index in the collator proof is not the current session
+        IncorrectCollatorSignatureSession,
+        /// The relay chain does not support the core purchase backend of this parathread
+        CorePurchaseBackendNotSupported,
     }
 
-    /// Proof that I am a collator, assigned to a para_id, and I can buy a core for that para_id
-    #[derive(Encode, Decode, CloneNoBound, PartialEq, Eq, DebugNoBound, TypeInfo)]
-    #[scale_info(skip_type_params(T))]
-    pub struct BuyCoreCollatorProof<T: Config> {
-        account: T::AccountId,
-        // TODO
-        _signature: (),
+    /// Nonce expected in the next `BuyCoreCollatorProof` of each parathread. It is increased after
+    /// every successful `buy_core`, so a signed proof can only be used once.
+    #[pallet::storage]
+    pub type CollatorSignatureNonce<T: Config> =
+        StorageMap<_, Twox64Concat, ParaId, u64, ValueQuery>;
+
+    /// An order to buy a core that has been sent to the relay chain, but has not been used yet.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+    pub struct InFlightCoreBuyingOrder {
+        /// Relay block number at the time the order was sent
+        pub relay_block_number: BlockNumber,
+        /// Id of the query that will be notified with the result of the order
+        pub query_id: QueryId,
     }
 
-    /// Set of parathreads that have already sent an XCM message to buy a core recently.
-    /// Used to avoid 2 collators buying a core at the same time, because it is only possible to buy
-    /// 1 core in 1 relay block for the same parathread.
+    /// Parathreads that have already sent an XCM message to buy a core recently.
+    /// Used to avoid 2 collators buying a core at the same time, and to avoid buying more cores
+    /// before the previous one has been used. An order is removed when `pallet_author_noting` sees
+    /// a new block for that parathread, or after `InFlightOrderTtl` relay blocks.
+    #[pallet::storage]
+    pub type InFlightOrders<T: Config> = StorageValue<
+        _,
+        BoundedBTreeMap<ParaId, InFlightCoreBuyingOrder, T::MaxParathreads>,
+        ValueQuery,
+    >;
+
+    /// Minimum number of relay blocks between two consecutive orders of the same parathread.
+    /// If not set, only one order per relay block is allowed.
+    #[pallet::storage]
+    pub type OrderCooldown<T: Config> =
+        StorageMap<_, Twox64Concat, ParaId, BlockNumber, OptionQuery>;
+
+    /// Relay block number of the latest order sent for each parathread. Used to enforce
+    /// `OrderCooldown`.
+    #[pallet::storage]
+    pub type LastOrderRelayBlockNumber<T: Config> =
+        StorageMap<_, Twox64Concat, ParaId, BlockNumber, OptionQuery>;
+
+    /// Pending XCM queries of in-flight orders, waiting for the relay chain to report the result
+    /// of the `Transact`.
     #[pallet::storage]
-    pub type InFlightOrders<T: Config> =
-        StorageValue<_, BoundedBTreeSet<ParaId, T::MaxParathreads>, ValueQuery>;
+    pub type QueryIdToParaId<T: Config> = StorageMap<_, Twox64Concat, QueryId, ParaId, OptionQuery>;
+
+    /// Number of consecutive orders of each parathread that the relay chain reported as failed.
+    /// Reset after a successful order.
+    #[pallet::storage]
+    pub type ConsecutiveFailedOrders<T: Config> =
+        StorageMap<_, Twox64Concat, ParaId, u32, ValueQuery>;
 
     /// This must be set by root with the value of the relay chain xcm call weight and extrinsic
     /// weight limit. This is a storage item because relay chain weights can change, so we need to
@@ -182,14 +307,13 @@ pub mod pallet {
         // tanssi collators. So we cannot force them to provide a complex proof, e.g. against relay
         // state.
         #[pallet::call_index(0)]
-        // TODO: weight
-        #[pallet::weight(T::WeightInfo::force_buy_core(T::MaxParathreads::get()))]
+        #[pallet::weight(T::WeightInfo::buy_core(T::MaxParathreads::get()))]
         pub fn buy_core(
             origin: OriginFor<T>,
             para_id: ParaId,
             // since signature verification is done in `validate_unsigned`
             // we can skip doing it here again.
-            proof: BuyCoreCollatorProof<T>,
+            proof: BuyCoreCollatorProof<T::CollatorPublicKey>,
         ) -> DispatchResult {
             // Signature verification is done in `validate_unsigned`.
             // We use `ensure_none` here because this can only be called by collators, and we do not
@@ -201,14 +325,26 @@ pub mod pallet {
                 return Err(Error::<T>::NoAssignedCollators.into());
             }
 
-            if !assigned_collators.contains(&proof.account) {
+            if !assigned_collators.contains(&proof.public_key) {
                 return Err(Error::<T>::CollatorNotAssigned.into());
             }
 
-            // TODO: implement proof validation
-            return Err(Error::<T>::InvalidProof.into());
+            let current_nonce = CollatorSignatureNonce::<T>::get(para_id);
+            if proof.nonce != current_nonce {
+                return Err(Error::<T>::IncorrectCollatorSignatureNonce.into());
+            }
+
+            if proof.session_index != T::CurrentSessionIndex::session_index() {
+                return Err(Error::<T>::IncorrectCollatorSignatureSession.into());
+            }
+
+            // Increase the nonce before buying the core, so the same proof cannot be used twice.
+            // If buying the core fails, this write is reverted as well, so `validate_unsigned`
+            // must reject any proof that would fail here, otherwise it could be included for free
+            // over and over again.
+            CollatorSignatureNonce::<T>::insert(para_id, current_nonce.saturating_add(1));
 
-            //Self::on_collator_instantaneous_core_requested(para_id)
+            Self::on_collator_instantaneous_core_requested(para_id)
         }
 
         /// Buy core for para id as root. Does not require any proof, useful in tests.
@@ -261,6 +397,91 @@ pub mod pallet {
 
             Ok(())
         }
+
+        /// Set the minimum number of relay blocks between two consecutive orders of this para id.
+        #[pallet::call_index(4)]
+        #[pallet::weight(T::WeightInfo::set_order_cooldown())]
+        pub fn set_order_cooldown(
+            origin: OriginFor<T>,
+            para_id: ParaId,
+            cooldown: Option<BlockNumber>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if let Some(cooldown) = cooldown {
+                OrderCooldown::<T>::insert(para_id, cooldown);
+            } else {
+                OrderCooldown::<T>::remove(para_id);
+            }
+
+            Ok(())
+        }
+
+        /// Handle the response of the relay chain to the `ReportTransactStatus` instruction of an
+        /// order. Only callable as an XCM response to a query registered by this pallet.
+        #[pallet::call_index(5)]
+        #[pallet::weight(T::WeightInfo::query_response())]
+        pub fn query_response(
+            origin: OriginFor<T>,
+            query_id: QueryId,
+            response: Response,
+        ) -> DispatchResult {
+            let _responder = T::XcmResponseOrigin::ensure_origin(origin)?;
+
+            let error = match response {
+                Response::DispatchResult(MaybeErrorCode::Success) => None,
+                Response::DispatchResult(error) => Some(error),
+                _ => return Err(Error::<T>::UnexpectedXcmResponse.into()),
+            };
+
+            // The order may have already been removed, if it expired or if the parathread
+            // produced a block before we got the response.
+            let para_id = match QueryIdToParaId::<T>::take(query_id) {
+                Some(para_id) => para_id,
+                None => return Ok(()),
+            };
+
+            if let Some(error) = error {
+                // The order failed, so collators can try again after the cooldown
+                let mut in_flight_orders = InFlightOrders::<T>::get();
+                in_flight_orders.remove(&para_id);
+                InFlightOrders::<T>::put(in_flight_orders);
+
+                let previous_failed_orders = ConsecutiveFailedOrders::<T>::get(para_id);
+                let failed_orders = previous_failed_orders.saturating_add(1);
+                ConsecutiveFailedOrders::<T>::insert(para_id, failed_orders);
+
+                Self::deposit_event(Event::BuyCoreFailed {
+                    para_id,
+                    query_id,
+                    error,
+                });
+                // Only notify once, the limit may have been lowered while orders were in flight
+                let max_failed_orders = T::MaxConsecutiveFailedOrders::get();
+                if failed_orders >= max_failed_orders && previous_failed_orders < max_failed_orders
+                {
+                    Self::deposit_event(Event::CoreBuyingPaused { para_id });
+                }
+            } else {
+                // Keep the order in flight until the block is produced
+                ConsecutiveFailedOrders::<T>::remove(para_id);
+                Self::deposit_event(Event::BuyCoreSucceeded { para_id, query_id });
+            }
+
+            Ok(())
+        }
+
+        /// Allow buying cores for this para id again, after too many consecutive failed orders.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::WeightInfo::resume_core_buying())]
+        pub fn resume_core_buying(origin: OriginFor<T>, para_id: ParaId) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ConsecutiveFailedOrders::<T>::remove(para_id);
+            Self::deposit_event(Event::CoreBuyingResumed { para_id });
+
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -291,46 +512,93 @@ pub mod pallet {
             Ok(reanchored)
         }
 
-        /// Send an XCM message to the relay chain to try to buy a core for this para_id.
-        fn on_collator_instantaneous_core_requested(para_id: ParaId) -> DispatchResult {
-            let mut in_flight_orders = InFlightOrders::<T>::get();
-            if in_flight_orders.contains(&para_id) {
-                return Err(Error::<T>::OrderAlreadyExists.into());
+        /// Hash of the genesis block of this chain, included in the collator proof payload so that
+        /// proofs cannot be replayed in other chains.
+        pub fn genesis_hash() -> T::Hash {
+            frame_system::Pallet::<T>::block_hash(BlockNumberFor::<T>::zero())
+        }
+
+        /// Check that an order to buy a core for this para_id can be sent now. Returns the encoded
+        /// relay call to buy the core and the relay xcm weight config needed to build the order.
+        /// Used by `validate_unsigned` as well, so any collator proof that would fail to buy a
+        /// core is rejected before being included in a block.
+        fn check_can_buy_core(
+            para_id: ParaId,
+            in_flight_orders: &BoundedBTreeMap<ParaId, InFlightCoreBuyingOrder, T::MaxParathreads>,
+            relay_block_number: BlockNumber,
+        ) -> Result<(Vec<u8>, RelayXcmWeightConfigInner<T>), Error<T>> {
+            if in_flight_orders.contains_key(&para_id) {
+                return Err(Error::<T>::OrderAlreadyExists);
+            }
+            // It is only possible to buy 1 core in 1 relay block for the same parathread, so the
+            // cooldown is at least 1 relay block.
+            if let Some(last_order_relay_block_number) =
+                LastOrderRelayBlockNumber::<T>::get(para_id)
+            {
+                let cooldown = OrderCooldown::<T>::get(para_id).unwrap_or(1).max(1);
+                if relay_block_number < last_order_relay_block_number.saturating_add(cooldown) {
+                    return Err(Error::<T>::OrderCooldownNotElapsed);
+                }
+            }
+            if in_flight_orders.len() as u32 >= T::MaxParathreads::get() {
+                return Err(Error::<T>::InFlightLimitReached);
             }
-            in_flight_orders
-                .try_insert(para_id)
-                .map_err(|_| Error::<T>::InFlightLimitReached)?;
 
             // Check that the para id is a parathread
-            let parathread_params = T::GetParathreadParams::get_parathread_params(para_id);
-            if parathread_params.is_none() {
-                return Err(Error::<T>::NotAParathread.into());
+            let parathread_params = T::GetParathreadParams::get_parathread_params(para_id)
+                .ok_or(Error::<T>::NotAParathread)?;
+
+            if ConsecutiveFailedOrders::<T>::get(para_id) >= T::MaxConsecutiveFailedOrders::get() {
+                return Err(Error::<T>::CoreBuyingPaused);
             }
 
-            // TODO: also compare the latest slot from pallet_author_noting with parathread_params.slot_frequency
+            // Do not buy a core if the next parathread block would be produced too early
+            if let Some(latest_noted_slot) = T::GetLatestNotedSlot::get_latest_noted_slot(para_id) {
+                let current_slot = u64::from(T::SlotBeacon::slot());
+                let slots_since_latest_block =
+                    current_slot.saturating_sub(u64::from(latest_noted_slot));
+                if slots_since_latest_block < u64::from(parathread_params.slot_frequency.min) {
+                    return Err(Error::<T>::SlotFrequencyMinNotReached);
+                }
+            }
 
             let xcm_weights_storage =
                 RelayXcmWeightConfig::<T>::get().ok_or(Error::<T>::XcmWeightStorageNotSet)?;
 
+            // TODO: max_amount is the max price of a core that this parathread is willing to pay
+            // It should be defined in a storage item somewhere, controllable by the container chain
+            // manager.
+            let max_amount =
+                T::GetParathreadMaxCorePrice::get_max_core_price(para_id).unwrap_or(u128::MAX);
+            // Buy a core using the backend selected in the parathread params: either an on-demand
+            // order or coretime instantaneous credits, if the relay chain supports it.
+            let call = T::GetPurchaseCoreCall::get_encoded(
+                RelayChain::<T>::get(),
+                parathread_params.core_purchase_backend,
+                max_amount,
+                para_id,
+            )
+            .ok_or(Error::<T>::CorePurchaseBackendNotSupported)?;
+
+            Ok((call, xcm_weights_storage))
+        }
+
+        /// Send an XCM message to the relay chain to try to buy a core for this para_id.
+        fn on_collator_instantaneous_core_requested(para_id: ParaId) -> DispatchResult {
+            let relay_block_number = T::RelayChainBlockNumberProvider::current_block_number();
+            let mut in_flight_orders = InFlightOrders::<T>::get();
+            let (call, xcm_weights_storage) =
+                Self::check_can_buy_core(para_id, &in_flight_orders, relay_block_number)?;
+
             let withdraw_amount = xcm_weights_storage.buy_execution_cost;
 
             // Use the account derived from the multilocation composed with DescendOrigin
-            // Buy on-demand cores
             // Any failure should return everything to the derivative account
 
             // Don't use utility::as_derivative because that will make the tanssi sovereign account
             // pay for fees, instead use `DescendOrigin` to make the parathread tank account
             // pay for fees.
-            // TODO: when coretime is implemented, use coretime instantaneous credits instead of
-            // buying on-demand cores at the price defined by the relay
             let origin = OriginKind::SovereignAccount;
-            // TODO: max_amount is the max price of a core that this parathread is willing to pay
-            // It should be defined in a storage item somewhere, controllable by the container chain
-            // manager.
-            let max_amount =
-                T::GetParathreadMaxCorePrice::get_max_core_price(para_id).unwrap_or(u128::MAX);
-            let call =
-                T::GetPurchaseCoreCall::get_encoded(RelayChain::<T>::get(), max_amount, para_id);
             let weight_at_most = xcm_weights_storage.weight_at_most;
 
             // Assumption: derived account already has DOT
@@ -345,6 +613,27 @@ pub mod pallet {
             // parathread para id.
             let derived_account = Self::relay_relative_multilocation(interior_multilocation)?;
 
+            // Register a query so that the relay chain reports the result of the `Transact`.
+            // The response will have the parathread tank account as querier, because of the
+            // `DescendOrigin`.
+            let relay_chain = MultiLocation::parent();
+            let notify_call = <T as Config>::RuntimeCall::from(Call::<T>::query_response {
+                query_id: 0,
+                response: Default::default(),
+            });
+            let notify_call_weight = notify_call.get_dispatch_info().weight;
+            let notify_query_ttl = <frame_system::Pallet<T>>::block_number()
+                .saturating_add(T::CoreBuyingXcmQueryTtl::get());
+            let query_id = T::XcmNotifier::new_notify_query(
+                relay_chain,
+                notify_call,
+                notify_query_ttl,
+                interior_multilocation.into(),
+            );
+            let response_destination = T::UniversalLocation::get()
+                .invert_target(&relay_chain)
+                .map_err(|_| Error::<T>::LocationInversionFailed)?;
+
             // Need to use `builder_unsafe` because safe `builder` does not allow `descend_origin` as first instruction.
             // We use `descend_origin` instead of wrapping the transact call in `utility.as_derivative`
             // because with `descend_origin` the parathread tank account will pay for fees, while
@@ -361,20 +650,75 @@ pub mod pallet {
                         .build(),
                 )
                 .transact(origin, weight_at_most, call.into())
+                .report_transact_status(QueryResponseInfo {
+                    destination: response_destination,
+                    query_id,
+                    max_weight: notify_call_weight,
+                })
                 .build();
 
             // Send XCM to relay chain
-            let relay_chain = MultiLocation::parent();
             // We intentionally do not charge any fees
             let (ticket, _price) =
                 T::XcmSender::validate(&mut Some(relay_chain), &mut Some(message))
                     .map_err(|_| Error::<T>::ErrorValidatingXCM)?;
             T::XcmSender::deliver(ticket).map_err(|_| Error::<T>::ErrorDeliveringXCM)?;
-            Self::deposit_event(Event::BuyCoreXcmSent { para_id });
+            Self::deposit_event(Event::BuyCoreXcmSent {
+                para_id,
+                transaction_status_query_id: query_id,
+            });
+            in_flight_orders
+                .try_insert(
+                    para_id,
+                    InFlightCoreBuyingOrder {
+                        relay_block_number,
+                        query_id,
+                    },
+                )
+                .map_err(|_| Error::<T>::InFlightLimitReached)?;
             InFlightOrders::<T>::put(in_flight_orders);
+            QueryIdToParaId::<T>::insert(query_id, para_id);
+            LastOrderRelayBlockNumber::<T>::insert(para_id, relay_block_number);
 
             Ok(())
         }
+
+        /// Remove the in-flight orders that have expired, emitting an event for each one of them.
+        /// Orders that resulted in a new block being noted have already been removed by the
+        /// `AuthorNotingHook`, so any order that expires here has not been used.
+        /// At most `MaxExpiredOrdersPerBlock` orders are removed, the rest will be removed in the
+        /// next blocks.
+        fn clean_up_expired_in_flight_orders() {
+            let relay_block_number = T::RelayChainBlockNumberProvider::current_block_number();
+            let ttl = T::InFlightOrderTtl::get();
+            let max_expired = T::MaxExpiredOrdersPerBlock::get() as usize;
+            let mut in_flight_orders = InFlightOrders::<T>::get();
+            let mut expired = vec![];
+
+            in_flight_orders.retain(|para_id, order| {
+                let has_expired = expired.len() < max_expired
+                    && relay_block_number >= order.relay_block_number.saturating_add(ttl);
+                if has_expired {
+                    expired.push((*para_id, order.clone()));
+                }
+
+                !has_expired
+            });
+
+            if expired.is_empty() {
+                return;
+            }
+
+            InFlightOrders::<T>::put(in_flight_orders);
+            for (para_id, order) in expired {
+                // pallet_xcm will not notify this query anymore, or the response will be ignored
+                QueryIdToParaId::<T>::remove(order.query_id);
+                Self::deposit_event(Event::BuyCoreOrderExpired {
+                    para_id,
+                    relay_block_number: order.relay_block_number,
+                });
+            }
+        }
     }
 
     #[pallet::hooks]
@@ -382,23 +726,19 @@ pub mod pallet {
         fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
             let mut weight = Weight::zero();
 
-            // 1 write in on_finalize
-            weight += T::DbWeight::get().writes(1);
+            // 2 reads and 1 write in on_finalize, plus removing the query and emitting an event for
+            // each expired order
+            weight += T::DbWeight::get().reads_writes(2, 1);
+            weight += T::DbWeight::get()
+                .writes(2u64.saturating_mul(T::MaxExpiredOrdersPerBlock::get().into()));
 
             weight
         }
 
         fn on_finalize(_: BlockNumberFor<T>) {
-            // We clear this storage item because we only need it to prevent collators from buying
-            // more than one core for the same parathread in the same relay block
-            // TODO: with async backing, it is possible that two tanssi blocks are included in the
-            // same relay block, so this kill is not correct, should only kill the storage if the
-            // relay block number has changed
-            // TODO: this allows collators to send N consecutive messages to buy 1 core for the same
-            // parathread, as long as the parathread block is not included in pallet_author_noting.
-            // So a malicious collator could drain the parathread tank account by buying cores on
-            // every tanssi block but not actually producing any block.
-            InFlightOrders::<T>::kill();
+            // We cannot remove expired orders in `on_initialize` because the relay block number
+            // of this block is only known after the `set_validation_data` inherent.
+            Self::clean_up_expired_in_flight_orders();
         }
     }
 
@@ -408,55 +748,57 @@ pub mod pallet {
 
         fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
             if let Call::buy_core { para_id, proof } = call {
-                /*
-                if <Pallet<T>>::is_online(heartbeat.authority_index) {
-                    // we already received a heartbeat for this authority
-                    return InvalidTransaction::Stale.into()
+                // Reject proofs that have already been used, and proofs for a future nonce,
+                // because they cannot be included until all the previous ones are.
+                let current_nonce = CollatorSignatureNonce::<T>::get(para_id);
+                if proof.nonce < current_nonce {
+                    return InvalidTransaction::Stale.into();
                 }
-
-                // check if session index from heartbeat is recent
-                let current_session = T::ValidatorSet::session_index();
-                if heartbeat.session_index != current_session {
-                    return InvalidTransaction::Stale.into()
+                if proof.nonce > current_nonce {
+                    return InvalidTransaction::Future.into();
                 }
 
-                // verify that the incoming (unverified) pubkey is actually an authority id
-                let keys = Keys::<T>::get();
-                if keys.len() as u32 != heartbeat.validators_len {
-                    return InvalidTransaction::Custom(INVALID_VALIDATORS_LEN).into()
+                // Proofs signed for a previous session expire, and proofs signed for a future
+                // session could be valid once it starts
+                let current_session_index = T::CurrentSessionIndex::session_index();
+                if proof.session_index < current_session_index {
+                    return InvalidTransaction::Stale.into();
+                }
+                if proof.session_index > current_session_index {
+                    return InvalidTransaction::Future.into();
                 }
-                let authority_id = match keys.get(heartbeat.authority_index as usize) {
-                    Some(id) => id,
-                    None => return InvalidTransaction::BadProof.into(),
-                };
-
-                // check signature (this is expensive so we do it last).
-                let signature_valid = heartbeat.using_encoded(|encoded_heartbeat| {
-                    authority_id.verify(&encoded_heartbeat, signature)
-                });
 
-                if !signature_valid {
-                    return InvalidTransaction::BadProof.into()
+                // Only collators assigned to this parathread can buy cores
+                let assigned_collators =
+                    T::GetAssignedCollators::get_parathread_collators(*para_id);
+                if !assigned_collators.contains(&proof.public_key) {
+                    return InvalidTransaction::BadSigner.into();
                 }
-                */
 
-                // TODO: read session or block number
-                let block_number = T::GetBlockNumber::get();
+                // Buying the core must succeed, because a failed `buy_core` reverts the nonce
+                // increase, so the same proof could be included again without paying any fees.
+                // Since `pre_dispatch` calls `validate_unsigned`, this is checked again when
+                // the transaction is included in a block.
+                let relay_block_number = T::RelayChainBlockNumberProvider::current_block_number();
+                if Self::check_can_buy_core(
+                    *para_id,
+                    &InFlightOrders::<T>::get(),
+                    relay_block_number,
+                )
+                .is_err()
+                {
+                    return InvalidTransaction::Call.into();
+                }
 
-                // TODO: validate proof
-                let _ = proof;
+                // Check signature (this is expensive so we do it last)
+                if !proof.verify_signature(Self::genesis_hash(), *para_id) {
+                    return InvalidTransaction::BadProof.into();
+                }
 
                 ValidTransaction::with_tag_prefix("XcmCoreBuyer")
                     .priority(T::UnsignedPriority::get())
-                    // TODO: tags
-                    .and_provides((block_number, para_id))
-                    //.and_provides((current_session, authority_id))
-                    //.longevity(
-                    //    TryInto::<u64>::try_into(
-                    //       T::NextSessionRotation::average_session_length() / 2u32.into(),
-                    //    )
-                    //        .unwrap_or(64_u64),
-                    //)
+                    // Only one transaction per (para_id, nonce) can be in the pool
+                    .and_provides((para_id, proof.nonce))
                     .longevity(64)
                     .propagate(true)
                     .build()
@@ -467,10 +809,119 @@ pub mod pallet {
     }
 }
 
+/// Prefix of the `BuyCoreCollatorProof` payload, so that the signature cannot be reused for other
+/// purposes.
+pub const BUY_CORE_PROOF_PREFIX: &[u8; 12] = b"XcmCoreBuyer";
+
+/// Proof that I am a collator, assigned to a para_id, and I can buy a core for that para_id.
+/// The collator signs the `(genesis_hash, session_index, nonce, para_id)` tuple with its
+/// `public_key`. The session index must be the current session, and the nonce must match
+/// `CollatorSignatureNonce` for that para_id.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BuyCoreCollatorProof<PublicKey>
+where
+    PublicKey: RuntimeAppPublic,
+{
+    pub nonce: u64,
+    pub session_index: u32,
+    pub public_key: PublicKey,
+    pub signature: PublicKey::Signature,
+}
+
+impl<PublicKey> BuyCoreCollatorProof<PublicKey>
+where
+    PublicKey: RuntimeAppPublic,
+{
+    /// Payload that the collator must sign. `genesis_hash` is the hash of the genesis block of the
+    /// orchestrator chain.
+    pub fn prepare_payload<Hash: Encode>(
+        genesis_hash: Hash,
+        session_index: u32,
+        nonce: u64,
+        para_id: ParaId,
+    ) -> Vec<u8> {
+        (
+            BUY_CORE_PROOF_PREFIX,
+            genesis_hash,
+            session_index,
+            nonce,
+            para_id,
+        )
+            .encode()
+    }
+
+    /// Create a new proof by signing the payload with `public_key`. Requires access to a keystore
+    /// containing the private key, so this returns `None` if the key cannot be found.
+    pub fn new<Hash: Encode>(
+        genesis_hash: Hash,
+        session_index: u32,
+        nonce: u64,
+        para_id: ParaId,
+        public_key: PublicKey,
+    ) -> Option<Self> {
+        let payload = Self::prepare_payload(genesis_hash, session_index, nonce, para_id);
+        let signature = public_key.sign(&payload)?;
+
+        Some(Self {
+            nonce,
+            session_index,
+            public_key,
+            signature,
+        })
+    }
+
+    /// Returns true if the signature is valid for this para_id.
+    pub fn verify_signature<Hash: Encode>(&self, genesis_hash: Hash, para_id: ParaId) -> bool {
+        let payload = Self::prepare_payload(genesis_hash, self.session_index, self.nonce, para_id);
+
+        self.public_key.verify(&payload, &self.signature)
+    }
+}
+
+impl<T: Config> AuthorNotingHook<T::AccountId> for Pallet<T> {
+    // A new block of this parathread has been noted, so the in-flight order has been used and
+    // collators can buy a new core, after the cooldown.
+    fn on_container_author_noted(
+        _author: &T::AccountId,
+        _previous_block_number: Option<BlockNumber>,
+        _block_number: BlockNumber,
+        para_id: ParaId,
+        _respects_slot_frequency: bool,
+    ) -> Weight {
+        let mut in_flight_orders = InFlightOrders::<T>::get();
+        let order = match in_flight_orders.remove(&para_id) {
+            Some(order) => order,
+            None => return T::DbWeight::get().reads(1),
+        };
+        InFlightOrders::<T>::put(in_flight_orders);
+        // The block has been produced, so we no longer care about the result of the order
+        QueryIdToParaId::<T>::remove(order.query_id);
+
+        T::DbWeight::get().reads_writes(1, 2)
+    }
+}
+
+/// Register XCM queries whose response will be dispatched as a call. Usually implemented using
+/// `pallet_xcm::Pallet::new_notify_query`.
+pub trait XcmNotifier<T: Config> {
+    fn new_notify_query(
+        responder: MultiLocation,
+        notify: <T as Config>::RuntimeCall,
+        timeout: BlockNumberFor<T>,
+        match_querier: MultiLocation,
+    ) -> QueryId;
+}
+
 pub trait GetPurchaseCoreCall<RelayChain> {
-    /// Get the encoded call to buy a core for this `para_id`, with this `max_amount`.
-    /// Returns the encoded call and its estimated weight.
-    fn get_encoded(relay_chain: RelayChain, max_amount: u128, para_id: ParaId) -> Vec<u8>;
+    /// Get the encoded call to buy a core for this `para_id`, with this `max_amount`, using the
+    /// `core_purchase_backend` selected by the parathread. Returns `None` if `relay_chain` does
+    /// not support that backend.
+    fn get_encoded(
+        relay_chain: RelayChain,
+        core_purchase_backend: CorePurchaseBackend,
+        max_amount: u128,
+        para_id: ParaId,
+    ) -> Option<Vec<u8>>;
 }
 
 pub trait GetParathreadCollators<AccountId> {
@@ -490,11 +941,15 @@ impl GetParathreadMaxCorePrice for () {
     }
 }
 
-pub trait GetParathreadParams {
-    fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParams>;
+pub trait GetLatestNotedSlot {
+    /// Tanssi slot at which the latest block of this para id was noted, if any.
+    fn get_latest_noted_slot(para_id: ParaId) -> Option<Slot>;
+}
 
-    #[cfg(feature = "runtime-benchmarks")]
-    fn set_parathread_params(para_id: ParaId, parathread_params: Option<ParathreadParams>);
+impl GetLatestNotedSlot for () {
+    fn get_latest_noted_slot(_para_id: ParaId) -> Option<Slot> {
+        None
+    }
 }
 
 /// Use `into_account_truncating` to convert a `ParaId` into a `[u8; 32]`.
@@ -16,8 +16,9 @@
 
 use {
     crate::{
-        self as pallet_xcm_core_buyer, GetParathreadCollators, GetPurchaseCoreCall,
-        ParaIdIntoAccountTruncating, RelayXcmWeightConfigInner,
+        self as pallet_xcm_core_buyer, BuyCoreCollatorProof, GetLatestNotedSlot,
+        GetParathreadCollators, GetPurchaseCoreCall, ParaIdIntoAccountTruncating,
+        RelayXcmWeightConfigInner, XcmNotifier,
     },
     dp_core::ParaId,
     frame_support::{
@@ -26,17 +27,20 @@ use {
         parameter_types,
         traits::{ConstU64, Everything},
     },
-    sp_core::H256,
+    frame_system::{pallet_prelude::BlockNumberFor, EnsureRootWithSuccess},
+    nimbus_primitives::NimbusId,
+    sp_core::{sr25519, Pair, H256},
     sp_io::TestExternalities,
     sp_runtime::{
-        traits::{BlakeTwo256, Convert, IdentityLookup},
+        traits::{BlakeTwo256, BlockNumberProvider, Convert, IdentityLookup},
         BuildStorage,
     },
     sp_std::collections::btree_map::BTreeMap,
     staging_xcm::latest::{
-        MultiAssets, MultiLocation, SendError, SendResult, SendXcm, Xcm, XcmHash,
+        InteriorMultiLocation, Junction::Parachain, MultiAssets, MultiLocation, QueryId, SendError,
+        SendResult, SendXcm, Xcm, XcmHash,
     },
-    tp_traits::{ParathreadParams, SlotFrequency},
+    tp_traits::{CorePurchaseBackend, GetSessionIndex, ParathreadParams, Slot, SlotFrequency},
 };
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -139,26 +143,46 @@ impl mock_data::Config for Test {}
 #[derive(Clone, Encode, Decode, PartialEq, sp_core::RuntimeDebug, scale_info::TypeInfo)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mocks {
-    pub container_chain_collators: BTreeMap<ParaId, Vec<AccountId>>,
+    pub container_chain_collators: BTreeMap<ParaId, Vec<NimbusId>>,
     pub parathread_params: BTreeMap<ParaId, ParathreadParams>,
+    pub relay_block_number: u32,
+    pub next_query_id: QueryId,
+    pub latest_noted_slot: BTreeMap<ParaId, u64>,
+    pub current_slot: u32,
+    pub last_core_purchase_backend: Option<CorePurchaseBackend>,
+    pub unsupported_core_purchase_backends: Vec<CorePurchaseBackend>,
+    pub session_index: u32,
 }
 
 impl Default for Mocks {
     fn default() -> Self {
         Self {
-            container_chain_collators: BTreeMap::from_iter([(ParaId::from(3333), vec![BOB])]),
+            container_chain_collators: BTreeMap::from_iter([(
+                ParaId::from(3333),
+                vec![get_collator_keypair(BOB).public().into()],
+            )]),
             parathread_params: BTreeMap::from_iter([(
                 ParaId::from(3333),
                 ParathreadParams {
                     slot_frequency: SlotFrequency { min: 10, max: 10 },
+                    core_purchase_backend: Default::default(),
                 },
             )]),
+            relay_block_number: 0,
+            next_query_id: 0,
+            latest_noted_slot: BTreeMap::new(),
+            current_slot: 0,
+            last_core_purchase_backend: None,
+            unsupported_core_purchase_backends: vec![],
+            session_index: 0,
         }
     }
 }
 
 parameter_types! {
     pub const ParachainId: ParaId = ParaId::new(1000);
+    pub UniversalLocation: InteriorMultiLocation = Parachain(1000).into();
+    pub RelayLocation: MultiLocation = MultiLocation::parent();
 }
 
 impl pallet_xcm_core_buyer::Config for Test {
@@ -166,14 +190,27 @@ impl pallet_xcm_core_buyer::Config for Test {
     type Currency = Balances;
     type XcmSender = DevNull;
     type GetPurchaseCoreCall = EncodedCallToBuyCore;
-    type GetBlockNumber = ();
     type GetParathreadAccountId = ParaIdIntoAccountTruncating;
     type GetParathreadMaxCorePrice = ();
     type SelfParaId = ParachainId;
     type RelayChain = ();
     type MaxParathreads = ConstU32<100>;
+    type RelayChainBlockNumberProvider = MockRelayChainBlockNumberProvider;
+    type InFlightOrderTtl = ConstU32<5>;
+    type MaxExpiredOrdersPerBlock = ConstU32<1>;
+    type RuntimeCall = RuntimeCall;
+    // In tests, responses are sent by root
+    type XcmResponseOrigin = EnsureRootWithSuccess<AccountId, RelayLocation>;
+    type XcmNotifier = MockXcmNotifier;
+    type UniversalLocation = UniversalLocation;
+    type CoreBuyingXcmQueryTtl = ConstU64<100>;
+    type MaxConsecutiveFailedOrders = ConstU32<3>;
     type GetParathreadParams = GetParathreadParamsImpl;
+    type GetLatestNotedSlot = GetLatestNotedSlotImpl;
+    type SlotBeacon = MockSlotBeacon;
     type GetAssignedCollators = GetAssignedCollatorsImpl;
+    type CollatorPublicKey = NimbusId;
+    type CurrentSessionIndex = MockSessionIndex;
     type UnsignedPriority = ();
 
     type WeightInfo = ();
@@ -192,7 +229,7 @@ impl SendXcm for DevNull {
 
 pub struct GetParathreadParamsImpl;
 
-impl crate::GetParathreadParams for GetParathreadParamsImpl {
+impl tp_traits::GetParathreadParams for GetParathreadParamsImpl {
     fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParams> {
         MockData::mock().parathread_params.get(&para_id).cloned()
     }
@@ -209,10 +246,29 @@ impl crate::GetParathreadParams for GetParathreadParamsImpl {
     }
 }
 
+pub struct GetLatestNotedSlotImpl;
+
+impl GetLatestNotedSlot for GetLatestNotedSlotImpl {
+    fn get_latest_noted_slot(para_id: ParaId) -> Option<Slot> {
+        MockData::mock()
+            .latest_noted_slot
+            .get(&para_id)
+            .map(|slot| (*slot).into())
+    }
+}
+
+pub struct MockSlotBeacon;
+
+impl nimbus_primitives::SlotBeacon for MockSlotBeacon {
+    fn slot() -> u32 {
+        MockData::mock().current_slot
+    }
+}
+
 pub struct GetAssignedCollatorsImpl;
 
-impl GetParathreadCollators<AccountId> for GetAssignedCollatorsImpl {
-    fn get_parathread_collators(para_id: ParaId) -> Vec<AccountId> {
+impl GetParathreadCollators<NimbusId> for GetAssignedCollatorsImpl {
+    fn get_parathread_collators(para_id: ParaId) -> Vec<NimbusId> {
         MockData::mock()
             .container_chain_collators
             .get(&para_id)
@@ -221,21 +277,73 @@ impl GetParathreadCollators<AccountId> for GetAssignedCollatorsImpl {
     }
 
     #[cfg(feature = "runtime-benchmarks")]
-    fn set_parathread_collators(para_id: ParaId, collators: Vec<AccountId>) {
+    fn set_parathread_collators(para_id: ParaId, collators: Vec<NimbusId>) {
         MockData::mutate(|m| {
             m.container_chain_collators.insert(para_id, collators);
         })
     }
 }
 
-pub struct GetBlockNumber;
+pub struct MockSessionIndex;
+
+impl GetSessionIndex<u32> for MockSessionIndex {
+    fn session_index() -> u32 {
+        MockData::mock().session_index
+    }
+}
+
+pub struct MockRelayChainBlockNumberProvider;
+
+impl BlockNumberProvider for MockRelayChainBlockNumberProvider {
+    type BlockNumber = u32;
+
+    fn current_block_number() -> Self::BlockNumber {
+        MockData::mock().relay_block_number
+    }
+}
+
+pub struct MockXcmNotifier;
+
+impl XcmNotifier<Test> for MockXcmNotifier {
+    fn new_notify_query(
+        _responder: MultiLocation,
+        _notify: RuntimeCall,
+        _timeout: BlockNumberFor<Test>,
+        _match_querier: MultiLocation,
+    ) -> QueryId {
+        MockData::mutate(|m| {
+            let query_id = m.next_query_id;
+            m.next_query_id += 1;
 
-impl Get<u32> for GetBlockNumber {
-    fn get() -> u32 {
-        System::block_number() as u32
+            query_id
+        })
     }
 }
 
+pub fn set_relay_block_number(relay_block_number: u32) {
+    MockData::mutate(|m| {
+        m.relay_block_number = relay_block_number;
+    });
+}
+
+pub fn set_session_index(session_index: u32) {
+    MockData::mutate(|m| {
+        m.session_index = session_index;
+    });
+}
+
+pub fn set_current_slot(current_slot: u32) {
+    MockData::mutate(|m| {
+        m.current_slot = current_slot;
+    });
+}
+
+pub fn set_latest_noted_slot(para_id: ParaId, latest_noted_slot: u64) {
+    MockData::mutate(|m| {
+        m.latest_noted_slot.insert(para_id, latest_noted_slot);
+    });
+}
+
 pub struct ParaIdToAccount32;
 
 impl Convert<ParaId, [u8; 32]> for ParaIdToAccount32 {
@@ -251,8 +359,22 @@ impl Convert<ParaId, [u8; 32]> for ParaIdToAccount32 {
 pub struct EncodedCallToBuyCore;
 
 impl GetPurchaseCoreCall<()> for EncodedCallToBuyCore {
-    fn get_encoded(_relay_chain: (), _max_amount: u128, _para_id: ParaId) -> Vec<u8> {
-        vec![]
+    fn get_encoded(
+        _relay_chain: (),
+        core_purchase_backend: CorePurchaseBackend,
+        _max_amount: u128,
+        _para_id: ParaId,
+    ) -> Option<Vec<u8>> {
+        MockData::mutate(|m| {
+            if m.unsupported_core_purchase_backends
+                .contains(&core_purchase_backend)
+            {
+                return None;
+            }
+            m.last_core_purchase_backend = Some(core_purchase_backend);
+
+            Some(vec![])
+        })
     }
 }
 
@@ -318,6 +440,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
         .build_storage()
         .unwrap()
         .into();
+    // Needed to sign collator proofs in the `buy_core` benchmark
+    ext.register_extension(sp_keystore::KeystoreExt::new(
+        sp_keystore::testing::MemoryKeystore::new(),
+    ));
 
     ext.execute_with(|| {
         assert_ok!(XcmCoreBuyer::set_relay_xcm_weight_config(
@@ -348,5 +474,45 @@ pub fn run_to_block(n: u64) {
     }
 }
 
+/// Deterministic collator keypair, derived from the account id.
+pub fn get_collator_keypair(account: AccountId) -> sr25519::Pair {
+    sr25519::Pair::from_string(&format!("//Collator{}", account), None)
+        .expect("static values are valid; qed")
+}
+
+/// Build a `BuyCoreCollatorProof` for the current session, signed by the collator keypair of
+/// `account`.
+pub fn get_collator_proof(
+    account: AccountId,
+    nonce: u64,
+    para_id: ParaId,
+) -> BuyCoreCollatorProof<NimbusId> {
+    get_collator_proof_for_session(account, MockSessionIndex::session_index(), nonce, para_id)
+}
+
+/// Build a `BuyCoreCollatorProof` for `session_index`, signed by the collator keypair of
+/// `account`.
+pub fn get_collator_proof_for_session(
+    account: AccountId,
+    session_index: u32,
+    nonce: u64,
+    para_id: ParaId,
+) -> BuyCoreCollatorProof<NimbusId> {
+    let keypair = get_collator_keypair(account);
+    let payload = BuyCoreCollatorProof::<NimbusId>::prepare_payload(
+        XcmCoreBuyer::genesis_hash(),
+        session_index,
+        nonce,
+        para_id,
+    );
+
+    BuyCoreCollatorProof {
+        nonce,
+        session_index,
+        public_key: keypair.public().into(),
+        signature: keypair.sign(&payload).into(),
+    }
+}
+
 pub const ALICE: u64 = 1;
 pub const BOB: u64 = 2;
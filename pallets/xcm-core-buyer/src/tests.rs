@@ -17,7 +17,9 @@
 use {
     crate::{mock::*, *},
     frame_support::{assert_noop, assert_ok},
-    sp_runtime::traits::BadOrigin,
+    sp_core::Pair,
+    sp_runtime::traits::{BadOrigin, ValidateUnsigned},
+    staging_xcm::v3::{MaybeErrorCode, Response},
 };
 
 #[test]
@@ -31,7 +33,13 @@ fn root_origin_can_force_buy_xcm() {
 
             assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
 
-            assert_eq!(events(), vec![Event::BuyCoreXcmSent { para_id }]);
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 0
+                }]
+            );
         });
 }
 
@@ -62,7 +70,13 @@ fn force_buy_two_messages_in_one_block_fails() {
 
             assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
 
-            assert_eq!(events(), vec![Event::BuyCoreXcmSent { para_id }]);
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 0
+                }]
+            );
 
             assert_noop!(
                 XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
@@ -72,7 +86,7 @@ fn force_buy_two_messages_in_one_block_fails() {
 }
 
 #[test]
-fn force_buy_two_messages_in_two_consecutive_blocks_works() {
+fn force_buy_two_messages_in_two_consecutive_blocks_same_relay_block_fails() {
     ExtBuilder::default()
         .with_balances([(ALICE, 1_000)].into())
         .build()
@@ -82,13 +96,224 @@ fn force_buy_two_messages_in_two_consecutive_blocks_works() {
 
             assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
 
-            assert_eq!(events(), vec![Event::BuyCoreXcmSent { para_id }]);
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 0
+                }]
+            );
 
+            // With async backing, two tanssi blocks can have the same relay parent
             run_to_block(2);
 
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::OrderAlreadyExists
+            );
+        });
+}
+
+#[test]
+fn in_flight_order_expires_after_ttl() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+
+            // The order is still in flight until the ttl has passed
+            set_relay_block_number(14);
+            run_to_block(2);
+            assert!(InFlightOrders::<Test>::get().contains_key(&para_id));
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::OrderAlreadyExists
+            );
+
+            set_relay_block_number(15);
+            run_to_block(3);
+            assert!(!InFlightOrders::<Test>::get().contains_key(&para_id));
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 1
+                }]
+            );
+        });
+}
+
+#[test]
+fn in_flight_order_expired_event() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+
+            set_relay_block_number(15);
+            XcmCoreBuyer::on_finalize(1);
+
+            assert_eq!(
+                events(),
+                vec![
+                    Event::BuyCoreXcmSent {
+                        para_id,
+                        transaction_status_query_id: 0
+                    },
+                    Event::BuyCoreOrderExpired {
+                        para_id,
+                        relay_block_number: 10
+                    }
+                ]
+            );
+            assert!(InFlightOrders::<Test>::get().is_empty());
+        });
+}
+
+#[test]
+fn expired_orders_are_removed_in_bounded_batches() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let other_para_id = 3334.into();
+            MockData::mutate(|m| {
+                let parathread_params = m.parathread_params[&para_id].clone();
+                let collators = m.container_chain_collators[&para_id].clone();
+                m.parathread_params.insert(other_para_id, parathread_params);
+                m.container_chain_collators.insert(other_para_id, collators);
+            });
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+            assert_ok!(XcmCoreBuyer::force_buy_core(
+                RuntimeOrigin::root(),
+                other_para_id
+            ));
+
+            // Both orders expire at the same time, but only 1 is removed per block
+            set_relay_block_number(15);
+            run_to_block(2);
+            assert_eq!(InFlightOrders::<Test>::get().len(), 1);
+
+            run_to_block(3);
+            assert!(InFlightOrders::<Test>::get().is_empty());
+        });
+}
+
+#[test]
+fn in_flight_order_is_removed_when_block_is_noted() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+
+            <XcmCoreBuyer as AuthorNotingHook<AccountId>>::on_container_author_noted(
+                &BOB, None, 1, para_id, true,
+            );
+            assert!(InFlightOrders::<Test>::get().is_empty());
+
+            // No expired event, because the order was used
+            set_relay_block_number(15);
+            XcmCoreBuyer::on_finalize(1);
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 0
+                }]
+            );
+        });
+}
+
+#[test]
+fn cannot_buy_two_cores_in_same_relay_block_even_if_block_is_noted() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            <XcmCoreBuyer as AuthorNotingHook<AccountId>>::on_container_author_noted(
+                &BOB, None, 1, para_id, true,
+            );
+
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::OrderCooldownNotElapsed
+            );
+
+            set_relay_block_number(11);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+        });
+}
+
+#[test]
+fn order_cooldown_is_respected() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            assert_ok!(XcmCoreBuyer::set_order_cooldown(
+                RuntimeOrigin::root(),
+                para_id,
+                Some(20)
+            ));
+            set_relay_block_number(10);
+
             assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            <XcmCoreBuyer as AuthorNotingHook<AccountId>>::on_container_author_noted(
+                &BOB, None, 1, para_id, true,
+            );
 
-            assert_eq!(events(), vec![Event::BuyCoreXcmSent { para_id }]);
+            set_relay_block_number(29);
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::OrderCooldownNotElapsed
+            );
+
+            set_relay_block_number(30);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+        });
+}
+
+#[test]
+fn signed_origin_cannot_set_order_cooldown() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_noop!(
+                XcmCoreBuyer::set_order_cooldown(RuntimeOrigin::signed(ALICE), para_id, Some(20)),
+                BadOrigin
+            );
         });
 }
 
@@ -103,7 +328,10 @@ fn cannot_force_buy_invalid_para_id() {
 
             MockData::mutate(|m| {
                 // Mock para_id 2000 as a container chain with collators, but not a parathread
-                m.container_chain_collators.insert(2000.into(), vec![ALICE]);
+                m.container_chain_collators.insert(
+                    2000.into(),
+                    vec![get_collator_keypair(ALICE).public().into()],
+                );
             });
 
             assert_noop!(
@@ -178,3 +406,557 @@ fn xcm_locations() {
             assert_eq!(format!("{:?}", absolute_mloc), "MultiLocation { parents: 0, interior: X2(Parachain(1000), AccountId32 { network: None, id: [112, 97, 114, 97, 5, 13, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0] }) }");
         });
 }
+
+#[test]
+fn collator_can_buy_core_with_valid_proof() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof(BOB, 0, para_id);
+
+            assert_ok!(XcmCoreBuyer::validate_unsigned(
+                TransactionSource::External,
+                &Call::buy_core {
+                    para_id,
+                    proof: proof.clone()
+                }
+            ));
+            assert_ok!(XcmCoreBuyer::buy_core(
+                RuntimeOrigin::none(),
+                para_id,
+                proof
+            ));
+
+            assert_eq!(
+                events(),
+                vec![Event::BuyCoreXcmSent {
+                    para_id,
+                    transaction_status_query_id: 0
+                }]
+            );
+            assert_eq!(CollatorSignatureNonce::<Test>::get(para_id), 1);
+        });
+}
+
+#[test]
+fn signed_origin_cannot_buy_core() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof(BOB, 0, para_id);
+
+            assert_noop!(
+                XcmCoreBuyer::buy_core(RuntimeOrigin::signed(BOB), para_id, proof),
+                BadOrigin
+            );
+        });
+}
+
+#[test]
+fn cannot_buy_core_twice_with_same_proof() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof(BOB, 0, para_id);
+
+            assert_ok!(XcmCoreBuyer::buy_core(
+                RuntimeOrigin::none(),
+                para_id,
+                proof.clone()
+            ));
+
+            run_to_block(2);
+            set_relay_block_number(10);
+
+            let call = Call::buy_core {
+                para_id,
+                proof: proof.clone(),
+            };
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(TransactionSource::External, &call),
+                InvalidTransaction::Stale.into()
+            );
+            assert_noop!(
+                XcmCoreBuyer::buy_core(RuntimeOrigin::none(), para_id, proof),
+                Error::<Test>::IncorrectCollatorSignatureNonce
+            );
+
+            // A new proof with the next nonce works
+            let proof = get_collator_proof(BOB, 1, para_id);
+            assert_ok!(XcmCoreBuyer::buy_core(
+                RuntimeOrigin::none(),
+                para_id,
+                proof
+            ));
+            assert_eq!(CollatorSignatureNonce::<Test>::get(para_id), 2);
+        });
+}
+
+#[test]
+fn proof_with_future_nonce_is_invalid() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof(BOB, 1, para_id);
+
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core { para_id, proof }
+                ),
+                InvalidTransaction::Future.into()
+            );
+        });
+}
+
+#[test]
+fn collator_not_assigned_cannot_buy_core() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof(ALICE, 0, para_id);
+
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core {
+                        para_id,
+                        proof: proof.clone()
+                    }
+                ),
+                InvalidTransaction::BadSigner.into()
+            );
+            assert_noop!(
+                XcmCoreBuyer::buy_core(RuntimeOrigin::none(), para_id, proof),
+                Error::<Test>::CollatorNotAssigned
+            );
+        });
+}
+
+#[test]
+fn proof_signed_for_other_para_id_is_invalid() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            // BOB is assigned to 3333, but the signature is for a different para id
+            let proof = get_collator_proof(BOB, 0, 3334.into());
+
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core { para_id, proof }
+                ),
+                InvalidTransaction::BadProof.into()
+            );
+        });
+}
+
+#[test]
+fn proof_signed_for_other_session_is_invalid() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            let proof = get_collator_proof_for_session(BOB, 0, 0, para_id);
+            set_session_index(1);
+
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core {
+                        para_id,
+                        proof: proof.clone()
+                    }
+                ),
+                InvalidTransaction::Stale.into()
+            );
+            assert_noop!(
+                XcmCoreBuyer::buy_core(RuntimeOrigin::none(), para_id, proof),
+                Error::<Test>::IncorrectCollatorSignatureSession
+            );
+
+            let proof = get_collator_proof_for_session(BOB, 2, 0, para_id);
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core { para_id, proof }
+                ),
+                InvalidTransaction::Future.into()
+            );
+        });
+}
+
+#[test]
+fn proof_is_invalid_if_buying_core_would_fail() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+
+            // There is already an order in flight, so buying a core would fail and revert the
+            // nonce increase. The proof must be rejected, otherwise it could be included again
+            // and again for free.
+            let proof = get_collator_proof(BOB, 0, para_id);
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core {
+                        para_id,
+                        proof: proof.clone()
+                    }
+                ),
+                InvalidTransaction::Call.into()
+            );
+            assert_noop!(
+                XcmCoreBuyer::buy_core(RuntimeOrigin::none(), para_id, proof),
+                Error::<Test>::OrderAlreadyExists
+            );
+            assert_eq!(CollatorSignatureNonce::<Test>::get(para_id), 0);
+        });
+}
+
+#[test]
+fn successful_order_response() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            assert_eq!(QueryIdToParaId::<Test>::get(0), Some(para_id));
+
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                0,
+                Response::DispatchResult(MaybeErrorCode::Success)
+            ));
+
+            assert_eq!(
+                events(),
+                vec![
+                    Event::BuyCoreXcmSent {
+                        para_id,
+                        transaction_status_query_id: 0
+                    },
+                    Event::BuyCoreSucceeded {
+                        para_id,
+                        query_id: 0
+                    }
+                ]
+            );
+            assert_eq!(QueryIdToParaId::<Test>::get(0), None);
+            // The order stays in flight until the parathread block is noted
+            assert!(InFlightOrders::<Test>::get().contains_key(&para_id));
+        });
+}
+
+#[test]
+fn failed_order_response_clears_in_flight_order() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+            set_relay_block_number(10);
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+
+            let error = MaybeErrorCode::Error(vec![1, 2, 3].try_into().unwrap());
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                0,
+                Response::DispatchResult(error.clone())
+            ));
+
+            assert_eq!(
+                events(),
+                vec![
+                    Event::BuyCoreXcmSent {
+                        para_id,
+                        transaction_status_query_id: 0
+                    },
+                    Event::BuyCoreFailed {
+                        para_id,
+                        query_id: 0,
+                        error
+                    }
+                ]
+            );
+            assert!(InFlightOrders::<Test>::get().is_empty());
+            assert_eq!(ConsecutiveFailedOrders::<Test>::get(para_id), 1);
+
+            // Collators can try again in the next relay block
+            set_relay_block_number(11);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+        });
+}
+
+#[test]
+fn response_to_unknown_query_is_ignored() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                0,
+                Response::DispatchResult(MaybeErrorCode::Success)
+            ));
+            assert_eq!(events(), vec![]);
+        });
+}
+
+#[test]
+fn unexpected_response_fails() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+
+            assert_noop!(
+                XcmCoreBuyer::query_response(RuntimeOrigin::root(), 0, Response::Null),
+                Error::<Test>::UnexpectedXcmResponse
+            );
+        });
+}
+
+#[test]
+fn signed_origin_cannot_send_query_response() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+
+            assert_noop!(
+                XcmCoreBuyer::query_response(
+                    RuntimeOrigin::signed(ALICE),
+                    0,
+                    Response::DispatchResult(MaybeErrorCode::Success)
+                ),
+                BadOrigin
+            );
+        });
+}
+
+#[test]
+fn core_buying_is_paused_after_consecutive_failures() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            // MaxConsecutiveFailedOrders is 3 in the mock
+            for query_id in 0..3 {
+                set_relay_block_number(query_id as u32);
+                assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+                assert_ok!(XcmCoreBuyer::query_response(
+                    RuntimeOrigin::root(),
+                    query_id,
+                    Response::DispatchResult(MaybeErrorCode::Error(Default::default()))
+                ));
+            }
+            assert!(events().contains(&Event::CoreBuyingPaused { para_id }));
+
+            set_relay_block_number(3);
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::CoreBuyingPaused
+            );
+
+            assert_ok!(XcmCoreBuyer::resume_core_buying(
+                RuntimeOrigin::root(),
+                para_id
+            ));
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+        });
+}
+
+#[test]
+fn core_buying_paused_event_is_emitted_once() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            // Core buying was paused while the order was in flight
+            ConsecutiveFailedOrders::<Test>::insert(para_id, 3);
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                0,
+                Response::DispatchResult(MaybeErrorCode::Error(Default::default()))
+            ));
+
+            assert_eq!(ConsecutiveFailedOrders::<Test>::get(para_id), 4);
+            assert!(!events().contains(&Event::CoreBuyingPaused { para_id }));
+        });
+}
+
+#[test]
+fn successful_response_resets_consecutive_failures() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                0,
+                Response::DispatchResult(MaybeErrorCode::Error(Default::default()))
+            ));
+            assert_eq!(ConsecutiveFailedOrders::<Test>::get(para_id), 1);
+
+            set_relay_block_number(1);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id,));
+            assert_ok!(XcmCoreBuyer::query_response(
+                RuntimeOrigin::root(),
+                1,
+                Response::DispatchResult(MaybeErrorCode::Success)
+            ));
+            assert_eq!(ConsecutiveFailedOrders::<Test>::get(para_id), 0);
+        });
+}
+
+#[test]
+fn cannot_buy_core_before_min_slot_frequency() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            // Para 3333 has a slot frequency of min: 10
+            set_latest_noted_slot(para_id, 20);
+            set_current_slot(29);
+
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::SlotFrequencyMinNotReached
+            );
+
+            set_current_slot(30);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+        });
+}
+
+#[test]
+fn can_buy_core_if_no_block_was_noted() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            set_current_slot(1);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+        });
+}
+
+#[test]
+fn core_is_bought_using_the_backend_of_the_parathread() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+            assert_eq!(
+                MockData::mock().last_core_purchase_backend,
+                Some(CorePurchaseBackend::OnDemand)
+            );
+
+            MockData::mutate(|m| {
+                m.parathread_params
+                    .get_mut(&para_id)
+                    .unwrap()
+                    .core_purchase_backend = CorePurchaseBackend::CoretimeCredits;
+            });
+            <XcmCoreBuyer as AuthorNotingHook<AccountId>>::on_container_author_noted(
+                &BOB, None, 1, para_id, true,
+            );
+            set_relay_block_number(1);
+            assert_ok!(XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id));
+            assert_eq!(
+                MockData::mock().last_core_purchase_backend,
+                Some(CorePurchaseBackend::CoretimeCredits)
+            );
+        });
+}
+
+#[test]
+fn cannot_buy_core_if_backend_is_not_supported_by_relay_chain() {
+    ExtBuilder::default()
+        .with_balances([(ALICE, 1_000)].into())
+        .build()
+        .execute_with(|| {
+            run_to_block(1);
+            let para_id = 3333.into();
+
+            MockData::mutate(|m| {
+                m.parathread_params
+                    .get_mut(&para_id)
+                    .unwrap()
+                    .core_purchase_backend = CorePurchaseBackend::CoretimeCredits;
+                m.unsupported_core_purchase_backends = vec![CorePurchaseBackend::CoretimeCredits];
+            });
+
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(RuntimeOrigin::root(), para_id),
+                Error::<Test>::CorePurchaseBackendNotSupported
+            );
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &Call::buy_core {
+                        para_id,
+                        proof: get_collator_proof(BOB, 0, para_id)
+                    }
+                ),
+                InvalidTransaction::Call.into()
+            );
+        });
+}
@@ -25,7 +25,7 @@ pub use cumulus_primitives_core::{
 };
 use {
     frame_support::{
-        pallet_prelude::{Decode, DispatchResultWithPostInfo, Encode, Get, Weight},
+        pallet_prelude::{Decode, DispatchResultWithPostInfo, Encode, Get, MaxEncodedLen, Weight},
         BoundedVec,
     },
     sp_std::{collections::btree_set::BTreeSet, vec::Vec},
@@ -68,27 +68,99 @@ impl<Balance> CollatorAssignmentTip<Balance> for () {
         None
     }
 }
+/// Number of collators requested by a container chain, which overrides the default number of
+/// collators of the host configuration.
+#[derive(
+    Clone, Copy, Debug, Encode, Decode, scale_info::TypeInfo, MaxEncodedLen, PartialEq, Eq,
+)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollatorRequirements {
+    /// Chains will not be assigned less than `min_collators`
+    pub min_collators: u32,
+    /// Chains with spare collators can be assigned up to `max_collators`
+    pub max_collators: u32,
+}
+
+impl CollatorRequirements {
+    /// Requirements must ask for at least 1 collator, the max cannot be lower than the min, and
+    /// no more than `max_allowed` collators can be requested.
+    pub fn is_valid(&self, max_allowed: u32) -> bool {
+        self.min_collators >= 1
+            && self.min_collators <= self.max_collators
+            && self.max_collators <= max_allowed
+    }
+}
+
+/// Get the number of collators requested by a container chain. Returns `None` if the chain uses
+/// the default number of collators.
+pub trait GetCollatorRequirements {
+    fn collator_requirements(para_id: ParaId) -> Option<CollatorRequirements>;
+}
+
+impl GetCollatorRequirements for () {
+    fn collator_requirements(_: ParaId) -> Option<CollatorRequirements> {
+        None
+    }
+}
+
+/// Get the total stake backing a collator. Used to weight the selection of collators.
+pub trait GetCollatorStake<AccountId, Balance> {
+    fn collator_stake(collator: &AccountId) -> Balance;
+}
+
+impl<AccountId, Balance: Default> GetCollatorStake<AccountId, Balance> for () {
+    fn collator_stake(_: &AccountId) -> Balance {
+        Balance::default()
+    }
+}
+
 /// The author-noting hook to react to container chains authoring.
 pub trait AuthorNotingHook<AccountId> {
     /// This hook is called partway through the `set_latest_author_data` inherent in author-noting.
     ///
     /// The hook should never panic and is required to return the weight consumed.
+    ///
+    /// `previous_block_number` is the block number noted previously for `para_id`, or `None` if
+    /// this is the first block noted. All the blocks after it, up to `block_number`, have been
+    /// produced since the previous noting.
+    ///
+    /// `respects_slot_frequency` is false if `para_id` is a parathread and the block was noted
+    /// outside of the bounds of its `SlotFrequency`. Such blocks should not be rewarded.
     fn on_container_author_noted(
         author: &AccountId,
+        previous_block_number: Option<BlockNumber>,
         block_number: BlockNumber,
         para_id: ParaId,
+        respects_slot_frequency: bool,
     ) -> Weight;
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(5)]
 impl<AccountId> AuthorNotingHook<AccountId> for Tuple {
-    fn on_container_author_noted(a: &AccountId, b: BlockNumber, p: ParaId) -> Weight {
+    fn on_container_author_noted(
+        a: &AccountId,
+        pb: Option<BlockNumber>,
+        b: BlockNumber,
+        p: ParaId,
+        r: bool,
+    ) -> Weight {
         let mut weight: Weight = Default::default();
-        for_tuples!( #( weight.saturating_accrue(Tuple::on_container_author_noted(a, b, p)); )* );
+        for_tuples!( #( weight.saturating_accrue(Tuple::on_container_author_noted(a, pb, b, p, r)); )* );
         weight
     }
 }
 
+/// Number of container chain blocks produced between two noted blocks, or 1 if no block had been
+/// noted before.
+pub fn blocks_produced_since(
+    previous_block_number: Option<BlockNumber>,
+    block_number: BlockNumber,
+) -> u32 {
+    previous_block_number
+        .map(|previous_block_number| block_number.saturating_sub(previous_block_number))
+        .unwrap_or(1)
+}
+
 pub trait DistributeRewards<AccountId, Imbalance> {
     fn distribute_rewards(rewarded: AccountId, amount: Imbalance) -> DispatchResultWithPostInfo;
 }
@@ -111,7 +183,6 @@ pub trait GetCurrentContainerChains {
 
 /// How often should a parathread collator propose blocks. The units are "1 out of n slots", where the slot time is the
 /// tanssi slot time, 12 seconds by default.
-// TODO: this is currently ignored
 #[derive(Clone, Debug, Encode, Decode, scale_info::TypeInfo, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct SlotFrequency {
@@ -131,10 +202,48 @@ impl Default for SlotFrequency {
     }
 }
 
+impl SlotFrequency {
+    /// Returns true if a block produced `slots_since_last_block` slots after the previous block of
+    /// the same parathread is within the `min` and `max` bounds.
+    pub fn is_within_bounds(&self, slots_since_last_block: u64) -> bool {
+        slots_since_last_block >= u64::from(self.min)
+            && slots_since_last_block <= u64::from(self.max)
+    }
+}
+
+/// How the cores used by a parathread to produce blocks are purchased in the relay chain.
+#[derive(Clone, Debug, Default, Encode, Decode, scale_info::TypeInfo, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum CorePurchaseBackend {
+    /// Place an on-demand order, paid by the parathread tank at the price defined by the relay.
+    #[default]
+    OnDemand,
+    /// Spend instantaneous coretime credits, previously bought in the coretime chain.
+    CoretimeCredits,
+}
+
 #[derive(Clone, Debug, Encode, Decode, scale_info::TypeInfo, PartialEq, Eq)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParathreadParams {
     pub slot_frequency: SlotFrequency,
+    pub core_purchase_backend: CorePurchaseBackend,
+}
+
+/// Get the params of a parathread. Returns `None` if the para id is not a parathread.
+pub trait GetParathreadParams {
+    fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParams>;
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_parathread_params(para_id: ParaId, parathread_params: Option<ParathreadParams>);
+}
+
+impl GetParathreadParams for () {
+    fn get_parathread_params(_: ParaId) -> Option<ParathreadParams> {
+        None
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn set_parathread_params(_: ParaId, _: Option<ParathreadParams>) {}
 }
 
 #[derive(Clone, Debug, Encode, Decode, scale_info::TypeInfo, PartialEq, Eq)]
@@ -166,6 +275,8 @@ pub trait GetHostConfiguration<SessionIndex> {
     fn max_collators_for_orchestrator(session_index: SessionIndex) -> u32;
     fn collators_per_container(session_index: SessionIndex) -> u32;
     fn collators_per_parathread(session_index: SessionIndex) -> u32;
+    fn parathreads_per_collator(session_index: SessionIndex) -> u32;
+    fn stake_weighted_collator_selection(session_index: SessionIndex) -> bool;
     #[cfg(feature = "runtime-benchmarks")]
     fn set_host_configuration(_session_index: SessionIndex) {}
 }
@@ -194,11 +305,16 @@ pub trait RemoveInvulnerables<AccountId> {
 pub trait RemoveParaIdsWithNoCredits {
     /// Remove para ids with not enough credits. The resulting order will affect priority: the first para id in the list
     /// will be the first one to get collators.
+    /// This must not modify storage, so that it can be used to preview an assignment.
     fn remove_para_ids_with_no_credits(
         para_ids: &mut Vec<ParaId>,
         currently_assigned: &BTreeSet<ParaId>,
     );
 
+    /// Called once per session change, before `remove_para_ids_with_no_credits`, to update the
+    /// state of para ids that cannot pay for the next session, such as their grace period.
+    fn on_session_change(_para_ids: &[ParaId], _currently_assigned: &BTreeSet<ParaId>) {}
+
     /// Make those para ids valid by giving them enough credits, for benchmarking.
     #[cfg(feature = "runtime-benchmarks")]
     fn make_valid_para_ids(para_ids: &[ParaId]);
@@ -17,3 +17,4 @@
 #[cfg(feature = "runtime-benchmarks")]
 pub mod benchmarking;
 pub mod migrations;
+pub mod rewards;
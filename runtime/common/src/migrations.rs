@@ -46,7 +46,9 @@ use {
     pallet_configuration::{weights::WeightInfo as _, HostConfiguration},
     pallet_migrations::{GetMigrations, Migration},
     sp_core::Get,
+    sp_runtime::{BoundedVec, Perbill},
     sp_std::{collections::btree_set::BTreeSet, marker::PhantomData, prelude::*},
+    tp_traits::{CorePurchaseBackend, ParaId, ParathreadParams, SlotFrequency},
 };
 
 #[derive(
@@ -65,6 +67,25 @@ struct HostConfigurationV1 {
     pub full_rotation_period: u32,
 }
 
+#[derive(
+    Clone,
+    parity_scale_codec::Encode,
+    parity_scale_codec::Decode,
+    PartialEq,
+    sp_core::RuntimeDebug,
+    scale_info::TypeInfo,
+)]
+struct HostConfigurationV2 {
+    pub max_collators: u32,
+    pub min_orchestrator_collators: u32,
+    pub max_orchestrator_collators: u32,
+    pub collators_per_container: u32,
+    pub full_rotation_period: u32,
+    pub collators_per_parathread: u32,
+    pub parathreads_per_collator: u32,
+    pub target_container_chain_fullness: Perbill,
+}
+
 pub struct MigrateConfigurationParathreads<T>(pub PhantomData<T>);
 impl<T> Migration for MigrateConfigurationParathreads<T>
 where
@@ -85,7 +106,7 @@ where
         let old_config: HostConfigurationV1 =
             frame_support::storage::unhashed::get(CONFIGURATION_ACTIVE_CONFIG_KEY)
                 .expect("configuration.activeConfig should have value");
-        let new_config = HostConfiguration {
+        let new_config = HostConfigurationV2 {
             max_collators: old_config.max_collators,
             min_orchestrator_collators: old_config.min_orchestrator_collators,
             max_orchestrator_collators: old_config.max_orchestrator_collators,
@@ -101,10 +122,10 @@ where
         let old_pending_configs: Vec<(u32, HostConfigurationV1)> =
             frame_support::storage::unhashed::get(CONFIGURATION_PENDING_CONFIGS_KEY)
                 .unwrap_or_default();
-        let mut new_pending_configs: Vec<(u32, HostConfiguration)> = vec![];
+        let mut new_pending_configs: Vec<(u32, HostConfigurationV2)> = vec![];
 
         for (session_index, old_config) in old_pending_configs {
-            let new_config = HostConfiguration {
+            let new_config = HostConfigurationV2 {
                 max_collators: old_config.max_collators,
                 min_orchestrator_collators: old_config.min_orchestrator_collators,
                 max_orchestrator_collators: old_config.max_orchestrator_collators,
@@ -167,6 +188,93 @@ where
     }
 }
 
+pub struct MigrateConfigurationStakeWeighted<T>(pub PhantomData<T>);
+impl<T> Migration for MigrateConfigurationStakeWeighted<T>
+where
+    T: pallet_configuration::Config,
+{
+    fn friendly_name(&self) -> &str {
+        "TM_MigrateConfigurationStakeWeighted"
+    }
+
+    fn migrate(&self, _available_weight: Weight) -> Weight {
+        const CONFIGURATION_ACTIVE_CONFIG_KEY: &[u8] =
+            &hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22db4b49d95320d9021994c850f25b8e385");
+        const CONFIGURATION_PENDING_CONFIGS_KEY: &[u8] =
+            &hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22d53b4123b2e186e07fb7bad5dda5f55c0");
+
+        let migrate_config = |old_config: HostConfigurationV2| HostConfiguration {
+            max_collators: old_config.max_collators,
+            min_orchestrator_collators: old_config.min_orchestrator_collators,
+            max_orchestrator_collators: old_config.max_orchestrator_collators,
+            collators_per_container: old_config.collators_per_container,
+            full_rotation_period: old_config.full_rotation_period,
+            collators_per_parathread: old_config.collators_per_parathread,
+            parathreads_per_collator: old_config.parathreads_per_collator,
+            target_container_chain_fullness: old_config.target_container_chain_fullness,
+            stake_weighted_collator_selection: false,
+        };
+
+        // Modify active config
+        let old_config: HostConfigurationV2 =
+            frame_support::storage::unhashed::get(CONFIGURATION_ACTIVE_CONFIG_KEY)
+                .expect("configuration.activeConfig should have value");
+        frame_support::storage::unhashed::put(
+            CONFIGURATION_ACTIVE_CONFIG_KEY,
+            &migrate_config(old_config),
+        );
+
+        // Modify pending configs, if any
+        let old_pending_configs: Vec<(u32, HostConfigurationV2)> =
+            frame_support::storage::unhashed::get(CONFIGURATION_PENDING_CONFIGS_KEY)
+                .unwrap_or_default();
+        let new_pending_configs: Vec<(u32, HostConfiguration)> = old_pending_configs
+            .into_iter()
+            .map(|(session_index, old_config)| (session_index, migrate_config(old_config)))
+            .collect();
+
+        if !new_pending_configs.is_empty() {
+            frame_support::storage::unhashed::put(
+                CONFIGURATION_PENDING_CONFIGS_KEY,
+                &new_pending_configs,
+            );
+        }
+
+        <T as pallet_configuration::Config>::WeightInfo::set_config_with_u32()
+    }
+
+    /// Run a standard pre-runtime test. This works the same way as in a normal runtime upgrade.
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade(&self) -> Result<Vec<u8>, sp_runtime::DispatchError> {
+        const CONFIGURATION_ACTIVE_CONFIG_KEY: &[u8] =
+            &hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22db4b49d95320d9021994c850f25b8e385");
+
+        let old_config_bytes =
+            frame_support::storage::unhashed::get_raw(CONFIGURATION_ACTIVE_CONFIG_KEY)
+                .expect("configuration.activeConfig should have value");
+        assert_eq!(old_config_bytes.len(), 32);
+
+        Ok(old_config_bytes)
+    }
+
+    /// Run a standard post-runtime test. This works the same way as in a normal runtime upgrade.
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(&self, old_config_bytes: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+        use parity_scale_codec::Decode;
+        let old_config = HostConfigurationV2::decode(&mut old_config_bytes.as_slice())
+            .expect("old config should decode");
+        let new_config = pallet_configuration::Pallet::<T>::config();
+        assert_eq!(new_config.max_collators, old_config.max_collators);
+        assert_eq!(
+            new_config.target_container_chain_fullness,
+            old_config.target_container_chain_fullness
+        );
+        assert!(!new_config.stake_weighted_collator_selection);
+
+        Ok(())
+    }
+}
+
 pub struct MigrateServicesPaymentAddCollatorAssignmentCredits<T>(pub PhantomData<T>);
 impl<T> Migration for MigrateServicesPaymentAddCollatorAssignmentCredits<T>
 where
@@ -245,6 +353,108 @@ where
     }
 }
 
+#[derive(
+    Clone,
+    parity_scale_codec::Encode,
+    parity_scale_codec::Decode,
+    PartialEq,
+    sp_core::RuntimeDebug,
+    scale_info::TypeInfo,
+)]
+struct ParathreadParamsV1 {
+    pub slot_frequency: SlotFrequency,
+}
+
+impl From<ParathreadParamsV1> for ParathreadParams {
+    fn from(old: ParathreadParamsV1) -> Self {
+        ParathreadParams {
+            slot_frequency: old.slot_frequency,
+            core_purchase_backend: CorePurchaseBackend::OnDemand,
+        }
+    }
+}
+
+pub struct MigrateRegistrarParathreadParamsCorePurchaseBackend<T>(pub PhantomData<T>);
+impl<T> Migration for MigrateRegistrarParathreadParamsCorePurchaseBackend<T>
+where
+    T: pallet_registrar::Config,
+{
+    fn friendly_name(&self) -> &str {
+        "TM_MigrateRegistrarParathreadParamsCorePurchaseBackend"
+    }
+
+    fn migrate(&self, _available_weight: Weight) -> Weight {
+        // Existing parathreads keep buying on-demand cores
+        let mut reads_writes = 0;
+        pallet_registrar::ParathreadParams::<T>::translate::<ParathreadParamsV1, _>(
+            |_para_id, old_params| {
+                reads_writes += 1;
+                Some(old_params.into())
+            },
+        );
+
+        let _ = pallet_registrar::PendingParathreadParams::<T>::translate::<
+            Vec<(
+                T::SessionIndex,
+                BoundedVec<(ParaId, ParathreadParamsV1), T::MaxLengthParaIds>,
+            )>,
+            _,
+        >(|old_pending_params| {
+            let old_pending_params = old_pending_params?;
+            let new_pending_params = old_pending_params
+                .into_iter()
+                .map(|(session_index, para_id_params)| {
+                    let para_id_params: Vec<_> = para_id_params
+                        .into_iter()
+                        .map(|(para_id, params)| (para_id, params.into()))
+                        .collect();
+
+                    // Same length as the old bounded vec, so this cannot fail
+                    (session_index, BoundedVec::truncate_from(para_id_params))
+                })
+                .collect();
+
+            Some(new_pending_params)
+        });
+        reads_writes += 1;
+
+        T::DbWeight::get().reads_writes(reads_writes, reads_writes)
+    }
+
+    /// Run a standard pre-runtime test. This works the same way as in a normal runtime upgrade.
+    #[cfg(feature = "try-runtime")]
+    fn pre_upgrade(&self) -> Result<Vec<u8>, sp_runtime::DispatchError> {
+        use parity_scale_codec::Encode;
+
+        let num_parathreads = pallet_registrar::ParathreadParams::<T>::iter_keys().count() as u32;
+
+        Ok(num_parathreads.encode())
+    }
+
+    /// Run a standard post-runtime test. This works the same way as in a normal runtime upgrade.
+    #[cfg(feature = "try-runtime")]
+    fn post_upgrade(&self, state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+        use parity_scale_codec::Decode;
+
+        let num_parathreads = u32::decode(&mut state.as_slice())
+            .map_err(|_| sp_runtime::DispatchError::Other("Failed to decode state"))?;
+        let mut migrated = 0;
+        for (_para_id, params) in pallet_registrar::ParathreadParams::<T>::iter() {
+            ensure!(
+                params.core_purchase_backend == CorePurchaseBackend::OnDemand,
+                "existing parathreads should use on-demand cores"
+            );
+            migrated += 1;
+        }
+        ensure!(
+            migrated == num_parathreads,
+            "all parathread params should be migrated"
+        );
+
+        Ok(())
+    }
+}
+
 pub struct PolkadotXcmMigrationFixVersion<T, PolkadotXcm>(pub PhantomData<(T, PolkadotXcm)>);
 impl<T, PolkadotXcm> Migration for PolkadotXcmMigrationFixVersion<T, PolkadotXcm>
 where
@@ -396,6 +606,10 @@ where
 
         let migrate_add_collator_assignment_credits =
             MigrateServicesPaymentAddCollatorAssignmentCredits::<Runtime>(Default::default());
+        let migrate_parathread_params_core_purchase_backend =
+            MigrateRegistrarParathreadParamsCorePurchaseBackend::<Runtime>(Default::default());
+        let migrate_config_stake_weighted =
+            MigrateConfigurationStakeWeighted::<Runtime>(Default::default());
 
         vec![
             // Applied in runtime 400
@@ -405,6 +619,8 @@ where
             // Applied in runtime 400
             Box::new(migrate_config_parathread_params),
             Box::new(migrate_add_collator_assignment_credits),
+            Box::new(migrate_parathread_params_core_purchase_backend),
+            Box::new(migrate_config_stake_weighted),
         ]
     }
 }
@@ -439,6 +655,10 @@ where
         let migrate_add_collator_assignment_credits =
             MigrateServicesPaymentAddCollatorAssignmentCredits::<Runtime>(Default::default());
         let migrate_xcmp_queue_v4 = XcmpQueueMigrationV4::<Runtime>(Default::default());
+        let migrate_parathread_params_core_purchase_backend =
+            MigrateRegistrarParathreadParamsCorePurchaseBackend::<Runtime>(Default::default());
+        let migrate_config_stake_weighted =
+            MigrateConfigurationStakeWeighted::<Runtime>(Default::default());
         vec![
             // Applied in runtime 200
             //Box::new(migrate_invulnerables),
@@ -459,6 +679,8 @@ where
             Box::new(migrate_config_parathread_params),
             Box::new(migrate_add_collator_assignment_credits),
             Box::new(migrate_xcmp_queue_v4),
+            Box::new(migrate_parathread_params_core_purchase_backend),
+            Box::new(migrate_config_stake_weighted),
         ]
     }
 }
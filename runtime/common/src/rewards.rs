@@ -0,0 +1,55 @@
+// Copyright (C) Moondance Labs Ltd.
+// This file is part of Tanssi.
+
+// Tanssi is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Tanssi is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Tanssi.  If not, see <http://www.gnu.org/licenses/>
+
+use {
+    frame_support::{
+        pallet_prelude::DispatchResultWithPostInfo,
+        traits::{
+            fungible::{Balanced, Credit},
+            Imbalance,
+        },
+    },
+    pallet_balances::NegativeImbalance,
+    sp_std::marker::PhantomData,
+    tp_traits::DistributeRewards,
+};
+
+/// Distributes rewards received as a `Currency` imbalance, such as the fees charged by
+/// `pallet_services_payment`, using a distributor that expects a `fungible` credit.
+pub struct CurrencyRewardsDistributor<Runtime, Distributor>(PhantomData<(Runtime, Distributor)>);
+
+impl<Runtime, Distributor> DistributeRewards<Runtime::AccountId, NegativeImbalance<Runtime>>
+    for CurrencyRewardsDistributor<Runtime, Distributor>
+where
+    Runtime: pallet_balances::Config,
+    Distributor: DistributeRewards<
+        Runtime::AccountId,
+        Credit<Runtime::AccountId, pallet_balances::Pallet<Runtime>>,
+    >,
+{
+    fn distribute_rewards(
+        rewarded: Runtime::AccountId,
+        amount: NegativeImbalance<Runtime>,
+    ) -> DispatchResultWithPostInfo {
+        let value = amount.peek();
+        // Both imbalances represent the same funds: dropping the imbalance decreases the total
+        // issuance, and issuing the credit increases it back by the same amount.
+        drop(amount);
+        let credit = <pallet_balances::Pallet<Runtime> as Balanced<_>>::issue(value);
+
+        Distributor::distribute_rewards(rewarded, credit)
+    }
+}
@@ -42,12 +42,17 @@ use {
     frame_support::{
         construct_runtime,
         dispatch::DispatchClass,
+        ensure,
         genesis_builder_helper::{build_config, create_default_config},
         pallet_prelude::DispatchResult,
         parameter_types,
         traits::{
             fungible::{Balanced, Credit, Inspect, InspectHold, Mutate, MutateHold},
-            tokens::{PayFromAccount, Precision, Preservation, UnityAssetBalanceConversion},
+            fungibles,
+            tokens::{
+                DepositConsequence, Fortitude, PayFromAccount, Precision, Preservation, Provenance,
+                UnityAssetBalanceConversion,
+            },
             ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, EitherOfDiverse,
             Imbalance, InsideBoth, InstanceFilter, OnUnbalanced, ValidatorRegistration,
         },
@@ -70,13 +75,18 @@ use {
     pallet_collator_assignment::{GetRandomnessForNextBlock, RotateCollatorsEveryNSessions},
     pallet_invulnerables::InvulnerableRewardDistribution,
     pallet_pooled_staking::traits::{IsCandidateEligible, Timer},
+    pallet_pooled_staking_runtime_api::PooledStakingApiError,
     pallet_registrar::RegistrarHooks,
     pallet_registrar_runtime_api::ContainerChainGenesisData,
-    pallet_services_payment::{ProvideBlockProductionCost, ProvideCollatorAssignmentCost},
+    pallet_services_payment::{
+        CollatorDemand, DemandBasedCost, FeeSplitter, ProvideBlockProductionCost,
+        ProvideCollatorAssignmentCost, RequestedCollatorsScaledCost,
+    },
     pallet_session::{SessionManager, ShouldEndSession},
     pallet_stream_payment_runtime_api::{StreamPaymentApiError, StreamPaymentApiStatus},
     pallet_transaction_payment::CurrencyAdapter,
     polkadot_runtime_common::BlockHashCount,
+    runtime_common::rewards::CurrencyRewardsDistributor,
     scale_info::{prelude::format, TypeInfo},
     smallvec::smallvec,
     sp_api::impl_runtime_apis,
@@ -87,18 +97,19 @@ use {
     sp_runtime::{
         create_runtime_str, generic, impl_opaque_keys,
         traits::{
-            AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Hash as HashT,
-            IdentityLookup, Verify,
+            AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT,
+            BlockNumberProvider, Convert, Hash as HashT, IdentityLookup, TrailingZeroInput, Verify,
         },
         transaction_validity::{TransactionSource, TransactionValidity},
-        AccountId32, ApplyExtrinsicResult,
+        AccountId32, ApplyExtrinsicResult, ArithmeticError, FixedU128, TokenError,
     },
     sp_std::{collections::btree_set::BTreeSet, marker::PhantomData, prelude::*},
     sp_version::RuntimeVersion,
     tp_traits::{
-        GetContainerChainAuthor, GetHostConfiguration, GetSessionContainerChains,
-        RemoveInvulnerables, RemoveParaIdsWithNoCredits,
+        GetCollatorRequirements, GetContainerChainAuthor, GetHostConfiguration,
+        GetSessionContainerChains, RemoveInvulnerables, RemoveParaIdsWithNoCredits,
     },
+    xcm_config::AssetId,
 };
 pub use {
     dp_core::{AccountId, Address, Balance, BlockNumber, Hash, Header, Index, Signature},
@@ -605,6 +616,12 @@ impl pallet_initializer::ApplyNewSession<Runtime> for OwnApplySession {
 
         let next_collators = queued.iter().map(|(k, _)| k.clone()).collect();
 
+        // Next: slash collators that were offline during the ending session, which needs to be
+        // done before CollatorAssignment replaces the assignment of that session.
+        if let Some(ending_session) = session_index.checked_sub(1) {
+            slash_offline_collators(ending_session);
+        }
+
         // Next: CollatorAssignment
         let assignments =
             CollatorAssignment::initializer_on_new_session(&session_index, next_collators);
@@ -618,6 +635,40 @@ impl pallet_initializer::ApplyNewSession<Runtime> for OwnApplySession {
     }
 }
 
+/// Slash the collators that were assigned to a container chain during `session` but never
+/// authored any of its blocks, while other collators assigned to the same chain did. Chains
+/// without any block authored are ignored, as it is most likely not the fault of their collators.
+/// Parathreads are ignored too, as they only produce blocks in some slots and their collators
+/// can go a whole session without being expected to author any block.
+fn slash_offline_collators(session: u32) {
+    let assignment = CollatorAssignment::collator_container_chain();
+
+    for (para_id, collators) in assignment.container_chains {
+        if Registrar::parathread_params(para_id).is_some() {
+            continue;
+        }
+
+        let (online, offline): (Vec<_>, Vec<_>) = collators
+            .into_iter()
+            .partition(|collator| AuthorNoting::authored_since_session(collator, session));
+
+        if online.is_empty() {
+            continue;
+        }
+
+        for collator in offline {
+            if let Err(e) = PooledStaking::slash(collator.clone(), OfflineSlashFraction::get()) {
+                log::warn!(
+                    "Failed to slash offline collator {:?} of para {:?}: {:?}",
+                    collator,
+                    u32::from(para_id),
+                    e,
+                );
+            }
+        }
+    }
+}
+
 impl pallet_initializer::Config for Runtime {
     type SessionIndex = u32;
 
@@ -632,24 +683,10 @@ impl parachain_info::Config for Runtime {}
 /// Returns a list of collators by combining pallet_invulnerables and pallet_pooled_staking.
 pub struct CollatorsFromInvulnerablesAndThenFromStaking;
 
-/// Play the role of the session manager.
-impl SessionManager<CollatorId> for CollatorsFromInvulnerablesAndThenFromStaking {
-    fn new_session(index: SessionIndex) -> Option<Vec<CollatorId>> {
-        if <frame_system::Pallet<Runtime>>::block_number() == 0 {
-            // Do not show this log in genesis
-            log::debug!(
-                "assembling new collators for new session {} at #{:?}",
-                index,
-                <frame_system::Pallet<Runtime>>::block_number(),
-            );
-        } else {
-            log::info!(
-                "assembling new collators for new session {} at #{:?}",
-                index,
-                <frame_system::Pallet<Runtime>>::block_number(),
-            );
-        }
-
+impl CollatorsFromInvulnerablesAndThenFromStaking {
+    /// Collators that will be queued for session `index`: first the invulnerables, then the
+    /// eligible staking candidates, up to the max number of collators.
+    pub fn collators(index: SessionIndex) -> Vec<CollatorId> {
         let invulnerables = Invulnerables::invulnerables().to_vec();
         let candidates_staking =
             pallet_pooled_staking::SortedEligibleCandidates::<Runtime>::get().to_vec();
@@ -657,7 +694,8 @@ impl SessionManager<CollatorId> for CollatorsFromInvulnerablesAndThenFromStaking
         let target_session_index = index.saturating_add(1);
         let max_collators =
             <Configuration as GetHostConfiguration<u32>>::max_collators(target_session_index);
-        let collators = invulnerables
+
+        invulnerables
             .iter()
             .cloned()
             .chain(candidates_staking.into_iter().filter_map(|elig| {
@@ -670,7 +708,29 @@ impl SessionManager<CollatorId> for CollatorsFromInvulnerablesAndThenFromStaking
                 }
             }))
             .take(max_collators as usize)
-            .collect();
+            .collect()
+    }
+}
+
+/// Play the role of the session manager.
+impl SessionManager<CollatorId> for CollatorsFromInvulnerablesAndThenFromStaking {
+    fn new_session(index: SessionIndex) -> Option<Vec<CollatorId>> {
+        if <frame_system::Pallet<Runtime>>::block_number() == 0 {
+            // Do not show this log in genesis
+            log::debug!(
+                "assembling new collators for new session {} at #{:?}",
+                index,
+                <frame_system::Pallet<Runtime>>::block_number(),
+            );
+        } else {
+            log::info!(
+                "assembling new collators for new session {} at #{:?}",
+                index,
+                <frame_system::Pallet<Runtime>>::block_number(),
+            );
+        }
+
+        let collators = Self::collators(index);
 
         // TODO: weight?
         /*
@@ -782,56 +842,104 @@ impl RemoveInvulnerables<CollatorId> for RemoveInvulnerablesImpl {
 
 pub struct RemoveParaIdsWithNoCreditsImpl;
 
-impl RemoveParaIdsWithNoCredits for RemoveParaIdsWithNoCreditsImpl {
-    fn remove_para_ids_with_no_credits(
-        para_ids: &mut Vec<ParaId>,
-        currently_assigned: &BTreeSet<ParaId>,
-    ) {
+impl RemoveParaIdsWithNoCreditsImpl {
+    /// Whether the para can pay for the next session, with credits or with its tank.
+    fn has_enough_funds(para_id: &ParaId, currently_assigned: &BTreeSet<ParaId>) -> bool {
         let blocks_per_session = Period::get();
 
-        para_ids.retain(|para_id| {
-            // If the para has been assigned collators for this session it must have enough block credits
-            // for the current and the next session.
-            let block_credits_needed = if currently_assigned.contains(para_id) {
-                blocks_per_session * 2
-            } else {
-                blocks_per_session
-            };
+        // If the para has been assigned collators for this session it must have enough block credits
+        // for the current and the next session.
+        let block_credits_needed = if currently_assigned.contains(para_id) {
+            blocks_per_session * 2
+        } else {
+            blocks_per_session
+        };
 
-            // Check if the container chain has enough credits for producing blocks
-            let free_block_credits = pallet_services_payment::BlockProductionCredits::<Runtime>::get(para_id)
+        // Check if the container chain has enough credits for producing blocks
+        let free_block_credits =
+            pallet_services_payment::BlockProductionCredits::<Runtime>::get(para_id)
                 .unwrap_or_default();
 
-            // Check if the container chain has enough credits for a session assignments
-            let free_session_credits = pallet_services_payment::CollatorAssignmentCredits::<Runtime>::get(para_id)
+        // Check if the container chain has enough credits for a session assignments
+        let free_session_credits =
+            pallet_services_payment::CollatorAssignmentCredits::<Runtime>::get(para_id)
                 .unwrap_or_default();
 
-            // If para's max tip is set it should have enough to pay for one assignment with tip
-            let max_tip = pallet_services_payment::MaxTip::<Runtime>::get(para_id).unwrap_or_default() ;
+        // If para's max tip is set it should have enough to pay for one assignment with tip
+        let max_tip = pallet_services_payment::MaxTip::<Runtime>::get(para_id).unwrap_or_default();
 
-            // Return if we can survive with free credits
-            if free_block_credits >= block_credits_needed && free_session_credits >= 1 {
-                // Max tip should always be checked, as it can be withdrawn even if free credits were used
-                return Balances::can_withdraw(&pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id), max_tip).into_result(true).is_ok()
-            }
+        // Return if we can survive with free credits
+        if free_block_credits >= block_credits_needed && free_session_credits >= 1 {
+            // Max tip should always be checked, as it can be withdrawn even if free credits were used
+            return Balances::can_withdraw(
+                &pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id),
+                max_tip,
+            )
+            .into_result(true)
+            .is_ok();
+        }
 
-            let remaining_block_credits = block_credits_needed.saturating_sub(free_block_credits);
-            let remaining_session_credits = 1u32.saturating_sub(free_session_credits);
+        let remaining_block_credits = block_credits_needed.saturating_sub(free_block_credits);
+        let remaining_session_credits = 1u32.saturating_sub(free_session_credits);
 
-            let (block_production_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(para_id);
-            let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(para_id);
-            // let's check if we can withdraw
-            let remaining_block_credits_to_pay = u128::from(remaining_block_credits).saturating_mul(block_production_costs);
-            let remaining_session_credits_to_pay = u128::from(remaining_session_credits).saturating_mul(collator_assignment_costs);
+        let (block_production_costs, _) =
+            <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(
+                para_id,
+            );
+        let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(para_id);
+        // let's check if we can withdraw
+        let remaining_block_credits_to_pay =
+            u128::from(remaining_block_credits).saturating_mul(block_production_costs);
+        let remaining_session_credits_to_pay =
+            u128::from(remaining_session_credits).saturating_mul(collator_assignment_costs);
+
+        let remaining_services_to_pay =
+            remaining_block_credits_to_pay.saturating_add(remaining_session_credits_to_pay);
+        let remaining_to_pay = remaining_services_to_pay.saturating_add(max_tip);
+
+        // This should take into account whether we tank goes below ED
+        // The true refers to keepAlive
+        let parachain_tank = pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id);
+        if Balances::can_withdraw(&parachain_tank, remaining_to_pay)
+            .into_result(true)
+            .is_ok()
+        {
+            return true;
+        }
 
-            let remaining_to_pay = remaining_block_credits_to_pay.saturating_add(remaining_session_credits_to_pay).saturating_add(max_tip);
+        // Otherwise services can be paid with the assets held in the tank, but the tip must
+        // still be paid with the native currency
+        Balances::can_withdraw(&parachain_tank, max_tip)
+            .into_result(true)
+            .is_ok()
+            && ServicesPayment::tank_asset_to_pay(*para_id, remaining_services_to_pay).is_some()
+    }
+}
 
-            // This should take into account whether we tank goes below ED
-            // The true refers to keepAlive
-            Balances::can_withdraw(&pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id), remaining_to_pay).into_result(true).is_ok()
+impl RemoveParaIdsWithNoCredits for RemoveParaIdsWithNoCreditsImpl {
+    fn remove_para_ids_with_no_credits(
+        para_ids: &mut Vec<ParaId>,
+        currently_assigned: &BTreeSet<ParaId>,
+    ) {
+        para_ids.retain(|para_id| {
+            // Paras that are already running keep their collators during a grace period, so that
+            // their managers have time to top up their tanks
+            Self::has_enough_funds(para_id, currently_assigned)
+                || (currently_assigned.contains(para_id)
+                    && ServicesPayment::in_grace_period(*para_id))
         });
     }
 
+    fn on_session_change(para_ids: &[ParaId], currently_assigned: &BTreeSet<ParaId>) {
+        for para_id in para_ids {
+            if Self::has_enough_funds(para_id, currently_assigned) {
+                ServicesPayment::reset_grace_period(*para_id);
+            } else if currently_assigned.contains(para_id) {
+                ServicesPayment::use_grace_period(*para_id);
+            }
+        }
+    }
+
     /// Make those para ids valid by giving them enough credits, for benchmarking.
     #[cfg(feature = "runtime-benchmarks")]
     fn make_valid_para_ids(para_ids: &[ParaId]) {
@@ -870,6 +978,8 @@ impl pallet_collator_assignment::Config for Runtime {
     type RemoveParaIdsWithNoCredits = RemoveParaIdsWithNoCreditsImpl;
     type CollatorAssignmentHook = ServicesPayment;
     type CollatorAssignmentTip = ServicesPayment;
+    type RequestedCollators = ServicesPayment;
+    type CollatorStake = PooledStaking;
     type Currency = Balances;
     type WeightInfo = weights::pallet_collator_assignment::SubstrateWeight<Runtime>;
 }
@@ -879,46 +989,238 @@ impl pallet_authority_assignment::Config for Runtime {
     type AuthorityId = NimbusId;
 }
 
-pub const FIXED_BLOCK_PRODUCTION_COST: u128 = 1 * currency::MICRODANCE;
-pub const FIXED_COLLATOR_ASSIGNMENT_COST: u128 = 100 * currency::MICRODANCE;
+parameter_types! {
+    pub const BaseBlockProductionCost: u128 = 1 * currency::MICRODANCE;
+    pub const BaseCollatorAssignmentCost: u128 = 100 * currency::MICRODANCE;
+    // Prices never go below the base cost, and can grow up to 10 times the base cost
+    pub MinimumCostMultiplier: FixedU128 = FixedU128::from_u32(1);
+    pub MaximumCostMultiplier: FixedU128 = FixedU128::from_u32(10);
+}
+
+/// Collator demand computed from the registered container chains of the current session,
+/// the collators of the current session and the active host configuration.
+pub struct CollatorDemandImpl;
+impl pallet_services_payment::ProvideCollatorDemand for CollatorDemandImpl {
+    fn collator_demand() -> (CollatorDemand, Weight) {
+        let session_index = Session::current_index();
+        let config = Configuration::config();
+        let container_chains = Registrar::session_container_chains(session_index);
+        let num_collators = pallet_session::Validators::<Runtime>::decode_len().unwrap_or_default();
+
+        // Chains that requested a different number of collators need at least their min
+        let min_collators = |para_id: &ParaId, default_collators: u32| {
+            ServicesPayment::collator_requirements(*para_id)
+                .map(|requirements| requirements.min_collators)
+                .unwrap_or(default_collators)
+        };
+        // Parathreads share collators, grouped like in the collator assignment
+        let required_collators = CollatorAssignment::min_collators_needed(
+            container_chains
+                .parachains
+                .iter()
+                .map(|para_id| min_collators(para_id, config.collators_per_container)),
+            container_chains
+                .parathreads
+                .iter()
+                .map(|(para_id, _)| min_collators(para_id, config.collators_per_parathread)),
+            config.parathreads_per_collator,
+        );
+        // Collators left once the orchestrator chain has the minimum number of collators
+        let available_collators = (num_collators as u32)
+            .min(config.max_collators)
+            .saturating_sub(config.min_orchestrator_collators);
+
+        let demand = CollatorDemand {
+            required_collators,
+            available_collators,
+            target_fullness: config.target_container_chain_fullness,
+        };
+        // Session index, active config, registered para ids, pending para ids, session validators
+        // and the parathread params and requested collators of each para
+        let num_paras = container_chains.parachains.len() + container_chains.parathreads.len();
+        let weight = <Runtime as frame_system::Config>::DbWeight::get()
+            .reads(5u64.saturating_add(2 * num_paras as u64));
 
-pub struct BlockProductionCost<Runtime>(PhantomData<Runtime>);
-impl ProvideBlockProductionCost<Runtime> for BlockProductionCost<Runtime> {
-    fn block_cost(_para_id: &ParaId) -> (u128, Weight) {
-        (FIXED_BLOCK_PRODUCTION_COST, Weight::zero())
+        (demand, weight)
     }
 }
 
-pub struct CollatorAssignmentCost<Runtime>(PhantomData<Runtime>);
-impl ProvideCollatorAssignmentCost<Runtime> for CollatorAssignmentCost<Runtime> {
-    fn collator_assignment_cost(_para_id: &ParaId) -> (u128, Weight) {
-        (FIXED_COLLATOR_ASSIGNMENT_COST, Weight::zero())
+/// Computing the collator demand reads every registered container chain, so it is only done once
+/// per session.
+pub type SessionCollatorDemand = pallet_services_payment::CollatorDemandCachedPerSession<
+    Runtime,
+    CollatorDemandImpl,
+    CurrentSessionIndexGetter,
+>;
+
+pub type BlockProductionCost = DemandBasedCost<
+    Runtime,
+    BaseBlockProductionCost,
+    SessionCollatorDemand,
+    MinimumCostMultiplier,
+    MaximumCostMultiplier,
+>;
+
+/// Default number of collators of a container chain, used to scale the collator assignment cost
+/// of chains that requested a different number of collators.
+pub struct DefaultCollatorsPerPara;
+impl Convert<ParaId, u32> for DefaultCollatorsPerPara {
+    fn convert(para_id: ParaId) -> u32 {
+        let config = Configuration::config();
+        if Registrar::parathread_params(para_id).is_some() {
+            config.collators_per_parathread
+        } else {
+            config.collators_per_container
+        }
     }
 }
 
+pub type CollatorAssignmentCost = RequestedCollatorsScaledCost<
+    Runtime,
+    DemandBasedCost<
+        Runtime,
+        BaseCollatorAssignmentCost,
+        SessionCollatorDemand,
+        MinimumCostMultiplier,
+        MaximumCostMultiplier,
+    >,
+    DefaultCollatorsPerPara,
+>;
+
 parameter_types! {
     // 60 days worth of blocks
     pub const FreeBlockProductionCredits: BlockNumber = 60 * DAYS;
     // 60 days worth of blocks
     pub const FreeCollatorAssignmentCredits: u32 = FreeBlockProductionCredits::get()/Period::get();
+    // Limit the amount charged at once if the orchestrator chain was not able to note some blocks
+    pub const MaxBlocksChargedPerNoting: u32 = 10;
+    // Warn container chain managers when their tank can pay for less than one day of service
+    pub const LowBalanceWarningSessions: u32 = 24;
+    // Number of sessions a running container chain keeps its collators after running out of funds
+    pub const GracePeriodSessions: u32 = 2;
+    pub const MaxRequestedCollators: u32 = 10;
+    pub const MaxTankAssets: u32 = 4;
+}
+
+/// Foreign assets can be held in the tank of a container chain to pay for its services.
+pub struct ServicesPaymentAssets;
+impl pallet_services_payment::Assets<AccountId, AssetId, Balance> for ServicesPaymentAssets {
+    fn reducible_balance(
+        asset_id: &AssetId,
+        account: &AccountId,
+        preservation: Preservation,
+    ) -> Balance {
+        <ForeignAssets as fungibles::Inspect<AccountId>>::reducible_balance(
+            *asset_id,
+            account,
+            preservation,
+            Fortitude::Polite,
+        )
+    }
+
+    fn transfer(
+        asset_id: &AssetId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Balance,
+        preservation: Preservation,
+    ) -> DispatchResult {
+        <ForeignAssets as fungibles::Mutate<AccountId>>::transfer(
+            *asset_id,
+            from,
+            to,
+            amount,
+            preservation,
+        )
+        .map(|_| ())
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_worst_case_asset_id() -> AssetId {
+        1
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_balance(asset_id: &AssetId, account: &AccountId, amount: Balance) {
+        if !<ForeignAssets as fungibles::Inspect<AccountId>>::asset_exists(*asset_id) {
+            assert!(<ForeignAssets as fungibles::Create<AccountId>>::create(
+                *asset_id,
+                account.clone(),
+                true,
+                1,
+            )
+            .is_ok());
+        }
+
+        let _ = <ForeignAssets as fungibles::Mutate<AccountId>>::set_balance(
+            *asset_id, account, amount,
+        );
+    }
+}
+
+/// Foreign assets are valued with the rates set in `pallet_asset_rate`.
+pub struct ServicesPaymentAssetRateConverter;
+impl pallet_services_payment::AssetRateConverter<AssetId, Balance>
+    for ServicesPaymentAssetRateConverter
+{
+    fn rate_to_native(asset_id: &AssetId) -> Option<FixedU128> {
+        pallet_asset_rate::ConversionRateToNative::<Runtime>::get(asset_id)
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn bench_set_rate(asset_id: &AssetId) {
+        pallet_asset_rate::ConversionRateToNative::<Runtime>::insert(
+            asset_id,
+            FixedU128::from_u32(1),
+        );
+    }
+}
+
+parameter_types! {
+    // Fees charged for services: 20% goes to the treasury, 30% is burned and the rest is given
+    // to the collator that provided the service, if any, or to the treasury otherwise
+    pub const ServicesPaymentTreasuryShare: Perbill = Perbill::from_percent(20);
+    pub const ServicesPaymentBurnShare: Perbill = Perbill::from_percent(30);
 }
 
+pub type ServicesPaymentFeeSplitter = FeeSplitter<
+    Runtime,
+    ServicesPaymentTreasuryShare,
+    ServicesPaymentBurnShare,
+    Treasury,
+    CurrencyRewardsDistributor<
+        Runtime,
+        InvulnerableRewardDistribution<Runtime, Balances, PooledStaking>,
+    >,
+>;
+
 impl pallet_services_payment::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     /// Handler for fees
-    type OnChargeForBlock = ();
-    type OnChargeForCollatorAssignment = ();
-    type OnChargeForCollatorAssignmentTip = ();
+    type OnChargeForBlock = ServicesPaymentFeeSplitter;
+    type OnChargeForCollatorAssignment = ServicesPaymentFeeSplitter;
+    type OnChargeForCollatorAssignmentTip = ServicesPaymentFeeSplitter;
     /// Currency type for fee payment
     type Currency = Balances;
     /// Provider of a block cost which can adjust from block to block
-    type ProvideBlockProductionCost = BlockProductionCost<Runtime>;
+    type ProvideBlockProductionCost = BlockProductionCost;
     /// Provider of a block cost which can adjust from block to block
-    type ProvideCollatorAssignmentCost = CollatorAssignmentCost<Runtime>;
+    type ProvideCollatorAssignmentCost = CollatorAssignmentCost;
     /// The maximum number of block credits that can be accumulated
     type FreeBlockProductionCredits = FreeBlockProductionCredits;
     /// The maximum number of session credits that can be accumulated
     type FreeCollatorAssignmentCredits = FreeCollatorAssignmentCredits;
+    /// The maximum number of blocks charged in a single author noting
+    type MaxBlocksChargedPerNoting = MaxBlocksChargedPerNoting;
+    type BlocksPerSession = Period;
+    type LowBalanceWarningSessions = LowBalanceWarningSessions;
+    type GracePeriodSessions = GracePeriodSessions;
+    type MaxRequestedCollators = MaxRequestedCollators;
+    type AssetId = AssetId;
+    type Assets = ServicesPaymentAssets;
+    type AssetRateConverter = ServicesPaymentAssetRateConverter;
+    type AssetFeesAccount = TreasuryAccount;
+    type MaxTankAssets = MaxTankAssets;
     type ManagerOrigin =
         EitherOfDiverse<pallet_registrar::EnsureSignedByManager<Runtime>, EnsureRoot<AccountId>>;
     type WeightInfo = weights::pallet_services_payment::SubstrateWeight<Runtime>;
@@ -940,12 +1242,14 @@ impl pallet_author_noting::Config for Runtime {
     type SelfParaId = parachain_info::Pallet<Runtime>;
     type SlotBeacon = dp_consensus::AuraDigestSlotBeacon<Runtime>;
     type ContainerChainAuthor = CollatorAssignment;
+    type GetParathreadParams = Registrar;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
     type RelayChainStateProvider = cumulus_pallet_parachain_system::RelaychainDataProvider<Self>;
     // We benchmark each hook individually, so for runtime-benchmarks this should be empty
     #[cfg(feature = "runtime-benchmarks")]
     type AuthorNotingHook = ();
     #[cfg(not(feature = "runtime-benchmarks"))]
-    type AuthorNotingHook = (InflationRewards, ServicesPayment);
+    type AuthorNotingHook = (XcmCoreBuyer, InflationRewards, ServicesPayment);
     type WeightInfo = weights::pallet_author_noting::SubstrateWeight<Runtime>;
 }
 
@@ -1266,6 +1570,12 @@ parameter_types! {
     pub const RewardsCollatorCommission: Perbill = Perbill::from_percent(20);
     // Need to wait 2 sessions before being able to join or leave staking pools
     pub const StakingSessionDelay: u32 = 2;
+    // Commission increases wait longer than leaving, so delegators can leave before they apply
+    pub const CommissionIncreaseSessionDelay: u32 = 3;
+    // Slashes are applied 2 sessions after being requested, leaving time to cancel them
+    pub const SlashDeferSessions: u32 = 2;
+    // Slash of collators assigned to a container chain that never authored a block of it
+    pub const OfflineSlashFraction: Perbill = Perbill::from_percent(1);
 }
 
 pub struct SessionTimer<G>(PhantomData<G>);
@@ -1281,13 +1591,16 @@ where
     }
 
     fn is_elapsed(instant: &Self::Instant) -> bool {
-        let delay = G::get();
-        let Some(end) = instant.checked_add(delay) else {
+        let Some(end) = Self::elapsed_at(instant) else {
             return false;
         };
         end <= Self::now()
     }
 
+    fn elapsed_at(instant: &Self::Instant) -> Option<Self::Instant> {
+        instant.checked_add(G::get())
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     fn elapsed_instant() -> Self::Instant {
         let delay = G::get();
@@ -1338,10 +1651,19 @@ impl pallet_pooled_staking::Config for Runtime {
     type MinimumSelfDelegation = MinimumSelfDelegation;
     type RuntimeHoldReason = RuntimeHoldReason;
     type RewardsCollatorCommission = RewardsCollatorCommission;
+    type CommissionIncreaseTimer = SessionTimer<CommissionIncreaseSessionDelay>;
+    type CommissionBoundsOrigin = EnsureRoot<AccountId>;
     type JoiningRequestTimer = SessionTimer<StakingSessionDelay>;
     type LeavingRequestTimer = SessionTimer<StakingSessionDelay>;
     type EligibleCandidatesBufferSize = ConstU32<100>;
     type EligibleCandidatesFilter = CandidateHasRegisteredKeys;
+    type SlashedFundsAccount = TreasuryAccount;
+    type SlashDeferTimer = SessionTimer<SlashDeferSessions>;
+    type MaxDeferredSlashes = ConstU32<100>;
+    type MaxSlashesAppliedPerBlock = ConstU32<10>;
+    type MaxSlashedHoldsRebalancedPerBlock = ConstU32<50>;
+    type SlashCancelOrigin = EnsureRoot<AccountId>;
+    type MaxAutoExecutedOperations = ConstU32<50>;
     type WeightInfo = weights::pallet_pooled_staking::SubstrateWeight<Runtime>;
 }
 
@@ -1390,6 +1712,8 @@ impl pallet_inflation_rewards::Config for Runtime {
     type PendingRewardsAccount = PendingRewardsAccount;
     type StakingRewardsDistributor = InvulnerableRewardDistribution<Self, Balances, PooledStaking>;
     type RewardsPortion = RewardsPortion;
+    /// Blocks are rewarded as long as they are charged by services payment
+    type MaxBlocksRewardedPerNoting = MaxBlocksChargedPerNoting;
 }
 
 impl pallet_tx_pause::Config for Runtime {
@@ -1402,12 +1726,149 @@ impl pallet_tx_pause::Config for Runtime {
     type WeightInfo = weights::pallet_tx_pause::SubstrateWeight<Runtime>;
 }
 
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen)]
 pub enum StreamPaymentAssetId {
     Native,
-}
+    ForeignAssets(AssetId),
+}
+
+/// Minimum balance of a foreign asset kept in the deposit account of an account, recorded when
+/// the deposit account is funded so that the deposit doesn't change if the minimum balance of the
+/// asset is changed afterwards.
+#[frame_support::storage_alias]
+pub type ForeignAssetsDepositReserve = StorageDoubleMap<
+    StreamPaymentAssets,
+    frame_support::Blake2_128Concat,
+    AccountId,
+    frame_support::Blake2_128Concat,
+    AssetId,
+    Balance,
+    frame_support::pallet_prelude::ValueQuery,
+>;
 
 pub struct StreamPaymentAssets;
+
+impl StreamPaymentAssets {
+    /// `pallet_assets` doesn't support holds, so deposits made with foreign assets are kept in an
+    /// account derived from the account of the depositor, which nobody controls. While some
+    /// deposit is left, this account also holds the minimum balance of the asset so that it is
+    /// not reaped whatever the amount deposited. This account has no native balance, so only
+    /// sufficient assets can be deposited.
+    pub fn foreign_assets_deposit_account(account: &AccountId) -> AccountId {
+        let entropy = (b"modlpy/strpaydep", account).using_encoded(sp_io::hashing::blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    /// Foreign assets deposited by `account`, which excludes the minimum balance kept in the
+    /// deposit account.
+    fn foreign_assets_deposit(asset_id: AssetId, account: &AccountId) -> Balance {
+        <ForeignAssets as fungibles::Inspect<AccountId>>::balance(
+            asset_id,
+            &Self::foreign_assets_deposit_account(account),
+        )
+        .saturating_sub(ForeignAssetsDepositReserve::get(account, asset_id))
+    }
+
+    /// Transfer foreign assets to the deposit account of `account`, along with the minimum
+    /// balance of the asset if the deposit account is empty.
+    fn increase_foreign_assets_deposit(
+        asset_id: AssetId,
+        account: &AccountId,
+        amount: Balance,
+    ) -> DispatchResult {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let deposit_account = Self::foreign_assets_deposit_account(account);
+        let deposit_account_balance =
+            <ForeignAssets as fungibles::Inspect<AccountId>>::balance(asset_id, &deposit_account);
+        let minimum_balance = if deposit_account_balance == 0 {
+            let minimum_balance =
+                <ForeignAssets as fungibles::Inspect<AccountId>>::minimum_balance(asset_id);
+            match <ForeignAssets as fungibles::Inspect<AccountId>>::can_deposit(
+                asset_id,
+                &deposit_account,
+                amount.saturating_add(minimum_balance),
+                Provenance::Extant,
+            ) {
+                // The deposit account has no native balance to provide for non-sufficient assets
+                DepositConsequence::CannotCreate => return Err(TokenError::Unsupported.into()),
+                consequence => consequence.into_result()?,
+            }
+            ForeignAssetsDepositReserve::insert(account, asset_id, minimum_balance);
+            minimum_balance
+        } else {
+            0
+        };
+
+        <ForeignAssets as fungibles::Mutate<AccountId>>::transfer(
+            asset_id,
+            account,
+            &deposit_account,
+            amount
+                .checked_add(minimum_balance)
+                .ok_or(ArithmeticError::Overflow)?,
+            Preservation::Preserve,
+        )
+        .map(|_| ())
+    }
+
+    /// Transfer foreign assets out of the deposit account of `account`. Once no deposit is left,
+    /// the minimum balance kept in the deposit account is returned to `account`.
+    fn transfer_foreign_assets_deposit(
+        asset_id: AssetId,
+        account: &AccountId,
+        to: &AccountId,
+        amount: Balance,
+    ) -> DispatchResult {
+        let deposit_account = Self::foreign_assets_deposit_account(account);
+        let remaining = Self::foreign_assets_deposit(asset_id, account)
+            .checked_sub(amount)
+            .ok_or(TokenError::FundsUnavailable)?;
+
+        if remaining != 0 {
+            return <ForeignAssets as fungibles::Mutate<AccountId>>::transfer(
+                asset_id,
+                &deposit_account,
+                to,
+                amount,
+                Preservation::Preserve,
+            )
+            .map(|_| ());
+        }
+
+        // The deposit account is emptied in a single transfer, as the minimum balance of the asset
+        // may have changed since it was funded and the kept amount could be below it.
+        let balance =
+            <ForeignAssets as fungibles::Inspect<AccountId>>::balance(asset_id, &deposit_account);
+        if balance != 0 {
+            <ForeignAssets as fungibles::Mutate<AccountId>>::transfer(
+                asset_id,
+                &deposit_account,
+                account,
+                balance,
+                Preservation::Expendable,
+            )?;
+        }
+        ForeignAssetsDepositReserve::remove(account, asset_id);
+
+        if to != account && amount != 0 {
+            <ForeignAssets as fungibles::Mutate<AccountId>>::transfer(
+                asset_id,
+                account,
+                to,
+                amount,
+                Preservation::Expendable,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
 impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
     for StreamPaymentAssets
 {
@@ -1423,6 +1884,9 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
                 Self::decrease_deposit(asset_id, from, amount)?;
                 Balances::transfer(from, to, amount, Preservation::Preserve).map(|_| ())
             }
+            StreamPaymentAssetId::ForeignAssets(asset_id) => {
+                Self::transfer_foreign_assets_deposit(*asset_id, from, to, amount)
+            }
         }
     }
 
@@ -1437,6 +1901,9 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
                 account,
                 amount,
             ),
+            StreamPaymentAssetId::ForeignAssets(asset_id) => {
+                Self::increase_foreign_assets_deposit(*asset_id, account, amount)
+            }
         }
     }
 
@@ -1453,6 +1920,9 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
                 Precision::Exact,
             )
             .map(|_| ()),
+            StreamPaymentAssetId::ForeignAssets(asset_id) => {
+                Self::transfer_foreign_assets_deposit(*asset_id, account, account, amount)
+            }
         }
     }
 
@@ -1462,6 +1932,9 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
                 &pallet_stream_payment::HoldReason::StreamPayment.into(),
                 account,
             ),
+            StreamPaymentAssetId::ForeignAssets(asset_id) => {
+                Self::foreign_assets_deposit(*asset_id, account)
+            }
         }
     }
 
@@ -1469,7 +1942,7 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
     /// with it.
     #[cfg(feature = "runtime-benchmarks")]
     fn bench_worst_case_asset_id() -> StreamPaymentAssetId {
-        StreamPaymentAssetId::Native
+        StreamPaymentAssetId::ForeignAssets(1)
     }
 
     /// Benchmarks: should return the another asset id which has the worst performance when interacting
@@ -1477,24 +1950,45 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
     /// from one asset to another.
     #[cfg(feature = "runtime-benchmarks")]
     fn bench_worst_case_asset_id2() -> StreamPaymentAssetId {
-        StreamPaymentAssetId::Native
+        StreamPaymentAssetId::ForeignAssets(2)
     }
 
     /// Benchmarks: should set the balance for the asset id returned by `bench_worst_case_asset_id`.
     #[cfg(feature = "runtime-benchmarks")]
     fn bench_set_balance(asset_id: &StreamPaymentAssetId, account: &AccountId, amount: Balance) {
-        // only one asset id
-        let StreamPaymentAssetId::Native = asset_id;
+        match asset_id {
+            StreamPaymentAssetId::Native => {
+                Balances::set_balance(account, amount);
+            }
+            StreamPaymentAssetId::ForeignAssets(asset_id) => {
+                if !ForeignAssets::asset_exists(*asset_id) {
+                    assert!(<ForeignAssets as fungibles::Create<AccountId>>::create(
+                        *asset_id,
+                        account.clone(),
+                        true,
+                        1,
+                    )
+                    .is_ok());
+                }
 
-        Balances::set_balance(account, amount);
+                // Deposits of native tokens are still needed to open streams
+                Balances::set_balance(account, amount);
+                let _ = <ForeignAssets as fungibles::Mutate<AccountId>>::set_balance(
+                    *asset_id, account, amount,
+                );
+            }
+        }
     }
 }
 
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen)]
 pub enum TimeUnit {
     BlockNumber,
     Timestamp,
-    // TODO: Container chains/relay block number.
+    RelayBlockNumber,
+    /// Block number of the latest noted block of the container chain.
+    ContainerBlockNumber(ParaId),
 }
 
 pub struct TimeProvider;
@@ -1503,6 +1997,13 @@ impl pallet_stream_payment::TimeProvider<TimeUnit, Balance> for TimeProvider {
         match *unit {
             TimeUnit::BlockNumber => Some(System::block_number().into()),
             TimeUnit::Timestamp => Some(Timestamp::now().into()),
+            TimeUnit::RelayBlockNumber => Some(
+                cumulus_pallet_parachain_system::RelaychainDataProvider::<Runtime>::current_block_number()
+                    .into(),
+            ),
+            TimeUnit::ContainerBlockNumber(para_id) => {
+                AuthorNoting::latest_author(para_id).map(|info| info.block_number.into())
+            }
         }
     }
 
@@ -1510,8 +2011,8 @@ impl pallet_stream_payment::TimeProvider<TimeUnit, Balance> for TimeProvider {
     /// `TimeProvider::now(unit)` with.
     #[cfg(feature = "runtime-benchmarks")]
     fn bench_worst_case_time_unit() -> TimeUnit {
-        // Both BlockNumber and Timestamp cost the same (1 db read), but overriding timestamp
-        // doesn't work well in benches, while block number works fine.
+        // All time units cost the same (1 db read), but overriding timestamp, relay or container
+        // block numbers doesn't work well in benches, while block number works fine.
         TimeUnit::BlockNumber
     }
 
@@ -1527,6 +2028,9 @@ type StreamId = u64;
 parameter_types! {
     // 1 entry, storing 173 bytes on-chain
     pub const OpenStreamHoldAmount: Balance = currency::deposit(1, 173);
+    pub const MaxStreamsSweptPerBlock: u32 = 20;
+    pub const StalledStreamGracePeriod: BlockNumber = 7 * DAYS;
+    pub const MaxBatchedPayments: u32 = 100;
 }
 
 impl pallet_stream_payment::Config for Runtime {
@@ -1540,9 +2044,33 @@ impl pallet_stream_payment::Config for Runtime {
     type OpenStreamHoldAmount = OpenStreamHoldAmount;
     type RuntimeHoldReason = RuntimeHoldReason;
     type TimeProvider = TimeProvider;
+    type MaxStreamsSweptPerBlock = MaxStreamsSweptPerBlock;
+    type StalledStreamGracePeriod = StalledStreamGracePeriod;
+    type MaxBatchedPayments = MaxBatchedPayments;
     type WeightInfo = weights::pallet_stream_payment::SubstrateWeight<Runtime>;
 }
 
+fn stream_payment_api_status(
+    stream_id: StreamId,
+    now: Option<Balance>,
+) -> Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError> {
+    match StreamPayment::stream_payment_status(stream_id, now) {
+        Ok(pallet_stream_payment::StreamPaymentStatus {
+            payment,
+            deposit_left,
+            stalled,
+        }) => Ok(StreamPaymentApiStatus {
+            payment,
+            deposit_left,
+            stalled,
+        }),
+        Err(pallet_stream_payment::Error::<Runtime>::UnknownStreamId) => {
+            Err(StreamPaymentApiError::UnknownStreamId)
+        }
+        Err(e) => Err(StreamPaymentApiError::Other(format!("{e:?}"))),
+    }
+}
+
 parameter_types! {
     // 1 entry, storing 258 bytes on-chain
     pub const BasicDeposit: Balance = currency::deposit(1, 258);
@@ -1740,6 +2268,26 @@ mod benches {
     );
 }
 
+/// Return all the paraIds assigned to `authority` in `session_index`. This includes all the
+/// parathreads that share collators.
+fn para_ids_of_authority(session_index: u32, authority: &NimbusId) -> Vec<ParaId> {
+    let Some(assigned_authorities) = AuthorityAssignment::collator_container_chain(session_index)
+    else {
+        return vec![];
+    };
+
+    if assigned_authorities.orchestrator_chain.contains(authority) {
+        return vec![ParachainInfo::get()];
+    }
+
+    assigned_authorities
+        .container_chains
+        .into_iter()
+        .filter(|(_para_id, authorities)| authorities.contains(authority))
+        .map(|(para_id, _authorities)| para_id)
+        .collect()
+}
+
 impl_runtime_apis! {
     impl sp_consensus_aura::AuraApi<Block, NimbusId> for Runtime {
         fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -2141,6 +2689,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_collator_assignment_runtime_api::CollatorAssignmentApi<Block, AccountId, ParaId> for Runtime {
         /// Return the parachain that the given `AccountId` is collating for.
         /// Returns `None` if the `AccountId` is not collating.
@@ -2182,6 +2731,17 @@ impl_runtime_apis! {
                 assigned_collators.container_chains.get(&para_id).cloned()
             }
         }
+
+        /// Return the collator assignment that will be computed on the next session change,
+        /// along with the registered paras that will not be assigned collators and why.
+        fn preview_next_assignment() -> pallet_collator_assignment_runtime_api::AssignmentPreview<AccountId> {
+            let session_index = Session::current_index() + 1;
+            // On session change, the queued collators are the ones selected for the session after
+            // the one that starts
+            let collators = CollatorsFromInvulnerablesAndThenFromStaking::collators(session_index + 1);
+
+            CollatorAssignment::preview_assignment(&session_index, collators)
+        }
     }
 
     impl pallet_registrar_runtime_api::RegistrarApi<Block, ParaId, MaxLengthTokenSymbol> for Runtime {
@@ -2251,6 +2811,49 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_services_payment_runtime_api::ServicesPaymentApi<Block, Balance, ParaId> for Runtime {
+        fn block_cost(para_id: ParaId) -> Balance {
+            let (block_production_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(&para_id);
+            block_production_costs
+        }
+
+        fn collator_assignment_cost(para_id: ParaId) -> Balance {
+            let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id);
+            collator_assignment_costs
+        }
+
+        fn sessions_of_runway(para_id: ParaId) -> u32 {
+            ServicesPayment::sessions_of_runway(para_id)
+        }
+    }
+
+    impl pallet_pooled_staking_runtime_api::PooledStakingApi<
+        Block,
+        AccountId,
+        u32,
+        pallet_pooled_staking::DelegatorPositionOf<Runtime>,
+    > for Runtime {
+        fn delegator_positions(
+            delegator: AccountId,
+        ) -> Result<Vec<pallet_pooled_staking::DelegatorPositionOf<Runtime>>, PooledStakingApiError> {
+            PooledStaking::delegator_positions(&delegator)
+                .map_err(|e| PooledStakingApiError::Other(format!("{e:?}")))
+        }
+
+        fn candidate_commission(candidate: AccountId) -> Perbill {
+            PooledStaking::commission(&candidate)
+        }
+
+        fn pending_commission_increase(candidate: AccountId) -> Option<(Perbill, u32)> {
+            pallet_pooled_staking::PendingCommissionIncreases::<Runtime>::get(candidate)
+                .map(|pending| (pending.commission, pending.at))
+        }
+
+        fn commission_bounds() -> (Perbill, Perbill) {
+            pallet_pooled_staking::CommissionBounds::<Runtime>::get()
+        }
+    }
+
     impl dp_consensus::TanssiAuthorityAssignmentApi<Block, NimbusId> for Runtime {
         /// Return the current authorities assigned to a given paraId
         fn para_id_authorities(para_id: ParaId) -> Option<Vec<NimbusId>> {
@@ -2304,6 +2907,31 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_collator_assignment_runtime_api::AuthorityParaIdsAssignmentApi<Block, NimbusId, ParaId> for Runtime {
+        /// Return all the paraIds assigned to a given authority
+        fn check_para_ids_assignment(authority: NimbusId) -> Vec<ParaId> {
+            let parent_number = System::block_number();
+            let should_end_session = <Runtime as pallet_session::Config>::ShouldEndSession::should_end_session(parent_number + 1);
+
+            let session_index = if should_end_session {
+                Session::current_index() +1
+            }
+            else {
+                Session::current_index()
+            };
+
+            para_ids_of_authority(session_index, &authority)
+        }
+
+        /// Return all the paraIds assigned to a given authority on the next session.
+        /// On session boundary this returns the same as `check_para_ids_assignment`.
+        fn check_para_ids_assignment_next_session(authority: NimbusId) -> Vec<ParaId> {
+            let session_index = Session::current_index() + 1;
+
+            para_ids_of_authority(session_index, &authority)
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
     for Runtime {
         fn query_info(
@@ -2329,18 +2957,31 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_stream_payment_runtime_api::StreamPaymentApi<Block, StreamId, Balance, Balance>
     for Runtime {
         fn stream_payment_status(
             stream_id: StreamId,
             now: Option<Balance>,
         ) -> Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError> {
-            match StreamPayment::stream_payment_status(stream_id, now) {
-                Ok(pallet_stream_payment::StreamPaymentStatus {
-                    payment, deposit_left, stalled
-                }) => Ok(StreamPaymentApiStatus {
-                    payment, deposit_left, stalled
-                }),
+            stream_payment_api_status(stream_id, now)
+        }
+
+        fn stream_payment_statuses(
+            stream_ids: Vec<StreamId>,
+            now: Option<Balance>,
+        ) -> Vec<Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError>> {
+            stream_ids
+                .into_iter()
+                .map(|stream_id| stream_payment_api_status(stream_id, now))
+                .collect()
+        }
+
+        fn stream_stall_time(
+            stream_id: StreamId,
+        ) -> Result<Option<Balance>, StreamPaymentApiError> {
+            match StreamPayment::stream_stall_time(stream_id) {
+                Ok(time) => Ok(time),
                 Err(pallet_stream_payment::Error::<Runtime>::UnknownStreamId)
                 => Err(StreamPaymentApiError::UnknownStreamId),
                 Err(e) => Err(StreamPaymentApiError::Other(format!("{e:?}")))
@@ -2348,6 +2989,48 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_stream_payment_runtime_api::StreamPaymentStreamsApi<
+        Block,
+        StreamId,
+        AccountId,
+        pallet_stream_payment::StreamOf<Runtime>,
+    > for Runtime {
+        fn streams_with_change_requested(
+            stream_ids: Vec<StreamId>,
+        ) -> Vec<(StreamId, pallet_stream_payment::StreamOf<Runtime>)> {
+            let stream_ids: BTreeSet<_> = stream_ids.into_iter().collect();
+
+            System::read_events_no_consensus()
+                .filter_map(|record| match record.event {
+                    RuntimeEvent::StreamPayment(
+                        pallet_stream_payment::Event::StreamConfigChangeRequested {
+                            stream_id, ..
+                        }
+                    ) if stream_ids.contains(&stream_id) => Some(stream_id),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter_map(|stream_id| {
+                    pallet_stream_payment::Streams::<Runtime>::get(stream_id)
+                        .map(|stream| (stream_id, stream))
+                })
+                .collect()
+        }
+
+        fn streams_with_source(source: AccountId) -> Vec<StreamId> {
+            StreamPayment::streams_with_source(&source)
+        }
+
+        fn streams_with_target(target: AccountId) -> Vec<StreamId> {
+            StreamPayment::streams_with_target(&target)
+        }
+
+        fn stream(stream_id: StreamId) -> Option<pallet_stream_payment::StreamOf<Runtime>> {
+            pallet_stream_payment::Streams::<Runtime>::get(stream_id)
+        }
+    }
+
     impl dp_slot_duration_runtime_api::TanssiSlotDurationApi<Block> for Runtime {
         fn slot_duration() -> u64 {
             SLOT_DURATION
@@ -202,4 +202,74 @@ impl<T: frame_system::Config> pallet_pooled_staking::WeightInfo for SubstrateWei
 			.saturating_add(T::DbWeight::get().reads(13_u64))
 			.saturating_add(T::DbWeight::get().writes(8_u64))
 	}
+	/// Storage: `PooledStaking::Pools` (r:10 w:5)
+	/// Proof: `PooledStaking::Pools` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PooledStaking::SortedEligibleCandidates` (r:1 w:1)
+	/// Proof: `PooledStaking::SortedEligibleCandidates` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Session::NextKeys` (r:1 w:0)
+	/// Proof: `Session::NextKeys` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `System::Account` (r:2 w:2)
+	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
+	/// Storage: `PooledStaking::UnpaidSlashedFunds` (r:1 w:1)
+	/// Proof: `PooledStaking::UnpaidSlashedFunds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn apply_slash() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1302`
+		//  Estimated: `25791`
+		// Minimum execution time: 156_304_000 picoseconds.
+		Weight::from_parts(184_520_000, 25791)
+			.saturating_add(T::DbWeight::get().reads(15_u64))
+			.saturating_add(T::DbWeight::get().writes(9_u64))
+	}
+	/// Storage: `PooledStaking::DeferredSlashes` (r:1 w:1)
+	/// Proof: `PooledStaking::DeferredSlashes` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn cancel_deferred_slashes() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `5035`
+		//  Estimated: `6520`
+		// Minimum execution time: 31_482_000 picoseconds.
+		Weight::from_parts(36_910_000, 6520)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `PooledStaking::CommissionBounds` (r:1 w:0)
+	/// Proof: `PooledStaking::CommissionBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `PooledStaking::PendingCommissionIncreases` (r:0 w:1)
+	/// Proof: `PooledStaking::PendingCommissionIncreases` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PooledStaking::CandidateCommission` (r:1 w:1)
+	/// Proof: `PooledStaking::CandidateCommission` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_commission() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `364`
+		//  Estimated: `3829`
+		// Minimum execution time: 18_210_000 picoseconds.
+		Weight::from_parts(21_404_000, 3829)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PooledStaking::PendingCommissionIncreases` (r:1 w:1)
+	/// Proof: `PooledStaking::PendingCommissionIncreases` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PooledStaking::CommissionBounds` (r:1 w:0)
+	/// Proof: `PooledStaking::CommissionBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `PooledStaking::CandidateCommission` (r:0 w:1)
+	/// Proof: `PooledStaking::CandidateCommission` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn execute_commission_increase() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `412`
+		//  Estimated: `3877`
+		// Minimum execution time: 19_652_000 picoseconds.
+		Weight::from_parts(22_870_000, 3877)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `PooledStaking::CommissionBounds` (r:0 w:1)
+	/// Proof: `PooledStaking::CommissionBounds` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	fn set_commission_bounds() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_012_000 picoseconds.
+		Weight::from_parts(9_256_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
\ No newline at end of file
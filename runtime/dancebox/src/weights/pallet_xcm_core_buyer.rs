@@ -54,12 +54,63 @@ use sp_std::marker::PhantomData;
 /// Weights for pallet_xcm_core_buyer using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> pallet_xcm_core_buyer::WeightInfo for SubstrateWeight<T> {
+	/// Storage: `XcmCoreBuyer::CollatorSignatureNonce` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::CollatorSignatureNonce` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `Session::CurrentIndex` (r:1 w:0)
+	/// Proof: `Session::CurrentIndex` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
 	/// Storage: `CollatorAssignment::CollatorContainerChain` (r:1 w:0)
 	/// Proof: `CollatorAssignment::CollatorContainerChain` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	/// Storage: `XcmCoreBuyer::InFlightOrders` (r:1 w:1)
 	/// Proof: `XcmCoreBuyer::InFlightOrders` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::LastOrderRelayBlockNumber` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::LastOrderRelayBlockNumber` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	/// Storage: `Registrar::ParathreadParams` (r:1 w:0)
 	/// Proof: `Registrar::ParathreadParams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::ConsecutiveFailedOrders` (r:1 w:0)
+	/// Proof: `XcmCoreBuyer::ConsecutiveFailedOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `AuthorNoting::LatestAuthor` (r:1 w:0)
+	/// Proof: `AuthorNoting::LatestAuthor` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::RelayXcmWeightConfig` (r:1 w:0)
+	/// Proof: `XcmCoreBuyer::RelayXcmWeightConfig` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `ServicesPayment::MaxCorePrice` (r:1 w:0)
+	/// Proof: `ServicesPayment::MaxCorePrice` (`max_values`: None, `max_size`: Some(36), added: 2511, mode: `MaxEncodedLen`)
+	/// Storage: `XcmCoreBuyer::RelayChain` (r:1 w:0)
+	/// Proof: `XcmCoreBuyer::RelayChain` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `ParachainSystem::UpwardDeliveryFeeFactor` (r:1 w:0)
+	/// Proof: `ParachainSystem::UpwardDeliveryFeeFactor` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::SupportedVersion` (r:1 w:0)
+	/// Proof: `PolkadotXcm::SupportedVersion` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::VersionDiscoveryQueue` (r:1 w:1)
+	/// Proof: `PolkadotXcm::VersionDiscoveryQueue` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::SafeXcmVersion` (r:1 w:0)
+	/// Proof: `PolkadotXcm::SafeXcmVersion` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `ParachainSystem::HostConfiguration` (r:1 w:0)
+	/// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
+	/// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::QueryIdToParaId` (r:0 w:1)
+	/// Proof: `XcmCoreBuyer::QueryIdToParaId` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// The range of component `x` is `[1, 99]`.
+	fn buy_core(x: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `887 + x * (4 ±0)`
+		//  Estimated: `4352 + x * (4 ±0)`
+		// Minimum execution time: 115_312_000 picoseconds.
+		Weight::from_parts(118_602_517, 4352)
+			// Standard Error: 1_402
+			.saturating_add(Weight::from_parts(52_391, 0).saturating_mul(x.into()))
+			.saturating_add(T::DbWeight::get().reads(17_u64))
+			.saturating_add(T::DbWeight::get().writes(6_u64))
+			.saturating_add(Weight::from_parts(0, 4).saturating_mul(x.into()))
+	}
+	/// Storage: `CollatorAssignment::CollatorContainerChain` (r:1 w:0)
+	/// Proof: `CollatorAssignment::CollatorContainerChain` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::InFlightOrders` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::InFlightOrders` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `Registrar::ParathreadParams` (r:1 w:0)
+	/// Proof: `Registrar::ParathreadParams` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `AuthorNoting::LatestAuthor` (r:1 w:0)
+	/// Proof: `AuthorNoting::LatestAuthor` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	/// Storage: `XcmCoreBuyer::RelayXcmWeightConfig` (r:1 w:0)
 	/// Proof: `XcmCoreBuyer::RelayXcmWeightConfig` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	/// Storage: `ServicesPayment::MaxCorePrice` (r:1 w:0)
@@ -87,7 +138,7 @@ impl<T: frame_system::Config> pallet_xcm_core_buyer::WeightInfo for SubstrateWei
 		Weight::from_parts(61_490_774, 4202)
 			// Standard Error: 1_131
 			.saturating_add(Weight::from_parts(39_945, 0).saturating_mul(x.into()))
-			.saturating_add(T::DbWeight::get().reads(12_u64))
+			.saturating_add(T::DbWeight::get().reads(13_u64))
 			.saturating_add(T::DbWeight::get().writes(3_u64))
 			.saturating_add(Weight::from_parts(0, 4).saturating_mul(x.into()))
 	}
@@ -111,4 +162,39 @@ impl<T: frame_system::Config> pallet_xcm_core_buyer::WeightInfo for SubstrateWei
 		Weight::from_parts(3_954_000, 0)
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `XcmCoreBuyer::OrderCooldown` (r:0 w:1)
+	/// Proof: `XcmCoreBuyer::OrderCooldown` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_order_cooldown() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 3_786_000 picoseconds.
+		Weight::from_parts(3_954_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmCoreBuyer::QueryIdToParaId` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::QueryIdToParaId` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::InFlightOrders` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::InFlightOrders` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmCoreBuyer::ConsecutiveFailedOrders` (r:1 w:1)
+	/// Proof: `XcmCoreBuyer::ConsecutiveFailedOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn query_response() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `170`
+		//  Estimated: `3635`
+		// Minimum execution time: 14_112_000 picoseconds.
+		Weight::from_parts(14_705_000, 3635)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: `XcmCoreBuyer::ConsecutiveFailedOrders` (r:0 w:1)
+	/// Proof: `XcmCoreBuyer::ConsecutiveFailedOrders` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn resume_core_buying() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 3_786_000 picoseconds.
+		Weight::from_parts(3_954_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
 }
\ No newline at end of file
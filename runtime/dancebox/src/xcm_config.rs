@@ -17,10 +17,11 @@
 use {
     super::{
         currency::MICRODANCE, weights::xcm::XcmWeight as XcmGenericWeights, AccountId,
-        AllPalletsWithSystem, AssetRate, Balance, Balances, CollatorAssignment, ForeignAssets,
-        ForeignAssetsCreator, MaintenanceMode, MessageQueue, ParachainInfo, ParachainSystem,
-        PolkadotXcm, Registrar, Runtime, RuntimeBlockWeights, RuntimeCall, RuntimeEvent,
-        RuntimeOrigin, System, TransactionByteFee, WeightToFee, XcmpQueue,
+        AllPalletsWithSystem, AssetRate, AuthorNoting, AuthorityAssignment, Balance, Balances,
+        CurrentSessionIndexGetter, ForeignAssets, ForeignAssetsCreator, MaintenanceMode,
+        MessageQueue, ParachainInfo, ParachainSystem, PolkadotXcm, Registrar, Runtime,
+        RuntimeBlockWeights, RuntimeCall, RuntimeEvent, RuntimeOrigin, Session, TransactionByteFee,
+        WeightToFee, XcmpQueue,
     },
     crate::weights,
     cumulus_primitives_core::{AggregateMessageOrigin, ParaId},
@@ -30,11 +31,12 @@ use {
         traits::{Everything, Nothing, PalletInfoAccess, TransformOrigin},
         weights::Weight,
     },
-    frame_system::EnsureRoot,
+    frame_system::{pallet_prelude::BlockNumberFor, EnsureRoot},
+    nimbus_primitives::NimbusId,
     pallet_xcm::XcmPassthrough,
     pallet_xcm_core_buyer::{
-        GetParathreadCollators, GetParathreadMaxCorePrice, GetParathreadParams,
-        GetPurchaseCoreCall, ParaIdIntoAccountTruncating,
+        GetLatestNotedSlot, GetParathreadCollators, GetParathreadMaxCorePrice, GetPurchaseCoreCall,
+        ParaIdIntoAccountTruncating, XcmNotifier,
     },
     parachains_common::message_queue::{NarrowOriginToSibling, ParaIdToSibling},
     parity_scale_codec::{Decode, Encode},
@@ -53,7 +55,7 @@ use {
         WeightInfoBounds, WithComputedOrigin,
     },
     staging_xcm_executor::{traits::JustTry, XcmExecutor},
-    tp_traits::ParathreadParams,
+    tp_traits::{CorePurchaseBackend, Slot},
 };
 
 parameter_types! {
@@ -475,6 +477,11 @@ impl pallet_message_queue::Config for Runtime {
 parameter_types! {
     pub const ParasUnsignedPriority: TransactionPriority = TransactionPriority::MAX;
     pub const XcmBuyExecutionDotRococo: u128 = XCM_BUY_EXECUTION_COST_ROCOCO;
+    // Relay blocks after which an order to buy a core is considered unused
+    pub const CoreBuyingInFlightOrderTtl: u32 = 5;
+    // Blocks to wait for the transact status of an order before pallet_xcm times out the query
+    pub const CoreBuyingXcmQueryTtl: u32 = 100;
+    pub const MaxConsecutiveFailedCoreBuyingOrders: u32 = 5;
 }
 
 pub const XCM_BUY_EXECUTION_COST_ROCOCO: u128 = 50_000_000;
@@ -484,61 +491,80 @@ impl pallet_xcm_core_buyer::Config for Runtime {
     type Currency = Balances;
 
     type XcmSender = XcmRouter;
+    type RuntimeCall = RuntimeCall;
+    type XcmResponseOrigin = pallet_xcm::EnsureResponse<Everything>;
+    type XcmNotifier = XcmNotifierImpl;
+    type UniversalLocation = UniversalLocation;
+    type CoreBuyingXcmQueryTtl = CoreBuyingXcmQueryTtl;
+    type MaxConsecutiveFailedOrders = MaxConsecutiveFailedCoreBuyingOrders;
     type GetPurchaseCoreCall = EncodedCallToBuyCore;
-    type GetBlockNumber = GetBlockNumber;
     type GetParathreadAccountId = ParaIdIntoAccountTruncating;
     type GetParathreadMaxCorePrice = GetMaxCorePriceFromServicesPayment;
     type SelfParaId = parachain_info::Pallet<Runtime>;
     type RelayChain = RelayChain;
     type MaxParathreads = ConstU32<100>;
-    type GetParathreadParams = GetParathreadParamsImpl;
+    type RelayChainBlockNumberProvider =
+        cumulus_pallet_parachain_system::RelaychainDataProvider<Runtime>;
+    type InFlightOrderTtl = CoreBuyingInFlightOrderTtl;
+    type MaxExpiredOrdersPerBlock = ConstU32<10>;
+    type GetParathreadParams = Registrar;
+    type GetLatestNotedSlot = GetLatestNotedSlotImpl;
+    type SlotBeacon = dp_consensus::AuraDigestSlotBeacon<Runtime>;
     type GetAssignedCollators = GetAssignedCollatorsImpl;
+    type CollatorPublicKey = NimbusId;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
     type UnsignedPriority = ParasUnsignedPriority;
     type WeightInfo = weights::pallet_xcm_core_buyer::SubstrateWeight<Runtime>;
 }
 
-pub struct GetBlockNumber;
+pub struct XcmNotifierImpl;
 
-impl Get<u32> for GetBlockNumber {
-    fn get() -> u32 {
-        System::block_number()
+impl XcmNotifier<Runtime> for XcmNotifierImpl {
+    fn new_notify_query(
+        responder: MultiLocation,
+        notify: RuntimeCall,
+        timeout: BlockNumberFor<Runtime>,
+        match_querier: MultiLocation,
+    ) -> QueryId {
+        pallet_xcm::Pallet::<Runtime>::new_notify_query(responder, notify, timeout, match_querier)
     }
 }
 
-pub struct GetParathreadParamsImpl;
+pub struct GetLatestNotedSlotImpl;
 
-impl GetParathreadParams for GetParathreadParamsImpl {
-    fn get_parathread_params(para_id: ParaId) -> Option<ParathreadParams> {
-        Registrar::parathread_params(para_id)
-    }
-
-    #[cfg(feature = "runtime-benchmarks")]
-    fn set_parathread_params(para_id: ParaId, parathread_params: Option<ParathreadParams>) {
-        if let Some(parathread_params) = parathread_params {
-            pallet_registrar::ParathreadParams::<Runtime>::insert(para_id, parathread_params);
-        } else {
-            pallet_registrar::ParathreadParams::<Runtime>::remove(para_id);
-        }
+impl GetLatestNotedSlot for GetLatestNotedSlotImpl {
+    fn get_latest_noted_slot(para_id: ParaId) -> Option<Slot> {
+        AuthorNoting::latest_author(para_id).map(|info| info.latest_slot_number)
     }
 }
 
 pub struct GetAssignedCollatorsImpl;
 
-impl GetParathreadCollators<AccountId> for GetAssignedCollatorsImpl {
-    fn get_parathread_collators(para_id: ParaId) -> Vec<AccountId> {
+impl GetParathreadCollators<NimbusId> for GetAssignedCollatorsImpl {
+    fn get_parathread_collators(para_id: ParaId) -> Vec<NimbusId> {
         // We do not need to check if the para_id is a valid parathread,
         // because that is already being checked by `GetParathreadParams`.
-        CollatorAssignment::collator_container_chain()
-            .container_chains
-            .get(&para_id)
-            .cloned()
+        // Use the nimbus keys of the current session, because those are the keys that collators
+        // use to sign the `BuyCoreCollatorProof`.
+        AuthorityAssignment::collator_container_chain(Session::current_index())
+            .and_then(|assigned_collators| {
+                assigned_collators.container_chains.get(&para_id).cloned()
+            })
             .unwrap_or_default()
     }
 
     #[cfg(feature = "runtime-benchmarks")]
-    fn set_parathread_collators(para_id: ParaId, collators: Vec<AccountId>) {
-        use tp_traits::GetContainerChainAuthor;
-        CollatorAssignment::set_authors_for_para_id(para_id, collators);
+    fn set_parathread_collators(para_id: ParaId, collators: Vec<NimbusId>) {
+        let session_index = Session::current_index();
+        let mut assigned_collators =
+            AuthorityAssignment::collator_container_chain(session_index).unwrap_or_default();
+        assigned_collators
+            .container_chains
+            .insert(para_id, collators);
+        pallet_authority_assignment::CollatorContainerChain::<Runtime>::insert(
+            session_index,
+            assigned_collators,
+        );
     }
 }
 
@@ -554,29 +580,45 @@ pub enum RelayChain {
 pub struct EncodedCallToBuyCore;
 
 impl GetPurchaseCoreCall<RelayChain> for EncodedCallToBuyCore {
-    fn get_encoded(relay_chain: RelayChain, max_amount: u128, para_id: ParaId) -> Vec<u8> {
-        match relay_chain {
+    fn get_encoded(
+        relay_chain: RelayChain,
+        core_purchase_backend: CorePurchaseBackend,
+        max_amount: u128,
+        para_id: ParaId,
+    ) -> Option<Vec<u8>> {
+        match core_purchase_backend {
+            CorePurchaseBackend::OnDemand => {}
+            // TODO: support coretime credits once the relay runtime we depend on has
+            // `place_order_with_credits`, until then parathreads using it cannot buy cores
+            CorePurchaseBackend::CoretimeCredits => return None,
+        }
+
+        let call = match relay_chain {
             RelayChain::Westend => {
-                let call = tanssi_relay_encoder::westend::RelayCall::OnDemandAssignmentProvider(
-                    tanssi_relay_encoder::westend::OnDemandAssignmentProviderCall::PlaceOrderAllowDeath {
+                use tanssi_relay_encoder::westend::{OnDemandAssignmentProviderCall, RelayCall};
+
+                RelayCall::OnDemandAssignmentProvider(
+                    OnDemandAssignmentProviderCall::PlaceOrderAllowDeath {
                         max_amount,
                         para_id,
                     },
-                );
-
-                call.encode()
+                )
+                .encode()
             }
             RelayChain::Rococo => {
-                let call = tanssi_relay_encoder::rococo::RelayCall::OnDemandAssignmentProvider(
-                    tanssi_relay_encoder::rococo::OnDemandAssignmentProviderCall::PlaceOrderAllowDeath {
+                use tanssi_relay_encoder::rococo::{OnDemandAssignmentProviderCall, RelayCall};
+
+                RelayCall::OnDemandAssignmentProvider(
+                    OnDemandAssignmentProviderCall::PlaceOrderAllowDeath {
                         max_amount,
                         para_id,
                     },
-                );
-
-                call.encode()
+                )
+                .encode()
             }
-        }
+        };
+
+        Some(call)
     }
 }
 
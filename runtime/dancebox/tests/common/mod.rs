@@ -46,7 +46,7 @@ pub use dancebox_runtime::{
     CollatorAssignment, Configuration, DataPreservers, ForeignAssets, ForeignAssetsCreator,
     InflationRewards, Initializer, Invulnerables, MinimumSelfDelegation, ParachainInfo,
     PooledStaking, Proxy, ProxyType, Registrar, RewardsPortion, Runtime, RuntimeCall,
-    ServicesPayment, Session, System, TransactionPayment,
+    ServicesPayment, Session, System, TransactionPayment, XcmCoreBuyer,
 };
 
 mod xcm;
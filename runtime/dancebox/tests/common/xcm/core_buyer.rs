@@ -150,7 +150,7 @@ fn do_test(tank_account_balance: u128, set_max_core_price: Option<u128>) {
             Dancebox,
             vec![
                 RuntimeEvent::XcmCoreBuyer(
-                    pallet_xcm_core_buyer::Event::BuyCoreXcmSent { para_id }
+                    pallet_xcm_core_buyer::Event::BuyCoreXcmSent { para_id, .. }
                 ) => {
                     para_id: *para_id == ParaId::from(PARATHREAD_ID),
                 },
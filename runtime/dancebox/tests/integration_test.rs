@@ -20,40 +20,53 @@ use {
     common::*,
     cumulus_primitives_core::ParaId,
     dancebox_runtime::{
-        RewardsCollatorCommission, StreamPayment, StreamPaymentAssetId, TimeUnit,
-        TransactionPayment,
+        BaseBlockProductionCost, BaseCollatorAssignmentCost, BlockProductionCost,
+        OfflineSlashFraction, RewardsCollatorCommission, ServicesPaymentBurnShare,
+        ServicesPaymentTreasuryShare, StreamPayment, StreamPaymentAssetId, StreamPaymentAssets,
+        TimeUnit, TransactionPayment,
     },
     dp_consensus::runtime_decl_for_tanssi_authority_assignment_api::TanssiAuthorityAssignmentApiV1,
     dp_core::well_known_keys,
-    frame_support::{assert_noop, assert_ok, BoundedVec},
+    frame_support::{
+        assert_noop, assert_ok,
+        traits::{fungible::InspectHold, OnInitialize},
+        BoundedVec,
+    },
     frame_system::ConsumedWeight,
-    nimbus_primitives::NIMBUS_KEY_ID,
+    nimbus_primitives::{NimbusId, NIMBUS_KEY_ID},
     pallet_author_noting::ContainerChainBlockInfo,
     pallet_author_noting_runtime_api::runtime_decl_for_author_noting_api::AuthorNotingApi,
     pallet_balances::Instance1,
-    pallet_collator_assignment_runtime_api::runtime_decl_for_collator_assignment_api::CollatorAssignmentApi,
+    pallet_collator_assignment_runtime_api::{
+        runtime_decl_for_collator_assignment_api::CollatorAssignmentApi, DroppedParaReason,
+    },
     pallet_migrations::Migration,
     pallet_pooled_staking::{
-        traits::IsCandidateEligible, AllTargetPool, EligibleCandidate, PendingOperationKey,
-        PendingOperationQuery, PoolsKey, SharesOrStake, TargetPool,
+        traits::IsCandidateEligible, AllTargetPool, DeferredSlash, EligibleCandidate,
+        PendingOperationKey, PendingOperationQuery, PoolsKey, SharesOrStake, TargetPool,
     },
     pallet_registrar_runtime_api::{
         runtime_decl_for_registrar_api::RegistrarApi, ContainerChainGenesisData,
     },
+    pallet_services_payment::ProvideBlockProductionCost,
+    pallet_services_payment_runtime_api::runtime_decl_for_services_payment_api::ServicesPaymentApi,
+    pallet_xcm_core_buyer::BuyCoreCollatorProof,
     parity_scale_codec::Encode,
     runtime_common::migrations::{
-        MigrateConfigurationParathreads, MigrateServicesPaymentAddCollatorAssignmentCredits,
+        MigrateConfigurationParathreads, MigrateConfigurationStakeWeighted,
+        MigrateServicesPaymentAddCollatorAssignmentCredits,
     },
     sp_consensus_aura::AURA_ENGINE_ID,
-    sp_core::Get,
+    sp_core::{Get, Pair},
     sp_runtime::{
-        traits::{BadOrigin, BlakeTwo256, OpaqueKeys},
-        DigestItem, FixedU128,
+        traits::{BadOrigin, BlakeTwo256, OpaqueKeys, ValidateUnsigned},
+        transaction_validity::{InvalidTransaction, TransactionSource},
+        DigestItem, FixedU128, Perbill, TokenError,
     },
     sp_std::vec,
     staging_xcm::latest::prelude::*,
     test_relay_sproof_builder::{HeaderAs, ParaHeaderSproofBuilder, ParaHeaderSproofBuilderItem},
-    tp_traits::SlotFrequency,
+    tp_traits::{CollatorRequirements, SlotFrequency},
 };
 
 mod common;
@@ -684,6 +697,92 @@ fn test_paras_registered_but_zero_credits() {
         });
 }
 
+#[test]
+fn test_paras_registered_with_credits_paid_with_foreign_assets() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+
+            assert_ok!(Registrar::register(
+                origin_of(ALICE.into()),
+                1001.into(),
+                empty_genesis_data()
+            ));
+            assert_ok!(DataPreservers::set_boot_nodes(
+                origin_of(ALICE.into()),
+                1001.into(),
+                dummy_boot_nodes()
+            ));
+            assert_ok!(Registrar::mark_valid_for_collating(
+                root_origin(),
+                1001.into()
+            ));
+            // Need to reset credits to 0 because now parachains are given free credits on register
+            assert_ok!(ServicesPayment::set_block_production_credits(
+                root_origin(),
+                1001.into(),
+                0
+            ));
+
+            // Register a foreign asset worth 2 native tokens, and use it to fill the tank with
+            // enough funds for a session
+            assert_ok!(ForeignAssetsCreator::create_foreign_asset(
+                root_origin(),
+                MultiLocation::parent(),
+                1,
+                AccountId::from(ALICE),
+                true,
+                1
+            ));
+            assert_ok!(AssetRate::create(
+                root_origin(),
+                Box::new(1),
+                FixedU128::from_u32(2)
+            ));
+            let asset_credits_1001 =
+                block_credits_to_required_balance(dancebox_runtime::Period::get(), 1001.into());
+            assert_ok!(ForeignAssets::mint(
+                origin_of(ALICE.into()),
+                1,
+                AccountId::from(ALICE).into(),
+                asset_credits_1001 * 2
+            ));
+            assert_ok!(ServicesPayment::purchase_credits_with_asset(
+                origin_of(ALICE.into()),
+                1001.into(),
+                1,
+                asset_credits_1001
+            ));
+
+            // Assignment should happen after 2 sessions
+            run_to_session(1u32);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert!(assignment.container_chains.is_empty());
+            run_to_session(2u32);
+
+            // Charlie and Dave should be assigned to para 1001
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into(), DAVE.into()]
+            );
+        });
+}
+
 #[test]
 fn test_paras_registered_but_not_enough_credits() {
     ExtBuilder::default()
@@ -867,7 +966,21 @@ fn test_paras_registered_but_only_credits_for_1_session() {
                     .unwrap_or_default();
             assert_eq!(credits, credits_1001 - 1);
 
+            // Para 1001 keeps its collators during the grace period
             run_to_session(4u32);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into(), DAVE.into()]
+            );
+            assert_eq!(
+                pallet_services_payment::GracePeriodSessionsLeft::<Runtime>::get(ParaId::from(
+                    1001
+                )),
+                Some(0)
+            );
+
+            run_to_session(5u32);
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -1701,6 +1814,97 @@ fn test_author_noting_not_self_para() {
         });
 }
 
+#[test]
+fn test_author_noting_slashes_offline_collators() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+        ])
+        .build()
+        .execute_with(|| {
+            let mut sproof = ParaHeaderSproofBuilder::default();
+            let slot: u64 = 5;
+            let other_para: ParaId = 1001u32.into();
+
+            // Charlie and Dave to 1001
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into(), DAVE.into()]
+            );
+
+            // Dave authors a block of 1001 while Charlie never does
+            let s = ParaHeaderSproofBuilderItem {
+                para_id: other_para,
+                author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                    parent_hash: Default::default(),
+                    number: 1,
+                    state_root: Default::default(),
+                    extrinsics_root: Default::default(),
+                    digest: sp_runtime::generic::Digest {
+                        logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                    },
+                }),
+            };
+            sproof.items.push(s);
+
+            set_author_noting_inherent_data(sproof);
+            assert!(AuthorNoting::authored_since_session(&DAVE.into(), 0));
+
+            let stake = MinimumSelfDelegation::get();
+            assert_ok!(PooledStaking::request_delegate(
+                origin_of(CHARLIE.into()),
+                CHARLIE.into(),
+                TargetPool::AutoCompounding,
+                stake
+            ));
+
+            // Charlie is slashed when session 0 ends, but the slash is deferred
+            run_to_session(1);
+            assert_eq!(
+                pallet_pooled_staking::DeferredSlashes::<Runtime>::get().into_inner(),
+                vec![DeferredSlash {
+                    candidate: CHARLIE.into(),
+                    fraction: OfflineSlashFraction::get(),
+                    at: 1,
+                }]
+            );
+            assert_eq!(
+                pallet_pooled_staking::Pools::<Runtime>::get(
+                    AccountId::from(CHARLIE),
+                    PoolsKey::CandidateTotalStake
+                ),
+                stake,
+            );
+
+            // Nobody authored 1001 during the following sessions, so Charlie isn't slashed again
+            run_to_session(3);
+            run_block();
+            assert!(pallet_pooled_staking::DeferredSlashes::<Runtime>::get().is_empty());
+            assert_eq!(
+                pallet_pooled_staking::Pools::<Runtime>::get(
+                    AccountId::from(CHARLIE),
+                    PoolsKey::CandidateTotalStake
+                ),
+                stake - OfflineSlashFraction::get().mul_floor(stake),
+            );
+        });
+}
+
 #[test]
 fn test_author_noting_set_author_and_kill_author_data() {
     ExtBuilder::default()
@@ -3465,6 +3669,67 @@ fn test_staking_swap() {
     )
 }
 
+#[test]
+fn test_staking_commission_increase_is_delayed() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+
+            // Bounds default to the previous constant commission
+            assert_eq!(
+                PooledStaking::commission(&ALICE.into()),
+                RewardsCollatorCommission::get()
+            );
+
+            assert_ok!(PooledStaking::set_commission_bounds(
+                root_origin(),
+                Perbill::from_percent(5),
+                Perbill::from_percent(30)
+            ));
+
+            // Decreases are immediate
+            assert_ok!(PooledStaking::set_commission(
+                origin_of(ALICE.into()),
+                Perbill::from_percent(5)
+            ));
+            assert_eq!(
+                PooledStaking::commission(&ALICE.into()),
+                Perbill::from_percent(5)
+            );
+
+            // Increases must wait longer than leaving the pools
+            assert_ok!(PooledStaking::set_commission(
+                origin_of(ALICE.into()),
+                Perbill::from_percent(30)
+            ));
+            run_to_session(2);
+            assert_noop!(
+                PooledStaking::execute_commission_increase(origin_of(BOB.into()), ALICE.into()),
+                pallet_pooled_staking::Error::<Runtime>::CommissionIncreaseNotReady
+            );
+
+            run_to_session(3);
+            assert_ok!(PooledStaking::execute_commission_increase(
+                origin_of(BOB.into()),
+                ALICE.into()
+            ));
+            assert_eq!(
+                PooledStaking::commission(&ALICE.into()),
+                Perbill::from_percent(30)
+            );
+        });
+}
+
 #[test]
 fn test_pallet_session_takes_validators_from_invulnerables_and_staking() {
     // Alice, Bob, Charlie are invulnerables
@@ -4029,6 +4294,9 @@ fn test_migration_config_full_rotation_period() {
 
             let migration = MigrateConfigurationParathreads::<Runtime>(Default::default());
             migration.migrate(Default::default());
+            // Later migrations bring the config to the current layout
+            let migration = MigrateConfigurationStakeWeighted::<Runtime>(Default::default());
+            migration.migrate(Default::default());
 
             let expected_active = pallet_configuration::HostConfiguration {
                 max_collators: 99,
@@ -4068,6 +4336,54 @@ fn test_migration_config_full_rotation_period() {
         });
 }
 
+#[test]
+fn test_migration_config_stake_weighted() {
+    ExtBuilder::default().build().execute_with(|| {
+        const CONFIGURATION_ACTIVE_CONFIG_KEY: &[u8] =
+            &hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22db4b49d95320d9021994c850f25b8e385");
+        const CONFIGURATION_PENDING_CONFIGS_KEY: &[u8] =
+            &hex_literal::hex!("06de3d8a54d27e44a9d5ce189618f22d53b4123b2e186e07fb7bad5dda5f55c0");
+
+        // Modify active config
+        frame_support::storage::unhashed::put_raw(
+            CONFIGURATION_ACTIVE_CONFIG_KEY,
+            &hex_literal::hex!("630000000200000005000000020000000000000003000000020000000008af2f"),
+        );
+        // Modify pending configs
+        frame_support::storage::unhashed::put_raw(
+            CONFIGURATION_PENDING_CONFIGS_KEY,
+            &hex_literal::hex!(
+                "04b1080000640000000200000005000000020000000000000003000000020000000008af2f"
+            ),
+        );
+
+        let migration = MigrateConfigurationStakeWeighted::<Runtime>(Default::default());
+        migration.migrate(Default::default());
+
+        let expected_active = pallet_configuration::HostConfiguration {
+            max_collators: 99,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 5,
+            collators_per_container: 2,
+            full_rotation_period: 0,
+            collators_per_parathread: 3,
+            parathreads_per_collator: 2,
+            target_container_chain_fullness: Perbill::from_percent(80),
+            stake_weighted_collator_selection: false,
+        };
+        assert_eq!(Configuration::config(), expected_active);
+
+        let expected_pending = vec![(
+            2225,
+            pallet_configuration::HostConfiguration {
+                max_collators: 100,
+                ..expected_active
+            },
+        )];
+        assert_eq!(Configuration::pending_configs(), expected_pending);
+    });
+}
+
 #[test]
 fn test_collator_assignment_gives_priority_to_invulnerables() {
     // Set max_collators = 2, take 1 invulnerable and the rest from staking
@@ -4700,8 +5016,8 @@ fn test_ed_plus_block_credit_session_purchase_works() {
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(3u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(3u32 + dancebox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -4884,8 +5200,8 @@ fn test_reassignment_ed_plus_two_block_credit_session_purchase_works() {
                 vec![CHARLIE.into(), DAVE.into()]
             );
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + dancebox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -4987,8 +5303,8 @@ fn test_reassignment_ed_plus_two_block_credit_session_minus_1_purchase_fails() {
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(3u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(3u32 + dancebox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -5138,106 +5454,400 @@ fn stream_payment_works() {
 }
 
 #[test]
-fn test_ed_plus_collator_assignment_session_purchase_works() {
+fn stream_payment_container_block_number_works() {
     ExtBuilder::default()
         .with_balances(vec![
-            // Alice gets 10k extra tokens for her mapping deposit
-            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(ALICE), 100_000 * UNIT),
             (AccountId::from(BOB), 100_000 * UNIT),
             (AccountId::from(CHARLIE), 100_000 * UNIT),
-            (AccountId::from(DAVE), 100_000 * UNIT),
         ])
         .with_collators(vec![
-            (AccountId::from(ALICE), 210 * UNIT),
-            (AccountId::from(BOB), 100 * UNIT),
             (AccountId::from(CHARLIE), 100 * UNIT),
             (AccountId::from(DAVE), 100 * UNIT),
         ])
         .build()
         .execute_with(|| {
-            run_to_block(2);
-            // Assert current slot gets updated
-            assert_eq!(current_slot(), 1u64);
-            assert!(current_author() == AccountId::from(BOB));
+            use pallet_stream_payment::StreamConfig;
 
-            // Alice and Bob collate in our chain
-            let alice_id = get_aura_id_from_seed(&AccountId::from(ALICE).to_string());
-            let bob_id = get_aura_id_from_seed(&AccountId::from(BOB).to_string());
+            let config = StreamConfig {
+                rate: 2 * UNIT,
+                asset_id: StreamPaymentAssetId::Native,
+                time_unit: TimeUnit::ContainerBlockNumber(1001.into()),
+            };
 
-            assert_eq!(authorities(), vec![alice_id, bob_id]);
+            // Container chain has not produced any block yet.
+            assert_noop!(
+                StreamPayment::open_stream(
+                    origin_of(ALICE.into()),
+                    BOB.into(),
+                    config,
+                    1_000 * UNIT,
+                ),
+                pallet_stream_payment::Error::<Runtime>::CantFetchCurrentTime
+            );
 
-            assert_ok!(Registrar::register(
-                origin_of(ALICE.into()),
+            assert_ok!(AuthorNoting::set_author(
+                root_origin(),
                 1001.into(),
-                empty_genesis_data()
+                1,
+                AccountId::from(CHARLIE),
+                1.into()
             ));
-            assert_ok!(DataPreservers::set_boot_nodes(
+            assert_ok!(StreamPayment::open_stream(
                 origin_of(ALICE.into()),
-                1001.into(),
-                dummy_boot_nodes()
+                BOB.into(),
+                config,
+                1_000 * UNIT,
             ));
-            assert_ok!(Registrar::mark_valid_for_collating(
-                root_origin(),
-                1001.into()
-            ));
-            // Need to reset credits to 0 because now parachains are given free credits on register
-            assert_ok!(ServicesPayment::set_collator_assignment_credits(
+
+            // Blocks of the orchestrator chain are not paid.
+            run_block();
+            assert_ok!(StreamPayment::perform_payment(origin_of(CHARLIE.into()), 0));
+            assert_eq!(Balances::free_balance(AccountId::from(BOB)), 100_000 * UNIT);
+
+            // Only container chain blocks are.
+            assert_ok!(AuthorNoting::set_author(
                 root_origin(),
                 1001.into(),
-                0
+                6,
+                AccountId::from(DAVE),
+                6.into()
             ));
-            let credits_1001 = collator_assignment_credits_to_required_balance(1, 1001.into())
-                + dancebox_runtime::EXISTENTIAL_DEPOSIT;
+            assert_ok!(StreamPayment::perform_payment(origin_of(CHARLIE.into()), 0));
+            assert_eq!(
+                Balances::free_balance(AccountId::from(BOB)),
+                100_000 * UNIT + 10 * UNIT
+            );
+        });
+}
 
-            // Fill the tank
-            assert_ok!(ServicesPayment::purchase_credits(
+#[test]
+fn stream_payment_with_foreign_assets_works() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 100_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            use pallet_stream_payment::{ChangeKind, DepositChange, StreamConfig};
+
+            assert_ok!(ForeignAssetsCreator::create_foreign_asset(
+                root_origin(),
+                MultiLocation::parent(),
+                1,
+                AccountId::from(ALICE),
+                true,
+                1
+            ));
+            assert_ok!(ForeignAssets::mint(
                 origin_of(ALICE.into()),
-                1001.into(),
-                credits_1001
+                1,
+                AccountId::from(ALICE).into(),
+                10_000 * UNIT
             ));
 
-            // Assignment should happen after 2 sessions
-            run_to_session(1u32);
-            let assignment = CollatorAssignment::collator_container_chain();
-            assert!(assignment.container_chains.is_empty());
-            run_to_session(2u32);
-            // Charlie and Dave should be assigned to para 1001
-            let assignment = CollatorAssignment::collator_container_chain();
+            assert_ok!(StreamPayment::open_stream(
+                origin_of(ALICE.into()),
+                BOB.into(),
+                StreamConfig {
+                    rate: 2 * UNIT,
+                    asset_id: StreamPaymentAssetId::ForeignAssets(1),
+                    time_unit: TimeUnit::BlockNumber,
+                },
+                1_000 * UNIT,
+            ));
+            // The minimum balance of the asset is also kept in the deposit account.
             assert_eq!(
-                assignment.container_chains[&1001u32.into()],
-                vec![CHARLIE.into(), DAVE.into()]
+                ForeignAssets::balance(1, AccountId::from(ALICE)),
+                9_000 * UNIT - 1
             );
 
-            // Simulate block inclusion from container chain 1001
-            let mut sproof: ParaHeaderSproofBuilder = ParaHeaderSproofBuilder::default();
-            let slot: u64 = 5;
-            let s = ParaHeaderSproofBuilderItem {
-                para_id: 1001.into(),
-                author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
-                    parent_hash: Default::default(),
-                    number: 1,
-                    state_root: Default::default(),
-                    extrinsics_root: Default::default(),
-                    digest: sp_runtime::generic::Digest {
-                        logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
-                    },
-                }),
+            run_block();
+
+            assert_ok!(StreamPayment::perform_payment(origin_of(CHARLIE.into()), 0));
+            assert_eq!(ForeignAssets::balance(1, AccountId::from(BOB)), 2 * UNIT);
+            assert_eq!(
+                ForeignAssets::balance(
+                    1,
+                    StreamPaymentAssets::foreign_assets_deposit_account(&AccountId::from(ALICE))
+                ),
+                998 * UNIT + 1
+            );
+
+            let native_config = StreamConfig {
+                rate: 2 * UNIT,
+                asset_id: StreamPaymentAssetId::Native,
+                time_unit: TimeUnit::BlockNumber,
             };
-            sproof.items.push(s);
-            set_author_noting_inherent_data(sproof);
+
+            // Source must provide the new deposit when changing asset.
+            assert_noop!(
+                StreamPayment::request_change(
+                    origin_of(ALICE.into()),
+                    0,
+                    ChangeKind::Suggestion,
+                    native_config,
+                    None,
+                ),
+                pallet_stream_payment::Error::<Runtime>::ChangingAssetRequiresAbsoluteDepositChange
+            );
+
+            assert_ok!(StreamPayment::request_change(
+                origin_of(BOB.into()),
+                0,
+                ChangeKind::Suggestion,
+                native_config,
+                None,
+            ));
+
+            // Same when the source accepts a change of asset requested by the target.
+            assert_noop!(
+                StreamPayment::accept_requested_change(
+                    origin_of(ALICE.into()),
+                    0,
+                    1, // nonce
+                    None,
+                ),
+                pallet_stream_payment::Error::<Runtime>::ChangingAssetRequiresAbsoluteDepositChange
+            );
+
+            assert_ok!(StreamPayment::accept_requested_change(
+                origin_of(ALICE.into()),
+                0,
+                1, // nonce
+                Some(DepositChange::Absolute(500 * UNIT)),
+            ));
+
+            // Foreign asset deposit is refunded and the new deposit is held in native tokens.
+            assert_eq!(
+                ForeignAssets::balance(1, AccountId::from(ALICE)),
+                10_000 * UNIT - 2 * UNIT
+            );
+            assert_eq!(
+                ForeignAssets::balance(
+                    1,
+                    StreamPaymentAssets::foreign_assets_deposit_account(&AccountId::from(ALICE))
+                ),
+                0
+            );
+            assert_eq!(
+                Balances::balance_on_hold(
+                    &pallet_stream_payment::HoldReason::StreamPayment.into(),
+                    &AccountId::from(ALICE)
+                ),
+                500 * UNIT
+            );
+
+            let stream = pallet_stream_payment::Streams::<Runtime>::get(0).unwrap();
+            assert_eq!(stream.config, native_config);
+            assert_eq!(stream.deposit, 500 * UNIT);
+        });
+}
+
+#[test]
+fn stream_payment_with_foreign_assets_can_leave_deposit_below_minimum_balance() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 100_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            use pallet_stream_payment::{Assets, StreamConfig};
+
+            let asset_id = StreamPaymentAssetId::ForeignAssets(1);
+            let deposit_account =
+                StreamPaymentAssets::foreign_assets_deposit_account(&AccountId::from(ALICE));
+
+            assert_ok!(ForeignAssetsCreator::create_foreign_asset(
+                root_origin(),
+                MultiLocation::parent(),
+                1,
+                AccountId::from(ALICE),
+                true,
+                2 * UNIT
+            ));
+            assert_ok!(ForeignAssets::mint(
+                origin_of(ALICE.into()),
+                1,
+                AccountId::from(ALICE).into(),
+                10_000 * UNIT
+            ));
+
+            assert_ok!(StreamPayment::open_stream(
+                origin_of(ALICE.into()),
+                BOB.into(),
+                StreamConfig {
+                    rate: 2 * UNIT,
+                    asset_id,
+                    time_unit: TimeUnit::BlockNumber,
+                },
+                3 * UNIT,
+            ));
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
-            // Nobody should be assigned to para 1001
-            let assignment = CollatorAssignment::collator_container_chain();
-            assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
+            // Only 1 UNIT of deposit is left, which is below the minimum balance of the asset.
+            assert_ok!(StreamPayment::perform_payment(origin_of(CHARLIE.into()), 0));
+            assert_eq!(ForeignAssets::balance(1, AccountId::from(BOB)), 2 * UNIT);
+            assert_eq!(
+                StreamPaymentAssets::get_deposit(&asset_id, &AccountId::from(ALICE)),
+                UNIT
+            );
+            assert_eq!(ForeignAssets::balance(1, deposit_account.clone()), 3 * UNIT);
+
+            // Closing the stream refunds the deposit along with the minimum balance.
+            assert_ok!(StreamPayment::close_stream(origin_of(ALICE.into()), 0));
+            assert_eq!(
+                ForeignAssets::balance(1, AccountId::from(ALICE)),
+                10_000 * UNIT - 2 * UNIT
+            );
+            assert_eq!(ForeignAssets::balance(1, deposit_account), 0);
         });
 }
 
 #[test]
-fn test_ed_plus_collator_assignment_credit_session_minus_1_purchase_fails() {
+fn stream_payment_with_foreign_assets_is_not_affected_by_minimum_balance_changes() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 100_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            use pallet_stream_payment::{Assets, StreamConfig};
+
+            let asset_id = StreamPaymentAssetId::ForeignAssets(1);
+            let deposit_account =
+                StreamPaymentAssets::foreign_assets_deposit_account(&AccountId::from(ALICE));
+
+            assert_ok!(ForeignAssetsCreator::create_foreign_asset(
+                root_origin(),
+                MultiLocation::parent(),
+                1,
+                AccountId::from(ALICE),
+                true,
+                2 * UNIT
+            ));
+            assert_ok!(ForeignAssets::mint(
+                origin_of(ALICE.into()),
+                1,
+                AccountId::from(ALICE).into(),
+                10_000 * UNIT
+            ));
+
+            assert_ok!(StreamPayment::open_stream(
+                origin_of(ALICE.into()),
+                BOB.into(),
+                StreamConfig {
+                    rate: 2 * UNIT,
+                    asset_id,
+                    time_unit: TimeUnit::BlockNumber,
+                },
+                10 * UNIT,
+            ));
+
+            assert_ok!(ForeignAssets::force_asset_status(
+                root_origin(),
+                1,
+                AccountId::from(ALICE).into(),
+                AccountId::from(ALICE).into(),
+                AccountId::from(ALICE).into(),
+                AccountId::from(ALICE).into(),
+                5 * UNIT,
+                true,
+                false,
+            ));
+
+            // The deposit doesn't depend on the current minimum balance of the asset.
+            assert_eq!(
+                StreamPaymentAssets::get_deposit(&asset_id, &AccountId::from(ALICE)),
+                10 * UNIT
+            );
+
+            run_block();
+
+            assert_ok!(StreamPayment::perform_payment(origin_of(CHARLIE.into()), 0));
+            assert_eq!(ForeignAssets::balance(1, AccountId::from(BOB)), 2 * UNIT);
+            assert_eq!(
+                StreamPaymentAssets::get_deposit(&asset_id, &AccountId::from(ALICE)),
+                8 * UNIT
+            );
+
+            // Closing the stream refunds the deposit along with the minimum balance.
+            assert_ok!(StreamPayment::close_stream(origin_of(ALICE.into()), 0));
+            assert_eq!(
+                ForeignAssets::balance(1, AccountId::from(ALICE)),
+                10_000 * UNIT - 2 * UNIT
+            );
+            assert_eq!(ForeignAssets::balance(1, deposit_account), 0);
+        });
+}
+
+#[test]
+fn stream_payment_with_non_sufficient_foreign_assets_is_rejected() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 100_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            use pallet_stream_payment::StreamConfig;
+
+            assert_ok!(ForeignAssetsCreator::create_foreign_asset(
+                root_origin(),
+                MultiLocation::parent(),
+                1,
+                AccountId::from(ALICE),
+                false,
+                1
+            ));
+            assert_ok!(ForeignAssets::mint(
+                origin_of(ALICE.into()),
+                1,
+                AccountId::from(ALICE).into(),
+                10_000 * UNIT
+            ));
+
+            assert_noop!(
+                StreamPayment::open_stream(
+                    origin_of(ALICE.into()),
+                    BOB.into(),
+                    StreamConfig {
+                        rate: 2 * UNIT,
+                        asset_id: StreamPaymentAssetId::ForeignAssets(1),
+                        time_unit: TimeUnit::BlockNumber,
+                    },
+                    1_000 * UNIT,
+                ),
+                TokenError::Unsupported,
+            );
+        });
+}
+
+#[test]
+fn test_ed_plus_collator_assignment_session_purchase_works() {
     ExtBuilder::default()
         .with_balances(vec![
             // Alice gets 10k extra tokens for her mapping deposit
@@ -5286,8 +5896,7 @@ fn test_ed_plus_collator_assignment_credit_session_minus_1_purchase_fails() {
                 0
             ));
             let credits_1001 = collator_assignment_credits_to_required_balance(1, 1001.into())
-                + dancebox_runtime::EXISTENTIAL_DEPOSIT
-                - 1;
+                + dancebox_runtime::EXISTENTIAL_DEPOSIT;
 
             // Fill the tank
             assert_ok!(ServicesPayment::purchase_credits(
@@ -5301,7 +5910,107 @@ fn test_ed_plus_collator_assignment_credit_session_minus_1_purchase_fails() {
             let assignment = CollatorAssignment::collator_container_chain();
             assert!(assignment.container_chains.is_empty());
             run_to_session(2u32);
-            // Charlie and Dave should not be assigned to para 1001
+            // Charlie and Dave should be assigned to para 1001
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into(), DAVE.into()]
+            );
+
+            // Simulate block inclusion from container chain 1001
+            let mut sproof: ParaHeaderSproofBuilder = ParaHeaderSproofBuilder::default();
+            let slot: u64 = 5;
+            let s = ParaHeaderSproofBuilderItem {
+                para_id: 1001.into(),
+                author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                    parent_hash: Default::default(),
+                    number: 1,
+                    state_root: Default::default(),
+                    extrinsics_root: Default::default(),
+                    digest: sp_runtime::generic::Digest {
+                        logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                    },
+                }),
+            };
+            sproof.items.push(s);
+            set_author_noting_inherent_data(sproof);
+
+            run_block();
+
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + dancebox_runtime::GracePeriodSessions::get());
+            // Nobody should be assigned to para 1001
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
+        });
+}
+
+#[test]
+fn test_ed_plus_collator_assignment_credit_session_minus_1_purchase_fails() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+            // Assert current slot gets updated
+            assert_eq!(current_slot(), 1u64);
+            assert!(current_author() == AccountId::from(BOB));
+
+            // Alice and Bob collate in our chain
+            let alice_id = get_aura_id_from_seed(&AccountId::from(ALICE).to_string());
+            let bob_id = get_aura_id_from_seed(&AccountId::from(BOB).to_string());
+
+            assert_eq!(authorities(), vec![alice_id, bob_id]);
+
+            assert_ok!(Registrar::register(
+                origin_of(ALICE.into()),
+                1001.into(),
+                empty_genesis_data()
+            ));
+            assert_ok!(DataPreservers::set_boot_nodes(
+                origin_of(ALICE.into()),
+                1001.into(),
+                dummy_boot_nodes()
+            ));
+            assert_ok!(Registrar::mark_valid_for_collating(
+                root_origin(),
+                1001.into()
+            ));
+            // Need to reset credits to 0 because now parachains are given free credits on register
+            assert_ok!(ServicesPayment::set_collator_assignment_credits(
+                root_origin(),
+                1001.into(),
+                0
+            ));
+            let credits_1001 = collator_assignment_credits_to_required_balance(1, 1001.into())
+                + dancebox_runtime::EXISTENTIAL_DEPOSIT
+                - 1;
+
+            // Fill the tank
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                1001.into(),
+                credits_1001
+            ));
+
+            // Assignment should happen after 2 sessions
+            run_to_session(1u32);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert!(assignment.container_chains.is_empty());
+            run_to_session(2u32);
+            // Charlie and Dave should not be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
         });
@@ -5465,8 +6174,8 @@ fn test_block_credits_and_collator_assignation_credits_through_tank() {
                 vec![CHARLIE.into(), DAVE.into()]
             );
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + dancebox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -5700,27 +6409,539 @@ fn test_collator_assignment_tip_priority_on_congestion() {
                 para_id.into(),
                 tank_funds,
             ));
-
-            // Set tip for 1003
-            assert_ok!(ServicesPayment::set_max_tip(
+
+            // Set tip for 1003
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id.into(),
+                Some(max_tip),
+            ));
+
+            run_to_session(2);
+            assert_eq!(
+                CollatorAssignment::collator_container_chain().container_chains[&para_id.into()]
+                    .len(),
+                2,
+            );
+        });
+}
+
+#[test]
+fn test_collator_assignment_tip_charged_on_congestion() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+        ])
+        .build()
+        .execute_with(|| {
+            let tank_funds = 100 * UNIT;
+            let max_tip = 1 * UNIT;
+            let para_id = 1003u32;
+
+            // Send funds to tank
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id.into(),
+                tank_funds,
+            ));
+
+            // Set tip for para_id
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id.into(),
+                Some(max_tip),
+            ));
+
+            run_to_session(1);
+            assert_eq!(
+                Balances::usable_balance(ServicesPayment::parachain_tank(para_id.into())),
+                tank_funds - max_tip,
+            );
+        });
+}
+
+#[test]
+fn test_collator_assignment_tip_not_assigned_on_insufficient_balance() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+        ])
+        .build()
+        .execute_with(|| {
+            let tank_funds = 1 * UNIT;
+            let max_tip = 1 * UNIT;
+            let para_id = 1003u32;
+
+            // Send insufficient funds to tank for tip for 2 sessions
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id.into(),
+                tank_funds,
+            ));
+
+            // Set tip for para_id
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id.into(),
+                Some(max_tip),
+            ));
+
+            run_to_session(1);
+            assert_eq!(
+                CollatorAssignment::collator_container_chain().container_chains[&para_id.into()]
+                    .len(),
+                0
+            );
+        });
+}
+
+#[test]
+fn test_collator_assignment_tip_only_charge_willing_paras() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+            (AccountId::from(EVE), 100_000 * UNIT),
+            (AccountId::from(FERDIE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+            (AccountId::from(EVE), 100 * UNIT),
+            (AccountId::from(FERDIE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+        ])
+        .build()
+        .execute_with(|| {
+            let tank_funds = 100 * UNIT;
+            let max_tip = 1 * UNIT;
+            let para_id_with_tip = 1003u32;
+            let para_id_without_tip = 1001u32;
+
+            // Send funds to tank to both paras
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id_with_tip.into(),
+                tank_funds,
+            ));
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id_without_tip.into(),
+                tank_funds,
+            ));
+
+            // Only set tip for 1003
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id_with_tip.into(),
+                Some(max_tip),
+            ));
+
+            run_to_session(2);
+
+            let assignment = CollatorAssignment::collator_container_chain().container_chains;
+
+            // 2 out of the 3 paras should have collators assigned, with one paying tip to get
+            // prioritized, and the other selected at random that should not be charged any tips
+            assert_eq!(assignment[&para_id_with_tip.into()].len(), 2);
+            assert_eq!(
+                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_with_tip.into())),
+                tank_funds - max_tip * 2,
+            );
+
+            assert_eq!(assignment[&para_id_without_tip.into()].len(), 2);
+            assert_eq!(
+                Balances::usable_balance(ServicesPayment::parachain_tank(
+                    para_id_without_tip.into()
+                )),
+                tank_funds,
+            );
+        });
+}
+
+#[test]
+fn test_collator_assignment_tip_withdraw_min_tip() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+            (AccountId::from(EVE), 100_000 * UNIT),
+            (AccountId::from(FERDIE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+            (AccountId::from(EVE), 100 * UNIT),
+            (AccountId::from(FERDIE), 100 * UNIT),
+        ])
+        .with_para_ids(vec![
+            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+        ])
+        .build()
+        .execute_with(|| {
+            let tank_funds = 100 * UNIT;
+            let max_tip_1003 = 3 * UNIT;
+            let max_tip_1002 = 2 * UNIT;
+            let para_id_1003 = 1003u32;
+            let para_id_1002 = 1002u32;
+
+            // Send funds to tank to both paras
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id_1003.into(),
+                tank_funds,
+            ));
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                para_id_1002.into(),
+                tank_funds,
+            ));
+
+            // Set tips
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id_1003.into(),
+                Some(max_tip_1003),
+            ));
+            assert_ok!(ServicesPayment::set_max_tip(
+                root_origin(),
+                para_id_1002.into(),
+                Some(max_tip_1002),
+            ));
+
+            run_to_session(2);
+
+            assert_eq!(
+                CollatorAssignment::collator_container_chain().container_chains
+                    [&para_id_1003.into()]
+                    .len(),
+                2
+            );
+            assert_eq!(
+                CollatorAssignment::collator_container_chain().container_chains
+                    [&para_id_1002.into()]
+                    .len(),
+                2
+            );
+
+            // Should have withdrawn the lowest tip from both paras
+            assert_eq!(
+                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_1003.into())),
+                tank_funds - max_tip_1002 * 2,
+            );
+
+            assert_eq!(
+                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_1002.into())),
+                tank_funds - max_tip_1002 * 2,
+            );
+        });
+}
+
+#[test]
+fn test_xcm_core_buyer_collator_proof() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+
+            assert_ok!(Registrar::register_parathread(
+                origin_of(ALICE.into()),
+                3001.into(),
+                SlotFrequency { min: 1, max: 1 },
+                empty_genesis_data()
+            ));
+            assert_ok!(DataPreservers::set_boot_nodes(
+                origin_of(ALICE.into()),
+                3001.into(),
+                dummy_boot_nodes()
+            ));
+            assert_ok!(Registrar::mark_valid_for_collating(
+                root_origin(),
+                3001.into()
+            ));
+
+            run_to_session(2);
+            let para_id = ParaId::from(3001);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(assignment.container_chains[&para_id], vec![CHARLIE.into()]);
+            // Proofs are only valid if buying a core would succeed, which needs the xcm weights
+            assert_ok!(XcmCoreBuyer::set_relay_xcm_weight_config(
+                root_origin(),
+                Some(pallet_xcm_core_buyer::RelayXcmWeightConfigInner {
+                    buy_execution_cost: 50_000_000,
+                    weight_at_most: frame_support::weights::Weight::from_parts(
+                        1_000_000_000,
+                        100_000
+                    ),
+                    _phantom: Default::default(),
+                }),
+            ));
+
+            let buy_core_proof = |account: [u8; 32], nonce: u64| {
+                let keypair = sp_core::sr25519::Pair::from_string(
+                    &format!("//{}", AccountId::from(account)),
+                    None,
+                )
+                .expect("static values are valid; qed");
+                let session_index = Session::current_index();
+                let payload = BuyCoreCollatorProof::<NimbusId>::prepare_payload(
+                    XcmCoreBuyer::genesis_hash(),
+                    session_index,
+                    nonce,
+                    para_id,
+                );
+
+                BuyCoreCollatorProof::<NimbusId> {
+                    nonce,
+                    session_index,
+                    public_key: keypair.public().into(),
+                    signature: keypair.sign(&payload).into(),
+                }
+            };
+
+            // Charlie is assigned to the parathread, so its proof is valid
+            assert_ok!(XcmCoreBuyer::validate_unsigned(
+                TransactionSource::External,
+                &pallet_xcm_core_buyer::Call::buy_core {
+                    para_id,
+                    proof: buy_core_proof(CHARLIE, 0),
+                }
+            ));
+
+            // Dave is not assigned to the parathread
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &pallet_xcm_core_buyer::Call::buy_core {
+                        para_id,
+                        proof: buy_core_proof(DAVE, 0),
+                    }
+                ),
+                InvalidTransaction::BadSigner.into()
+            );
+
+            // Using an already used nonce is not allowed
+            pallet_xcm_core_buyer::CollatorSignatureNonce::<Runtime>::insert(para_id, 1);
+            assert_eq!(
+                XcmCoreBuyer::validate_unsigned(
+                    TransactionSource::External,
+                    &pallet_xcm_core_buyer::Call::buy_core {
+                        para_id,
+                        proof: buy_core_proof(CHARLIE, 0),
+                    }
+                ),
+                InvalidTransaction::Stale.into()
+            );
+        });
+}
+
+#[test]
+fn test_xcm_core_buyer_coretime_credits_not_supported() {
+    use {
+        dancebox_runtime::xcm_config::{EncodedCallToBuyCore, RelayChain},
+        pallet_xcm_core_buyer::GetPurchaseCoreCall,
+        tp_traits::CorePurchaseBackend,
+    };
+
+    for relay_chain in [RelayChain::Westend, RelayChain::Rococo] {
+        assert!(EncodedCallToBuyCore::get_encoded(
+            relay_chain.clone(),
+            CorePurchaseBackend::OnDemand,
+            u128::MAX,
+            3001.into()
+        )
+        .is_some());
+        // The relay runtime we depend on does not support coretime credits yet
+        assert_eq!(
+            EncodedCallToBuyCore::get_encoded(
+                relay_chain,
+                CorePurchaseBackend::CoretimeCredits,
+                u128::MAX,
+                3001.into()
+            ),
+            None
+        );
+    }
+}
+
+#[test]
+fn test_parathread_slot_frequency_is_enforced() {
+    ExtBuilder::default()
+        .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
+            (AccountId::from(ALICE), 210_000 * UNIT),
+            (AccountId::from(BOB), 100_000 * UNIT),
+            (AccountId::from(CHARLIE), 100_000 * UNIT),
+            (AccountId::from(DAVE), 100_000 * UNIT),
+        ])
+        .with_collators(vec![
+            (AccountId::from(ALICE), 210 * UNIT),
+            (AccountId::from(BOB), 100 * UNIT),
+            (AccountId::from(CHARLIE), 100 * UNIT),
+            (AccountId::from(DAVE), 100 * UNIT),
+        ])
+        .build()
+        .execute_with(|| {
+            run_to_block(2);
+
+            assert_ok!(Registrar::register_parathread(
+                origin_of(ALICE.into()),
+                3001.into(),
+                SlotFrequency { min: 2, max: 5 },
+                empty_genesis_data()
+            ));
+            assert_ok!(DataPreservers::set_boot_nodes(
+                origin_of(ALICE.into()),
+                3001.into(),
+                dummy_boot_nodes()
+            ));
+            assert_ok!(Registrar::mark_valid_for_collating(
+                root_origin(),
+                3001.into()
+            ));
+            assert_ok!(ServicesPayment::set_block_production_credits(
                 root_origin(),
-                para_id.into(),
-                Some(max_tip),
+                3001.into(),
+                100
             ));
 
             run_to_session(2);
-            assert_eq!(
-                CollatorAssignment::collator_container_chain().container_chains[&para_id.into()]
-                    .len(),
-                2,
+            let para_id = ParaId::from(3001);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(assignment.container_chains[&para_id], vec![CHARLIE.into()]);
+
+            // Note a new parathread block in a new tanssi block, returns whether the author was
+            // rewarded
+            let note_parathread_block = |block_number: u32| {
+                run_block_with_operation(|_slot| {
+                    AuthorNoting::on_initialize(System::block_number());
+                    let mut sproof = ParaHeaderSproofBuilder::default();
+                    let slot: u64 = block_number.into();
+                    sproof.items.push(ParaHeaderSproofBuilderItem {
+                        para_id,
+                        author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<
+                            u32,
+                            BlakeTwo256,
+                        > {
+                            parent_hash: Default::default(),
+                            number: block_number,
+                            state_root: Default::default(),
+                            extrinsics_root: Default::default(),
+                            digest: sp_runtime::generic::Digest {
+                                logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                            },
+                        }),
+                    });
+                    set_author_noting_inherent_data(sproof);
+                });
+
+                System::events().iter().any(|record| {
+                    matches!(
+                        record.event,
+                        dancebox_runtime::RuntimeEvent::InflationRewards(
+                            pallet_inflation_rewards::Event::RewardedContainer { para_id: rewarded, .. }
+                        ) if rewarded == para_id
+                    )
+                })
+            };
+            let block_credits = || {
+                pallet_services_payment::BlockProductionCredits::<Runtime>::get(para_id)
+                    .unwrap_or_default()
+            };
+
+            // The first block is always valid
+            assert!(note_parathread_block(1));
+            assert_eq!(block_credits(), 99);
+
+            // Only 1 slot passed, but min is 2: the block is not rewarded and not charged
+            assert!(!note_parathread_block(2));
+            assert_eq!(block_credits(), 99);
+
+            // Buying a core before min slots have passed is not allowed
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(root_origin(), para_id),
+                pallet_xcm_core_buyer::Error::<Runtime>::SlotFrequencyMinNotReached
+            );
+            run_block();
+            run_block();
+            // After min slots the slot frequency check passes, so the call fails later because
+            // the xcm weights are not set
+            assert_noop!(
+                XcmCoreBuyer::force_buy_core(root_origin(), para_id),
+                pallet_xcm_core_buyer::Error::<Runtime>::XcmWeightStorageNotSet
             );
+
+            // 3 slots passed
+            assert!(note_parathread_block(3));
+            assert_eq!(block_credits(), 98);
+
+            // 6 slots passed, but max is 5: the block is not rewarded and not charged
+            run_to_block(System::block_number() + 5);
+            assert!(!note_parathread_block(4));
+            assert_eq!(block_credits(), 98);
         });
 }
 
 #[test]
-fn test_collator_assignment_tip_charged_on_congestion() {
+fn test_services_payment_cost_adjusts_with_collator_demand() {
     ExtBuilder::default()
         .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
             (AccountId::from(ALICE), 210_000 * UNIT),
             (AccountId::from(BOB), 100_000 * UNIT),
             (AccountId::from(CHARLIE), 100_000 * UNIT),
@@ -5735,95 +6956,125 @@ fn test_collator_assignment_tip_charged_on_congestion() {
         .with_para_ids(vec![
             (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
             (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
-            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
         ])
+        .with_config(pallet_configuration::HostConfiguration {
+            max_collators: 100,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 2,
+            collators_per_container: 1,
+            full_rotation_period: 24,
+            target_container_chain_fullness: Perbill::from_percent(50),
+            ..Default::default()
+        })
         .build()
         .execute_with(|| {
-            let tank_funds = 100 * UNIT;
-            let max_tip = 1 * UNIT;
-            let para_id = 1003u32;
+            run_to_block(2);
+            let para_id = ParaId::from(1001);
 
-            // Send funds to tank
-            assert_ok!(ServicesPayment::purchase_credits(
-                origin_of(ALICE.into()),
-                para_id.into(),
-                tank_funds,
-            ));
+            // 2 collators available for container chains, with a target of 1 collator in use.
+            // 2 container chains need 2 collators, so the price doubles.
+            assert_eq!(
+                Runtime::block_cost(para_id),
+                2 * BaseBlockProductionCost::get()
+            );
+            assert_eq!(
+                Runtime::collator_assignment_cost(para_id),
+                2 * BaseCollatorAssignmentCost::get()
+            );
 
-            // Set tip for para_id
-            assert_ok!(ServicesPayment::set_max_tip(
-                root_origin(),
-                para_id.into(),
-                Some(max_tip),
-            ));
+            // Once a chain is deregistered the demand is at the target, so the base price applies
+            assert_ok!(Registrar::deregister(root_origin(), 1002.into()));
+            run_to_session(2);
+            assert_eq!(Runtime::block_cost(para_id), BaseBlockProductionCost::get());
 
-            run_to_session(1);
+            // Prices never go below the base price
+            assert_ok!(Registrar::deregister(root_origin(), 1001.into()));
+            run_to_session(4);
+            assert_eq!(Runtime::block_cost(para_id), BaseBlockProductionCost::get());
             assert_eq!(
-                Balances::usable_balance(ServicesPayment::parachain_tank(para_id.into())),
-                tank_funds - max_tip,
+                Runtime::collator_assignment_cost(para_id),
+                BaseCollatorAssignmentCost::get()
             );
         });
 }
 
 #[test]
-fn test_collator_assignment_tip_not_assigned_on_insufficient_balance() {
+fn test_collator_assignment_with_requested_collators() {
     ExtBuilder::default()
         .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
             (AccountId::from(ALICE), 210_000 * UNIT),
             (AccountId::from(BOB), 100_000 * UNIT),
             (AccountId::from(CHARLIE), 100_000 * UNIT),
             (AccountId::from(DAVE), 100_000 * UNIT),
+            (AccountId::from(EVE), 100_000 * UNIT),
         ])
         .with_collators(vec![
             (AccountId::from(ALICE), 210 * UNIT),
             (AccountId::from(BOB), 100 * UNIT),
             (AccountId::from(CHARLIE), 100 * UNIT),
             (AccountId::from(DAVE), 100 * UNIT),
+            (AccountId::from(EVE), 100 * UNIT),
         ])
         .with_para_ids(vec![
             (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
             (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
-            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
         ])
+        .with_config(pallet_configuration::HostConfiguration {
+            max_collators: 100,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 2,
+            collators_per_container: 2,
+            full_rotation_period: 24,
+            target_container_chain_fullness: Perbill::from_percent(100),
+            ..Default::default()
+        })
         .build()
         .execute_with(|| {
-            let tank_funds = 1 * UNIT;
-            let max_tip = 1 * UNIT;
-            let para_id = 1003u32;
+            run_to_block(2);
 
-            // Send insufficient funds to tank for tip for 2 sessions
-            assert_ok!(ServicesPayment::purchase_credits(
-                origin_of(ALICE.into()),
-                para_id.into(),
-                tank_funds,
-            ));
+            // 3 collators left for 2 container chains that need 2 collators each
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(assignment.container_chains[&1001u32.into()].len(), 2);
+            assert_eq!(assignment.container_chains[&1002u32.into()].len(), 0);
 
-            // Set tip for para_id
-            assert_ok!(ServicesPayment::set_max_tip(
+            // 1001 only needs 1 collator, so there are enough collators for 1002
+            assert_ok!(ServicesPayment::set_requested_collators(
                 root_origin(),
-                para_id.into(),
-                Some(max_tip),
+                1001u32.into(),
+                Some(CollatorRequirements {
+                    min_collators: 1,
+                    max_collators: 1,
+                }),
             ));
+            run_to_session(2);
 
-            run_to_session(1);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(assignment.container_chains[&1001u32.into()].len(), 1);
+            assert_eq!(assignment.container_chains[&1002u32.into()].len(), 2);
+
+            // The demand is at the target, and 1001 pays for half of the default collators
             assert_eq!(
-                CollatorAssignment::collator_container_chain().container_chains[&para_id.into()]
-                    .len(),
-                0
+                Runtime::collator_assignment_cost(1001u32.into()),
+                BaseCollatorAssignmentCost::get() / 2
+            );
+            assert_eq!(
+                Runtime::collator_assignment_cost(1002u32.into()),
+                BaseCollatorAssignmentCost::get()
             );
         });
 }
 
 #[test]
-fn test_collator_assignment_tip_only_charge_willing_paras() {
+fn test_preview_next_collator_assignment() {
     ExtBuilder::default()
         .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
             (AccountId::from(ALICE), 210_000 * UNIT),
             (AccountId::from(BOB), 100_000 * UNIT),
             (AccountId::from(CHARLIE), 100_000 * UNIT),
             (AccountId::from(DAVE), 100_000 * UNIT),
             (AccountId::from(EVE), 100_000 * UNIT),
-            (AccountId::from(FERDIE), 100_000 * UNIT),
         ])
         .with_collators(vec![
             (AccountId::from(ALICE), 210 * UNIT),
@@ -5831,141 +7082,172 @@ fn test_collator_assignment_tip_only_charge_willing_paras() {
             (AccountId::from(CHARLIE), 100 * UNIT),
             (AccountId::from(DAVE), 100 * UNIT),
             (AccountId::from(EVE), 100 * UNIT),
-            (AccountId::from(FERDIE), 100 * UNIT),
         ])
         .with_para_ids(vec![
             (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
             (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
-            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
+            // 1003 has no credits and no funds in its tank
+            (1003, empty_genesis_data(), vec![], 0, 0).into(),
         ])
+        .with_config(pallet_configuration::HostConfiguration {
+            max_collators: 100,
+            min_orchestrator_collators: 2,
+            max_orchestrator_collators: 2,
+            collators_per_container: 2,
+            full_rotation_period: 24,
+            ..Default::default()
+        })
         .build()
         .execute_with(|| {
-            let tank_funds = 100 * UNIT;
-            let max_tip = 1 * UNIT;
-            let para_id_with_tip = 1003u32;
-            let para_id_without_tip = 1001u32;
+            run_to_block(2);
 
-            // Send funds to tank to both paras
-            assert_ok!(ServicesPayment::purchase_credits(
-                origin_of(ALICE.into()),
-                para_id_with_tip.into(),
-                tank_funds,
-            ));
-            assert_ok!(ServicesPayment::purchase_credits(
-                origin_of(ALICE.into()),
-                para_id_without_tip.into(),
-                tank_funds,
-            ));
+            let storage_root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+            let preview = Runtime::preview_next_assignment();
+            assert_eq!(
+                sp_io::storage::root(sp_runtime::StateVersion::V1),
+                storage_root
+            );
 
-            // Only set tip for 1003
-            assert_ok!(ServicesPayment::set_max_tip(
-                root_origin(),
-                para_id_with_tip.into(),
-                Some(max_tip),
-            ));
+            // 3 collators left for 2 container chains that need 2 collators each
+            assert_eq!(preview.assignment.orchestrator_chain.len(), 2);
+            assert_eq!(
+                preview.assignment.container_chains[&1001u32.into()].len(),
+                2
+            );
+            assert_eq!(
+                preview.assignment.container_chains[&1002u32.into()].len(),
+                0
+            );
+            assert_eq!(
+                preview.dropped_paras,
+                vec![
+                    (1002u32.into(), DroppedParaReason::NotEnoughCollators),
+                    (1003u32.into(), DroppedParaReason::NotEnoughCredits),
+                ]
+            );
+            assert!(!preview.randomness_known);
 
             run_to_session(2);
-
-            let assignment = CollatorAssignment::collator_container_chain().container_chains;
-
-            // 2 out of the 3 paras should have collators assigned, with one paying tip to get
-            // prioritized, and the other selected at random that should not be charged any tips
-            assert_eq!(assignment[&para_id_with_tip.into()].len(), 2);
             assert_eq!(
-                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_with_tip.into())),
-                tank_funds - max_tip * 2,
+                CollatorAssignment::collator_container_chain(),
+                preview.assignment
             );
 
-            assert_eq!(assignment[&para_id_without_tip.into()].len(), 2);
+            // 1001 runs out of credits, it keeps its collators during its grace period and the
+            // preview does not start it
+            pallet_services_payment::BlockProductionCredits::<Runtime>::remove(ParaId::from(1001));
+            pallet_services_payment::CollatorAssignmentCredits::<Runtime>::remove(ParaId::from(
+                1001,
+            ));
+            let storage_root = sp_io::storage::root(sp_runtime::StateVersion::V1);
+            let preview = Runtime::preview_next_assignment();
             assert_eq!(
-                Balances::usable_balance(ServicesPayment::parachain_tank(
-                    para_id_without_tip.into()
+                sp_io::storage::root(sp_runtime::StateVersion::V1),
+                storage_root
+            );
+            assert_eq!(
+                preview.assignment.container_chains[&1001u32.into()].len(),
+                2
+            );
+            assert_eq!(
+                pallet_services_payment::GracePeriodSessionsLeft::<Runtime>::get(ParaId::from(
+                    1001
                 )),
-                tank_funds,
+                None
             );
         });
 }
 
 #[test]
-fn test_collator_assignment_tip_withdraw_min_tip() {
+fn test_block_production_fees_are_split() {
     ExtBuilder::default()
         .with_balances(vec![
+            // Alice gets 10k extra tokens for her mapping deposit
             (AccountId::from(ALICE), 210_000 * UNIT),
             (AccountId::from(BOB), 100_000 * UNIT),
             (AccountId::from(CHARLIE), 100_000 * UNIT),
             (AccountId::from(DAVE), 100_000 * UNIT),
-            (AccountId::from(EVE), 100_000 * UNIT),
-            (AccountId::from(FERDIE), 100_000 * UNIT),
         ])
         .with_collators(vec![
             (AccountId::from(ALICE), 210 * UNIT),
             (AccountId::from(BOB), 100 * UNIT),
             (AccountId::from(CHARLIE), 100 * UNIT),
             (AccountId::from(DAVE), 100 * UNIT),
-            (AccountId::from(EVE), 100 * UNIT),
-            (AccountId::from(FERDIE), 100 * UNIT),
-        ])
-        .with_para_ids(vec![
-            (1001, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
-            (1002, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
-            (1003, empty_genesis_data(), vec![], u32::MAX, u32::MAX).into(),
         ])
         .build()
         .execute_with(|| {
-            let tank_funds = 100 * UNIT;
-            let max_tip_1003 = 3 * UNIT;
-            let max_tip_1002 = 2 * UNIT;
-            let para_id_1003 = 1003u32;
-            let para_id_1002 = 1002u32;
+            run_to_block(2);
 
-            // Send funds to tank to both paras
-            assert_ok!(ServicesPayment::purchase_credits(
+            assert_ok!(Registrar::register(
                 origin_of(ALICE.into()),
-                para_id_1003.into(),
-                tank_funds,
+                1001.into(),
+                empty_genesis_data()
             ));
-            assert_ok!(ServicesPayment::purchase_credits(
+            assert_ok!(DataPreservers::set_boot_nodes(
                 origin_of(ALICE.into()),
-                para_id_1002.into(),
-                tank_funds,
+                1001.into(),
+                dummy_boot_nodes()
             ));
-
-            // Set tips
-            assert_ok!(ServicesPayment::set_max_tip(
+            assert_ok!(Registrar::mark_valid_for_collating(
                 root_origin(),
-                para_id_1003.into(),
-                Some(max_tip_1003),
+                1001.into()
             ));
-            assert_ok!(ServicesPayment::set_max_tip(
+            // Remove the free block credits so that blocks are paid from the tank
+            assert_ok!(ServicesPayment::set_block_production_credits(
                 root_origin(),
-                para_id_1002.into(),
-                Some(max_tip_1002),
+                1001.into(),
+                0
+            ));
+            assert_ok!(ServicesPayment::purchase_credits(
+                origin_of(ALICE.into()),
+                1001.into(),
+                100 * UNIT
             ));
 
-            run_to_session(2);
+            run_to_session(2u32);
+            let tank = ServicesPayment::parachain_tank(1001.into());
+            let tank_balance_before = System::account(&tank).data.free;
 
-            assert_eq!(
-                CollatorAssignment::collator_container_chain().container_chains
-                    [&para_id_1003.into()]
-                    .len(),
-                2
-            );
-            assert_eq!(
-                CollatorAssignment::collator_container_chain().container_chains
-                    [&para_id_1002.into()]
-                    .len(),
-                2
-            );
+            // Simulate block inclusion from container chain 1001
+            let mut sproof = ParaHeaderSproofBuilder::default();
+            let slot: u64 = 5;
+            let s = ParaHeaderSproofBuilderItem {
+                para_id: 1001.into(),
+                author_id: HeaderAs::NonEncoded(sp_runtime::generic::Header::<u32, BlakeTwo256> {
+                    parent_hash: Default::default(),
+                    number: 1,
+                    state_root: Default::default(),
+                    extrinsics_root: Default::default(),
+                    digest: sp_runtime::generic::Digest {
+                        logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+                    },
+                }),
+            };
+            sproof.items.push(s);
+            set_author_noting_inherent_data(sproof);
 
-            // Should have withdrawn the lowest tip from both paras
-            assert_eq!(
-                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_1003.into())),
-                tank_funds - max_tip_1002 * 2,
-            );
+            run_block();
 
-            assert_eq!(
-                Balances::usable_balance(ServicesPayment::parachain_tank(para_id_1002.into())),
-                tank_funds - max_tip_1002 * 2,
-            );
+            let block_cost = BlockProductionCost::block_cost(&1001.into()).0;
+            assert_eq!(
+                System::account(&tank).data.free,
+                tank_balance_before - block_cost
+            );
+
+            let author = AuthorNoting::latest_author(ParaId::from(1001))
+                .expect("block should be noted")
+                .author;
+            let to_treasury = ServicesPaymentTreasuryShare::get() * block_cost;
+            let burned = ServicesPaymentBurnShare::get() * block_cost;
+            assert!(System::events().iter().any(|record| record.event
+                == dancebox_runtime::RuntimeEvent::ServicesPayment(
+                    pallet_services_payment::Event::ServiceFeesDistributed {
+                        para_id: 1001.into(),
+                        to_treasury,
+                        burned,
+                        collator: Some(author.clone()),
+                        to_collator: block_cost - to_treasury - burned,
+                    }
+                )));
         });
 }
@@ -68,11 +68,14 @@ use {
     pallet_invulnerables::InvulnerableRewardDistribution,
     pallet_registrar::RegistrarHooks,
     pallet_registrar_runtime_api::ContainerChainGenesisData,
-    pallet_services_payment::ProvideBlockProductionCost,
+    pallet_services_payment::{
+        FeeSplitter, ProvideBlockProductionCost, RequestedCollatorsScaledCost,
+    },
     pallet_session::{SessionManager, ShouldEndSession},
     pallet_stream_payment_runtime_api::{StreamPaymentApiError, StreamPaymentApiStatus},
     pallet_transaction_payment::CurrencyAdapter,
     polkadot_runtime_common::BlockHashCount,
+    runtime_common::rewards::CurrencyRewardsDistributor,
     scale_info::{prelude::format, TypeInfo},
     smallvec::smallvec,
     sp_api::impl_runtime_apis,
@@ -81,8 +84,8 @@ use {
     sp_runtime::{
         create_runtime_str, generic, impl_opaque_keys,
         traits::{
-            AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, IdentityLookup,
-            Verify,
+            AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Convert,
+            IdentityLookup, Verify,
         },
         transaction_validity::{TransactionSource, TransactionValidity},
         AccountId32, ApplyExtrinsicResult, RuntimeDebug,
@@ -554,6 +557,23 @@ impl parachain_info::Config for Runtime {}
 
 pub struct CollatorsFromInvulnerables;
 
+impl CollatorsFromInvulnerables {
+    /// Collators that will be queued for session `index`: the invulnerables, up to the max number
+    /// of collators.
+    pub fn collators(index: SessionIndex) -> Vec<CollatorId> {
+        let invulnerables = Invulnerables::invulnerables().to_vec();
+        let target_session_index = index.saturating_add(1);
+        let max_collators =
+            <Configuration as GetHostConfiguration<u32>>::max_collators(target_session_index);
+
+        invulnerables
+            .iter()
+            .take(max_collators as usize)
+            .cloned()
+            .collect()
+    }
+}
+
 /// Play the role of the session manager.
 impl SessionManager<CollatorId> for CollatorsFromInvulnerables {
     fn new_session(index: SessionIndex) -> Option<Vec<CollatorId>> {
@@ -563,17 +583,7 @@ impl SessionManager<CollatorId> for CollatorsFromInvulnerables {
             <frame_system::Pallet<Runtime>>::block_number(),
         );
 
-        let invulnerables = Invulnerables::invulnerables().to_vec();
-        let target_session_index = index.saturating_add(1);
-        let max_collators =
-            <Configuration as GetHostConfiguration<u32>>::max_collators(target_session_index);
-        let collators = invulnerables
-            .iter()
-            .take(max_collators as usize)
-            .cloned()
-            .collect();
-
-        Some(collators)
+        Some(Self::collators(index))
     }
     fn start_session(_: SessionIndex) {
         // we don't care.
@@ -634,56 +644,104 @@ impl RemoveInvulnerables<CollatorId> for RemoveInvulnerablesImpl {
 
 pub struct RemoveParaIdsWithNoCreditsImpl;
 
-impl RemoveParaIdsWithNoCredits for RemoveParaIdsWithNoCreditsImpl {
-    fn remove_para_ids_with_no_credits(
-        para_ids: &mut Vec<ParaId>,
-        currently_assigned: &BTreeSet<ParaId>,
-    ) {
+impl RemoveParaIdsWithNoCreditsImpl {
+    /// Whether the para can pay for the next session, with credits or with its tank.
+    fn has_enough_funds(para_id: &ParaId, currently_assigned: &BTreeSet<ParaId>) -> bool {
         let blocks_per_session = Period::get();
 
-        para_ids.retain(|para_id| {
-            // If the para has been assigned collators for this session it must have enough block credits
-            // for the current and the next session.
-            let block_credits_needed = if currently_assigned.contains(para_id) {
-                blocks_per_session * 2
-            } else {
-                blocks_per_session
-            };
+        // If the para has been assigned collators for this session it must have enough block credits
+        // for the current and the next session.
+        let block_credits_needed = if currently_assigned.contains(para_id) {
+            blocks_per_session * 2
+        } else {
+            blocks_per_session
+        };
 
-            // Check if the container chain has enough credits for producing blocks
-            let free_block_credits = pallet_services_payment::BlockProductionCredits::<Runtime>::get(para_id)
+        // Check if the container chain has enough credits for producing blocks
+        let free_block_credits =
+            pallet_services_payment::BlockProductionCredits::<Runtime>::get(para_id)
                 .unwrap_or_default();
 
-            // Check if the container chain has enough credits for a session assignments
-            let free_session_credits = pallet_services_payment::CollatorAssignmentCredits::<Runtime>::get(para_id)
+        // Check if the container chain has enough credits for a session assignments
+        let free_session_credits =
+            pallet_services_payment::CollatorAssignmentCredits::<Runtime>::get(para_id)
                 .unwrap_or_default();
 
-            // If para's max tip is set it should have enough to pay for one assignment with tip
-            let max_tip = pallet_services_payment::MaxTip::<Runtime>::get(para_id).unwrap_or_default() ;
+        // If para's max tip is set it should have enough to pay for one assignment with tip
+        let max_tip = pallet_services_payment::MaxTip::<Runtime>::get(para_id).unwrap_or_default();
 
-            // Return if we can survive with free credits
-            if free_block_credits >= block_credits_needed && free_session_credits >= 1 {
-                // Max tip should always be checked, as it can be withdrawn even if free credits were used
-                return Balances::can_withdraw(&pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id), max_tip).into_result(true).is_ok()
-            }
+        // Return if we can survive with free credits
+        if free_block_credits >= block_credits_needed && free_session_credits >= 1 {
+            // Max tip should always be checked, as it can be withdrawn even if free credits were used
+            return Balances::can_withdraw(
+                &pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id),
+                max_tip,
+            )
+            .into_result(true)
+            .is_ok();
+        }
 
-            let remaining_block_credits = block_credits_needed.saturating_sub(free_block_credits);
-            let remaining_session_credits = 1u32.saturating_sub(free_session_credits);
+        let remaining_block_credits = block_credits_needed.saturating_sub(free_block_credits);
+        let remaining_session_credits = 1u32.saturating_sub(free_session_credits);
 
-            let (block_production_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(para_id);
-            let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(para_id);
-            // let's check if we can withdraw
-            let remaining_block_credits_to_pay = u128::from(remaining_block_credits).saturating_mul(block_production_costs);
-            let remaining_session_credits_to_pay = u128::from(remaining_session_credits).saturating_mul(collator_assignment_costs);
+        let (block_production_costs, _) =
+            <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(
+                para_id,
+            );
+        let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(para_id);
+        // let's check if we can withdraw
+        let remaining_block_credits_to_pay =
+            u128::from(remaining_block_credits).saturating_mul(block_production_costs);
+        let remaining_session_credits_to_pay =
+            u128::from(remaining_session_credits).saturating_mul(collator_assignment_costs);
+
+        let remaining_services_to_pay =
+            remaining_block_credits_to_pay.saturating_add(remaining_session_credits_to_pay);
+        let remaining_to_pay = remaining_services_to_pay.saturating_add(max_tip);
+
+        // This should take into account whether we tank goes below ED
+        // The true refers to keepAlive
+        let parachain_tank = pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id);
+        if Balances::can_withdraw(&parachain_tank, remaining_to_pay)
+            .into_result(true)
+            .is_ok()
+        {
+            return true;
+        }
 
-            let remaining_to_pay = remaining_block_credits_to_pay.saturating_add(remaining_session_credits_to_pay).saturating_add(max_tip);
+        // Otherwise services can be paid with the assets held in the tank, but the tip must
+        // still be paid with the native currency
+        Balances::can_withdraw(&parachain_tank, max_tip)
+            .into_result(true)
+            .is_ok()
+            && ServicesPayment::tank_asset_to_pay(*para_id, remaining_services_to_pay).is_some()
+    }
+}
 
-            // This should take into account whether we tank goes below ED
-            // The true refers to keepAlive
-            Balances::can_withdraw(&pallet_services_payment::Pallet::<Runtime>::parachain_tank(*para_id), remaining_to_pay).into_result(true).is_ok()
+impl RemoveParaIdsWithNoCredits for RemoveParaIdsWithNoCreditsImpl {
+    fn remove_para_ids_with_no_credits(
+        para_ids: &mut Vec<ParaId>,
+        currently_assigned: &BTreeSet<ParaId>,
+    ) {
+        para_ids.retain(|para_id| {
+            // Paras that are already running keep their collators during a grace period, so that
+            // their managers have time to top up their tanks
+            Self::has_enough_funds(para_id, currently_assigned)
+                || (currently_assigned.contains(para_id)
+                    && ServicesPayment::in_grace_period(*para_id))
         });
     }
 
+    fn on_session_change(para_ids: &[ParaId], currently_assigned: &BTreeSet<ParaId>) {
+        for para_id in para_ids {
+            if Self::has_enough_funds(para_id, currently_assigned) {
+                ServicesPayment::reset_grace_period(*para_id);
+            } else if currently_assigned.contains(para_id) {
+                ServicesPayment::use_grace_period(*para_id);
+            }
+        }
+    }
+
     /// Make those para ids valid by giving them enough credits, for benchmarking.
     #[cfg(feature = "runtime-benchmarks")]
     fn make_valid_para_ids(para_ids: &[ParaId]) {
@@ -729,6 +787,8 @@ impl pallet_collator_assignment::Config for Runtime {
     type RemoveParaIdsWithNoCredits = RemoveParaIdsWithNoCreditsImpl;
     type CollatorAssignmentHook = ServicesPayment;
     type CollatorAssignmentTip = ServicesPayment;
+    type RequestedCollators = ServicesPayment;
+    type CollatorStake = ();
     type Currency = Balances;
     type WeightInfo = weights::pallet_collator_assignment::SubstrateWeight<Runtime>;
 }
@@ -755,29 +815,82 @@ impl ProvideCollatorAssignmentCost<Runtime> for CollatorAssignmentCost<Runtime>
     }
 }
 
+/// Default number of collators of a container chain, used to scale the collator assignment cost
+/// of chains that requested a different number of collators.
+pub struct DefaultCollatorsPerPara;
+impl Convert<ParaId, u32> for DefaultCollatorsPerPara {
+    fn convert(para_id: ParaId) -> u32 {
+        let config = Configuration::config();
+        if Registrar::parathread_params(para_id).is_some() {
+            config.collators_per_parathread
+        } else {
+            config.collators_per_container
+        }
+    }
+}
+
 parameter_types! {
     // 60 days worth of blocks
     pub const FreeBlockProductionCredits: BlockNumber = 60 * DAYS;
     // 60 days worth of blocks
     pub const FreeCollatorAssignmentCredits: u32 = FreeBlockProductionCredits::get()/Period::get();
+    // Limit the amount charged at once if the orchestrator chain was not able to note some blocks
+    pub const MaxBlocksChargedPerNoting: u32 = 10;
+    // Warn container chain managers when their tank can pay for less than one hour of service
+    pub const LowBalanceWarningSessions: u32 = 12;
+    // Number of sessions a running container chain keeps its collators after running out of funds
+    pub const GracePeriodSessions: u32 = 2;
+    pub const MaxRequestedCollators: u32 = 10;
+    pub const MaxTankAssets: u32 = 1;
 }
 
+parameter_types! {
+    // Fees charged for services: 20% goes to the treasury, 30% is burned and the rest is given
+    // to the collator that provided the service, if any, or to the treasury otherwise
+    pub const ServicesPaymentTreasuryShare: Perbill = Perbill::from_percent(20);
+    pub const ServicesPaymentBurnShare: Perbill = Perbill::from_percent(30);
+}
+
+pub type ServicesPaymentFeeSplitter = FeeSplitter<
+    Runtime,
+    ServicesPaymentTreasuryShare,
+    ServicesPaymentBurnShare,
+    Treasury,
+    CurrencyRewardsDistributor<Runtime, InvulnerableRewardDistribution<Runtime, Balances, ()>>,
+>;
+
 impl pallet_services_payment::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     /// Handler for fees
-    type OnChargeForBlock = ();
-    type OnChargeForCollatorAssignment = ();
-    type OnChargeForCollatorAssignmentTip = ();
+    type OnChargeForBlock = ServicesPaymentFeeSplitter;
+    type OnChargeForCollatorAssignment = ServicesPaymentFeeSplitter;
+    type OnChargeForCollatorAssignmentTip = ServicesPaymentFeeSplitter;
     /// Currency type for fee payment
     type Currency = Balances;
     /// Provider of a block cost which can adjust from block to block
     type ProvideBlockProductionCost = BlockProductionCost<Runtime>;
     /// Provider of a block cost which can adjust from block to block
-    type ProvideCollatorAssignmentCost = CollatorAssignmentCost<Runtime>;
+    type ProvideCollatorAssignmentCost = RequestedCollatorsScaledCost<
+        Runtime,
+        CollatorAssignmentCost<Runtime>,
+        DefaultCollatorsPerPara,
+    >;
     /// The maximum number of block credits that can be accumulated
     type FreeBlockProductionCredits = FreeBlockProductionCredits;
     /// The maximum number of session credits that can be accumulated
     type FreeCollatorAssignmentCredits = FreeCollatorAssignmentCredits;
+    /// The maximum number of blocks charged in a single author noting
+    type MaxBlocksChargedPerNoting = MaxBlocksChargedPerNoting;
+    type BlocksPerSession = Period;
+    type LowBalanceWarningSessions = LowBalanceWarningSessions;
+    type GracePeriodSessions = GracePeriodSessions;
+    type MaxRequestedCollators = MaxRequestedCollators;
+    // Services can only be paid with the native currency
+    type AssetId = u16;
+    type Assets = ();
+    type AssetRateConverter = ();
+    type AssetFeesAccount = TreasuryAccount;
+    type MaxTankAssets = MaxTankAssets;
     type ManagerOrigin =
         EitherOfDiverse<pallet_registrar::EnsureSignedByManager<Runtime>, EnsureRoot<AccountId>>;
     type WeightInfo = weights::pallet_services_payment::SubstrateWeight<Runtime>;
@@ -798,6 +911,8 @@ impl pallet_author_noting::Config for Runtime {
     type SelfParaId = parachain_info::Pallet<Runtime>;
     type SlotBeacon = dp_consensus::AuraDigestSlotBeacon<Runtime>;
     type ContainerChainAuthor = CollatorAssignment;
+    type GetParathreadParams = Registrar;
+    type CurrentSessionIndex = CurrentSessionIndexGetter;
     type RelayChainStateProvider = cumulus_pallet_parachain_system::RelaychainDataProvider<Self>;
     // We benchmark each hook individually, so for runtime-benchmarks this should be empty
     #[cfg(feature = "runtime-benchmarks")]
@@ -1152,6 +1267,8 @@ impl pallet_inflation_rewards::Config for Runtime {
     type PendingRewardsAccount = PendingRewardsAccount;
     type StakingRewardsDistributor = InvulnerableRewardDistribution<Self, Balances, ()>;
     type RewardsPortion = RewardsPortion;
+    /// Blocks are rewarded as long as they are charged by services payment
+    type MaxBlocksRewardedPerNoting = MaxBlocksChargedPerNoting;
 }
 
 impl pallet_tx_pause::Config for Runtime {
@@ -1164,6 +1281,7 @@ impl pallet_tx_pause::Config for Runtime {
     type WeightInfo = weights::pallet_tx_pause::SubstrateWeight<Runtime>;
 }
 
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen)]
 pub enum StreamPaymentAssetId {
     Native,
@@ -1252,6 +1370,7 @@ impl pallet_stream_payment::Assets<AccountId, StreamPaymentAssetId, Balance>
     }
 }
 
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 #[derive(RuntimeDebug, PartialEq, Eq, Encode, Decode, Copy, Clone, TypeInfo, MaxEncodedLen)]
 pub enum TimeUnit {
     BlockNumber,
@@ -1289,6 +1408,9 @@ type StreamId = u64;
 parameter_types! {
     // 1 entry, storing 173 bytes on-chain
     pub const OpenStreamHoldAmount: Balance = currency::deposit(1, 173);
+    pub const MaxStreamsSweptPerBlock: u32 = 20;
+    pub const StalledStreamGracePeriod: BlockNumber = 7 * DAYS;
+    pub const MaxBatchedPayments: u32 = 100;
 }
 
 impl pallet_stream_payment::Config for Runtime {
@@ -1302,9 +1424,33 @@ impl pallet_stream_payment::Config for Runtime {
     type OpenStreamHoldAmount = OpenStreamHoldAmount;
     type RuntimeHoldReason = RuntimeHoldReason;
     type TimeProvider = TimeProvider;
+    type MaxStreamsSweptPerBlock = MaxStreamsSweptPerBlock;
+    type StalledStreamGracePeriod = StalledStreamGracePeriod;
+    type MaxBatchedPayments = MaxBatchedPayments;
     type WeightInfo = weights::pallet_stream_payment::SubstrateWeight<Runtime>;
 }
 
+fn stream_payment_api_status(
+    stream_id: StreamId,
+    now: Option<Balance>,
+) -> Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError> {
+    match StreamPayment::stream_payment_status(stream_id, now) {
+        Ok(pallet_stream_payment::StreamPaymentStatus {
+            payment,
+            deposit_left,
+            stalled,
+        }) => Ok(StreamPaymentApiStatus {
+            payment,
+            deposit_left,
+            stalled,
+        }),
+        Err(pallet_stream_payment::Error::<Runtime>::UnknownStreamId) => {
+            Err(StreamPaymentApiError::UnknownStreamId)
+        }
+        Err(e) => Err(StreamPaymentApiError::Other(format!("{e:?}"))),
+    }
+}
+
 parameter_types! {
     // 1 entry, storing 258 bytes on-chain
     pub const BasicDeposit: Balance = currency::deposit(1, 258);
@@ -1479,6 +1625,26 @@ mod benches {
     );
 }
 
+/// Return all the paraIds assigned to `authority` in `session_index`. This includes all the
+/// parathreads that share collators.
+fn para_ids_of_authority(session_index: u32, authority: &NimbusId) -> Vec<ParaId> {
+    let Some(assigned_authorities) = AuthorityAssignment::collator_container_chain(session_index)
+    else {
+        return vec![];
+    };
+
+    if assigned_authorities.orchestrator_chain.contains(authority) {
+        return vec![ParachainInfo::get()];
+    }
+
+    assigned_authorities
+        .container_chains
+        .into_iter()
+        .filter(|(_para_id, authorities)| authorities.contains(authority))
+        .map(|(para_id, _authorities)| para_id)
+        .collect()
+}
+
 impl_runtime_apis! {
     impl sp_consensus_aura::AuraApi<Block, NimbusId> for Runtime {
         fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -1702,6 +1868,7 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_collator_assignment_runtime_api::CollatorAssignmentApi<Block, AccountId, ParaId> for Runtime {
         /// Return the parachain that the given `AccountId` is collating for.
         /// Returns `None` if the `AccountId` is not collating.
@@ -1743,6 +1910,17 @@ impl_runtime_apis! {
                 assigned_collators.container_chains.get(&para_id).cloned()
             }
         }
+
+        /// Return the collator assignment that will be computed on the next session change,
+        /// along with the registered paras that will not be assigned collators and why.
+        fn preview_next_assignment() -> pallet_collator_assignment_runtime_api::AssignmentPreview<AccountId> {
+            let session_index = Session::current_index() + 1;
+            // On session change, the queued collators are the ones selected for the session after
+            // the one that starts
+            let collators = CollatorsFromInvulnerables::collators(session_index + 1);
+
+            CollatorAssignment::preview_assignment(&session_index, collators)
+        }
     }
 
     impl pallet_registrar_runtime_api::RegistrarApi<Block, ParaId, MaxLengthTokenSymbol> for Runtime {
@@ -1796,6 +1974,22 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_services_payment_runtime_api::ServicesPaymentApi<Block, Balance, ParaId> for Runtime {
+        fn block_cost(para_id: ParaId) -> Balance {
+            let (block_production_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideBlockProductionCost::block_cost(&para_id);
+            block_production_costs
+        }
+
+        fn collator_assignment_cost(para_id: ParaId) -> Balance {
+            let (collator_assignment_costs, _) = <Runtime as pallet_services_payment::Config>::ProvideCollatorAssignmentCost::collator_assignment_cost(&para_id);
+            collator_assignment_costs
+        }
+
+        fn sessions_of_runway(para_id: ParaId) -> u32 {
+            ServicesPayment::sessions_of_runway(para_id)
+        }
+    }
+
     impl dp_consensus::TanssiAuthorityAssignmentApi<Block, NimbusId> for Runtime {
         /// Return the current authorities assigned to a given paraId
         fn para_id_authorities(para_id: ParaId) -> Option<Vec<NimbusId>> {
@@ -1849,6 +2043,31 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_collator_assignment_runtime_api::AuthorityParaIdsAssignmentApi<Block, NimbusId, ParaId> for Runtime {
+        /// Return all the paraIds assigned to a given authority
+        fn check_para_ids_assignment(authority: NimbusId) -> Vec<ParaId> {
+            let parent_number = System::block_number();
+            let should_end_session = <Runtime as pallet_session::Config>::ShouldEndSession::should_end_session(parent_number + 1);
+
+            let session_index = if should_end_session {
+                Session::current_index() +1
+            }
+            else {
+                Session::current_index()
+            };
+
+            para_ids_of_authority(session_index, &authority)
+        }
+
+        /// Return all the paraIds assigned to a given authority on the next session.
+        /// On session boundary this returns the same as `check_para_ids_assignment`.
+        fn check_para_ids_assignment_next_session(authority: NimbusId) -> Vec<ParaId> {
+            let session_index = Session::current_index() + 1;
+
+            para_ids_of_authority(session_index, &authority)
+        }
+    }
+
     impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi<Block, Balance>
     for Runtime {
         fn query_info(
@@ -1874,18 +2093,31 @@ impl_runtime_apis! {
         }
     }
 
+    #[api_version(2)]
     impl pallet_stream_payment_runtime_api::StreamPaymentApi<Block, StreamId, Balance, Balance>
     for Runtime {
         fn stream_payment_status(
             stream_id: StreamId,
             now: Option<Balance>,
         ) -> Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError> {
-            match StreamPayment::stream_payment_status(stream_id, now) {
-                Ok(pallet_stream_payment::StreamPaymentStatus {
-                    payment, deposit_left, stalled
-                }) => Ok(StreamPaymentApiStatus {
-                    payment, deposit_left, stalled
-                }),
+            stream_payment_api_status(stream_id, now)
+        }
+
+        fn stream_payment_statuses(
+            stream_ids: Vec<StreamId>,
+            now: Option<Balance>,
+        ) -> Vec<Result<StreamPaymentApiStatus<Balance>, StreamPaymentApiError>> {
+            stream_ids
+                .into_iter()
+                .map(|stream_id| stream_payment_api_status(stream_id, now))
+                .collect()
+        }
+
+        fn stream_stall_time(
+            stream_id: StreamId,
+        ) -> Result<Option<Balance>, StreamPaymentApiError> {
+            match StreamPayment::stream_stall_time(stream_id) {
+                Ok(time) => Ok(time),
                 Err(pallet_stream_payment::Error::<Runtime>::UnknownStreamId)
                 => Err(StreamPaymentApiError::UnknownStreamId),
                 Err(e) => Err(StreamPaymentApiError::Other(format!("{e:?}")))
@@ -1893,6 +2125,48 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_stream_payment_runtime_api::StreamPaymentStreamsApi<
+        Block,
+        StreamId,
+        AccountId,
+        pallet_stream_payment::StreamOf<Runtime>,
+    > for Runtime {
+        fn streams_with_change_requested(
+            stream_ids: Vec<StreamId>,
+        ) -> Vec<(StreamId, pallet_stream_payment::StreamOf<Runtime>)> {
+            let stream_ids: BTreeSet<_> = stream_ids.into_iter().collect();
+
+            System::read_events_no_consensus()
+                .filter_map(|record| match record.event {
+                    RuntimeEvent::StreamPayment(
+                        pallet_stream_payment::Event::StreamConfigChangeRequested {
+                            stream_id, ..
+                        }
+                    ) if stream_ids.contains(&stream_id) => Some(stream_id),
+                    _ => None,
+                })
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .filter_map(|stream_id| {
+                    pallet_stream_payment::Streams::<Runtime>::get(stream_id)
+                        .map(|stream| (stream_id, stream))
+                })
+                .collect()
+        }
+
+        fn streams_with_source(source: AccountId) -> Vec<StreamId> {
+            StreamPayment::streams_with_source(&source)
+        }
+
+        fn streams_with_target(target: AccountId) -> Vec<StreamId> {
+            StreamPayment::streams_with_target(&target)
+        }
+
+        fn stream(stream_id: StreamId) -> Option<pallet_stream_payment::StreamOf<Runtime>> {
+            pallet_stream_payment::Streams::<Runtime>::get(stream_id)
+        }
+    }
+
     impl async_backing_primitives::UnincludedSegmentApi<Block> for Runtime {
         fn can_build_upon(
             included_hash: <Block as BlockT>::Hash,
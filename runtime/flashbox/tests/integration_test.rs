@@ -856,7 +856,15 @@ fn test_paras_registered_but_only_credits_for_1_session() {
                     .unwrap_or_default();
             assert_eq!(credits, credits_1001 - 1);
 
+            // Para 1001 keeps its collators during the grace period
             run_to_session(4u32);
+            let assignment = CollatorAssignment::collator_container_chain();
+            assert_eq!(
+                assignment.container_chains[&1001u32.into()],
+                vec![CHARLIE.into(), DAVE.into()]
+            );
+
+            run_to_session(5u32);
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -2720,8 +2728,8 @@ fn test_ed_plus_block_credit_session_purchase_works() {
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(3u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(3u32 + flashbox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -2904,8 +2912,8 @@ fn test_reassignment_ed_plus_two_block_credit_session_purchase_works() {
                 vec![CHARLIE.into(), DAVE.into()]
             );
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + flashbox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -3007,8 +3015,8 @@ fn test_reassignment_ed_plus_two_block_credit_session_minus_1_purchase_fails() {
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(3u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(3u32 + flashbox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -3248,8 +3256,8 @@ fn test_ed_plus_collator_assignment_session_purchase_works() {
 
             run_block();
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + flashbox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
@@ -3485,8 +3493,8 @@ fn test_block_credits_and_collator_assignation_credits_through_tank() {
                 vec![CHARLIE.into(), DAVE.into()]
             );
 
-            // After this it should not be assigned anymore, since credits are not payable
-            run_to_session(4u32);
+            // Credits are not payable, so para 1001 is unassigned once its grace period is over
+            run_to_session(4u32 + flashbox_runtime::GracePeriodSessions::get());
             // Nobody should be assigned to para 1001
             let assignment = CollatorAssignment::collator_container_chain();
             assert_eq!(assignment.container_chains.get(&1001u32.into()), None,);
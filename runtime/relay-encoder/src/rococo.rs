@@ -31,6 +31,9 @@ pub enum OnDemandAssignmentProviderCall {
         max_amount: Balance,
         para_id: ParaId,
     },
+    // TODO: add `PlaceOrderWithCredits`, to spend instantaneous coretime credits bought in the
+    // coretime chain, once the relay runtime we depend on has `place_order_with_credits`, so its
+    // index can be checked in the tests below.
 }
 
 #[cfg(test)]
@@ -176,5 +176,49 @@ describeSuite({
                 }
             },
         });
+
+        it({
+            id: "E02",
+            title: "Stream listing and projection RPCs",
+            test: async function () {
+                const txOpenStream = await polkadotJs.tx.streamPayment
+                    .openStream(
+                        bob.address,
+                        {
+                            timeUnit: "BlockNumber",
+                            assetId: "Native",
+                            rate: 100_000,
+                        },
+                        10_050_000
+                    )
+                    .signAsync(alice);
+                const newBlock = await context.createBlock([txOpenStream]);
+                const blockNumber = newBlock.block.header.number.toNumber();
+                const block = newBlock.block.hash;
+
+                // stream 0 has been closed in the previous test
+                expect(
+                    await customDevRpcRequest("tanssi_streamPaymentStreamsWithSource", [block, alice.address])
+                ).to.deep.equal([1]);
+                expect(
+                    await customDevRpcRequest("tanssi_streamPaymentStreamsWithTarget", [block, bob.address])
+                ).to.deep.equal([1]);
+                expect(
+                    await customDevRpcRequest("tanssi_streamPaymentStreamsWithTarget", [block, alice.address])
+                ).to.deep.equal([]);
+
+                const stream = await customDevRpcRequest("tanssi_streamPaymentStream", [block, 1]);
+                expect(stream.deposit).to.be.equal(10_050_000);
+                expect(stream.config.rate).to.be.equal(100_000);
+                expect(stream.last_time_updated).to.be.equal(blockNumber);
+                expect(stream.pending_request).to.be.null;
+                expect(await customDevRpcRequest("tanssi_streamPaymentStream", [block, 0])).to.be.null;
+
+                // 10_050_000 / 100_000 = 100.5, the stream will stall after 101 blocks
+                expect(await customDevRpcRequest("tanssi_streamPaymentStallTime", [block, 1])).to.be.equal(
+                    blockNumber + 101
+                );
+            },
+        });
     },
 });
@@ -0,0 +1,52 @@
+import "@tanssi/api-augment";
+import { describeSuite, beforeAll, expect, customDevRpcRequest } from "@moonwall/cli";
+import { KeyringPair } from "@moonwall/util";
+import { ApiPromise } from "@polkadot/api";
+
+describeSuite({
+    id: "DT0306",
+    title: "Staking delegator positions RPC",
+    foundationMethods: "dev",
+    testCases: ({ it, context }) => {
+        let polkadotJs: ApiPromise;
+        let alice: KeyringPair;
+
+        beforeAll(async () => {
+            alice = context.keyring.alice;
+            polkadotJs = context.polkadotJs();
+        });
+
+        it({
+            id: "E01",
+            title: "Pending delegation is returned with its unlock session",
+            test: async function () {
+                const stake = 10000000000000000n;
+                const tx = polkadotJs.tx.pooledStaking.requestDelegate(alice.address, "AutoCompounding", stake);
+                const newBlock = await context.createBlock([await tx.signAsync(alice)]);
+
+                const positions = await customDevRpcRequest("tanssi_pooledStakingDelegatorPositions", [
+                    newBlock.block.hash,
+                    alice.address,
+                ]);
+
+                expect(positions.length).to.be.equal(1);
+                expect(polkadotJs.createType("AccountId", positions[0].candidate).toString()).to.be.equal(
+                    alice.address
+                );
+                expect(positions[0].joining).to.deep.equal([
+                    {
+                        pool: "AutoCompounding",
+                        stake: Number(stake),
+                        at: 0,
+                        // Joining requests can be executed 2 sessions later
+                        unlocks_at: 2,
+                    },
+                ]);
+                expect(positions[0].auto_compounding_stake).to.be.equal(0);
+                expect(positions[0].manual_rewards_stake).to.be.equal(0);
+                expect(positions[0].claimable_rewards).to.be.equal(0);
+                expect(positions[0].leaving).to.deep.equal([]);
+            },
+        });
+    },
+});
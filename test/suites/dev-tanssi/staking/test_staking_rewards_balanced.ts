@@ -92,6 +92,7 @@ describeSuite({
             title: "Alice should receive shared rewards with delegators through staking now",
             test: async function () {
                 // All pending operations where in session 0
+                // They may already have been executed in on_idle, in which case this is a no-op
                 await context.createBlock([
                     await polkadotJs.tx.pooledStaking
                         .executePendingOperations([